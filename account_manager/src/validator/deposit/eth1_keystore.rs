@@ -0,0 +1,120 @@
+//! Decrypts eth1 accounts from the version 3 JSON keystores used by geth and most other eth1
+//! clients, as per the [Web3 Secret Storage
+//! Definition](https://github.com/ethereum/wiki/wiki/Web3-Secret-Storage-Definition).
+
+use super::transaction::keccak256;
+use account_utils::eth2_keystore::{
+    derive_key,
+    json_keystore::{HexBytes, Kdf},
+    PlainText,
+};
+use aes_ctr::stream_cipher::generic_array::GenericArray;
+use aes_ctr::stream_cipher::{NewStreamCipher, SyncStreamCipher};
+use aes_ctr::Aes128Ctr;
+use secp256k1::SecretKey;
+use serde::Deserialize;
+use serde_json::Value;
+
+#[derive(Deserialize)]
+struct JsonKeystore {
+    // Some older clients capitalise this field.
+    #[serde(alias = "Crypto")]
+    crypto: Crypto,
+    version: u64,
+}
+
+#[derive(Deserialize)]
+struct Crypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: HexBytes,
+    kdf: String,
+    kdfparams: Value,
+    mac: HexBytes,
+}
+
+#[derive(Deserialize)]
+struct CipherParams {
+    iv: HexBytes,
+}
+
+/// Decrypts the secret key from the JSON keystore `json` with `password`.
+pub fn decrypt(json: &str, password: &[u8]) -> Result<SecretKey, String> {
+    let keystore: JsonKeystore =
+        serde_json::from_str(json).map_err(|e| format!("Invalid eth1 keystore: {:?}", e))?;
+    let crypto = keystore.crypto;
+
+    if keystore.version != 3 {
+        return Err(format!(
+            "Unsupported eth1 keystore version: {}",
+            keystore.version
+        ));
+    }
+    if crypto.cipher != "aes-128-ctr" {
+        return Err(format!(
+            "Unsupported eth1 keystore cipher: {}",
+            crypto.cipher
+        ));
+    }
+
+    let kdf = match crypto.kdf.as_str() {
+        "scrypt" => serde_json::from_value(crypto.kdfparams).map(Kdf::Scrypt),
+        "pbkdf2" => serde_json::from_value(crypto.kdfparams).map(Kdf::Pbkdf2),
+        other => return Err(format!("Unsupported eth1 keystore kdf: {}", other)),
+    }
+    .map_err(|e| format!("Invalid eth1 keystore kdfparams: {:?}", e))?;
+
+    let derived_key =
+        derive_key(password, &kdf).map_err(|e| format!("Unable to derive key: {:?}", e))?;
+
+    // Unlike EIP-2335, the MAC uses keccak256 rather than sha256.
+    let mut mac_preimage = derived_key.as_bytes()[16..32].to_vec();
+    mac_preimage.extend_from_slice(crypto.ciphertext.as_bytes());
+    if keccak256(&mac_preimage)[..] != *crypto.mac.as_bytes() {
+        return Err("Invalid eth1 keystore password".to_string());
+    }
+
+    if crypto.cipherparams.iv.len() != 16 {
+        return Err("Invalid eth1 keystore iv".to_string());
+    }
+    let mut secret = PlainText::from(crypto.ciphertext.as_bytes().to_vec());
+    let key = GenericArray::from_slice(&derived_key.as_bytes()[0..16]);
+    let nonce = GenericArray::from_slice(crypto.cipherparams.iv.as_bytes());
+    Aes128Ctr::new(&key, &nonce).apply_keystream(secret.as_mut_bytes());
+
+    SecretKey::parse_slice(secret.as_bytes())
+        .map_err(|e| format!("Invalid eth1 secret key: {:?}", e))
+}
+
+#[cfg(all(test, not(debug_assertions)))]
+mod tests {
+    use super::*;
+
+    /// The PBKDF2 test vector from the Web3 Secret Storage Definition.
+    #[test]
+    fn pbkdf2() {
+        let json = r#"{
+                "crypto": {
+                    "cipher": "aes-128-ctr",
+                    "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+                    "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+                    "kdf": "pbkdf2",
+                    "kdfparams": {
+                        "c": 262144,
+                        "dklen": 32,
+                        "prf": "hmac-sha256",
+                        "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+                    },
+                    "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+                },
+                "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+                "version": 3
+            }"#;
+
+        assert_eq!(
+            hex::encode(&decrypt(json, b"testpassword").unwrap().serialize()[..]),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+        assert!(decrypt(json, b"wrongpassword").is_err());
+    }
+}
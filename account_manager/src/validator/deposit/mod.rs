@@ -0,0 +1,654 @@
+mod eth1_keystore;
+mod transaction;
+
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use crate::VALIDATOR_DIR_FLAG;
+use account_utils::{read_input_from_user, read_password, read_password_from_user, PlainText};
+use clap::{App, Arg, ArgMatches};
+use deposit_contract::DEPOSIT_GAS;
+use environment::Environment;
+use futures::compat::Future01CompatExt;
+use secp256k1::SecretKey;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tokio::time::delay_for;
+use transaction::{address_of, keccak256, Transaction};
+use types::EthSpec;
+use validator_dir::{
+    Eth1DepositData, Manager as ValidatorManager, ValidatorDir, ETH1_DEPOSIT_TX_HASH_FILE,
+};
+use web3::{
+    transports::Http,
+    types::{Address, BlockNumber, Bytes, TransactionRequest, H256, U256, U64},
+    Transport, Web3,
+};
+
+pub const CMD: &str = "deposit";
+pub const VALIDATOR_FLAG: &str = "validator";
+pub const ETH1_HTTP_FLAG: &str = "eth1-http";
+pub const ETH1_CHAIN_ID_FLAG: &str = "eth1-chain-id";
+pub const FROM_ADDRESS_FLAG: &str = "from-address";
+pub const ETH1_KEYSTORE_FLAG: &str = "eth1-keystore";
+pub const ETH1_KEYSTORE_PASSWORD_FLAG: &str = "eth1-keystore-password";
+pub const GAS_PRICE_FLAG: &str = "gas-price";
+pub const GAS_LIMIT_FLAG: &str = "gas-limit";
+pub const CONFIRMATIONS_FLAG: &str = "confirmations";
+pub const RECEIPT_TIMEOUT_FLAG: &str = "receipt-timeout";
+pub const BATCH_SIZE_FLAG: &str = "batch-size";
+pub const DRY_RUN_FLAG: &str = "dry-run";
+
+pub const VALIDATOR_ALL: &str = "all";
+pub const ETH1_KEYSTORE_PASSWORD_PROMPT: &str = "Enter the password for the eth1 keystore:";
+pub const CONFIRMATION_PROMPT: &str = "Enter \"yes\" to submit these deposits:";
+pub const BATCH_CONFIRMATION_PROMPT: &str = "Enter \"yes\" to submit this batch of deposits:";
+
+/// How often to check whether the deposit transactions have been included.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Submits the deposit transactions for validators in the validator directory to the \
+            eth1 deposit contract, then waits for them to be included. The transactions are \
+            signed with a local eth1 keystore or by an account which is unlocked in the eth1 \
+            node. The hash of each transaction is saved in its validator directory and \
+            validators which already have a transaction hash are skipped.",
+        )
+        .arg(
+            Arg::with_name(VALIDATOR_FLAG)
+                .long(VALIDATOR_FLAG)
+                .value_name("VALIDATOR_NAME")
+                .help(
+                    "The name of the directory in --validator-dir for which to submit a \
+                    deposit. Use \"all\" to submit deposits for all validators.",
+                )
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(ETH1_HTTP_FLAG)
+                .long(ETH1_HTTP_FLAG)
+                .value_name("ETH1_HTTP_URL")
+                .help("The URL of an eth1 node's JSON-RPC HTTP endpoint.")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name(ETH1_CHAIN_ID_FLAG)
+                .long(ETH1_CHAIN_ID_FLAG)
+                .value_name("CHAIN_ID")
+                .help(
+                    "The chain id of the eth1 network which hosts the deposit contract. Only \
+                    required if the testnet config does not specify a DEPOSIT_CHAIN_ID (e.g., \
+                    5 for the Goerli-based Medalla testnet). The deposits are refused if the \
+                    eth1 node is on a different chain.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FROM_ADDRESS_FLAG)
+                .long(FROM_ADDRESS_FLAG)
+                .value_name("FROM_ETH1_ADDRESS")
+                .help(
+                    "The address of an account which is unlocked in the eth1 node. The eth1 \
+                    node will sign the deposit transactions.",
+                )
+                .takes_value(true)
+                .conflicts_with(ETH1_KEYSTORE_FLAG)
+                .required_unless(ETH1_KEYSTORE_FLAG),
+        )
+        .arg(
+            Arg::with_name(ETH1_KEYSTORE_FLAG)
+                .long(ETH1_KEYSTORE_FLAG)
+                .value_name("ETH1_KEYSTORE_PATH")
+                .help(
+                    "A path to an eth1 JSON keystore (e.g., from geth's keystore directory). \
+                    The deposit transactions will be signed locally.",
+                )
+                .takes_value(true)
+                .conflicts_with(FROM_ADDRESS_FLAG)
+                .required_unless(FROM_ADDRESS_FLAG),
+        )
+        .arg(
+            Arg::with_name(ETH1_KEYSTORE_PASSWORD_FLAG)
+                .long(ETH1_KEYSTORE_PASSWORD_FLAG)
+                .value_name("ETH1_KEYSTORE_PASSWORD_PATH")
+                .help(
+                    "A path to a file containing the password for --eth1-keystore. If not \
+                    supplied, the password will be requested interactively.",
+                )
+                .takes_value(true)
+                .requires(ETH1_KEYSTORE_FLAG),
+        )
+        .arg(
+            Arg::with_name(GAS_PRICE_FLAG)
+                .long(GAS_PRICE_FLAG)
+                .value_name("GWEI")
+                .help("The gas price for each transaction. Defaults to the eth1 node's estimate.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(GAS_LIMIT_FLAG)
+                .long(GAS_LIMIT_FLAG)
+                .value_name("GAS")
+                .help("The gas limit for each transaction.")
+                .default_value("400000")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(CONFIRMATIONS_FLAG)
+                .long(CONFIRMATIONS_FLAG)
+                .value_name("BLOCKS")
+                .help(
+                    "The number of blocks which must include or follow each transaction before \
+                    it is considered complete.",
+                )
+                .default_value("1")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(RECEIPT_TIMEOUT_FLAG)
+                .long(RECEIPT_TIMEOUT_FLAG)
+                .value_name("SECONDS")
+                .help(
+                    "How long to wait for the transactions to be confirmed. Transactions which \
+                    are not confirmed by then are reported as pending and any remaining \
+                    batches are not submitted.",
+                )
+                .default_value("1200")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(BATCH_SIZE_FLAG)
+                .long(BATCH_SIZE_FLAG)
+                .value_name("COUNT")
+                .help(
+                    "Submit the deposits in batches of this many, asking for confirmation \
+                    before each batch and waiting for it to be confirmed before the next. \
+                    Defaults to a single batch of all deposits.",
+                )
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(DRY_RUN_FLAG)
+                .long(DRY_RUN_FLAG)
+                .help("If present, print the deposits but do not send any transactions."),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+/// Signs the deposit transactions.
+enum Signer {
+    /// An account which is unlocked in the eth1 node.
+    Node(Address),
+    /// A secret key which has been decrypted from an eth1 keystore.
+    Local(SecretKey),
+}
+
+impl Signer {
+    fn address(&self) -> Address {
+        match self {
+            Signer::Node(address) => *address,
+            Signer::Local(secret_key) => address_of(secret_key),
+        }
+    }
+}
+
+/// A validator which is yet to submit its deposit.
+struct PendingDeposit {
+    name: String,
+    validator_dir: ValidatorDir,
+    deposit: Eth1DepositData,
+}
+
+pub fn cli_run<T: EthSpec>(
+    matches: &ArgMatches,
+    mut env: Environment<T>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let spec = env.core_context().eth2_config.spec;
+
+    let validator: String = clap_utils::parse_required(matches, VALIDATOR_FLAG)?;
+    let eth1_http: String = clap_utils::parse_required(matches, ETH1_HTTP_FLAG)?;
+    let eth1_chain_id: Option<u64> = clap_utils::parse_optional(matches, ETH1_CHAIN_ID_FLAG)?;
+    let from_address: Option<Address> = clap_utils::parse_optional(matches, FROM_ADDRESS_FLAG)?;
+    let eth1_keystore: Option<PathBuf> = clap_utils::parse_optional(matches, ETH1_KEYSTORE_FLAG)?;
+    let eth1_keystore_password: Option<PathBuf> =
+        clap_utils::parse_optional(matches, ETH1_KEYSTORE_PASSWORD_FLAG)?;
+    let gas_price_gwei: Option<u64> = clap_utils::parse_optional(matches, GAS_PRICE_FLAG)?;
+    let gas_limit: u64 = clap_utils::parse_required(matches, GAS_LIMIT_FLAG)?;
+    let confirmations: u64 = clap_utils::parse_required(matches, CONFIRMATIONS_FLAG)?;
+    let receipt_timeout =
+        Duration::from_secs(clap_utils::parse_required(matches, RECEIPT_TIMEOUT_FLAG)?);
+    let batch_size: Option<usize> = clap_utils::parse_optional(matches, BATCH_SIZE_FLAG)?;
+    let dry_run = matches.is_present(DRY_RUN_FLAG);
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    if batch_size == Some(0) {
+        return Err(format!("--{} must be greater than zero", BATCH_SIZE_FLAG));
+    }
+
+    // Never send deposits to a contract on a different chain to the one the testnet expects.
+    let testnet_chain_id = env
+        .testnet
+        .as_ref()
+        .and_then(|testnet| testnet.yaml_config.as_ref())
+        .and_then(|yaml_config| yaml_config.deposit_chain_id());
+    let deposit_chain_id = match (testnet_chain_id, eth1_chain_id) {
+        (Some(expected), Some(supplied)) if expected != supplied => {
+            return Err(format!(
+                "--{} is {} but the DEPOSIT_CHAIN_ID of the testnet is {}",
+                ETH1_CHAIN_ID_FLAG, supplied, expected
+            ))
+        }
+        (Some(chain_id), _) | (None, Some(chain_id)) => chain_id,
+        (None, None) => {
+            return Err(format!(
+                "The testnet config does not specify a DEPOSIT_CHAIN_ID, so --{} is required",
+                ETH1_CHAIN_ID_FLAG
+            ))
+        }
+    };
+
+    if gas_limit < DEPOSIT_GAS as u64 {
+        eprintln!(
+            "WARNING: --{} is less than the recommended {} and the deposits may fail.",
+            GAS_LIMIT_FLAG, DEPOSIT_GAS
+        );
+    }
+
+    let manager = ValidatorManager::open(&validator_dir)
+        .map_err(|e| format!("Unable to read --{}: {:?}", VALIDATOR_DIR_FLAG, e))?;
+
+    let validators = match validator.as_ref() {
+        VALIDATOR_ALL => manager
+            .open_all_validators()
+            .map_err(|e| format!("Unable to read all validators: {:?}", e))?,
+        name => vec![manager
+            .open_validator(validator_dir.join(name))
+            .map_err(|e| format!("Unable to open validator {}: {:?}", name, e))?],
+    };
+
+    // Only submit deposits which have deposit data and have not been submitted already.
+    let mut deposits = vec![];
+    for validator_dir in validators {
+        let name = validator_dir
+            .dir()
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        if validator_dir.eth1_deposit_tx_hash_exists() {
+            eprintln!(
+                "Skipping {}: a deposit transaction has already been sent.",
+                name
+            );
+            continue;
+        }
+
+        match validator_dir
+            .eth1_deposit_data()
+            .map_err(|e| format!("Unable to read deposit data for {}: {:?}", name, e))?
+        {
+            Some(deposit) => deposits.push(PendingDeposit {
+                name,
+                validator_dir,
+                deposit,
+            }),
+            None => eprintln!("Skipping {}: there is no deposit data.", name),
+        }
+    }
+
+    if deposits.is_empty() {
+        eprintln!("There are no deposits to submit.");
+        return Ok(());
+    }
+
+    let signer = if let Some(path) = eth1_keystore {
+        let json = fs::read_to_string(&path)
+            .map_err(|e| format!("Unable to read --{}: {:?}", ETH1_KEYSTORE_FLAG, e))?;
+        let password = if let Some(password_path) = eth1_keystore_password {
+            read_password(&password_path)
+                .map_err(|e| format!("Unable to read --{}: {:?}", ETH1_KEYSTORE_PASSWORD_FLAG, e))?
+        } else {
+            eprintln!("{}", ETH1_KEYSTORE_PASSWORD_PROMPT);
+            PlainText::from(read_password_from_user(stdin_inputs)?.as_ref().to_vec())
+        };
+        Signer::Local(eth1_keystore::decrypt(&json, password.as_bytes())?)
+    } else {
+        Signer::Node(from_address.ok_or_else(|| {
+            format!(
+                "Must supply either --{} or --{}",
+                FROM_ADDRESS_FLAG, ETH1_KEYSTORE_FLAG
+            )
+        })?)
+    };
+    let from = signer.address();
+
+    let (_event_loop, transport) = Http::new(&eth1_http)
+        .map_err(|e| format!("Unable to connect to --{}: {:?}", ETH1_HTTP_FLAG, e))?;
+    let web3 = Web3::new(transport);
+
+    let (chain_id, gas_price, balance, mut nonce) = env.runtime().block_on(async {
+        let chain_id = web3
+            .transport()
+            .execute("eth_chainId", vec![])
+            .compat()
+            .await
+            .map_err(|e| format!("Unable to get eth1 chain id: {:?}", e))
+            .and_then(|value| {
+                value
+                    .as_str()
+                    .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+                    .ok_or_else(|| format!("Invalid eth1 chain id: {}", value))
+            })?;
+
+        let gas_price = if let Some(gwei) = gas_price_gwei {
+            from_gwei(gwei)
+        } else {
+            web3.eth()
+                .gas_price()
+                .compat()
+                .await
+                .map_err(|e| format!("Unable to get gas price: {:?}", e))?
+        };
+
+        let balance = web3
+            .eth()
+            .balance(from, None)
+            .compat()
+            .await
+            .map_err(|e| format!("Unable to get balance of {:?}: {:?}", from, e))?;
+
+        let nonce = web3
+            .eth()
+            .transaction_count(from, Some(BlockNumber::Pending))
+            .compat()
+            .await
+            .map_err(|e| format!("Unable to get nonce of {:?}: {:?}", from, e))?;
+
+        Ok::<_, String>((chain_id, gas_price, balance, nonce))
+    })?;
+
+    if chain_id != deposit_chain_id {
+        return Err(format!(
+            "The eth1 node is on chain {} but the deposit contract is on chain {}. Check --{}.",
+            chain_id, deposit_chain_id, ETH1_HTTP_FLAG
+        ));
+    }
+
+    let total_value = deposits.iter().fold(U256::zero(), |total, pending| {
+        total + from_gwei(pending.deposit.deposit_data.amount)
+    });
+    let max_fees = gas_price * U256::from(gas_limit) * U256::from(deposits.len());
+
+    eprintln!("");
+    eprintln!("Deposits to submit:");
+    eprintln!("");
+    for pending in &deposits {
+        eprintln!(
+            " - {}: {} gwei",
+            pending.name, pending.deposit.deposit_data.amount
+        );
+    }
+    eprintln!("");
+    eprintln!(" - Deposit contract: {:?}", spec.deposit_contract_address);
+    eprintln!(" - Eth1 chain id: {}", chain_id);
+    eprintln!(" - From: {:?}", from);
+    eprintln!(" - Total value: {} wei", total_value);
+    eprintln!(
+        " - Gas price: {} wei, gas limit: {} per deposit",
+        gas_price, gas_limit
+    );
+    eprintln!(" - Maximum total fees: {} wei", max_fees);
+    eprintln!("");
+
+    if balance < total_value + max_fees {
+        return Err(format!(
+            "The balance of {:?} ({} wei) is insufficient for these deposits",
+            from, balance
+        ));
+    }
+
+    if dry_run {
+        eprintln!("Dry run: no deposits were submitted.");
+        return Ok(());
+    }
+
+    let batch_size = batch_size.unwrap_or_else(|| deposits.len());
+    let batch_count = (deposits.len() + batch_size - 1) / batch_size;
+
+    for (batch_index, batch) in deposits.chunks_mut(batch_size).enumerate() {
+        if batch_count > 1 {
+            eprintln!("");
+            eprintln!("Batch {} of {}:", batch_index + 1, batch_count);
+            for pending in batch.iter() {
+                eprintln!(" - {}", pending.name);
+            }
+            eprintln!("");
+            eprintln!("{}", BATCH_CONFIRMATION_PROMPT);
+        } else {
+            eprintln!("{}", CONFIRMATION_PROMPT);
+        }
+
+        if read_input_from_user(stdin_inputs)? != "yes" {
+            return Err(if batch_index == 0 {
+                "No deposits were submitted.".to_string()
+            } else {
+                "The remaining deposits were not submitted.".to_string()
+            });
+        }
+
+        let tx_hashes = env.runtime().block_on(async {
+            let mut tx_hashes = vec![];
+
+            // Each transaction hash is saved as soon as it is known, so that the deposit is not
+            // submitted twice if a later transaction fails.
+            for pending in batch.iter_mut() {
+                let value = from_gwei(pending.deposit.deposit_data.amount);
+                let data = pending.deposit.rlp.clone();
+
+                let tx_hash = match &signer {
+                    Signer::Node(from) => {
+                        let tx_hash = web3
+                            .eth()
+                            .send_transaction(TransactionRequest {
+                                from: *from,
+                                to: Some(spec.deposit_contract_address),
+                                gas: Some(U256::from(gas_limit)),
+                                gas_price: Some(gas_price),
+                                value: Some(value),
+                                data: Some(data.into()),
+                                nonce: None,
+                                condition: None,
+                            })
+                            .compat()
+                            .await
+                            .map_err(|e| {
+                                format!("Unable to send deposit for {}: {:?}", pending.name, e)
+                            })?;
+
+                        save_tx_hash(pending, tx_hash).map_err(|e| {
+                            format!(
+                                "Sent deposit for {} in {:?} but unable to save it: {}",
+                                pending.name, tx_hash, e
+                            )
+                        })?;
+
+                        tx_hash
+                    }
+                    Signer::Local(secret_key) => {
+                        let raw_tx = Transaction {
+                            nonce,
+                            gas_price,
+                            gas: U256::from(gas_limit),
+                            to: spec.deposit_contract_address,
+                            value,
+                            data,
+                        }
+                        .sign(secret_key, chain_id);
+                        nonce += U256::one();
+
+                        // The hash of a signed transaction is known before it is sent, so save it
+                        // first. An interruption whilst sending then never leaves a deposit which
+                        // may have been submitted without a record of it.
+                        let tx_hash = H256::from(keccak256(&raw_tx));
+                        save_tx_hash(pending, tx_hash).map_err(|e| {
+                            format!(
+                                "Unable to save deposit for {} before sending it: {}",
+                                pending.name, e
+                            )
+                        })?;
+
+                        let sent_tx_hash = web3
+                            .eth()
+                            .send_raw_transaction(Bytes::from(raw_tx))
+                            .compat()
+                            .await
+                            .map_err(|e| {
+                                format!(
+                                    "Unable to send deposit for {} in {:?}: {:?}. Its hash is \
+                                     saved in the {} file of its validator directory; only remove \
+                                     it if the transaction was not received by the eth1 network.",
+                                    pending.name, tx_hash, e, ETH1_DEPOSIT_TX_HASH_FILE
+                                )
+                            })?;
+
+                        if sent_tx_hash != tx_hash {
+                            return Err(format!(
+                                "Sent deposit for {} in {:?} but the eth1 node reported {:?}",
+                                pending.name, tx_hash, sent_tx_hash
+                            ));
+                        }
+
+                        tx_hash
+                    }
+                };
+
+                eprintln!("Sent deposit for {}: {:?}", pending.name, tx_hash);
+                tx_hashes.push((pending.name.clone(), tx_hash));
+            }
+
+            Ok::<_, String>(tx_hashes)
+        })?;
+
+        eprintln!("");
+        eprintln!(
+            "Waiting for {} confirmation(s) of {} deposits...",
+            confirmations,
+            tx_hashes.len()
+        );
+
+        let (failed, pending) = env.runtime().block_on(wait_for_receipts(
+            &web3,
+            tx_hashes,
+            confirmations,
+            receipt_timeout,
+        ))?;
+
+        let mut errors = vec![];
+        if !failed.is_empty() {
+            errors.push(format!(
+                "The deposit transactions for {} failed. Remove the {} file from their \
+                 validator directories before trying again.",
+                failed.join(", "),
+                ETH1_DEPOSIT_TX_HASH_FILE
+            ));
+        }
+        if !pending.is_empty() {
+            errors.push(format!(
+                "The deposit transactions for {} are still pending after {} seconds. Their \
+                 hashes are saved in the {} file of their validator directories; only remove \
+                 it if the transaction has been dropped by the eth1 network.",
+                pending.join(", "),
+                receipt_timeout.as_secs(),
+                ETH1_DEPOSIT_TX_HASH_FILE
+            ));
+        }
+        if !errors.is_empty() {
+            if batch_index + 1 < batch_count {
+                errors.push("The remaining deposits were not submitted.".to_string());
+            }
+            return Err(errors.join(" "));
+        }
+    }
+
+    eprintln!("All deposits were successful.");
+    Ok(())
+}
+
+/// Saves `tx_hash` in the validator directory of `pending`.
+fn save_tx_hash(pending: &mut PendingDeposit, tx_hash: H256) -> Result<(), String> {
+    pending
+        .validator_dir
+        .save_eth1_deposit_tx_hash(&format!("{:?}", tx_hash))
+        .map_err(|e| format!("{:?}", e))
+}
+
+/// Waits until each of `tx_hashes` has been included with `confirmations`, or until `timeout`
+/// has passed. Returns the names of the validators whose transactions failed, followed by those
+/// whose transactions are still pending.
+async fn wait_for_receipts<T: Transport>(
+    web3: &Web3<T>,
+    mut tx_hashes: Vec<(String, H256)>,
+    confirmations: u64,
+    timeout: Duration,
+) -> Result<(Vec<String>, Vec<String>), String> {
+    let started = Instant::now();
+    let mut failed = vec![];
+
+    loop {
+        let head = web3
+            .eth()
+            .block_number()
+            .compat()
+            .await
+            .map_err(|e| format!("Unable to get block number: {:?}", e))?
+            .as_u64();
+
+        let mut still_pending = vec![];
+        for (name, tx_hash) in tx_hashes {
+            let receipt = web3
+                .eth()
+                .transaction_receipt(tx_hash)
+                .compat()
+                .await
+                .map_err(|e| format!("Unable to get receipt for {:?}: {:?}", tx_hash, e))?;
+
+            match receipt.and_then(|receipt| Some((receipt.block_number?, receipt.status))) {
+                Some((block_number, status))
+                    if head + 1 >= block_number.as_u64() + confirmations =>
+                {
+                    if status == Some(U64::from(1)) {
+                        eprintln!("Deposit for {} confirmed in block {}", name, block_number);
+                    } else {
+                        eprintln!("Deposit for {} FAILED in block {}", name, block_number);
+                        failed.push(name);
+                    }
+                }
+                _ => still_pending.push((name, tx_hash)),
+            }
+        }
+
+        if still_pending.is_empty() {
+            return Ok((failed, vec![]));
+        }
+
+        if started.elapsed() >= timeout {
+            let pending = still_pending.into_iter().map(|(name, _)| name).collect();
+            return Ok((failed, pending));
+        }
+
+        tx_hashes = still_pending;
+        delay_for(RECEIPT_POLL_INTERVAL).await;
+    }
+}
+
+fn from_gwei(gwei: u64) -> U256 {
+    U256::from(gwei) * U256::exp10(9)
+}
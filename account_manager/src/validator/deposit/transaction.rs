@@ -0,0 +1,123 @@
+//! Signs legacy eth1 transactions locally, with replay protection as per
+//! [EIP-155](https://eips.ethereum.org/EIPS/eip-155).
+
+use rlp::RlpStream;
+use secp256k1::{Message, PublicKey, SecretKey};
+use tiny_keccak::{Hasher, Keccak};
+use web3::types::{Address, U256};
+
+/// An eth1 transaction which is yet to be signed.
+pub struct Transaction {
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas: U256,
+    pub to: Address,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+impl Transaction {
+    /// Returns the RLP encoding of `self`, signed by `secret_key` for the chain with `chain_id`.
+    ///
+    /// The result is suitable for `eth_sendRawTransaction`.
+    pub fn sign(&self, secret_key: &SecretKey, chain_id: u64) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(9);
+        self.append_unsigned_fields(&mut stream);
+        append_uint(&mut stream, U256::from(chain_id));
+        append_uint(&mut stream, U256::zero());
+        append_uint(&mut stream, U256::zero());
+
+        let message = Message::parse(&keccak256(&stream.out()));
+        let (signature, recovery_id) = secp256k1::sign(&message, secret_key);
+        let signature = signature.serialize();
+
+        let mut stream = RlpStream::new_list(9);
+        self.append_unsigned_fields(&mut stream);
+        append_uint(
+            &mut stream,
+            U256::from(u64::from(recovery_id.serialize()) + chain_id * 2 + 35),
+        );
+        append_uint(&mut stream, U256::from_big_endian(&signature[0..32]));
+        append_uint(&mut stream, U256::from_big_endian(&signature[32..64]));
+        stream.out()
+    }
+
+    fn append_unsigned_fields(&self, stream: &mut RlpStream) {
+        append_uint(stream, self.nonce);
+        append_uint(stream, self.gas_price);
+        append_uint(stream, self.gas);
+        stream.append(&self.to.as_bytes().to_vec());
+        append_uint(stream, self.value);
+        stream.append(&self.data);
+    }
+}
+
+/// Returns the eth1 address which is controlled by `secret_key`.
+pub fn address_of(secret_key: &SecretKey) -> Address {
+    let public_key = PublicKey::from_secret_key(secret_key).serialize();
+    // Skip the `0x04` prefix of the uncompressed public key.
+    Address::from_slice(&keccak256(&public_key[1..])[12..])
+}
+
+pub fn keccak256(bytes: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    hasher.update(bytes);
+    let mut output = [0; 32];
+    hasher.finalize(&mut output);
+    output
+}
+
+/// Appends `value` as a big-endian integer without leading zeros, as RLP requires.
+fn append_uint(stream: &mut RlpStream, value: U256) {
+    let mut bytes = [0; 32];
+    value.to_big_endian(&mut bytes);
+    let first_non_zero = bytes.iter().position(|byte| *byte != 0).unwrap_or(32);
+    stream.append(&bytes[first_non_zero..].to_vec());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example from EIP-155.
+    #[test]
+    fn eip155_example() {
+        let secret_key = SecretKey::parse(&[0x46; 32]).unwrap();
+        let transaction = Transaction {
+            nonce: U256::from(9),
+            gas_price: U256::from(20_000_000_000u64),
+            gas: U256::from(21_000),
+            to: Address::from_slice(&[0x35; 20]),
+            value: U256::exp10(18),
+            data: vec![],
+        };
+
+        assert_eq!(
+            hex::encode(transaction.sign(&secret_key, 1)),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+
+    /// The hash of a signed transaction, as reported by `eth_sendRawTransaction`.
+    #[test]
+    fn eip155_example_hash() {
+        let raw_tx = hex::decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+
+        assert_eq!(
+            hex::encode(keccak256(&raw_tx)),
+            "33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788"
+        );
+    }
+
+    #[test]
+    fn address() {
+        let secret_key = SecretKey::parse(&[0x46; 32]).unwrap();
+        assert_eq!(
+            hex::encode(address_of(&secret_key)),
+            "9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f"
+        );
+    }
+}
@@ -0,0 +1,462 @@
+use super::create::{COUNT_FLAG, DEPOSIT_GWEI_FLAG};
+use super::recover::{FIRST_INDEX_FLAG, MNEMONIC_FLAG};
+use crate::common::read_mnemonic_from_cli;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    eth2_keystore::{keypair_from_secret, Keystore, KeystoreBuilder},
+    is_password_sufficiently_complex, read_password_from_user, strip_off_newlines,
+    validator_definitions::recursively_find_voting_keystores,
+    PlainText,
+};
+use bls::get_withdrawal_credentials;
+use clap::{App, Arg, ArgMatches};
+use directory::ensure_dir_exists;
+use environment::Environment;
+use eth2_wallet::bip39::Seed;
+use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType, ValidatorPath};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tree_hash::TreeHash;
+use types::{ChainSpec, DepositData, EthSpec, Hash256, Keypair, SignatureBytes};
+
+pub const CMD: &str = "deposit-data";
+pub const GENERATE_CMD: &str = "generate";
+pub const VERIFY_CMD: &str = "verify";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+pub const DEPOSIT_DATA_FLAG: &str = "deposit-data";
+pub const KEYSTORES_DIR_FLAG: &str = "keystores-dir";
+pub const KEYSTORE_PASSWORD_FLAG: &str = "keystore-password-path";
+
+pub const NEW_KEYSTORE_PASSWORD_PROMPT: &str =
+    "Enter a password for the new keystores that is at least 12 characters long:";
+pub const RETYPE_PASSWORD_PROMPT: &str = "Please re-enter the keystore password:";
+
+/// A single entry of the `deposit_data-*.json` file produced by the `eth2.0-deposit-cli` and
+/// consumed by the Eth2 launchpad.
+///
+/// All byte values are hex-encoded *without* a `0x` prefix, as per the launchpad.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LaunchpadDepositData {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: u64,
+    pub signature: String,
+    pub deposit_message_root: String,
+    pub deposit_data_root: String,
+    pub fork_version: String,
+}
+
+impl LaunchpadDepositData {
+    pub fn new(deposit_data: &DepositData, spec: &ChainSpec) -> Self {
+        Self {
+            pubkey: hex::encode(deposit_data.pubkey.as_serialized()),
+            withdrawal_credentials: hex::encode(deposit_data.withdrawal_credentials),
+            amount: deposit_data.amount,
+            signature: hex::encode(&deposit_data.signature.serialize()[..]),
+            deposit_message_root: hex::encode(deposit_data.as_deposit_message().tree_hash_root()),
+            deposit_data_root: hex::encode(deposit_data.tree_hash_root()),
+            fork_version: hex::encode(spec.genesis_fork_version),
+        }
+    }
+}
+
+/// The keys for a single validator, derived from a mnemonic as per EIP-2334.
+struct DerivedValidator {
+    voting_keypair: Keypair,
+    voting_path: ValidatorPath,
+    deposit_data: DepositData,
+}
+
+impl DerivedValidator {
+    fn from_seed(seed: &[u8], index: u32, amount: u64, spec: &ChainSpec) -> Result<Self, String> {
+        let derive = |key_type: KeyType| -> Result<(Keypair, ValidatorPath), String> {
+            let (secret, path) = recover_validator_secret_from_mnemonic(seed, index, key_type)
+                .map_err(|e| format!("Unable to derive validator keys: {:?}", e))?;
+            let keypair = keypair_from_secret(secret.as_bytes())
+                .map_err(|e| format!("Unable to derive validator keys: {:?}", e))?;
+            Ok((keypair, path))
+        };
+
+        let (voting_keypair, voting_path) = derive(KeyType::Voting)?;
+        let (withdrawal_keypair, _) = derive(KeyType::Withdrawal)?;
+
+        let mut deposit_data = DepositData {
+            pubkey: voting_keypair.pk.clone().into(),
+            withdrawal_credentials: Hash256::from_slice(&get_withdrawal_credentials(
+                &withdrawal_keypair.pk,
+                spec.bls_withdrawal_prefix_byte,
+            )),
+            amount,
+            signature: SignatureBytes::empty(),
+        };
+        deposit_data.signature = deposit_data.create_signature(&voting_keypair.sk, spec);
+
+        Ok(Self {
+            voting_keypair,
+            voting_path,
+            deposit_data,
+        })
+    }
+
+    /// Returns the file name used by the `eth2.0-deposit-cli` for the voting keystore,
+    /// e.g., `keystore-m_12381_3600_0_0_0-1602201234.json`.
+    fn keystore_file_name(&self, timestamp: u64) -> String {
+        format!(
+            "keystore-{}-{}.json",
+            self.voting_path.to_string().replace("/", "_"),
+            timestamp
+        )
+    }
+}
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    let mnemonic_args = |app: App<'a, 'b>| {
+        app.arg(
+            Arg::with_name(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help("If present, the mnemonic will be read in from this file.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(FIRST_INDEX_FLAG)
+                .long(FIRST_INDEX_FLAG)
+                .value_name("FIRST_INDEX")
+                .help("The EIP-2334 index of the first validator.")
+                .takes_value(true)
+                .default_value("0"),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+    };
+
+    App::new(CMD)
+        .about(
+            "Generates or verifies EIP-2335 keystores and a launchpad-compatible deposit data \
+            file from a BIP-39 mnemonic, without requiring a wallet or network access.",
+        )
+        .subcommand(mnemonic_args(
+            App::new(GENERATE_CMD)
+                .about(
+                    "Derives validator keys from a mnemonic as per EIP-2333/EIP-2334 and writes \
+                    a keystore for each validator and a single deposit_data-*.json file.",
+                )
+                .arg(
+                    Arg::with_name(COUNT_FLAG)
+                        .long(COUNT_FLAG)
+                        .value_name("COUNT")
+                        .help("The number of consecutive validators to generate.")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name(OUTPUT_DIR_FLAG)
+                        .long(OUTPUT_DIR_FLAG)
+                        .value_name("OUTPUT_DIR")
+                        .help("The directory in which to write the keystores and deposit data.")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name(DEPOSIT_GWEI_FLAG)
+                        .long(DEPOSIT_GWEI_FLAG)
+                        .value_name("DEPOSIT_GWEI")
+                        .help(
+                            "The GWEI value of each deposit. Defaults to the minimum amount \
+                            required for an active validator (MAX_EFFECTIVE_BALANCE)",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(KEYSTORE_PASSWORD_FLAG)
+                        .long(KEYSTORE_PASSWORD_FLAG)
+                        .value_name("KEYSTORE_PASSWORD_PATH")
+                        .help(
+                            "A path to a file containing the password for the new keystores. \
+                            If absent, the password will be requested interactively.",
+                        )
+                        .takes_value(true),
+                ),
+        ))
+        .subcommand(mnemonic_args(
+            App::new(VERIFY_CMD)
+                .about(
+                    "Re-derives the validator keys from a mnemonic and checks that they match \
+                    a deposit_data-*.json file and, optionally, a directory of keystores.",
+                )
+                .arg(
+                    Arg::with_name(DEPOSIT_DATA_FLAG)
+                        .long(DEPOSIT_DATA_FLAG)
+                        .value_name("DEPOSIT_DATA_PATH")
+                        .help("The deposit_data-*.json file to verify.")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name(KEYSTORES_DIR_FLAG)
+                        .long(KEYSTORES_DIR_FLAG)
+                        .value_name("KEYSTORES_DIR")
+                        .help(
+                            "If present, check that this directory contains a keystore with the \
+                            correct public key and derivation path for each deposit.",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(KEYSTORE_PASSWORD_FLAG)
+                        .long(KEYSTORE_PASSWORD_FLAG)
+                        .value_name("KEYSTORE_PASSWORD_PATH")
+                        .help(
+                            "If present, the keystores will also be decrypted with the password \
+                            in this file and their secret keys compared.",
+                        )
+                        .takes_value(true)
+                        .requires(KEYSTORES_DIR_FLAG),
+                ),
+        ))
+}
+
+pub fn cli_run<T: EthSpec>(matches: &ArgMatches, mut env: Environment<T>) -> Result<(), String> {
+    let spec = env.core_context().eth2_config.spec;
+
+    match matches.subcommand() {
+        (GENERATE_CMD, Some(matches)) => generate(matches, &spec),
+        (VERIFY_CMD, Some(matches)) => verify(matches, &spec),
+        ("", _) => Err("No subcommand provided, see --help for options".to_string()),
+        (command, _) => Err(format!("No such subcommand `{}`", command)),
+    }
+}
+
+fn generate(matches: &ArgMatches, spec: &ChainSpec) -> Result<(), String> {
+    let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
+    let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let output_dir: PathBuf = clap_utils::parse_required(matches, OUTPUT_DIR_FLAG)?;
+    let amount = clap_utils::parse_optional(matches, DEPOSIT_GWEI_FLAG)?
+        .unwrap_or(spec.max_effective_balance);
+    let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
+    let password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, KEYSTORE_PASSWORD_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let last_index = first_index
+        .checked_add(count)
+        .ok_or_else(|| format!("--{} is too large", COUNT_FLAG))?;
+
+    ensure_dir_exists(&output_dir)?;
+
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+    let password = read_new_keystore_password(password_path, stdin_inputs)?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Unable to read system time: {:?}", e))?
+        .as_secs();
+
+    let mut deposits = Vec::with_capacity(count as usize);
+
+    for index in first_index..last_index {
+        let validator = DerivedValidator::from_seed(seed.as_bytes(), index, amount, spec)?;
+
+        let keystore = KeystoreBuilder::new(
+            &validator.voting_keypair,
+            password.as_bytes(),
+            validator.voting_path.to_string(),
+        )
+        .and_then(|builder| builder.build())
+        .map_err(|e| format!("Unable to build keystore: {:?}", e))?;
+
+        let keystore_path = output_dir.join(validator.keystore_file_name(timestamp));
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&keystore_path)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", keystore_path, e))
+            .and_then(|file| {
+                keystore
+                    .to_json_writer(file)
+                    .map_err(|e| format!("Unable to write {:?}: {:?}", keystore_path, e))
+            })?;
+
+        println!(
+            "{}/{}\tIndex: {}\t0x{}",
+            index - first_index + 1,
+            count,
+            index,
+            keystore.pubkey()
+        );
+
+        deposits.push(LaunchpadDepositData::new(&validator.deposit_data, spec));
+    }
+
+    let deposit_data_path = output_dir.join(format!("deposit_data-{}.json", timestamp));
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&deposit_data_path)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", deposit_data_path, e))
+        .and_then(|file| {
+            serde_json::to_writer(file, &deposits)
+                .map_err(|e| format!("Unable to write {:?}: {:?}", deposit_data_path, e))
+        })?;
+
+    eprintln!("Deposit data written to {:?}", deposit_data_path);
+
+    Ok(())
+}
+
+fn verify(matches: &ArgMatches, spec: &ChainSpec) -> Result<(), String> {
+    let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
+    let deposit_data_path: PathBuf = clap_utils::parse_required(matches, DEPOSIT_DATA_FLAG)?;
+    let keystores_dir: Option<PathBuf> = clap_utils::parse_optional(matches, KEYSTORES_DIR_FLAG)?;
+    let mnemonic_path: Option<PathBuf> = clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
+    let password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, KEYSTORE_PASSWORD_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    let deposits: Vec<LaunchpadDepositData> = File::open(&deposit_data_path)
+        .map_err(|e| format!("Unable to open {:?}: {:?}", deposit_data_path, e))
+        .and_then(|file| {
+            serde_json::from_reader(file)
+                .map_err(|e| format!("Unable to parse {:?}: {:?}", deposit_data_path, e))
+        })?;
+
+    let password = password_path
+        .map(|path| read_password_file(&path))
+        .transpose()?;
+
+    let keystores = keystores_dir
+        .map(|dir| {
+            let mut paths = vec![];
+            recursively_find_voting_keystores(&dir, &mut paths)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))?;
+            paths
+                .into_iter()
+                .map(|path| {
+                    Keystore::from_json_file(&path)
+                        .map_err(|e| format!("Unable to read keystore {:?}: {:?}", path, e))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+    let seed = Seed::new(&mnemonic, "");
+
+    let mut errors = vec![];
+
+    for (i, deposit) in deposits.iter().enumerate() {
+        let index = first_index
+            .checked_add(i as u32)
+            .ok_or_else(|| "Validator index overflow".to_string())?;
+        let validator = DerivedValidator::from_seed(seed.as_bytes(), index, deposit.amount, spec)?;
+        let expected = LaunchpadDepositData::new(&validator.deposit_data, spec);
+
+        if *deposit != expected {
+            errors.push(format!(
+                "Deposit {} does not match validator index {}: expected {:?}, got {:?}",
+                i, index, expected, deposit
+            ));
+            continue;
+        }
+
+        if let Some(keystores) = &keystores {
+            let matching = keystores
+                .iter()
+                .filter(|keystore| keystore.pubkey() == expected.pubkey)
+                .collect::<Vec<_>>();
+
+            match matching.as_slice() {
+                [keystore] => {
+                    if keystore.path() != Some(validator.voting_path.to_string()) {
+                        errors.push(format!(
+                            "Keystore for validator index {} has path {:?}, expected {}",
+                            index,
+                            keystore.path(),
+                            validator.voting_path
+                        ));
+                    }
+
+                    if let Some(password) = &password {
+                        match keystore.decrypt_keypair(password.as_bytes()) {
+                            Ok(keypair) if keypair.pk == validator.voting_keypair.pk => {}
+                            Ok(_) => errors.push(format!(
+                                "Keystore for validator index {} has the wrong secret key",
+                                index
+                            )),
+                            Err(e) => errors.push(format!(
+                                "Unable to decrypt keystore for validator index {}: {:?}",
+                                index, e
+                            )),
+                        }
+                    }
+                }
+                [] => errors.push(format!("No keystore for validator index {}", index)),
+                _ => errors.push(format!("Multiple keystores for validator index {}", index)),
+            }
+        }
+
+        println!("{}\tIndex: {}\t0x{}", i, index, expected.pubkey);
+    }
+
+    if errors.is_empty() {
+        eprintln!(
+            "Verified {} deposits in {:?}",
+            deposits.len(),
+            deposit_data_path
+        );
+        Ok(())
+    } else {
+        for error in &errors {
+            eprintln!("{}", error);
+        }
+        Err(format!("{} verification errors", errors.len()))
+    }
+}
+
+/// Reads a password from `path`, stripping off any trailing newlines.
+pub(crate) fn read_password_file(path: &Path) -> Result<PlainText, String> {
+    fs::read(path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))
+        .map(|bytes| strip_off_newlines(bytes).into())
+}
+
+/// Reads the password for new keystores from `password_path`, or from an interactive prompt if
+/// `password_path` is `None`. The password must be sufficiently complex.
+pub(crate) fn read_new_keystore_password(
+    password_path: Option<PathBuf>,
+    stdin_inputs: bool,
+) -> Result<PlainText, String> {
+    match password_path {
+        Some(path) => {
+            let password = read_password_file(&path)?;
+            is_password_sufficiently_complex(password.as_bytes())?;
+            Ok(password)
+        }
+        None => loop {
+            eprintln!("");
+            eprintln!("{}", NEW_KEYSTORE_PASSWORD_PROMPT);
+            let password =
+                PlainText::from(read_password_from_user(stdin_inputs)?.as_ref().to_vec());
+
+            match is_password_sufficiently_complex(password.as_bytes()) {
+                Ok(_) => {
+                    eprintln!("{}", RETYPE_PASSWORD_PROMPT);
+                    let retyped_password =
+                        PlainText::from(read_password_from_user(stdin_inputs)?.as_ref().to_vec());
+                    if retyped_password == password {
+                        break Ok(password);
+                    } else {
+                        eprintln!("Passwords do not match.");
+                    }
+                }
+                Err(message) => eprintln!("{}", message),
+            }
+        },
+    }
+}
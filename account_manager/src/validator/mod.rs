@@ -1,8 +1,12 @@
 pub mod create;
+pub mod deposit;
+pub mod deposit_data;
 pub mod import;
 pub mod list;
+pub mod modify_keystore;
 pub mod recover;
 pub mod slashing_protection;
+pub mod withdrawal;
 
 use crate::VALIDATOR_DIR_FLAG;
 use clap::{App, Arg, ArgMatches};
@@ -28,10 +32,14 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .conflicts_with("datadir"),
         )
         .subcommand(create::cli_app())
+        .subcommand(deposit::cli_app())
+        .subcommand(deposit_data::cli_app())
         .subcommand(import::cli_app())
         .subcommand(list::cli_app())
+        .subcommand(modify_keystore::cli_app())
         .subcommand(recover::cli_app())
         .subcommand(slashing_protection::cli_app())
+        .subcommand(withdrawal::cli_app())
 }
 
 pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<(), String> {
@@ -45,12 +53,20 @@ pub fn cli_run<T: EthSpec>(matches: &ArgMatches, env: Environment<T>) -> Result<
 
     match matches.subcommand() {
         (create::CMD, Some(matches)) => create::cli_run::<T>(matches, env, validator_base_dir),
+        (deposit::CMD, Some(matches)) => deposit::cli_run::<T>(matches, env, validator_base_dir),
+        (deposit_data::CMD, Some(matches)) => deposit_data::cli_run::<T>(matches, env),
         (import::CMD, Some(matches)) => import::cli_run(matches, validator_base_dir),
         (list::CMD, Some(_)) => list::cli_run(validator_base_dir),
+        (modify_keystore::CMD, Some(matches)) => {
+            modify_keystore::cli_run(matches, validator_base_dir)
+        }
         (recover::CMD, Some(matches)) => recover::cli_run(matches, validator_base_dir),
         (slashing_protection::CMD, Some(matches)) => {
             slashing_protection::cli_run(matches, env, validator_base_dir)
         }
+        (withdrawal::CMD, Some(matches)) => {
+            withdrawal::cli_run::<T>(matches, env, validator_base_dir)
+        }
         (unknown, _) => Err(format!(
             "{} does not have a {} command. See --help",
             CMD, unknown
@@ -0,0 +1,579 @@
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use account_utils::{
+    create_with_600_perms,
+    eth2_keystore::{
+        default_kdf,
+        json_keystore::{Kdf, Pbkdf2, Prf, Scrypt},
+        Keystore, KeystoreBuilder, DKLEN, SALT_SIZE,
+    },
+    is_password_sufficiently_complex, random_password, read_password, read_password_from_user,
+    validator_definitions::{
+        SigningDefinition, ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME,
+    },
+    write_with_600_perms_atomically, PlainText, ZeroizeString,
+};
+use clap::{App, Arg, ArgMatches};
+use rand::Rng;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{Keypair, PublicKey};
+
+pub const CMD: &str = "modify-keystore";
+pub const PUBKEY_FLAG: &str = "pubkey";
+pub const ALL_FLAG: &str = "all";
+pub const NEW_PASSWORD_FILE_FLAG: &str = "new-password-file";
+pub const RANDOM_PASSWORD_FLAG: &str = "random-password";
+pub const KDF_FLAG: &str = "kdf";
+pub const KDF_COST_FLAG: &str = "kdf-cost";
+
+pub const SCRYPT: &str = "scrypt";
+pub const PBKDF2: &str = "pbkdf2";
+/// The `c` parameter used when switching a keystore to PBKDF2, as per the EIP-2335 test vectors.
+pub const DEFAULT_PBKDF2_C: u32 = 262_144;
+
+pub const PASSWORD_PROMPT: &str = "Enter the current keystore password:";
+pub const BACKUP_EXTENSION: &str = "bak";
+/// Appended to the path of each re-encrypted keystore until it replaces the original.
+pub const STAGED_EXTENSION: &str = "new";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    App::new(CMD)
+        .about(
+            "Re-encrypts one or more voting keystores in the validator directory, optionally \
+            with a new password or key derivation function. The keystores, password files and \
+            validator definitions are replaced atomically and the previous versions are kept \
+            alongside them with a .bak extension (or .<timestamp>.bak if a backup already \
+            exists). The validator client must not be running.",
+        )
+        .arg(
+            Arg::with_name(PUBKEY_FLAG)
+                .long(PUBKEY_FLAG)
+                .value_name("PUBKEY")
+                .help("The voting public key of the single validator to modify.")
+                .conflicts_with(ALL_FLAG)
+                .required_unless(ALL_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(ALL_FLAG)
+                .long(ALL_FLAG)
+                .help("If present, all validators in the validator definitions will be modified.")
+                .conflicts_with(PUBKEY_FLAG)
+                .required_unless(PUBKEY_FLAG),
+        )
+        .arg(
+            Arg::with_name(NEW_PASSWORD_FILE_FLAG)
+                .long(NEW_PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_FILE_PATH")
+                .help(
+                    "A path to a file containing the new password for all modified keystores. \
+                    If neither this flag nor --random-password is supplied the current \
+                    password is retained.",
+                )
+                .conflicts_with(RANDOM_PASSWORD_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(RANDOM_PASSWORD_FLAG)
+                .long(RANDOM_PASSWORD_FLAG)
+                .help(
+                    "If present, each keystore will be given a new random password. Only \
+                    applies to keystores whose password is stored in the validator definitions \
+                    or in a password file.",
+                )
+                .conflicts_with(NEW_PASSWORD_FILE_FLAG),
+        )
+        .arg(
+            Arg::with_name(KDF_FLAG)
+                .long(KDF_FLAG)
+                .value_name("KDF")
+                .help(
+                    "The key derivation function to use for the new keystores. Defaults to the \
+                    function used by each existing keystore.",
+                )
+                .possible_values(&[SCRYPT, PBKDF2])
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(KDF_COST_FLAG)
+                .long(KDF_COST_FLAG)
+                .value_name("COST")
+                .help(
+                    "The cost parameter for --kdf: `n` for scrypt, which must be a power of two, \
+                    or `c` for pbkdf2. Defaults to 262144.",
+                )
+                .requires(KDF_FLAG)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name(STDIN_INPUTS_FLAG)
+                .long(STDIN_INPUTS_FLAG)
+                .help("If present, read all user inputs from stdin instead of tty."),
+        )
+}
+
+/// The password that each keystore will be re-encrypted with.
+enum NewPassword {
+    Keep,
+    Random,
+    Fixed(PlainText),
+}
+
+/// Where the validator client finds the password for a keystore, in order of precedence.
+enum PasswordStorage {
+    Inline,
+    Source,
+    File(PathBuf),
+    None,
+}
+
+impl PasswordStorage {
+    fn from_definition(def: &ValidatorDefinition) -> Self {
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password,
+                voting_keystore_password_source,
+                voting_keystore_password_path,
+                ..
+            } => {
+                if voting_keystore_password.is_some() {
+                    PasswordStorage::Inline
+                } else if voting_keystore_password_source.is_some() {
+                    PasswordStorage::Source
+                } else if let Some(path) = voting_keystore_password_path {
+                    PasswordStorage::File(path.clone())
+                } else {
+                    PasswordStorage::None
+                }
+            }
+        }
+    }
+}
+
+/// A keystore which has been decrypted with its current password.
+struct Unlocked {
+    /// The index of the keystore's definition.
+    index: usize,
+    path: PathBuf,
+    storage: PasswordStorage,
+    keystore: Keystore,
+    keypair: Keypair,
+    password: PlainText,
+}
+
+pub fn cli_run(matches: &ArgMatches, validator_dir: PathBuf) -> Result<(), String> {
+    let pubkey: Option<PublicKey> = clap_utils::parse_optional(matches, PUBKEY_FLAG)?;
+    let new_password_file: Option<PathBuf> =
+        clap_utils::parse_optional(matches, NEW_PASSWORD_FILE_FLAG)?;
+    let kdf_function = matches.value_of(KDF_FLAG);
+    let kdf_cost: Option<u32> = clap_utils::parse_optional(matches, KDF_COST_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+
+    match (kdf_function, kdf_cost) {
+        (Some(SCRYPT), Some(n)) if n < 2 || !n.is_power_of_two() => {
+            return Err(format!(
+                "--{} must be a power of two greater than one for {}",
+                KDF_COST_FLAG, SCRYPT
+            ))
+        }
+        (Some(PBKDF2), Some(0)) => {
+            return Err(format!(
+                "--{} must be greater than zero for {}",
+                KDF_COST_FLAG, PBKDF2
+            ))
+        }
+        _ => (),
+    }
+
+    let new_password = if let Some(path) = new_password_file {
+        let password = read_password(&path)
+            .map_err(|e| format!("Unable to read --{}: {:?}", NEW_PASSWORD_FILE_FLAG, e))?;
+        is_password_sufficiently_complex(password.as_bytes())?;
+        NewPassword::Fixed(password)
+    } else if matches.is_present(RANDOM_PASSWORD_FLAG) {
+        NewPassword::Random
+    } else {
+        NewPassword::Keep
+    };
+
+    let mut defs = ValidatorDefinitions::open(&validator_dir)
+        .map_err(|e| format!("Unable to open {}: {:?}", CONFIG_FILENAME, e))?;
+
+    let indices = if let Some(pubkey) = pubkey {
+        let index = defs
+            .as_slice()
+            .iter()
+            .position(|def| def.voting_public_key == pubkey)
+            .ok_or_else(|| {
+                format!(
+                    "No validator with public key {} in {}",
+                    pubkey, CONFIG_FILENAME
+                )
+            })?;
+        vec![index]
+    } else {
+        (0..defs.as_slice().len()).collect()
+    };
+
+    // Check every keystore and its current password before modifying any of them, so that a bad
+    // flag, a wrong password or a running validator client does not leave the directory
+    // half-modified. Reading every password first also means that a password file shared by
+    // several keystores is read before it is replaced.
+    let mut password_files = HashSet::new();
+    let mut keystores = Vec::with_capacity(indices.len());
+    for &i in &indices {
+        let def = &defs.as_slice()[i];
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            voting_keystore_password,
+            voting_keystore_password_source,
+            ..
+        } = &def.signing_definition;
+        let storage = PasswordStorage::from_definition(def);
+
+        if with_extension_appended(voting_keystore_path, "lock").exists() {
+            return Err(format!(
+                "The keystore for {} is locked. Stop the validator client before modifying it.",
+                def.voting_public_key
+            ));
+        }
+
+        match (&new_password, &storage) {
+            (NewPassword::Keep, _) => (),
+            (_, PasswordStorage::Source) => {
+                return Err(format!(
+                    "The password for {} is read from a password source which cannot be \
+                     updated by this command. Change the password source manually.",
+                    def.voting_public_key
+                ))
+            }
+            (NewPassword::Random, PasswordStorage::None) => {
+                return Err(format!(
+                    "The password for {} is not stored, so a random password would be lost. \
+                     Use --{} instead.",
+                    def.voting_public_key, NEW_PASSWORD_FILE_FLAG
+                ))
+            }
+            (NewPassword::Random, PasswordStorage::File(path)) => {
+                if !password_files.insert(path.clone()) {
+                    return Err(format!(
+                        "The password file {:?} is shared by multiple keystores, so they cannot \
+                         be given different random passwords. Use --{} instead.",
+                        path, NEW_PASSWORD_FILE_FLAG
+                    ));
+                }
+            }
+            _ => (),
+        }
+
+        let keystore = Keystore::from_json_file(voting_keystore_path).map_err(|e| {
+            format!(
+                "Unable to read keystore JSON {:?}: {:?}",
+                voting_keystore_path, e
+            )
+        })?;
+
+        let current_password = match &storage {
+            PasswordStorage::Inline => voting_keystore_password
+                .as_ref()
+                .map(|password| PlainText::from(password.as_ref().to_vec())),
+            PasswordStorage::Source => voting_keystore_password_source
+                .as_ref()
+                .map(|source| source.read())
+                .transpose()
+                .map_err(|e| format!("Unable to read password from source: {:?}", e))?,
+            PasswordStorage::File(path) => Some(
+                read_password(path)
+                    .map_err(|e| format!("Unable to read password file {:?}: {:?}", path, e))?,
+            ),
+            PasswordStorage::None => None,
+        };
+
+        let (current_password, keypair) = match current_password {
+            Some(password) => {
+                let keypair = keystore.decrypt_keypair(password.as_bytes()).map_err(|e| {
+                    format!(
+                        "Unable to decrypt keystore {:?}: {:?}",
+                        voting_keystore_path, e
+                    )
+                })?;
+                (password, keypair)
+            }
+            None => loop {
+                eprintln!("");
+                eprintln!("Keystore for 0x{}:", keystore.pubkey());
+                eprintln!("{}", PASSWORD_PROMPT);
+
+                let password = read_password_from_user(stdin_inputs)?;
+
+                match keystore.decrypt_keypair(password.as_ref()) {
+                    Ok(keypair) => break (PlainText::from(password.as_ref().to_vec()), keypair),
+                    Err(eth2_keystore::Error::InvalidPassword) => {
+                        eprintln!("Invalid password");
+                    }
+                    Err(e) => return Err(format!("Error whilst decrypting keypair: {:?}", e)),
+                }
+            },
+        };
+
+        keystores.push(Unlocked {
+            index: i,
+            path: voting_keystore_path.clone(),
+            storage,
+            keystore,
+            keypair,
+            password: current_password,
+        });
+    }
+
+    // Re-encrypt every keystore, keeping its UUID, path and description, and write it to a
+    // staging file alongside the original. Nothing is replaced until every keystore has been
+    // staged, so a failure here leaves the directory untouched.
+    let mut staged = Vec::with_capacity(keystores.len());
+    for unlocked in keystores {
+        match stage_keystore(unlocked, &new_password, kdf_function, kdf_cost) {
+            Ok(keystore) => staged.push(keystore),
+            Err(e) => {
+                for keystore in &staged {
+                    let _ = fs::remove_file(&keystore.staged_path);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    let mut backups = Backups::new()?;
+    backups.backup(&validator_dir.join(CONFIG_FILENAME))?;
+
+    // Store every new password before any keystore is replaced, so that a random password is
+    // never lost. If the command is interrupted between the two steps, the keystores which were
+    // not yet replaced still use their old password, which is kept in the backup of the password
+    // file or validator definitions.
+    if !matches!(new_password, NewPassword::Keep) {
+        let mut defs_modified = false;
+        let mut written = HashSet::new();
+        for keystore in &staged {
+            match &keystore.storage {
+                PasswordStorage::Inline => {
+                    let password = String::from_utf8(keystore.password.as_bytes().to_vec())
+                        .map_err(|_| "Password is not valid UTF-8".to_string())?;
+                    let SigningDefinition::LocalKeystore {
+                        voting_keystore_password,
+                        ..
+                    } = &mut defs.as_mut_slice()[keystore.index].signing_definition;
+                    *voting_keystore_password = Some(ZeroizeString::from(password));
+                    defs_modified = true;
+                }
+                // A password file shared by several keystores is given the same password for
+                // each of them, so it only needs to be written once.
+                PasswordStorage::File(path) => {
+                    if written.insert(path.clone()) {
+                        backups.backup(path)?;
+                        write_with_600_perms_atomically(path, keystore.password.as_bytes())
+                            .map_err(|e| format!("Unable to write {:?}: {:?}", path, e))?;
+                    }
+                }
+                PasswordStorage::Source | PasswordStorage::None => (),
+            }
+        }
+
+        if defs_modified {
+            defs.save(&validator_dir)
+                .map_err(|e| format!("Unable to save {}: {:?}", CONFIG_FILENAME, e))?;
+        }
+    }
+
+    for keystore in &staged {
+        backups.backup(&keystore.path)?;
+        fs::rename(&keystore.staged_path, &keystore.path).map_err(|e| {
+            format!(
+                "Unable to replace {:?} with {:?}: {:?}",
+                keystore.path, keystore.staged_path, e
+            )
+        })?;
+
+        eprintln!("Successfully modified keystore for 0x{}", keystore.pubkey);
+    }
+
+    eprintln!("");
+    eprintln!("Successfully modified {} keystores.", indices.len());
+
+    Ok(())
+}
+
+/// A re-encrypted keystore which has been written to a staging file but not yet swapped in.
+struct Staged {
+    /// The index of the keystore's definition.
+    index: usize,
+    path: PathBuf,
+    staged_path: PathBuf,
+    storage: PasswordStorage,
+    pubkey: String,
+    /// The password the staged keystore is encrypted with.
+    password: PlainText,
+}
+
+/// Re-encrypts `unlocked` with its new password and KDF and writes it to a staging file.
+fn stage_keystore(
+    unlocked: Unlocked,
+    new_password: &NewPassword,
+    kdf_function: Option<&str>,
+    kdf_cost: Option<u32>,
+) -> Result<Staged, String> {
+    let Unlocked {
+        index,
+        path,
+        storage,
+        keystore,
+        keypair,
+        password: current_password,
+    } = unlocked;
+
+    let password = match new_password {
+        NewPassword::Keep => current_password,
+        NewPassword::Random => random_password(),
+        NewPassword::Fixed(password) => password.clone(),
+    };
+
+    let kdf = new_kdf(keystore.kdf(), kdf_function, kdf_cost)?;
+
+    let new_keystore = KeystoreBuilder::new(
+        &keypair,
+        password.as_bytes(),
+        keystore.path().unwrap_or_else(String::new),
+    )
+    .map_err(|e| format!("Unable to create keystore: {:?}", e))?
+    .kdf(kdf)
+    .uuid(*keystore.uuid())
+    .description(keystore.description().unwrap_or("").to_string())
+    .build()
+    .map_err(|e| format!("Unable to encrypt keystore: {:?}", e))?;
+
+    // Never replace a keystore with one that cannot be decrypted.
+    match new_keystore.decrypt_keypair(password.as_bytes()) {
+        Ok(decrypted) if decrypted.pk == keypair.pk => (),
+        _ => {
+            return Err(format!(
+                "Re-encrypted keystore for {:?} failed verification",
+                path
+            ))
+        }
+    }
+
+    let keystore_json = new_keystore
+        .to_json_string()
+        .map_err(|e| format!("Unable to encode keystore: {:?}", e))?;
+
+    let staged_path = with_extension_appended(&path, STAGED_EXTENSION);
+    create_with_600_perms(&staged_path, keystore_json.as_bytes())
+        .and_then(|()| File::open(&staged_path)?.sync_all())
+        .map_err(|e| {
+            let _ = fs::remove_file(&staged_path);
+            format!("Unable to write {:?}: {:?}", staged_path, e)
+        })?;
+
+    Ok(Staged {
+        index,
+        path,
+        staged_path,
+        storage,
+        pubkey: keystore.pubkey().to_string(),
+        password,
+    })
+}
+
+/// Returns the KDF for a re-encrypted keystore, with a fresh salt.
+///
+/// Retains the function and parameters of `current` unless `function` is specified.
+fn new_kdf(current: &Kdf, function: Option<&str>, cost: Option<u32>) -> Result<Kdf, String> {
+    let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>().to_vec();
+
+    let mut kdf = match function {
+        None => match current.clone() {
+            Kdf::Scrypt(scrypt) => Kdf::Scrypt(Scrypt {
+                salt: salt.into(),
+                ..scrypt
+            }),
+            Kdf::Pbkdf2(pbkdf2) => Kdf::Pbkdf2(Pbkdf2 {
+                salt: salt.into(),
+                ..pbkdf2
+            }),
+        },
+        Some(SCRYPT) => default_kdf(salt),
+        Some(PBKDF2) => Kdf::Pbkdf2(Pbkdf2 {
+            c: DEFAULT_PBKDF2_C,
+            dklen: DKLEN,
+            prf: Prf::HmacSha256,
+            salt: salt.into(),
+        }),
+        Some(other) => return Err(format!("Unknown --{}: {}", KDF_FLAG, other)),
+    };
+
+    if let Some(cost) = cost {
+        match &mut kdf {
+            Kdf::Scrypt(scrypt) => scrypt.n = cost,
+            Kdf::Pbkdf2(pbkdf2) => pbkdf2.c = cost,
+        }
+    }
+
+    Ok(kdf)
+}
+
+/// Backs up each file at most once per run, never replacing an existing backup.
+struct Backups {
+    /// Distinguishes this run's backups from any left by a previous run.
+    timestamp: u64,
+    backed_up: HashSet<PathBuf>,
+}
+
+impl Backups {
+    fn new() -> Result<Self, String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to read system time: {:?}", e))?
+            .as_secs();
+
+        Ok(Self {
+            timestamp,
+            backed_up: HashSet::new(),
+        })
+    }
+
+    /// Copies `path` to the same path with `BACKUP_EXTENSION` appended, unless it has already been
+    /// backed up by this run. If that backup already exists then the run's timestamp is inserted
+    /// before the extension instead (e.g., `voting-keystore.json.1608000000.bak`).
+    fn backup(&mut self, path: &Path) -> Result<(), String> {
+        if self.backed_up.contains(path) {
+            return Ok(());
+        }
+
+        let mut backup_path = with_extension_appended(path, BACKUP_EXTENSION);
+        if backup_path.exists() {
+            backup_path = with_extension_appended(
+                &with_extension_appended(path, &self.timestamp.to_string()),
+                BACKUP_EXTENSION,
+            );
+        }
+        if backup_path.exists() {
+            return Err(format!(
+                "Unable to back up {:?}, {:?} already exists",
+                path, backup_path
+            ));
+        }
+
+        fs::copy(path, &backup_path)
+            .map_err(|e| format!("Unable to back up {:?} to {:?}: {:?}", path, backup_path, e))?;
+        self.backed_up.insert(path.to_path_buf());
+
+        Ok(())
+    }
+}
+
+fn with_extension_appended(path: &Path, extension: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(extension);
+    path.with_file_name(file_name)
+}
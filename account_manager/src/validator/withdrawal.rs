@@ -0,0 +1,426 @@
+use super::create::{
+    read_wallet_password_from_cli, COUNT_FLAG, WALLET_NAME_FLAG, WALLET_PASSWORD_FLAG,
+};
+use super::deposit_data::{read_new_keystore_password, read_password_file, KEYSTORE_PASSWORD_FLAG};
+use super::recover::FIRST_INDEX_FLAG;
+use crate::common::read_wallet_name_from_cli;
+use crate::wallet::create::STDIN_INPUTS_FLAG;
+use crate::{VALIDATOR_DIR_FLAG, WALLETS_DIR_FLAG};
+use account_utils::eth2_keystore::{keypair_from_secret, Keystore, KeystoreBuilder};
+use bls::get_withdrawal_credentials;
+use clap::{App, Arg, ArgMatches};
+use directory::{ensure_dir_exists, parse_path_or_default_with_flag, DEFAULT_WALLET_DIR};
+use environment::Environment;
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, Url,
+};
+use eth2_wallet::{recover_validator_secret, KeyType};
+use eth2_wallet_manager::WalletManager;
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{ChainSpec, EthSpec, Hash256, PublicKeyBytes};
+use validator_dir::{Manager as ValidatorManager, VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE};
+
+pub const CMD: &str = "withdrawal";
+pub const DERIVE_CMD: &str = "derive";
+pub const STATUS_CMD: &str = "status";
+pub const CHECK_CMD: &str = "check";
+pub const OUTPUT_DIR_FLAG: &str = "output-dir";
+pub const BEACON_NODE_FLAG: &str = "beacon-node";
+pub const KEYSTORE_FLAG: &str = "keystore";
+pub const VALIDATOR_FLAG: &str = "validator";
+
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    let beacon_node_arg = || {
+        Arg::with_name(BEACON_NODE_FLAG)
+            .long(BEACON_NODE_FLAG)
+            .value_name("NETWORK_ADDRESS")
+            .help("Address to a beacon node HTTP API")
+            .default_value(DEFAULT_BEACON_NODE)
+            .takes_value(true)
+    };
+
+    App::new(CMD)
+        .about(
+            "Provides commands for managing the BLS withdrawal keys of validators and checking \
+            them against the withdrawal credentials that are recorded on-chain.",
+        )
+        .subcommand(
+            App::new(DERIVE_CMD)
+                .about(
+                    "Derives withdrawal keys from an EIP-2386 wallet using the EIP-2334 path \
+                    m/12381/3600/i/0, printing the withdrawal credentials for each index and \
+                    optionally writing an EIP-2335 keystore for each.",
+                )
+                .arg(
+                    Arg::with_name(WALLET_NAME_FLAG)
+                        .long(WALLET_NAME_FLAG)
+                        .value_name("WALLET_NAME")
+                        .help("Use the wallet identified by this name")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(WALLET_PASSWORD_FLAG)
+                        .long(WALLET_PASSWORD_FLAG)
+                        .value_name("WALLET_PASSWORD_PATH")
+                        .help(
+                            "A path to a file containing the password which will unlock the \
+                            wallet.",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(WALLETS_DIR_FLAG)
+                        .long(WALLETS_DIR_FLAG)
+                        .value_name(WALLETS_DIR_FLAG)
+                        .help(
+                            "A path containing Eth2 EIP-2386 wallets. Defaults to \
+                            ~/.lighthouse/{testnet}/wallets",
+                        )
+                        .takes_value(true)
+                        .conflicts_with("datadir"),
+                )
+                .arg(
+                    Arg::with_name(FIRST_INDEX_FLAG)
+                        .long(FIRST_INDEX_FLAG)
+                        .value_name("FIRST_INDEX")
+                        .help("The EIP-2334 index of the first validator.")
+                        .takes_value(true)
+                        .default_value("0"),
+                )
+                .arg(
+                    Arg::with_name(COUNT_FLAG)
+                        .long(COUNT_FLAG)
+                        .value_name("COUNT")
+                        .help("The number of consecutive withdrawal keys to derive.")
+                        .takes_value(true)
+                        .default_value("1"),
+                )
+                .arg(
+                    Arg::with_name(OUTPUT_DIR_FLAG)
+                        .long(OUTPUT_DIR_FLAG)
+                        .value_name("OUTPUT_DIR")
+                        .help(
+                            "If present, an EIP-2335 keystore for each withdrawal key will be \
+                            written to this directory.",
+                        )
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(KEYSTORE_PASSWORD_FLAG)
+                        .long(KEYSTORE_PASSWORD_FLAG)
+                        .value_name("KEYSTORE_PASSWORD_PATH")
+                        .help(
+                            "A path to a file containing the password for the new keystores. \
+                            If absent, the password will be requested interactively.",
+                        )
+                        .requires(OUTPUT_DIR_FLAG)
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name(STDIN_INPUTS_FLAG)
+                        .long(STDIN_INPUTS_FLAG)
+                        .help("If present, read all user inputs from stdin instead of tty."),
+                ),
+        )
+        .subcommand(
+            App::new(STATUS_CMD)
+                .about(
+                    "Queries a beacon node for the on-chain withdrawal credentials of each \
+                    validator in --validator-dir and compares them to the withdrawal keystore \
+                    stored alongside the validator, if any.",
+                )
+                .arg(beacon_node_arg()),
+        )
+        .subcommand(
+            App::new(CHECK_CMD)
+                .about(
+                    "Checks that a withdrawal keystore matches the on-chain withdrawal \
+                    credentials of a validator.",
+                )
+                .arg(
+                    Arg::with_name(KEYSTORE_FLAG)
+                        .long(KEYSTORE_FLAG)
+                        .value_name("KEYSTORE_PATH")
+                        .help("Path to the EIP-2335 withdrawal keystore.")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name(VALIDATOR_FLAG)
+                        .long(VALIDATOR_FLAG)
+                        .value_name("VALIDATOR_ID")
+                        .help(
+                            "The validator to check, either as a 0x-prefixed voting public key \
+                            or a validator index.",
+                        )
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name(KEYSTORE_PASSWORD_FLAG)
+                        .long(KEYSTORE_PASSWORD_FLAG)
+                        .value_name("KEYSTORE_PASSWORD_PATH")
+                        .help(
+                            "If present, the keystore will be decrypted with the password in \
+                            this file to ensure that the secret key is available.",
+                        )
+                        .takes_value(true),
+                )
+                .arg(beacon_node_arg()),
+        )
+}
+
+pub fn cli_run<T: EthSpec>(
+    matches: &ArgMatches,
+    mut env: Environment<T>,
+    validator_dir: PathBuf,
+) -> Result<(), String> {
+    let spec = env.core_context().eth2_config.spec;
+
+    match matches.subcommand() {
+        (DERIVE_CMD, Some(matches)) => derive(matches, &spec),
+        (STATUS_CMD, Some(matches)) => {
+            let client = beacon_node_client(matches)?;
+            env.runtime()
+                .block_on(status(&client, &validator_dir, &spec))
+        }
+        (CHECK_CMD, Some(matches)) => {
+            let client = beacon_node_client(matches)?;
+            env.runtime().block_on(check(matches, &client, &spec))
+        }
+        ("", _) => Err("No subcommand provided, see --help for options".to_string()),
+        (command, _) => Err(format!("No such subcommand `{}`", command)),
+    }
+}
+
+/// Returns the BLS withdrawal credentials for the public key in `keystore`.
+fn keystore_withdrawal_credentials(
+    keystore: &Keystore,
+    spec: &ChainSpec,
+) -> Result<Hash256, String> {
+    let pubkey = keystore
+        .public_key()
+        .ok_or_else(|| format!("Keystore public key is invalid: {}", keystore.pubkey()))?;
+
+    Ok(Hash256::from_slice(&get_withdrawal_credentials(
+        &pubkey,
+        spec.bls_withdrawal_prefix_byte,
+    )))
+}
+
+fn beacon_node_client(matches: &ArgMatches) -> Result<BeaconNodeHttpClient, String> {
+    let url: Url = clap_utils::parse_required(matches, BEACON_NODE_FLAG)?;
+    Ok(BeaconNodeHttpClient::new(url))
+}
+
+/// Returns the withdrawal credentials recorded in the head state of the beacon node, or `None` if
+/// the validator is not known to the beacon node.
+async fn on_chain_withdrawal_credentials(
+    client: &BeaconNodeHttpClient,
+    validator_id: &ValidatorId,
+) -> Result<Option<(u64, Hash256)>, String> {
+    let response = client
+        .get_beacon_states_validator_id(StateId::Head, validator_id)
+        .await
+        .map_err(|e| format!("Unable to query validator {}: {:?}", validator_id, e))?;
+
+    Ok(response.map(|response| {
+        (
+            response.data.index,
+            response.data.validator.withdrawal_credentials,
+        )
+    }))
+}
+
+fn derive(matches: &ArgMatches, spec: &ChainSpec) -> Result<(), String> {
+    let name: Option<String> = clap_utils::parse_optional(matches, WALLET_NAME_FLAG)?;
+    let wallet_password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, WALLET_PASSWORD_FLAG)?;
+    let first_index: u32 = clap_utils::parse_required(matches, FIRST_INDEX_FLAG)?;
+    let count: u32 = clap_utils::parse_required(matches, COUNT_FLAG)?;
+    let output_dir: Option<PathBuf> = clap_utils::parse_optional(matches, OUTPUT_DIR_FLAG)?;
+    let keystore_password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, KEYSTORE_PASSWORD_FLAG)?;
+    let stdin_inputs = matches.is_present(STDIN_INPUTS_FLAG);
+    let wallet_base_dir = if matches.value_of("datadir").is_some() {
+        let path: PathBuf = clap_utils::parse_required(matches, "datadir")?;
+        path.join(DEFAULT_WALLET_DIR)
+    } else {
+        parse_path_or_default_with_flag(matches, WALLETS_DIR_FLAG, DEFAULT_WALLET_DIR)?
+    };
+
+    let last_index = first_index
+        .checked_add(count)
+        .ok_or_else(|| format!("--{} is too large", COUNT_FLAG))?;
+
+    let wallet_name = read_wallet_name_from_cli(name, stdin_inputs)?;
+    let wallet_password = read_wallet_password_from_cli(wallet_password_path, stdin_inputs)?;
+
+    let mgr = WalletManager::open(&wallet_base_dir)
+        .map_err(|e| format!("Unable to open --{}: {:?}", WALLETS_DIR_FLAG, e))?;
+    let wallet = mgr
+        .wallet_by_name(&wallet_name)
+        .map_err(|e| format!("Unable to open wallet: {:?}", e))?;
+
+    let keystore_password = output_dir
+        .as_ref()
+        .map(|output_dir| {
+            ensure_dir_exists(output_dir)?;
+            read_new_keystore_password(keystore_password_path, stdin_inputs)
+        })
+        .transpose()?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("Unable to read system time: {:?}", e))?
+        .as_secs();
+
+    for index in first_index..last_index {
+        let (secret, path) = recover_validator_secret(
+            wallet.wallet(),
+            wallet_password.as_bytes(),
+            index,
+            KeyType::Withdrawal,
+        )
+        .map_err(|e| format!("Unable to derive withdrawal key: {:?}", e))?;
+        let keypair = keypair_from_secret(secret.as_bytes())
+            .map_err(|e| format!("Unable to derive withdrawal key: {:?}", e))?;
+
+        let withdrawal_credentials =
+            get_withdrawal_credentials(&keypair.pk, spec.bls_withdrawal_prefix_byte);
+
+        if let (Some(output_dir), Some(password)) = (&output_dir, &keystore_password) {
+            let keystore = KeystoreBuilder::new(&keypair, password.as_bytes(), path.to_string())
+                .and_then(|builder| builder.build())
+                .map_err(|e| format!("Unable to build keystore: {:?}", e))?;
+
+            // Unlike voting keystores, the path has four levels and therefore will not be
+            // mistaken for a voting keystore by `lighthouse account validator import`.
+            let keystore_path = output_dir.join(format!(
+                "keystore-{}-{}.json",
+                path.to_string().replace("/", "_"),
+                timestamp
+            ));
+            OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&keystore_path)
+                .map_err(|e| format!("Unable to create {:?}: {:?}", keystore_path, e))
+                .and_then(|file| {
+                    keystore
+                        .to_json_writer(file)
+                        .map_err(|e| format!("Unable to write {:?}: {:?}", keystore_path, e))
+                })?;
+        }
+
+        println!(
+            "Index: {}\t{}\t{}\t0x{}",
+            index,
+            path,
+            keypair.pk,
+            hex::encode(withdrawal_credentials)
+        );
+    }
+
+    Ok(())
+}
+
+async fn status(
+    client: &BeaconNodeHttpClient,
+    validator_dir: &Path,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    let mgr = ValidatorManager::open(validator_dir)
+        .map_err(|e| format!("Unable to read --{}: {:?}", VALIDATOR_DIR_FLAG, e))?;
+
+    let mut mismatches = 0;
+
+    for (name, path) in mgr
+        .directory_names()
+        .map_err(|e| format!("Unable to list validators: {:?}", e))?
+    {
+        let voting_keystore_path = path.join(VOTING_KEYSTORE_FILE);
+        let voting_pubkey = Keystore::from_json_file(&voting_keystore_path)
+            .map_err(|e| format!("Unable to read {:?}: {:?}", voting_keystore_path, e))?
+            .public_key()
+            .ok_or_else(|| format!("Invalid public key in {:?}", voting_keystore_path))?;
+        let validator_id = ValidatorId::PublicKey(PublicKeyBytes::from(&voting_pubkey));
+
+        let withdrawal_keystore_path = path.join(WITHDRAWAL_KEYSTORE_FILE);
+        let local_credentials = if withdrawal_keystore_path.exists() {
+            Keystore::from_json_file(&withdrawal_keystore_path)
+                .map_err(|e| format!("Unable to read {:?}: {:?}", withdrawal_keystore_path, e))
+                .and_then(|keystore| keystore_withdrawal_credentials(&keystore, spec))
+                .map(Some)?
+        } else {
+            None
+        };
+
+        match on_chain_withdrawal_credentials(client, &validator_id).await? {
+            Some((index, on_chain)) => {
+                let comparison = match local_credentials {
+                    Some(local) if local == on_chain => "match",
+                    Some(_) => {
+                        mismatches += 1;
+                        "MISMATCH"
+                    }
+                    None => "no withdrawal keystore",
+                };
+                println!("{}\tIndex: {}\t{:?}\t{}", name, index, on_chain, comparison);
+            }
+            None => println!("{}\tnot found on-chain", name),
+        }
+    }
+
+    if mismatches == 0 {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} validators have a withdrawal keystore which does not match their on-chain \
+            withdrawal credentials",
+            mismatches
+        ))
+    }
+}
+
+async fn check(
+    matches: &ArgMatches<'_>,
+    client: &BeaconNodeHttpClient,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
+    let validator_id: ValidatorId = clap_utils::parse_required(matches, VALIDATOR_FLAG)?;
+    let password_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, KEYSTORE_PASSWORD_FLAG)?;
+
+    let keystore = Keystore::from_json_file(&keystore_path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", keystore_path, e))?;
+    let local = keystore_withdrawal_credentials(&keystore, spec)?;
+
+    if let Some(password_path) = password_path {
+        let password = read_password_file(&password_path)?;
+        keystore
+            .decrypt_keypair(password.as_bytes())
+            .map_err(|e| format!("Unable to decrypt {:?}: {:?}", keystore_path, e))?;
+    }
+
+    let (index, on_chain) = on_chain_withdrawal_credentials(client, &validator_id)
+        .await?
+        .ok_or_else(|| format!("Validator {} is not known to the beacon node", validator_id))?;
+
+    if local == on_chain {
+        println!("Index: {}\t{:?}\tmatch", index, on_chain);
+        Ok(())
+    } else {
+        Err(format!(
+            "Keystore withdrawal credentials {:?} do not match the on-chain withdrawal \
+            credentials {:?} of validator {}",
+            local, on_chain, index
+        ))
+    }
+}
@@ -25,6 +25,12 @@
 //!                                ▼
 //!                  impl SignatureVerifiedAttestation
 //! ```
+//!
+//! Internally, each type first passes through an "indexed" stage where all checks *except*
+//! signature verification have been performed. This allows the
+//! `batch_verify_unaggregated_attestations` and `batch_verify_aggregated_attestations` functions to
+//! verify the signatures of many attestations with a single call to `bls::verify_signature_sets`,
+//! falling back to verifying each signature individually if the batch is invalid.
 
 use crate::{
     beacon_chain::{
@@ -33,9 +39,10 @@ use crate::{
     metrics,
     observed_attestations::ObserveOutcome,
     observed_attesters::Error as ObservedAttestersError,
+    validator_pubkey_cache::ValidatorPubkeyCache,
     BeaconChain, BeaconChainError, BeaconChainTypes,
 };
-use bls::verify_signature_sets;
+use bls::{verify_signature_sets, SignatureSet};
 use proto_array::Block as ProtoBlock;
 use slot_clock::SlotClock;
 use state_processing::{
@@ -49,8 +56,8 @@ use state_processing::{
 use std::borrow::Cow;
 use tree_hash::TreeHash;
 use types::{
-    Attestation, BeaconCommittee, CommitteeIndex, Epoch, EthSpec, Hash256, IndexedAttestation,
-    SelectionProof, SignedAggregateAndProof, Slot, SubnetId,
+    Attestation, BeaconCommittee, CommitteeIndex, Epoch, EthSpec, Fork, Hash256,
+    IndexedAttestation, SelectionProof, SignedAggregateAndProof, Slot, SubnetId,
 };
 
 /// Returned when an attestation was not successfully verified. It might not have been verified for
@@ -267,6 +274,30 @@ pub struct VerifiedUnaggregatedAttestation<T: BeaconChainTypes> {
     subnet_id: SubnetId,
 }
 
+/// Wraps a `SignedAggregateAndProof` that has passed all gossip checks except for signature
+/// verification.
+struct IndexedAggregatedAttestation<T: BeaconChainTypes> {
+    signed_aggregate: SignedAggregateAndProof<T::EthSpec>,
+    indexed_attestation: IndexedAttestation<T::EthSpec>,
+    attestation_root: Hash256,
+}
+
+/// Wraps an `Attestation` that has passed all gossip checks except for signature verification.
+struct IndexedUnaggregatedAttestation<T: BeaconChainTypes> {
+    attestation: Attestation<T::EthSpec>,
+    indexed_attestation: IndexedAttestation<T::EthSpec>,
+    subnet_id: SubnetId,
+    validator_index: u64,
+}
+
+/// Indicates if the signatures of an attestation still need to be verified when progressing it
+/// from the "indexed" stage to the "verified" stage.
+#[derive(Clone, Copy, PartialEq)]
+enum CheckAttestationSignature {
+    Yes,
+    No,
+}
+
 /// Custom `Clone` implementation is to avoid the restrictive trait bounds applied by the usual derive
 /// macro.
 impl<T: BeaconChainTypes> Clone for VerifiedUnaggregatedAttestation<T> {
@@ -297,10 +328,10 @@ impl<T: BeaconChainTypes> SignatureVerifiedAttestation<T> for VerifiedUnaggregat
     }
 }
 
-impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
-    /// Returns `Ok(Self)` if the `signed_aggregate` is valid to be (re)published on the gossip
-    /// network.
-    pub fn verify(
+impl<T: BeaconChainTypes> IndexedAggregatedAttestation<T> {
+    /// Returns `Ok(Self)` if the `signed_aggregate` passes all gossip checks, excluding signature
+    /// verification.
+    fn verify(
         signed_aggregate: SignedAggregateAndProof<T::EthSpec>,
         chain: &BeaconChain<T>,
     ) -> Result<Self, Error> {
@@ -389,11 +420,50 @@ impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
                     .map_err(|e| BeaconChainError::from(e).into())
             })?;
 
+        Ok(IndexedAggregatedAttestation {
+            signed_aggregate,
+            indexed_attestation,
+            attestation_root,
+        })
+    }
+}
+
+impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
+    /// Returns `Ok(Self)` if the `signed_aggregate` is valid to be (re)published on the gossip
+    /// network.
+    pub fn verify(
+        signed_aggregate: SignedAggregateAndProof<T::EthSpec>,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, Error> {
+        let indexed = IndexedAggregatedAttestation::verify(signed_aggregate, chain)?;
+        Self::from_indexed(indexed, chain, CheckAttestationSignature::Yes)
+    }
+
+    /// Completes verification of an `IndexedAggregatedAttestation`, verifying its signatures only
+    /// if `check_signature == CheckAttestationSignature::Yes`.
+    fn from_indexed(
+        indexed: IndexedAggregatedAttestation<T>,
+        chain: &BeaconChain<T>,
+        check_signature: CheckAttestationSignature,
+    ) -> Result<Self, Error> {
+        let IndexedAggregatedAttestation {
+            signed_aggregate,
+            indexed_attestation,
+            attestation_root,
+        } = indexed;
+
         // Ensure that all signatures are valid.
-        if !verify_signed_aggregate_signatures(chain, &signed_aggregate, &indexed_attestation)? {
+        if check_signature == CheckAttestationSignature::Yes
+            && !verify_signed_aggregate_signatures(chain, &signed_aggregate, &indexed_attestation)?
+        {
             return Err(Error::InvalidSignature);
         }
 
+        send_to_slasher(chain, &indexed_attestation);
+
+        let attestation = &signed_aggregate.message.aggregate;
+        let aggregator_index = signed_aggregate.message.aggregator_index;
+
         // Observe the valid attestation so we do not re-process it.
         //
         // It's important to double check that the attestation is not already known, otherwise two
@@ -443,13 +513,13 @@ impl<T: BeaconChainTypes> VerifiedAggregatedAttestation<T> {
     }
 }
 
-impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
-    /// Returns `Ok(Self)` if the `attestation` is valid to be (re)published on the gossip
-    /// network.
+impl<T: BeaconChainTypes> IndexedUnaggregatedAttestation<T> {
+    /// Returns `Ok(Self)` if the `attestation` passes all gossip checks, excluding signature
+    /// verification.
     ///
     /// `subnet_id` is the subnet from which we received this attestation. This function will
     /// verify that it was received on the correct subnet.
-    pub fn verify(
+    fn verify(
         attestation: Attestation<T::EthSpec>,
         subnet_id: Option<SubnetId>,
         chain: &BeaconChain<T>,
@@ -521,14 +591,63 @@ impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
             .validator_has_been_observed(&attestation, validator_index as usize)
             .map_err(BeaconChainError::from)?
         {
+            // A second attestation from this validator in the same epoch might be a double vote,
+            // so the slasher should still see it if it is correctly signed.
+            if chain.slasher.is_some()
+                && verify_attestation_signature(chain, &indexed_attestation).is_ok()
+            {
+                send_to_slasher(chain, &indexed_attestation);
+            }
             return Err(Error::PriorAttestationKnown {
                 validator_index,
                 epoch: attestation.data.target.epoch,
             });
         }
 
+        Ok(Self {
+            attestation,
+            indexed_attestation,
+            subnet_id: expected_subnet_id,
+            validator_index,
+        })
+    }
+}
+
+impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
+    /// Returns `Ok(Self)` if the `attestation` is valid to be (re)published on the gossip
+    /// network.
+    ///
+    /// `subnet_id` is the subnet from which we received this attestation. This function will
+    /// verify that it was received on the correct subnet.
+    pub fn verify(
+        attestation: Attestation<T::EthSpec>,
+        subnet_id: Option<SubnetId>,
+        chain: &BeaconChain<T>,
+    ) -> Result<Self, Error> {
+        let indexed = IndexedUnaggregatedAttestation::verify(attestation, subnet_id, chain)?;
+        Self::from_indexed(indexed, chain, CheckAttestationSignature::Yes)
+    }
+
+    /// Completes verification of an `IndexedUnaggregatedAttestation`, verifying its signature
+    /// only if `check_signature == CheckAttestationSignature::Yes`.
+    fn from_indexed(
+        indexed: IndexedUnaggregatedAttestation<T>,
+        chain: &BeaconChain<T>,
+        check_signature: CheckAttestationSignature,
+    ) -> Result<Self, Error> {
+        let IndexedUnaggregatedAttestation {
+            attestation,
+            indexed_attestation,
+            subnet_id,
+            validator_index,
+        } = indexed;
+
         // The aggregate signature of the attestation is valid.
-        verify_attestation_signature(chain, &indexed_attestation)?;
+        if check_signature == CheckAttestationSignature::Yes {
+            verify_attestation_signature(chain, &indexed_attestation)?;
+        }
+
+        send_to_slasher(chain, &indexed_attestation);
 
         // Now that the attestation has been fully verified, store that we have received a valid
         // attestation from this validator.
@@ -550,7 +669,7 @@ impl<T: BeaconChainTypes> VerifiedUnaggregatedAttestation<T> {
         Ok(Self {
             attestation,
             indexed_attestation,
-            subnet_id: expected_subnet_id,
+            subnet_id,
         })
     }
 
@@ -695,6 +814,18 @@ pub fn verify_attestation_signature<T: BeaconChainTypes>(
     }
 }
 
+/// Sends `indexed_attestation` to the slasher, if one is enabled.
+///
+/// The signature of `indexed_attestation` must already have been verified.
+fn send_to_slasher<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    indexed_attestation: &IndexedAttestation<T::EthSpec>,
+) {
+    if let Some(slasher) = chain.slasher.as_ref() {
+        slasher.accept_attestation(indexed_attestation.clone());
+    }
+}
+
 /// Verifies that the `attestation.data.target.root` is indeed the target root of the block at
 /// `attestation.data.beacon_block_root`.
 pub fn verify_attestation_target_root<T: EthSpec>(
@@ -768,22 +899,42 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
         .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
         .ok_or_else(|| BeaconChainError::ValidatorPubkeyCacheLockTimeout)?;
 
-    let aggregator_index = signed_aggregate.message.aggregator_index;
-    if aggregator_index >= pubkey_cache.len() as u64 {
-        return Err(Error::AggregatorPubkeyUnknown(aggregator_index));
-    }
-
     let fork = chain
         .canonical_head
         .try_read_for(HEAD_LOCK_TIMEOUT)
         .ok_or_else(|| BeaconChainError::CanonicalHeadLockTimeout)
         .map(|head| head.beacon_state.fork)?;
 
-    let signature_sets = vec![
+    let signature_sets = signed_aggregate_signature_sets(
+        chain,
+        &pubkey_cache,
+        &fork,
+        signed_aggregate,
+        indexed_attestation,
+    )?;
+
+    Ok(verify_signature_sets(signature_sets.iter()))
+}
+
+/// Returns the three signature sets of a `SignedAggregateAndProof`, as described in
+/// `verify_signed_aggregate_signatures`.
+fn signed_aggregate_signature_sets<'a, T: BeaconChainTypes>(
+    chain: &'a BeaconChain<T>,
+    pubkey_cache: &'a ValidatorPubkeyCache,
+    fork: &Fork,
+    signed_aggregate: &'a SignedAggregateAndProof<T::EthSpec>,
+    indexed_attestation: &IndexedAttestation<T::EthSpec>,
+) -> Result<Vec<SignatureSet<'a>>, Error> {
+    let aggregator_index = signed_aggregate.message.aggregator_index;
+    if aggregator_index >= pubkey_cache.len() as u64 {
+        return Err(Error::AggregatorPubkeyUnknown(aggregator_index));
+    }
+
+    Ok(vec![
         signed_aggregate_selection_proof_signature_set(
             |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
             &signed_aggregate,
-            &fork,
+            fork,
             chain.genesis_validators_root,
             &chain.spec,
         )
@@ -791,23 +942,196 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
         signed_aggregate_signature_set(
             |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
             &signed_aggregate,
-            &fork,
+            fork,
             chain.genesis_validators_root,
             &chain.spec,
         )
         .map_err(BeaconChainError::SignatureSetError)?,
         indexed_attestation_signature_set_from_pubkeys(
             |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
-            &indexed_attestation.signature,
+            &signed_aggregate.message.aggregate.signature,
             &indexed_attestation,
-            &fork,
+            fork,
             chain.genesis_validators_root,
             &chain.spec,
         )
         .map_err(BeaconChainError::SignatureSetError)?,
-    ];
+    ])
+}
 
-    Ok(verify_signature_sets(signature_sets.iter()))
+/// Verifies a batch of unaggregated attestations received on gossip, verifying all of their
+/// signatures with a single call to `bls::verify_signature_sets`.
+///
+/// Each attestation receives its own result, in the same order as `attestations`. If the batch
+/// of signatures is invalid then each signature is verified individually so that the valid
+/// attestations can still be accepted.
+///
+/// An `Err` is only returned if the batch could not be verified at all (e.g., a lock timeout).
+pub fn batch_verify_unaggregated_attestations<T, I>(
+    attestations: I,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<Result<VerifiedUnaggregatedAttestation<T>, Error>>, Error>
+where
+    T: BeaconChainTypes,
+    I: Iterator<Item = (Attestation<T::EthSpec>, Option<SubnetId>)>,
+{
+    let mut indexed_results = attestations
+        .map(|(attestation, subnet_id)| {
+            IndexedUnaggregatedAttestation::verify(attestation, subnet_id, chain)
+        })
+        .collect::<Vec<_>>();
+
+    let signature_setup_timer =
+        metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_SIGNATURE_SETUP_TIMES);
+
+    let pubkey_cache = chain
+        .validator_pubkey_cache
+        .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+        .ok_or_else(|| BeaconChainError::ValidatorPubkeyCacheLockTimeout)?;
+
+    let fork = chain
+        .canonical_head
+        .try_read_for(HEAD_LOCK_TIMEOUT)
+        .ok_or_else(|| BeaconChainError::CanonicalHeadLockTimeout)
+        .map(|head| head.beacon_state.fork)?;
+
+    let mut signature_sets = Vec::with_capacity(indexed_results.len());
+    let mut signature_set_errors = vec![];
+
+    for (i, indexed) in indexed_results.iter().enumerate() {
+        if let Ok(indexed) = indexed {
+            match indexed_attestation_signature_set_from_pubkeys(
+                |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
+                &indexed.attestation.signature,
+                &indexed.indexed_attestation,
+                &fork,
+                chain.genesis_validators_root,
+                &chain.spec,
+            ) {
+                Ok(signature_set) => signature_sets.push(signature_set),
+                Err(e) => signature_set_errors.push((i, BeaconChainError::SignatureSetError(e))),
+            }
+        }
+    }
+
+    metrics::stop_timer(signature_setup_timer);
+
+    let batch_is_valid = verify_signature_set_batch(&signature_sets);
+
+    drop(signature_sets);
+    drop(pubkey_cache);
+
+    for (i, e) in signature_set_errors {
+        indexed_results[i] = Err(e.into());
+    }
+
+    let check_signature = if batch_is_valid {
+        CheckAttestationSignature::No
+    } else {
+        CheckAttestationSignature::Yes
+    };
+
+    Ok(indexed_results
+        .into_iter()
+        .map(|indexed| {
+            indexed.and_then(|indexed| {
+                VerifiedUnaggregatedAttestation::from_indexed(indexed, chain, check_signature)
+            })
+        })
+        .collect())
+}
+
+/// Verifies a batch of `SignedAggregateAndProof` received on gossip, verifying all of their
+/// signatures with a single call to `bls::verify_signature_sets`.
+///
+/// See `batch_verify_unaggregated_attestations` for a description of the return value.
+pub fn batch_verify_aggregated_attestations<T, I>(
+    signed_aggregates: I,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<Result<VerifiedAggregatedAttestation<T>, Error>>, Error>
+where
+    T: BeaconChainTypes,
+    I: Iterator<Item = SignedAggregateAndProof<T::EthSpec>>,
+{
+    let mut indexed_results = signed_aggregates
+        .map(|signed_aggregate| IndexedAggregatedAttestation::verify(signed_aggregate, chain))
+        .collect::<Vec<_>>();
+
+    let signature_setup_timer =
+        metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_SIGNATURE_SETUP_TIMES);
+
+    let pubkey_cache = chain
+        .validator_pubkey_cache
+        .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+        .ok_or_else(|| BeaconChainError::ValidatorPubkeyCacheLockTimeout)?;
+
+    let fork = chain
+        .canonical_head
+        .try_read_for(HEAD_LOCK_TIMEOUT)
+        .ok_or_else(|| BeaconChainError::CanonicalHeadLockTimeout)
+        .map(|head| head.beacon_state.fork)?;
+
+    // Each aggregate has three signatures.
+    let mut signature_sets = Vec::with_capacity(indexed_results.len() * 3);
+    let mut signature_set_errors = vec![];
+
+    for (i, indexed) in indexed_results.iter().enumerate() {
+        if let Ok(indexed) = indexed {
+            match signed_aggregate_signature_sets(
+                chain,
+                &pubkey_cache,
+                &fork,
+                &indexed.signed_aggregate,
+                &indexed.indexed_attestation,
+            ) {
+                Ok(sets) => signature_sets.extend(sets),
+                Err(e) => signature_set_errors.push((i, e)),
+            }
+        }
+    }
+
+    metrics::stop_timer(signature_setup_timer);
+
+    let batch_is_valid = verify_signature_set_batch(&signature_sets);
+
+    drop(signature_sets);
+    drop(pubkey_cache);
+
+    for (i, e) in signature_set_errors {
+        indexed_results[i] = Err(e);
+    }
+
+    let check_signature = if batch_is_valid {
+        CheckAttestationSignature::No
+    } else {
+        CheckAttestationSignature::Yes
+    };
+
+    Ok(indexed_results
+        .into_iter()
+        .map(|indexed| {
+            indexed.and_then(|indexed| {
+                VerifiedAggregatedAttestation::from_indexed(indexed, chain, check_signature)
+            })
+        })
+        .collect())
+}
+
+/// Verifies `signature_sets` as a single batch, returning `true` if all signatures are valid.
+///
+/// An empty batch is considered invalid, which is harmless since there are no attestations which
+/// would skip signature verification.
+fn verify_signature_set_batch(signature_sets: &[SignatureSet]) -> bool {
+    let _signature_verification_timer =
+        metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_SIGNATURE_TIMES);
+
+    let is_valid = verify_signature_sets(signature_sets.iter());
+
+    if !is_valid && !signature_sets.is_empty() {
+        metrics::inc_counter(&metrics::ATTESTATION_PROCESSING_BATCH_SIGNATURE_FAILURES);
+    }
+
+    is_valid
 }
 
 /// Assists in readability.
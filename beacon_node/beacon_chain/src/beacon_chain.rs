@@ -1,7 +1,9 @@
 use crate::attestation_verification::{
+    batch_verify_aggregated_attestations, batch_verify_unaggregated_attestations,
     Error as AttestationError, SignatureVerifiedAttestation, VerifiedAggregatedAttestation,
     VerifiedUnaggregatedAttestation,
 };
+use crate::block_production_policy::BlockProductionPolicy;
 use crate::block_verification::{
     check_block_is_finalized_descendant, check_block_relevancy, get_block_root,
     signature_verify_chain_segment, BlockError, FullyVerifiedBlock, GossipVerifiedBlock,
@@ -20,6 +22,7 @@ use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::persisted_naive_aggregation_pool::PersistedNaiveAggregationPool;
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::snapshot_cache::SnapshotCache;
 use crate::timeout_rw_lock::TimeoutRwLock;
@@ -32,6 +35,7 @@ use futures::channel::mpsc::Sender;
 use itertools::process_results;
 use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::RwLock;
+use slasher::Slasher;
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use state_processing::{
@@ -70,6 +74,7 @@ pub const VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT: Duration = Duration::from_secs(1)
 // These keys are all zero because they get stored in different columns, see `DBColumn` type.
 pub const BEACON_CHAIN_DB_KEY: Hash256 = Hash256::zero();
 pub const OP_POOL_DB_KEY: Hash256 = Hash256::zero();
+pub const NAIVE_AGGREGATION_POOL_DB_KEY: Hash256 = Hash256::zero();
 pub const ETH1_CACHE_DB_KEY: Hash256 = Hash256::zero();
 pub const FORK_CHOICE_DB_KEY: Hash256 = Hash256::zero();
 
@@ -231,6 +236,8 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) log: Logger,
     /// Arbitrary bytes included in the blocks.
     pub(crate) graffiti: Graffiti,
+    /// Optional slasher, which is sent the attestations and blocks seen by the chain.
+    pub slasher: Option<Arc<Slasher<T::EthSpec>>>,
 }
 
 type BeaconBlockAndState<T> = (BeaconBlock<T>, BeaconState<T>);
@@ -296,6 +303,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Persists `self.naive_aggregation_pool` to disk.
+    pub fn persist_naive_aggregation_pool(&self) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::PERSIST_NAIVE_AGGREGATION_POOL);
+
+        self.store.put_item(
+            &NAIVE_AGGREGATION_POOL_DB_KEY,
+            &PersistedNaiveAggregationPool::from_naive_aggregation_pool(
+                &self.naive_aggregation_pool.read(),
+            ),
+        )?;
+
+        Ok(())
+    }
+
     /// Persists `self.eth1_chain` and its caches to disk.
     pub fn persist_eth1_cache(&self) -> Result<(), Error> {
         let _timer = metrics::start_timer(&metrics::PERSIST_OP_POOL);
@@ -977,6 +998,61 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Performs the same verification as `verify_unaggregated_attestation_for_gossip` on each of
+    /// the given `attestations`, verifying their signatures as a single batch.
+    ///
+    /// Returns a result for each attestation, in the same order as `attestations`.
+    pub fn batch_verify_unaggregated_attestations_for_gossip<I>(
+        &self,
+        attestations: I,
+    ) -> Result<Vec<Result<VerifiedUnaggregatedAttestation<T>, AttestationError>>, AttestationError>
+    where
+        I: Iterator<Item = (Attestation<T::EthSpec>, Option<SubnetId>)> + ExactSizeIterator,
+    {
+        metrics::inc_counter_by(
+            &metrics::UNAGGREGATED_ATTESTATION_PROCESSING_REQUESTS,
+            attestations.len() as i64,
+        );
+        let _timer = metrics::start_timer(
+            &metrics::UNAGGREGATED_ATTESTATION_BATCH_GOSSIP_VERIFICATION_TIMES,
+        );
+
+        batch_verify_unaggregated_attestations(attestations, self).map(|results| {
+            metrics::inc_counter_by(
+                &metrics::UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES,
+                results.iter().filter(|result| result.is_ok()).count() as i64,
+            );
+            results
+        })
+    }
+
+    /// Performs the same verification as `verify_aggregated_attestation_for_gossip` on each of
+    /// the given `signed_aggregates`, verifying their signatures as a single batch.
+    ///
+    /// Returns a result for each aggregate, in the same order as `signed_aggregates`.
+    pub fn batch_verify_aggregated_attestations_for_gossip<I>(
+        &self,
+        signed_aggregates: I,
+    ) -> Result<Vec<Result<VerifiedAggregatedAttestation<T>, AttestationError>>, AttestationError>
+    where
+        I: Iterator<Item = SignedAggregateAndProof<T::EthSpec>> + ExactSizeIterator,
+    {
+        metrics::inc_counter_by(
+            &metrics::AGGREGATED_ATTESTATION_PROCESSING_REQUESTS,
+            signed_aggregates.len() as i64,
+        );
+        let _timer =
+            metrics::start_timer(&metrics::AGGREGATED_ATTESTATION_BATCH_GOSSIP_VERIFICATION_TIMES);
+
+        batch_verify_aggregated_attestations(signed_aggregates, self).map(|results| {
+            metrics::inc_counter_by(
+                &metrics::AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES,
+                results.iter().filter(|result| result.is_ok()).count() as i64,
+            );
+            results
+        })
+    }
+
     /// Accepts some attestation-type object and attempts to verify it in the context of fork
     /// choice. If it is valid it is applied to `self.fork_choice`.
     ///
@@ -1592,11 +1668,33 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         {
             let _fork_choice_block_timer =
                 metrics::start_timer(&metrics::FORK_CHOICE_PROCESS_BLOCK_TIMES);
+            // If the delay cannot be determined, treat the block as late so it is not boosted.
+            let block_delay = self
+                .slot_clock
+                .duration_since_start_of(block.slot)
+                .unwrap_or_else(|| self.slot_clock.slot_duration());
+            metrics::observe(
+                &metrics::BLOCK_IMPORT_SLOT_START_DELAY_TIME,
+                block_delay.as_secs_f64(),
+            );
             fork_choice
-                .on_block(current_slot, block, block_root, &state)
+                .on_block(
+                    current_slot,
+                    block,
+                    block_root,
+                    block_delay,
+                    &state,
+                    &self.spec,
+                )
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
         }
 
+        // Blocks imported via RPC do not pass through gossip verification, so send every imported
+        // block to the slasher.
+        if let Some(slasher) = self.slasher.as_ref() {
+            slasher.accept_block_header(signed_block.signed_block_header());
+        }
+
         // Register each attestation in the block with the fork choice service.
         for attestation in &block.body.attestations[..] {
             let _fork_choice_attestation_timer =
@@ -1607,6 +1705,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let indexed_attestation = get_indexed_attestation(committee.committee, attestation)
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
 
+            // The signatures of the block's attestations were verified during block processing.
+            if let Some(slasher) = self.slasher.as_ref() {
+                slasher.accept_attestation(indexed_attestation.clone());
+            }
+
             match fork_choice.on_attestation(current_slot, &indexed_attestation) {
                 Ok(()) => Ok(()),
                 // Ignore invalid attestations whilst importing attestations from a block. The
@@ -1667,7 +1770,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(block_root)
     }
 
-    /// Produce a new block at the given `slot`.
+    /// Produce a new block at the given `slot`, using the default `BlockProductionPolicy`.
     ///
     /// The produced block will not be inherently valid, it must be signed by a block producer.
     /// Block signing is out of the scope of this function and should be done by a separate program.
@@ -1677,11 +1780,45 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         slot: Slot,
         validator_graffiti: Option<Graffiti>,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
-        let state = self
-            .state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
-            .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?;
+        self.produce_block_with_policy(
+            randao_reveal,
+            slot,
+            validator_graffiti,
+            BlockProductionPolicy::default(),
+        )
+    }
 
-        self.produce_block_on_state(state, slot, randao_reveal, validator_graffiti)
+    /// Produce a new block at the given `slot`, selecting operations according to `policy`.
+    ///
+    /// See `Self::produce_block`.
+    pub fn produce_block_with_policy(
+        &self,
+        randao_reveal: Signature,
+        slot: Slot,
+        validator_graffiti: Option<Graffiti>,
+        policy: BlockProductionPolicy,
+    ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
+        // Use the head state pre-computed by the state advance timer, if it is available.
+        let advanced_state = self
+            .head_beacon_block_root()
+            .ok()
+            .and_then(|head_block_root| {
+                self.snapshot_cache
+                    .try_read_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                    .and_then(|snapshot_cache| {
+                        snapshot_cache.get_pre_state_for_block_production(head_block_root, slot)
+                    })
+            });
+
+        let state = if let Some(state) = advanced_state {
+            metrics::inc_counter(&metrics::BLOCK_PRODUCTION_ADVANCED_STATE_HITS);
+            state
+        } else {
+            self.state_at_slot(slot - 1, StateSkipConfig::WithStateRoots)
+                .map_err(|_| BlockProductionError::UnableToProduceAtSlot(slot))?
+        };
+
+        self.produce_block_on_state(state, slot, randao_reveal, validator_graffiti, policy)
     }
 
     /// Produce a block for some `slot` upon the given `state`.
@@ -1692,12 +1829,16 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     ///
     /// The given state will be advanced to the given `produce_at_slot`, then a block will be
     /// produced at that slot height.
+    ///
+    /// Operations for validators on the `block_production_deny_list` of the `ChainConfig` are
+    /// excluded in addition to those excluded by `policy`.
     pub fn produce_block_on_state(
         &self,
         mut state: BeaconState<T::EthSpec>,
         produce_at_slot: Slot,
         randao_reveal: Signature,
         validator_graffiti: Option<Graffiti>,
+        mut policy: BlockProductionPolicy,
     ) -> Result<BeaconBlockAndState<T::EthSpec>, BlockProductionError> {
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_REQUESTS);
         let timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_TIMES);
@@ -1717,6 +1858,41 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
 
+        let proposer_index = state.get_beacon_proposer_index(state.slot, &self.spec)?;
+
+        if policy.verify_randao || !self.config.block_production_deny_list.is_empty() {
+            let pubkey_cache = self
+                .validator_pubkey_cache
+                .try_read_for(VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT)
+                .ok_or(BlockProductionError::ValidatorPubkeyCacheLockTimeout)?;
+
+            if policy.verify_randao {
+                let epoch = state.current_epoch();
+                let domain = self.spec.get_domain(
+                    epoch,
+                    Domain::Randao,
+                    &state.fork,
+                    state.genesis_validators_root,
+                );
+                let message = epoch.signing_root(domain);
+                let randao_valid = pubkey_cache
+                    .get(proposer_index)
+                    .map_or(false, |pubkey| randao_reveal.verify(pubkey, message));
+
+                if !randao_valid {
+                    return Err(BlockProductionError::InvalidRandaoReveal);
+                }
+            }
+
+            policy.exclude_validators(
+                self.config
+                    .block_production_deny_list
+                    .iter()
+                    .filter_map(|pubkey| pubkey_cache.get_index(pubkey))
+                    .map(|validator_index| validator_index as u64),
+            );
+        }
+
         let parent_root = if state.slot > 0 {
             *state
                 .get_block_root(state.slot - 1)
@@ -1725,8 +1901,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             state.latest_block_header.canonical_root()
         };
 
-        let (proposer_slashings, attester_slashings) =
-            self.op_pool.get_slashings(&state, &self.spec);
+        let (proposer_slashings, attester_slashings) = self.op_pool.get_slashings(
+            &state,
+            |slashing| policy.allows_proposer_slashing(slashing),
+            |slashing| policy.allows_attester_slashing(slashing),
+            &self.spec,
+        );
 
         let eth1_data = eth1_chain.eth1_data_for_block_production(&state, &self.spec)?;
         let deposits = eth1_chain
@@ -1766,6 +1946,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             }
         }
 
+        let pool_attestations = self.op_pool.num_includable_attestations(&state, &self.spec);
+
+        let mut attestations = self
+            .op_pool
+            .get_attestations(&state, attestation_filter, &self.spec)
+            .map_err(BlockProductionError::OpPoolError)?;
+        if let Some(max_attestations) = policy.max_attestations {
+            attestations.truncate(max_attestations);
+        }
+
+        let voluntary_exits =
+            self.op_pool
+                .get_voluntary_exits(&state, |exit| policy.allows_exit(exit), &self.spec);
+
         // Override the beacon node's graffiti with graffiti from the validator, if present.
         let graffiti = match validator_graffiti {
             Some(graffiti) => graffiti,
@@ -1775,7 +1969,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let mut block = SignedBeaconBlock {
             message: BeaconBlock {
                 slot: state.slot,
-                proposer_index: state.get_beacon_proposer_index(state.slot, &self.spec)? as u64,
+                proposer_index: proposer_index as u64,
                 parent_root,
                 state_root: Hash256::zero(),
                 body: BeaconBlockBody {
@@ -1784,13 +1978,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     graffiti,
                     proposer_slashings: proposer_slashings.into(),
                     attester_slashings: attester_slashings.into(),
-                    attestations: self
-                        .op_pool
-                        .get_attestations(&state, attestation_filter, &self.spec)
-                        .map_err(BlockProductionError::OpPoolError)?
-                        .into(),
+                    attestations: attestations.into(),
                     deposits,
-                    voluntary_exits: self.op_pool.get_voluntary_exits(&state, &self.spec).into(),
+                    voluntary_exits: voluntary_exits.into(),
                 },
             },
             // The block is not signed here, that is the task of a validator client.
@@ -1812,6 +2002,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
         metrics::stop_timer(timer);
 
+        // An empty block whilst the pool is full of attestations usually indicates a problem with
+        // the op pool or with the attestations themselves, resulting in lost rewards.
+        if block.message.body.attestations.is_empty()
+            && pool_attestations > 0
+            && policy.max_attestations != Some(0)
+        {
+            metrics::inc_counter(&metrics::BLOCK_PRODUCTION_EMPTY_BLOCKS);
+            warn!(
+                self.log,
+                "Produced block without attestations";
+                "info" => "none of the recent attestations in the op pool could be included",
+                "pool_attestations" => pool_attestations,
+                "slot" => block.message.slot,
+            );
+        }
+
         trace!(
             self.log,
             "Produced beacon block";
@@ -1839,7 +2045,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     fn fork_choice_internal(&self) -> Result<(), Error> {
         // Determine the root of the block that is the head of the chain.
-        let beacon_block_root = self.fork_choice.write().get_head(self.slot()?)?;
+        let beacon_block_root = self
+            .fork_choice
+            .write()
+            .get_head(self.slot()?, &self.spec)?;
 
         let current_head = self.head_info()?;
         let old_finalized_checkpoint = current_head.finalized_checkpoint;
@@ -2056,12 +2265,116 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
     /// Called by the timer on every slot.
     ///
-    /// Performs slot-based pruning.
+    /// Performs slot-based pruning, and persists the operation pools at the start of each epoch.
     pub fn per_slot_task(&self) {
         trace!(self.log, "Running beacon chain per slot tasks");
         if let Some(slot) = self.slot_clock.now() {
             self.naive_aggregation_pool.write().prune(slot);
+
+            // Persist the pools once per epoch so they can be restored after an unclean shutdown,
+            // not just when the chain is dropped.
+            if slot % T::EthSpec::slots_per_epoch() == 0 {
+                if let Err(e) = self
+                    .persist_op_pool()
+                    .and_then(|()| self.persist_naive_aggregation_pool())
+                {
+                    error!(
+                        self.log,
+                        "Failed to persist operation pools";
+                        "error" => format!("{:?}", e)
+                    );
+                }
+            }
+        }
+    }
+
+    /// Called by the timer shortly before the start of every slot.
+    ///
+    /// Advances a copy of the head state to the next slot and builds its committee caches, storing
+    /// it in the snapshot cache. This moves the cost of slot processing (and epoch processing at
+    /// epoch boundaries) out of block import and block production.
+    ///
+    /// The head state is only ever advanced by a single slot, so the advanced state can never be
+    /// later than the slot of a child of the head.
+    pub fn advance_head_state(&self) -> Result<(), Error> {
+        let next_slot = self.slot()? + 1;
+        let head_info = self.head_info()?;
+
+        // Don't bother advancing the state of a head that is far behind the current slot (e.g.,
+        // whilst syncing). Its state will likely be superseded before it is used.
+        if head_info.slot + T::EthSpec::slots_per_epoch() < next_slot {
+            debug!(
+                self.log,
+                "Not advancing distant head state";
+                "head_slot" => head_info.slot,
+                "next_slot" => next_slot
+            );
+            metrics::inc_counter(&metrics::STATE_ADVANCE_SKIPPED);
+            return Ok(());
         }
+
+        let advance = self
+            .snapshot_cache
+            .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::SnapshotCacheLockTimeout)?
+            .take_state_for_advance(head_info.block_root);
+        let (mut state, state_root) = match advance {
+            Some(advance) if advance.state.slot < next_slot => (advance.state, advance.state_root),
+            Some(advance) => {
+                // The state is already at the next slot, put it back untouched.
+                self.snapshot_cache
+                    .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+                    .ok_or_else(|| Error::SnapshotCacheLockTimeout)?
+                    .update_pre_state(head_info.block_root, advance.state);
+                metrics::inc_counter(&metrics::STATE_ADVANCE_SKIPPED);
+                return Ok(());
+            }
+            None => {
+                trace!(
+                    self.log,
+                    "Head state not available for advance";
+                    "head_root" => format!("{}", head_info.block_root)
+                );
+                metrics::inc_counter(&metrics::STATE_ADVANCE_SKIPPED);
+                return Ok(());
+            }
+        };
+
+        let timer = metrics::start_timer(&metrics::STATE_ADVANCE_TIMES);
+
+        per_slot_processing(&mut state, Some(state_root), &self.spec)?;
+        state.build_committee_cache(RelativeEpoch::Previous, &self.spec)?;
+        state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
+        state.build_committee_cache(RelativeEpoch::Next, &self.spec)?;
+
+        metrics::stop_timer(timer);
+
+        let advanced_slot = state.slot;
+        let updated = self
+            .snapshot_cache
+            .try_write_for(BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT)
+            .ok_or_else(|| Error::SnapshotCacheLockTimeout)?
+            .update_pre_state(head_info.block_root, state);
+
+        if updated.is_some() {
+            debug!(
+                self.log,
+                "Advanced head state";
+                "head_root" => format!("{}", head_info.block_root),
+                "state_slot" => advanced_slot
+            );
+        } else {
+            // The head snapshot was removed from the cache (e.g., by a child block being imported)
+            // whilst it was being advanced.
+            debug!(
+                self.log,
+                "Discarded advanced head state";
+                "head_root" => format!("{}", head_info.block_root),
+                "state_slot" => advanced_slot
+            );
+        }
+
+        Ok(())
     }
 
     /// Called after `self` has had a new block finalized.
@@ -2432,6 +2745,7 @@ impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
         let drop = || -> Result<(), Error> {
             self.persist_head_and_fork_choice()?;
             self.persist_op_pool()?;
+            self.persist_naive_aggregation_pool()?;
             self.persist_eth1_cache()
         };
 
@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use types::{AttesterSlashing, EthSpec, ProposerSlashing, SignedVoluntaryExit};
+
+/// Controls which operations from the `OperationPool` are packed into a block produced by
+/// `BeaconChain::produce_block`.
+///
+/// The default policy verifies the randao reveal and includes as many operations as possible.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockProductionPolicy {
+    /// Verify the randao reveal against the proposer's public key before producing a block.
+    pub verify_randao: bool,
+    /// Include at most this many attestations, if `Some`.
+    ///
+    /// Values larger than `MAX_ATTESTATIONS` have no effect.
+    pub max_attestations: Option<usize>,
+    /// Do not include voluntary exits for any of these validator indices.
+    pub excluded_exits: HashSet<u64>,
+    /// Do not include proposer or attester slashings that would slash any of these validator
+    /// indices.
+    pub excluded_slashings: HashSet<u64>,
+}
+
+impl Default for BlockProductionPolicy {
+    fn default() -> Self {
+        Self {
+            verify_randao: true,
+            max_attestations: None,
+            excluded_exits: HashSet::new(),
+            excluded_slashings: HashSet::new(),
+        }
+    }
+}
+
+impl BlockProductionPolicy {
+    /// Exclude all exits and slashings for the given validator indices, in addition to those
+    /// already excluded.
+    pub fn exclude_validators(&mut self, indices: impl IntoIterator<Item = u64>) {
+        for index in indices {
+            self.excluded_exits.insert(index);
+            self.excluded_slashings.insert(index);
+        }
+    }
+
+    /// Returns `true` if `exit` may be included in the block.
+    pub fn allows_exit(&self, exit: &SignedVoluntaryExit) -> bool {
+        !self.excluded_exits.contains(&exit.message.validator_index)
+    }
+
+    /// Returns `true` if `slashing` may be included in the block.
+    pub fn allows_proposer_slashing(&self, slashing: &ProposerSlashing) -> bool {
+        !self
+            .excluded_slashings
+            .contains(&slashing.signed_header_1.message.proposer_index)
+    }
+
+    /// Returns `true` if `slashing` may be included in the block.
+    ///
+    /// An attester slashing is excluded if any validator it would slash is excluded.
+    pub fn allows_attester_slashing<T: EthSpec>(&self, slashing: &AttesterSlashing<T>) -> bool {
+        let attesters_1 = slashing
+            .attestation_1
+            .attesting_indices
+            .iter()
+            .collect::<HashSet<_>>();
+
+        !slashing
+            .attestation_2
+            .attesting_indices
+            .iter()
+            .any(|index| attesters_1.contains(index) && self.excluded_slashings.contains(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{test_utils::test_random_instance, MinimalEthSpec, VariableList};
+
+    fn exit(validator_index: u64) -> SignedVoluntaryExit {
+        let mut exit = test_random_instance::<SignedVoluntaryExit>();
+        exit.message.validator_index = validator_index;
+        exit
+    }
+
+    fn proposer_slashing(proposer_index: u64) -> ProposerSlashing {
+        let mut slashing = test_random_instance::<ProposerSlashing>();
+        slashing.signed_header_1.message.proposer_index = proposer_index;
+        slashing.signed_header_2.message.proposer_index = proposer_index;
+        slashing
+    }
+
+    fn attester_slashing(
+        attesters_1: Vec<u64>,
+        attesters_2: Vec<u64>,
+    ) -> AttesterSlashing<MinimalEthSpec> {
+        let mut slashing = test_random_instance::<AttesterSlashing<MinimalEthSpec>>();
+        slashing.attestation_1.attesting_indices = VariableList::from(attesters_1);
+        slashing.attestation_2.attesting_indices = VariableList::from(attesters_2);
+        slashing
+    }
+
+    #[test]
+    fn default_allows_everything() {
+        let policy = BlockProductionPolicy::default();
+        assert!(policy.allows_exit(&exit(1)));
+        assert!(policy.allows_proposer_slashing(&proposer_slashing(1)));
+        assert!(policy.allows_attester_slashing(&attester_slashing(vec![1, 2], vec![1, 2])));
+    }
+
+    #[test]
+    fn allows_exit() {
+        let mut policy = BlockProductionPolicy::default();
+        policy.excluded_exits.insert(1);
+
+        assert!(!policy.allows_exit(&exit(1)));
+        assert!(policy.allows_exit(&exit(2)));
+        // Excluding exits does not exclude slashings.
+        assert!(policy.allows_proposer_slashing(&proposer_slashing(1)));
+    }
+
+    #[test]
+    fn allows_attester_slashing() {
+        let mut policy = BlockProductionPolicy::default();
+        policy.excluded_slashings.insert(3);
+
+        // Validator 3 would be slashed.
+        assert!(!policy.allows_attester_slashing(&attester_slashing(vec![1, 3], vec![2, 3])));
+        // Validator 3 is only in one of the attestations, so would not be slashed.
+        assert!(policy.allows_attester_slashing(&attester_slashing(vec![1, 3], vec![1, 2])));
+        assert!(policy.allows_attester_slashing(&attester_slashing(vec![1, 2], vec![2, 3])));
+        assert!(policy.allows_attester_slashing(&attester_slashing(vec![1, 2], vec![1, 2])));
+        // Excluding slashings does not exclude exits.
+        assert!(policy.allows_exit(&exit(3)));
+    }
+
+    #[test]
+    fn allows_proposer_slashing() {
+        let mut policy = BlockProductionPolicy::default();
+        policy.excluded_slashings.insert(4);
+
+        assert!(!policy.allows_proposer_slashing(&proposer_slashing(4)));
+        assert!(policy.allows_proposer_slashing(&proposer_slashing(5)));
+    }
+
+    #[test]
+    fn deny_list_excludes_exits_and_slashings() {
+        let mut policy = BlockProductionPolicy::default();
+        policy.excluded_exits.insert(1);
+        policy.exclude_validators(vec![2, 3]);
+
+        // Exclusions from the request are kept.
+        assert!(!policy.allows_exit(&exit(1)));
+        assert!(policy.allows_proposer_slashing(&proposer_slashing(1)));
+
+        for index in &[2, 3] {
+            assert!(!policy.allows_exit(&exit(*index)));
+            assert!(!policy.allows_proposer_slashing(&proposer_slashing(*index)));
+            assert!(!policy
+                .allows_attester_slashing(&attester_slashing(vec![1, *index], vec![*index, 4])));
+        }
+
+        assert!(policy.allows_exit(&exit(4)));
+        assert!(policy.allows_proposer_slashing(&proposer_slashing(4)));
+        assert!(policy.allows_attester_slashing(&attester_slashing(vec![1, 4], vec![4, 5])));
+    }
+}
@@ -40,13 +40,14 @@
 //!            END
 //!
 //! ```
+use crate::snapshot_cache::PreProcessingSnapshot;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::{
     beacon_chain::{
         BLOCK_PROCESSING_CACHE_LOCK_TIMEOUT, MAXIMUM_GOSSIP_CLOCK_DISPARITY,
         VALIDATOR_PUBKEY_CACHE_LOCK_TIMEOUT,
     },
-    metrics, BeaconChain, BeaconChainError, BeaconChainTypes, BeaconSnapshot,
+    metrics, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use fork_choice::{ForkChoice, ForkChoiceStore};
 use parking_lot::RwLockReadGuard;
@@ -296,11 +297,8 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
         .map(|(_, block)| block.slot())
         .unwrap_or_else(|| slot);
 
-    let state = cheap_state_advance_to_obtain_committees(
-        &mut parent.beacon_state,
-        highest_slot,
-        &chain.spec,
-    )?;
+    let state =
+        cheap_state_advance_to_obtain_committees(&mut parent.pre_state, highest_slot, &chain.spec)?;
 
     let pubkey_cache = get_validator_pubkey_cache(chain)?;
     let mut signature_verifier = get_signature_verifier(&state, &pubkey_cache, &chain.spec);
@@ -336,7 +334,7 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
 pub struct GossipVerifiedBlock<T: BeaconChainTypes> {
     pub block: SignedBeaconBlock<T::EthSpec>,
     pub block_root: Hash256,
-    parent: BeaconSnapshot<T::EthSpec>,
+    parent: PreProcessingSnapshot<T::EthSpec>,
 }
 
 /// A wrapper around a `SignedBeaconBlock` that indicates that all signatures (except the deposit
@@ -344,7 +342,7 @@ pub struct GossipVerifiedBlock<T: BeaconChainTypes> {
 pub struct SignatureVerifiedBlock<T: BeaconChainTypes> {
     block: SignedBeaconBlock<T::EthSpec>,
     block_root: Hash256,
-    parent: Option<BeaconSnapshot<T::EthSpec>>,
+    parent: Option<PreProcessingSnapshot<T::EthSpec>>,
 }
 
 /// A wrapper around a `SignedBeaconBlock` that indicates that this block is fully verified and
@@ -421,6 +419,13 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
             .proposer_has_been_observed(&block.message)
             .map_err(|e| BlockError::BeaconChainError(e.into()))?
         {
+            // A second block from this proposer at this slot might be a double proposal, so the
+            // slasher should still see it if it is correctly signed.
+            if chain.slasher.is_some()
+                && verify_proposal_signature_at_head_fork(chain, &block, block_root)?
+            {
+                send_block_header_to_slasher(chain, &block);
+            }
             return Err(BlockError::RepeatProposal {
                 proposer: block.message.proposer_index,
                 slot: block.message.slot,
@@ -442,7 +447,7 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
         check_block_skip_slots(chain, &parent.beacon_block.message, &block.message)?;
 
         let state = cheap_state_advance_to_obtain_committees(
-            &mut parent.beacon_state,
+            &mut parent.pre_state,
             block.slot(),
             &chain.spec,
         )?;
@@ -465,6 +470,8 @@ impl<T: BeaconChainTypes> GossipVerifiedBlock<T> {
             return Err(BlockError::ProposalSignatureInvalid);
         }
 
+        send_block_header_to_slasher(chain, &block);
+
         // Now the signature is valid, store the proposal so we don't accept another from this
         // validator and slot.
         //
@@ -534,7 +541,7 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         let block_root = get_block_root(&block);
 
         let state = cheap_state_advance_to_obtain_committees(
-            &mut parent.beacon_state,
+            &mut parent.pre_state,
             block.slot(),
             &chain.spec,
         )?;
@@ -566,7 +573,7 @@ impl<T: BeaconChainTypes> SignatureVerifiedBlock<T> {
         let block = from.block;
 
         let state = cheap_state_advance_to_obtain_committees(
-            &mut parent.beacon_state,
+            &mut parent.pre_state,
             block.slot(),
             &chain.spec,
         )?;
@@ -640,7 +647,7 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
     pub fn from_signature_verified_components(
         block: SignedBeaconBlock<T::EthSpec>,
         block_root: Hash256,
-        parent: BeaconSnapshot<T::EthSpec>,
+        parent: PreProcessingSnapshot<T::EthSpec>,
         chain: &BeaconChain<T>,
     ) -> Result<Self, BlockError<T::EthSpec>> {
         // Reject any block if its parent is not known to fork choice.
@@ -681,20 +688,23 @@ impl<'a, T: BeaconChainTypes> FullyVerifiedBlock<'a, T> {
         let mut intermediate_states: Vec<StoreOp<T::EthSpec>> = Vec::new();
 
         // The block must have a higher slot than its parent.
-        if block.slot() <= parent.beacon_state.slot {
+        //
+        // The parent state may have been advanced by the state advance timer, however it is never
+        // advanced beyond the slot of any valid child block.
+        if block.slot() <= parent.beacon_block.slot() || block.slot() < parent.pre_state.slot {
             return Err(BlockError::BlockIsNotLaterThanParent {
                 block_slot: block.slot(),
-                state_slot: parent.beacon_state.slot,
+                state_slot: parent.pre_state.slot,
             });
         }
 
         let mut summaries = vec![];
 
         // Transition the parent state to the block slot.
-        let mut state = parent.beacon_state;
+        let mut state = parent.pre_state;
         let distance = block.slot().as_u64().saturating_sub(state.slot.as_u64());
-        for i in 0..distance {
-            let state_root = if i == 0 {
+        for _ in 0..distance {
+            let state_root = if state.slot == parent.beacon_block.slot() {
                 parent.beacon_block.state_root()
             } else {
                 // This is a new state we've reached, so stage it for storage in the DB.
@@ -952,7 +962,13 @@ pub fn get_block_root<E: EthSpec>(block: &SignedBeaconBlock<E>) -> Hash256 {
 fn load_parent<T: BeaconChainTypes>(
     block: SignedBeaconBlock<T::EthSpec>,
     chain: &BeaconChain<T>,
-) -> Result<(BeaconSnapshot<T::EthSpec>, SignedBeaconBlock<T::EthSpec>), BlockError<T::EthSpec>> {
+) -> Result<
+    (
+        PreProcessingSnapshot<T::EthSpec>,
+        SignedBeaconBlock<T::EthSpec>,
+    ),
+    BlockError<T::EthSpec>,
+> {
     // Reject any block if its parent is not known to fork choice.
     //
     // A block that is not in fork choice is either:
@@ -1009,11 +1025,10 @@ fn load_parent<T: BeaconChainTypes>(
             })?;
 
         Ok((
-            BeaconSnapshot {
+            PreProcessingSnapshot {
                 beacon_block: parent_block,
                 beacon_block_root: root,
-                beacon_state: parent_state,
-                beacon_state_root: parent_state_root,
+                pre_state: parent_state,
             },
             block,
         ))
@@ -1079,6 +1094,40 @@ fn get_validator_pubkey_cache<T: BeaconChainTypes>(
         .map_err(BlockError::BeaconChainError)
 }
 
+/// Verify the proposer signature of `block` using the fork of the current head.
+///
+/// Only used where the state at the block's slot is not readily available.
+fn verify_proposal_signature_at_head_fork<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+    block_root: Hash256,
+) -> Result<bool, BlockError<T::EthSpec>> {
+    let fork = chain.head_info()?.fork;
+    let pubkey_cache = get_validator_pubkey_cache(chain)?;
+    let pubkey = pubkey_cache
+        .get(block.message.proposer_index as usize)
+        .ok_or_else(|| BlockError::UnknownValidator(block.message.proposer_index))?;
+    Ok(block.verify_signature(
+        Some(block_root),
+        pubkey,
+        &fork,
+        chain.genesis_validators_root,
+        &chain.spec,
+    ))
+}
+
+/// Sends the header of `block` to the slasher, if one is enabled.
+///
+/// The proposer signature of `block` must already have been verified.
+fn send_block_header_to_slasher<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: &SignedBeaconBlock<T::EthSpec>,
+) {
+    if let Some(slasher) = chain.slasher.as_ref() {
+        slasher.accept_block_header(block.signed_block_header());
+    }
+}
+
 /// Produces an _empty_ `BlockSignatureVerifier`.
 ///
 /// The signature verifier is empty because it does not yet have any of this block's signatures
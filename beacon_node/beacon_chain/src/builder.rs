@@ -1,12 +1,15 @@
 use crate::beacon_chain::{
-    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, OP_POOL_DB_KEY,
+    BEACON_CHAIN_DB_KEY, ETH1_CACHE_DB_KEY, FORK_CHOICE_DB_KEY, NAIVE_AGGREGATION_POOL_DB_KEY,
+    OP_POOL_DB_KEY,
 };
 use crate::eth1_chain::{CachingEth1Backend, SszEth1};
 use crate::events::NullEventHandler;
 use crate::head_tracker::HeadTracker;
+use crate::metrics;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::persisted_naive_aggregation_pool::PersistedNaiveAggregationPool;
 use crate::shuffling_cache::ShufflingCache;
 use crate::snapshot_cache::{SnapshotCache, DEFAULT_SNAPSHOT_CACHE_SIZE};
 use crate::timeout_rw_lock::TimeoutRwLock;
@@ -21,7 +24,8 @@ use fork_choice::ForkChoice;
 use futures::channel::mpsc::Sender;
 use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::RwLock;
-use slog::{crit, info, Logger};
+use slasher::Slasher;
+use slog::{crit, info, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::marker::PhantomData;
 use std::path::PathBuf;
@@ -86,6 +90,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
         ForkChoice<BeaconForkChoiceStore<T::EthSpec, T::HotStore, T::ColdStore>, T::EthSpec>,
     >,
     op_pool: Option<OperationPool<T::EthSpec>>,
+    persisted_naive_aggregation_pool: Option<PersistedNaiveAggregationPool<T::EthSpec>>,
     eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
     event_handler: Option<T::EventHandler>,
     slot_clock: Option<T::SlotClock>,
@@ -99,6 +104,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     disabled_forks: Vec<String>,
     log: Option<Logger>,
     graffiti: Graffiti,
+    slasher: Option<Arc<Slasher<T::EthSpec>>>,
 }
 
 impl<TSlotClock, TEth1Backend, TEthSpec, TEventHandler, THotStore, TColdStore>
@@ -126,6 +132,7 @@ where
             genesis_state_root: None,
             fork_choice: None,
             op_pool: None,
+            persisted_naive_aggregation_pool: None,
             eth1_chain: None,
             event_handler: None,
             slot_clock: None,
@@ -139,6 +146,7 @@ where
             chain_config: ChainConfig::default(),
             log: None,
             graffiti: Graffiti::default(),
+            slasher: None,
         }
     }
 
@@ -281,13 +289,34 @@ where
 
         self.genesis_time = Some(genesis_state.genesis_time);
 
+        // The operation pools are not critical, so start with empty pools rather than failing if
+        // they were persisted in an unsupported format.
         self.op_pool = Some(
             store
                 .get_item::<PersistedOperationPool<TEthSpec>>(&OP_POOL_DB_KEY)
-                .map_err(|e| format!("DB error whilst reading persisted op pool: {:?}", e))?
+                .unwrap_or_else(|e| {
+                    warn!(
+                        log,
+                        "Unable to restore op pool";
+                        "error" => ?e,
+                        "info" => "starting with an empty op pool"
+                    );
+                    None
+                })
                 .map(PersistedOperationPool::into_operation_pool)
                 .unwrap_or_else(OperationPool::new),
         );
+        self.persisted_naive_aggregation_pool = store
+            .get_item::<PersistedNaiveAggregationPool<TEthSpec>>(&NAIVE_AGGREGATION_POOL_DB_KEY)
+            .unwrap_or_else(|e| {
+                warn!(
+                    log,
+                    "Unable to restore naive aggregation pool";
+                    "error" => ?e,
+                    "info" => "starting with an empty naive aggregation pool"
+                );
+                None
+            });
 
         let pubkey_cache = ValidatorPubkeyCache::load_from_file(pubkey_cache_path)
             .map_err(|e| format!("Unable to open persisted pubkey cache: {:?}", e))?;
@@ -406,6 +435,12 @@ where
         self
     }
 
+    /// Sets the `Slasher` which will be sent the attestations and blocks seen by the chain.
+    pub fn slasher(mut self, slasher: Arc<Slasher<TEthSpec>>) -> Self {
+        self.slasher = Some(slasher);
+        self
+    }
+
     /// Sets the `ChainConfig` that determines `BeaconChain` runtime behaviour.
     pub fn chain_config(mut self, config: ChainConfig) -> Self {
         self.chain_config = config;
@@ -459,7 +494,7 @@ where
         };
 
         let head_block_root = fork_choice
-            .get_head(current_slot)
+            .get_head(current_slot, &self.spec)
             .map_err(|e| format!("Unable to get fork choice head: {:?}", e))?;
 
         let head_block = store
@@ -526,17 +561,62 @@ where
             log.clone(),
         );
 
+        let op_pool = self
+            .op_pool
+            .ok_or_else(|| "Cannot build without op pool".to_string())?;
+        let op_pool = validate_restored_op_pool(
+            op_pool,
+            || {
+                let finalized_block = store
+                    .get_item::<SignedBeaconBlock<TEthSpec>>(&fc_finalized.root)
+                    .map_err(|e| format!("DB error when reading finalized block: {:?}", e))?
+                    .ok_or_else(|| "Finalized block not found in store".to_string())?;
+                store
+                    .get_state(&finalized_block.state_root(), Some(finalized_block.slot()))
+                    .map_err(|e| format!("DB error when reading finalized state: {:?}", e))?
+                    .ok_or_else(|| "Finalized state not found in store".to_string())
+            },
+            &canonical_head.beacon_state,
+            current_slot,
+            &self.spec,
+            &log,
+        );
+
+        let naive_aggregation_pool = match self.persisted_naive_aggregation_pool {
+            Some(persisted) => {
+                let (pool, dropped) = persisted
+                    .into_naive_aggregation_pool(&canonical_head.beacon_state, current_slot);
+                let restored = pool.iter().count();
+                let kind = &["naive_aggregate"];
+                metrics::inc_counter_vec_by(
+                    &metrics::RESTORED_POOL_OPERATIONS,
+                    kind,
+                    restored as i64,
+                );
+                metrics::inc_counter_vec_by(
+                    &metrics::RESTORED_POOL_OPERATIONS_DROPPED,
+                    kind,
+                    dropped as i64,
+                );
+                info!(
+                    log,
+                    "Restored naive aggregation pool";
+                    "restored" => restored,
+                    "dropped" => dropped,
+                );
+                pool
+            }
+            None => <_>::default(),
+        };
+
         let beacon_chain = BeaconChain {
             spec: self.spec,
             config: self.chain_config,
             store,
             store_migrator,
             slot_clock,
-            op_pool: self
-                .op_pool
-                .ok_or_else(|| "Cannot build without op pool".to_string())?,
-            // TODO: allow for persisting and loading the pool from disk.
-            naive_aggregation_pool: <_>::default(),
+            op_pool,
+            naive_aggregation_pool: RwLock::new(naive_aggregation_pool),
             // TODO: allow for persisting and loading the pool from disk.
             observed_attestations: <_>::default(),
             // TODO: allow for persisting and loading the pool from disk.
@@ -571,6 +651,7 @@ where
                 .ok_or_else(|| "Cannot build without a shutdown sender.".to_string())?,
             log: log.clone(),
             graffiti: self.graffiti,
+            slasher: self.slasher.clone(),
         };
 
         let head = beacon_chain
@@ -719,6 +800,80 @@ fn genesis_block<T: EthSpec>(
     Ok(genesis_block)
 }
 
+/// Drops operations restored from disk that are stale or invalid with respect to the finalized
+/// state and `head_state`, recording the number of operations of each kind that were kept and
+/// dropped.
+///
+/// The finalized state is only loaded if the restored pool is not empty. If it cannot be loaded
+/// then the restored operations cannot be validated, so they are all dropped rather than
+/// preventing the node from starting.
+fn validate_restored_op_pool<T: EthSpec>(
+    op_pool: OperationPool<T>,
+    finalized_state: impl FnOnce() -> Result<BeaconState<T>, String>,
+    head_state: &BeaconState<T>,
+    current_slot: Slot,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> OperationPool<T> {
+    let counts = |op_pool: &OperationPool<T>| {
+        [
+            ("attestation", op_pool.num_attestations()),
+            ("attester_slashing", op_pool.num_attester_slashings()),
+            ("proposer_slashing", op_pool.num_proposer_slashings()),
+            ("voluntary_exit", op_pool.num_voluntary_exits()),
+        ]
+    };
+
+    let before = counts(&op_pool);
+    if before.iter().all(|(_, count)| *count == 0) {
+        return op_pool;
+    }
+
+    let op_pool = match finalized_state() {
+        Ok(finalized_state) => {
+            op_pool.prune_all(
+                &finalized_state,
+                current_slot.epoch(T::slots_per_epoch()),
+                head_state.fork,
+                spec,
+            );
+            op_pool.prune_invalid_attestations(head_state);
+            op_pool
+        }
+        Err(e) => {
+            warn!(
+                log,
+                "Dropping restored op pool";
+                "reason" => "unable to load finalized state",
+                "error" => e,
+            );
+            OperationPool::new()
+        }
+    };
+    let after = counts(&op_pool);
+
+    for ((kind, before), (_, after)) in before.iter().zip(after.iter()) {
+        metrics::inc_counter_vec_by(&metrics::RESTORED_POOL_OPERATIONS, &[*kind], *after as i64);
+        metrics::inc_counter_vec_by(
+            &metrics::RESTORED_POOL_OPERATIONS_DROPPED,
+            &[*kind],
+            before.saturating_sub(*after) as i64,
+        );
+    }
+
+    info!(
+        log,
+        "Restored op pool";
+        "attestations" => after[0].1,
+        "attester_slashings" => after[1].1,
+        "proposer_slashings" => after[2].1,
+        "voluntary_exits" => after[3].1,
+        "dropped" => before.iter().zip(after.iter()).map(|((_, b), (_, a))| b - a).sum::<usize>(),
+    );
+
+    op_pool
+}
+
 #[cfg(not(debug_assertions))]
 #[cfg(test)]
 mod test {
@@ -731,7 +886,7 @@ mod test {
     use store::config::StoreConfig;
     use store::{HotColdDB, MemoryStore};
     use tempfile::tempdir;
-    use types::{EthSpec, MinimalEthSpec, Slot};
+    use types::{test_utils::test_random_instance, EthSpec, MinimalEthSpec, Slot};
 
     type TestEthSpec = MinimalEthSpec;
 
@@ -815,6 +970,39 @@ mod test {
         );
     }
 
+    #[test]
+    fn restored_op_pool_without_finalized_state() {
+        let spec = MinimalEthSpec::default_spec();
+        let head_state = interop_genesis_state::<TestEthSpec>(
+            &generate_deterministic_keypairs(1),
+            13_371_337,
+            &spec,
+        )
+        .expect("should build state");
+
+        let op_pool = OperationPool::new();
+        op_pool
+            .insert_attestation(
+                test_random_instance(),
+                &head_state.fork,
+                head_state.genesis_validators_root,
+                &spec,
+            )
+            .expect("should insert attestation");
+        assert_eq!(op_pool.num_attestations(), 1);
+
+        // Startup continues without the restored operations.
+        let op_pool = validate_restored_op_pool(
+            op_pool,
+            || Err("Finalized state not found in store".to_string()),
+            &head_state,
+            Slot::new(0),
+            &spec,
+            &get_logger(),
+        );
+        assert_eq!(op_pool.num_attestations(), 0);
+    }
+
     #[test]
     fn interop_state() {
         let validator_count = 16;
@@ -1,5 +1,5 @@
 use serde_derive::{Deserialize, Serialize};
-use types::Checkpoint;
+use types::{Checkpoint, PublicKeyBytes};
 
 /// There is a 693 block skip in the current canonical Medalla chain, we use 700 to be safe.
 pub const DEFAULT_IMPORT_BLOCK_MAX_SKIP_SLOTS: u64 = 700;
@@ -15,6 +15,8 @@ pub struct ChainConfig {
     ///
     /// If `None`, there is no weak subjectivity verification.
     pub weak_subjectivity_checkpoint: Option<Checkpoint>,
+    /// Validators whose voluntary exits and slashings are never included in produced blocks.
+    pub block_production_deny_list: Vec<PublicKeyBytes>,
 }
 
 impl Default for ChainConfig {
@@ -22,6 +24,7 @@ impl Default for ChainConfig {
         Self {
             import_max_skip_slots: Some(DEFAULT_IMPORT_BLOCK_MAX_SKIP_SLOTS),
             weak_subjectivity_checkpoint: None,
+            block_production_deny_list: vec![],
         }
     }
 }
@@ -68,6 +68,7 @@ pub enum BeaconChainError {
     InvariantViolated(String),
     SszTypesError(SszTypesError),
     CanonicalHeadLockTimeout,
+    SnapshotCacheLockTimeout,
     AttestationCacheLockTimeout,
     ValidatorPubkeyCacheLockTimeout,
     IncorrectStateForAttestation(RelativeEpochError),
@@ -120,6 +121,9 @@ pub enum BlockProductionError {
     /// The `BeaconChain` was explicitly configured _without_ a connection to eth1, therefore it
     /// cannot produce blocks.
     NoEth1ChainConnection,
+    /// The randao reveal is not a valid signature by the proposer.
+    InvalidRandaoReveal,
+    ValidatorPubkeyCacheLockTimeout,
 }
 
 easy_from_to!(BlockProcessingError, BlockProductionError);
@@ -3,6 +3,7 @@ pub mod attestation_verification;
 mod beacon_chain;
 mod beacon_fork_choice_store;
 mod beacon_snapshot;
+mod block_production_policy;
 mod block_verification;
 pub mod builder;
 pub mod chain_config;
@@ -19,6 +20,7 @@ mod observed_block_producers;
 pub mod observed_operations;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
+mod persisted_naive_aggregation_pool;
 mod shuffling_cache;
 mod snapshot_cache;
 pub mod test_utils;
@@ -27,9 +29,10 @@ mod validator_pubkey_cache;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, BeaconChain, BeaconChainTypes, ChainSegmentResult,
-    ForkChoiceError, StateSkipConfig,
+    ForkChoiceError, StateSkipConfig, ETH1_CACHE_DB_KEY,
 };
 pub use self::beacon_snapshot::BeaconSnapshot;
+pub use self::block_production_policy::BlockProductionPolicy;
 pub use self::chain_config::ChainConfig;
 pub use self::errors::{BeaconChainError, BlockProductionError};
 pub use attestation_verification::Error as AttestationError;
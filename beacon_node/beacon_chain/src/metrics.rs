@@ -54,6 +54,10 @@ lazy_static! {
         "beacon_block_processing_attestation_observation_seconds",
         "Time spent hashing and remembering all the attestations in the block"
     );
+    pub static ref BLOCK_IMPORT_SLOT_START_DELAY_TIME: Result<Histogram> = try_create_histogram(
+        "beacon_block_import_slot_start_delay_seconds",
+        "Duration between the start of the block's slot and its import into fork choice"
+    );
 
     /*
      * Block Production
@@ -68,6 +72,26 @@ lazy_static! {
     );
     pub static ref BLOCK_PRODUCTION_TIMES: Result<Histogram> =
         try_create_histogram("beacon_block_production_seconds", "Full runtime of block production");
+    pub static ref BLOCK_PRODUCTION_ADVANCED_STATE_HITS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_advanced_state_hits_total",
+        "Count of blocks produced upon a state pre-computed by the state advance timer"
+    );
+    pub static ref BLOCK_PRODUCTION_EMPTY_BLOCKS: Result<IntCounter> = try_create_int_counter(
+        "beacon_block_production_empty_blocks_total",
+        "Count of blocks produced without attestations whilst the op pool contained attestations"
+    );
+
+    /*
+     * State Advance
+     */
+    pub static ref STATE_ADVANCE_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_state_advance_seconds",
+        "Time taken to advance the head state to the next slot ahead of time"
+    );
+    pub static ref STATE_ADVANCE_SKIPPED: Result<IntCounter> = try_create_int_counter(
+        "beacon_state_advance_skipped_total",
+        "Count of times the head state was not advanced because it was already advanced or missing from the snapshot cache"
+    );
 
     /*
      * Block Statistics
@@ -92,6 +116,10 @@ lazy_static! {
         "beacon_unaggregated_attestation_gossip_verification_seconds",
         "Full runtime of aggregated attestation gossip verification"
     );
+    pub static ref UNAGGREGATED_ATTESTATION_BATCH_GOSSIP_VERIFICATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_unaggregated_attestation_batch_gossip_verification_seconds",
+        "Full runtime of the gossip verification of a batch of unaggregated attestations"
+    );
 
     /*
      * Aggregated Attestation Verification
@@ -108,6 +136,10 @@ lazy_static! {
         "beacon_aggregated_attestation_gossip_verification_seconds",
         "Full runtime of aggregated attestation gossip verification"
     );
+    pub static ref AGGREGATED_ATTESTATION_BATCH_GOSSIP_VERIFICATION_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_aggregated_attestation_batch_gossip_verification_seconds",
+        "Full runtime of the gossip verification of a batch of aggregated attestations"
+    );
 
     /*
      * General Attestation Processing
@@ -172,6 +204,18 @@ lazy_static! {
         "beacon_attestation_processing_signature_seconds",
         "Time spent on the signature verification of attestation processing"
     );
+    pub static ref ATTESTATION_PROCESSING_BATCH_SIGNATURE_SETUP_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_attestation_processing_batch_signature_setup_seconds",
+        "Time spent on setting up for the signature verification of a batch of attestations"
+    );
+    pub static ref ATTESTATION_PROCESSING_BATCH_SIGNATURE_TIMES: Result<Histogram> = try_create_histogram(
+        "beacon_attestation_processing_batch_signature_seconds",
+        "Time spent on the signature verification of a batch of attestations"
+    );
+    pub static ref ATTESTATION_PROCESSING_BATCH_SIGNATURE_FAILURES: Result<IntCounter> = try_create_int_counter(
+        "beacon_attestation_processing_batch_signature_failures_total",
+        "Count of attestation batches with an invalid signature, requiring individual verification"
+    );
 
     /*
      * Shuffling cache
@@ -243,11 +287,27 @@ lazy_static! {
         try_create_histogram("beacon_persist_head", "Time taken to persist the canonical head");
     pub static ref PERSIST_OP_POOL: Result<Histogram> =
         try_create_histogram("beacon_persist_op_pool", "Time taken to persist the operations pool");
+    pub static ref PERSIST_NAIVE_AGGREGATION_POOL: Result<Histogram> =
+        try_create_histogram("beacon_persist_naive_aggregation_pool", "Time taken to persist the naive aggregation pool");
     pub static ref PERSIST_ETH1_CACHE: Result<Histogram> =
         try_create_histogram("beacon_persist_eth1_cache", "Time taken to persist the eth1 caches");
     pub static ref PERSIST_FORK_CHOICE: Result<Histogram> =
         try_create_histogram("beacon_persist_fork_choice", "Time taken to persist the fork choice struct");
 
+    /*
+     * Restoring BeaconChain components from disk
+     */
+    pub static ref RESTORED_POOL_OPERATIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_restored_pool_operations_total",
+        "Count of operations restored into the op pool and naive aggregation pool on startup",
+        &["kind"]
+    );
+    pub static ref RESTORED_POOL_OPERATIONS_DROPPED: Result<IntCounterVec> = try_create_int_counter_vec(
+        "beacon_restored_pool_operations_dropped_total",
+        "Count of persisted operations dropped on startup for being stale or invalid against the head state",
+        &["kind"]
+    );
+
     /*
      * Eth1
      */
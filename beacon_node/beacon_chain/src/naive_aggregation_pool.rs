@@ -116,6 +116,23 @@ impl<E: EthSpec> AggregatedAttestationMap<E> {
         }
     }
 
+    /// Insert an attestation that may already be aggregated into `self`, replacing any existing
+    /// attestation with the same `data`.
+    pub fn insert_aggregate(&mut self, a: &Attestation<E>) -> Result<(), Error> {
+        let attestation_data_root = a.data.tree_hash_root();
+
+        if !self.map.contains_key(&attestation_data_root)
+            && self.map.len() >= MAX_ATTESTATIONS_PER_SLOT
+        {
+            return Err(Error::ReachedMaxAttestationsPerSlot(
+                MAX_ATTESTATIONS_PER_SLOT,
+            ));
+        }
+
+        self.map.insert(attestation_data_root, a.clone());
+        Ok(())
+    }
+
     /// Returns an aggregated `Attestation` with the given `data`, if any.
     ///
     /// The given `a.data.slot` must match the slot that `self` was initialized with.
@@ -228,6 +245,31 @@ impl<E: EthSpec> NaiveAggregationPool<E> {
         outcome
     }
 
+    /// Insert an attestation that has already been aggregated, such as one that was persisted to
+    /// disk before a restart.
+    ///
+    /// Unlike `Self::insert`, the attestation may have any number of aggregation bits set.
+    pub fn insert_aggregate(&mut self, attestation: &Attestation<E>) -> Result<(), Error> {
+        let slot = attestation.data.slot;
+        let lowest_permissible_slot = self.lowest_permissible_slot;
+
+        if slot < lowest_permissible_slot {
+            return Err(Error::SlotTooLow {
+                slot,
+                lowest_permissible_slot,
+            });
+        }
+
+        if attestation.aggregation_bits.num_set_bits() == 0 {
+            return Err(Error::NoAggregationBitsSet);
+        }
+
+        self.maps
+            .entry(slot)
+            .or_insert_with(|| AggregatedAttestationMap::new(128))
+            .insert_aggregate(attestation)
+    }
+
     /// Returns an aggregated `Attestation` with the given `data`, if any.
     pub fn get(&self, data: &AttestationData) -> Option<Attestation<E>> {
         self.maps.get(&data.slot).and_then(|map| map.get(data))
@@ -369,6 +411,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn aggregate_attestation() {
+        let mut a = get_attestation(Slot::new(0));
+
+        let mut pool = NaiveAggregationPool::default();
+
+        assert_eq!(
+            pool.insert_aggregate(&a),
+            Err(Error::NoAggregationBitsSet),
+            "should not accept aggregate without any signatures"
+        );
+
+        let genesis_validators_root = Hash256::random();
+        sign(&mut a, 0, genesis_validators_root);
+        sign(&mut a, 1, genesis_validators_root);
+
+        assert_eq!(
+            pool.insert_aggregate(&a),
+            Ok(()),
+            "should accept aggregate with multiple signatures"
+        );
+        assert_eq!(
+            pool.get(&a.data),
+            Some(a.clone()),
+            "retrieved aggregate should equal the one inserted"
+        );
+
+        pool.prune(Slot::new(SLOTS_RETAINED as u64 + 1));
+
+        assert_eq!(
+            pool.insert_aggregate(&a),
+            Err(Error::SlotTooLow {
+                slot: Slot::new(0),
+                lowest_permissible_slot: Slot::new(1),
+            }),
+            "should not accept aggregate below the lowest permissible slot"
+        );
+    }
+
     #[test]
     fn multiple_attestations() {
         let mut a_0 = get_attestation(Slot::new(0));
@@ -0,0 +1,92 @@
+use crate::naive_aggregation_pool::NaiveAggregationPool;
+use ssz::{Decode, DecodeError, Encode};
+use ssz_derive::{Decode, Encode};
+use store::{DBColumn, Error as StoreError, StoreItem};
+use types::{Attestation, BeaconState, EthSpec, Slot};
+
+/// The version of the on-disk format of `PersistedNaiveAggregationPool`.
+///
+/// It is stored as a prefix (SSZ `u64`) to the SSZ-encoded pool. Increment this whenever the
+/// persisted format changes.
+pub const NAIVE_AGGREGATION_POOL_VERSION: u64 = 1;
+
+/// SSZ-serializable version of `NaiveAggregationPool`.
+#[derive(Encode, Decode)]
+pub struct PersistedNaiveAggregationPool<E: EthSpec> {
+    attestations: Vec<Attestation<E>>,
+}
+
+impl<E: EthSpec> PersistedNaiveAggregationPool<E> {
+    /// Convert a `NaiveAggregationPool` into serializable form.
+    pub fn from_naive_aggregation_pool(pool: &NaiveAggregationPool<E>) -> Self {
+        Self {
+            attestations: pool.iter().cloned().collect(),
+        }
+    }
+
+    /// Reconstruct a `NaiveAggregationPool` for the given `current_slot`.
+    ///
+    /// Attestations are only restored if they are recent enough to be retained by the pool and
+    /// match a committee in `head_state`. The number of attestations dropped is returned alongside
+    /// the pool.
+    pub fn into_naive_aggregation_pool(
+        self,
+        head_state: &BeaconState<E>,
+        current_slot: Slot,
+    ) -> (NaiveAggregationPool<E>, usize) {
+        let mut pool = NaiveAggregationPool::default();
+        pool.prune(current_slot);
+
+        let mut dropped = 0;
+        for attestation in &self.attestations {
+            let committee_ok = head_state
+                .get_beacon_committee(attestation.data.slot, attestation.data.index)
+                .map_or(false, |committee| {
+                    committee.committee.len() == attestation.aggregation_bits.len()
+                });
+
+            if !committee_ok
+                || attestation.data.slot > current_slot
+                || pool.insert_aggregate(attestation).is_err()
+            {
+                dropped += 1;
+            }
+        }
+
+        (pool, dropped)
+    }
+}
+
+impl<E: EthSpec> StoreItem for PersistedNaiveAggregationPool<E> {
+    fn db_column() -> DBColumn {
+        DBColumn::NaiveAggregationPool
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        let mut bytes = NAIVE_AGGREGATION_POOL_VERSION.as_ssz_bytes();
+        bytes.append(&mut self.as_ssz_bytes());
+        bytes
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        let version_len = <u64 as Decode>::ssz_fixed_len();
+        if bytes.len() < version_len {
+            return Err(DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: version_len,
+            }
+            .into());
+        }
+
+        let version = u64::from_ssz_bytes(&bytes[..version_len])?;
+        if version != NAIVE_AGGREGATION_POOL_VERSION {
+            return Err(DecodeError::BytesInvalid(format!(
+                "Unsupported naive aggregation pool version {}, expected {}",
+                version, NAIVE_AGGREGATION_POOL_VERSION
+            ))
+            .into());
+        }
+
+        Self::from_ssz_bytes(&bytes[version_len..]).map_err(Into::into)
+    }
+}
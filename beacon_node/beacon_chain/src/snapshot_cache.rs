@@ -1,10 +1,63 @@
 use crate::BeaconSnapshot;
 use std::cmp;
-use types::{Epoch, EthSpec, Hash256};
+use types::{BeaconState, CloneConfig, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
 /// The default size of the cache.
 pub const DEFAULT_SNAPSHOT_CACHE_SIZE: usize = 4;
 
+/// A snapshot which is ready to have a child block applied to it.
+///
+/// The `pre_state` is the state of `beacon_block`, which may have been advanced to a later slot by
+/// the state advance timer.
+pub struct PreProcessingSnapshot<T: EthSpec> {
+    pub pre_state: BeaconState<T>,
+    pub beacon_block: SignedBeaconBlock<T>,
+    pub beacon_block_root: Hash256,
+}
+
+impl<T: EthSpec> From<BeaconSnapshot<T>> for PreProcessingSnapshot<T> {
+    fn from(snapshot: BeaconSnapshot<T>) -> Self {
+        Self {
+            pre_state: snapshot.beacon_state,
+            beacon_block: snapshot.beacon_block,
+            beacon_block_root: snapshot.beacon_block_root,
+        }
+    }
+}
+
+/// The state of a snapshot, taken from the cache so it can be advanced by the state advance timer.
+pub struct StateAdvance<T: EthSpec> {
+    pub state: BeaconState<T>,
+    pub state_root: Hash256,
+}
+
+/// An entry in the cache: a `BeaconSnapshot` and, optionally, its state advanced to a later slot.
+struct CacheItem<T: EthSpec> {
+    snapshot: BeaconSnapshot<T>,
+    pre_state: Option<BeaconState<T>>,
+}
+
+impl<T: EthSpec> CacheItem<T> {
+    fn beacon_block_root(&self) -> Hash256 {
+        self.snapshot.beacon_block_root
+    }
+
+    fn slot(&self) -> Slot {
+        self.snapshot.beacon_state.slot
+    }
+
+    fn into_pre_processing_snapshot(self) -> PreProcessingSnapshot<T> {
+        match self.pre_state {
+            Some(pre_state) => PreProcessingSnapshot {
+                pre_state,
+                beacon_block: self.snapshot.beacon_block,
+                beacon_block_root: self.snapshot.beacon_block_root,
+            },
+            None => self.snapshot.into(),
+        }
+    }
+}
+
 /// Provides a cache of `BeaconSnapshot` that is intended primarily for block processing.
 ///
 /// ## Cache Queuing
@@ -17,10 +70,17 @@ pub const DEFAULT_SNAPSHOT_CACHE_SIZE: usize = 4;
 ///
 /// - Never be the `head_block_root`.
 /// - Be the snapshot with the lowest `state.slot` (ties broken arbitrarily).
+///
+/// ## State Advance
+///
+/// Each snapshot may also hold a "pre-state": a copy of its state that has been advanced to a
+/// later slot ahead of time (see `Self::take_state_for_advance` and `Self::update_pre_state`). The
+/// pre-state is used in place of the snapshot's state when applying a child block or producing a
+/// block, saving the cost of slot (and perhaps epoch) processing at the start of the slot.
 pub struct SnapshotCache<T: EthSpec> {
     max_len: usize,
     head_block_root: Hash256,
-    snapshots: Vec<BeaconSnapshot<T>>,
+    snapshots: Vec<CacheItem<T>>,
 }
 
 impl<T: EthSpec> SnapshotCache<T> {
@@ -31,23 +91,31 @@ impl<T: EthSpec> SnapshotCache<T> {
         Self {
             max_len: cmp::max(max_len, 1),
             head_block_root: head.beacon_block_root,
-            snapshots: vec![head],
+            snapshots: vec![CacheItem {
+                snapshot: head,
+                pre_state: None,
+            }],
         }
     }
 
     /// Insert a snapshot, potentially removing an existing snapshot if `self` is at capacity (see
     /// struct-level documentation for more info).
     pub fn insert(&mut self, snapshot: BeaconSnapshot<T>) {
+        let item = CacheItem {
+            snapshot,
+            pre_state: None,
+        };
+
         if self.snapshots.len() < self.max_len {
-            self.snapshots.push(snapshot);
+            self.snapshots.push(item);
         } else {
             let insert_at = self
                 .snapshots
                 .iter()
                 .enumerate()
-                .filter_map(|(i, snapshot)| {
-                    if snapshot.beacon_block_root != self.head_block_root {
-                        Some((i, snapshot.beacon_state.slot))
+                .filter_map(|(i, item)| {
+                    if item.beacon_block_root() != self.head_block_root {
+                        Some((i, item.slot()))
                     } else {
                         None
                     }
@@ -56,33 +124,85 @@ impl<T: EthSpec> SnapshotCache<T> {
                 .map(|(i, _slot)| i);
 
             if let Some(i) = insert_at {
-                self.snapshots[i] = snapshot;
+                self.snapshots[i] = item;
             }
         }
     }
 
     /// If there is a snapshot with `block_root`, remove and return it.
-    pub fn try_remove(&mut self, block_root: Hash256) -> Option<BeaconSnapshot<T>> {
+    ///
+    /// If the snapshot has been advanced by the state advance timer, the advanced state is
+    /// returned as the `pre_state`.
+    pub fn try_remove(&mut self, block_root: Hash256) -> Option<PreProcessingSnapshot<T>> {
         self.snapshots
             .iter()
-            .position(|snapshot| snapshot.beacon_block_root == block_root)
-            .map(|i| self.snapshots.remove(i))
+            .position(|item| item.beacon_block_root() == block_root)
+            .map(|i| self.snapshots.remove(i).into_pre_processing_snapshot())
     }
 
     /// If there is a snapshot with `block_root`, clone it (with only the committee caches) and
     /// return the clone.
+    ///
+    /// The state of the returned snapshot is never advanced.
     pub fn get_cloned(&self, block_root: Hash256) -> Option<BeaconSnapshot<T>> {
         self.snapshots
             .iter()
-            .find(|snapshot| snapshot.beacon_block_root == block_root)
-            .map(|snapshot| snapshot.clone_with_only_committee_caches())
+            .find(|item| item.beacon_block_root() == block_root)
+            .map(|item| item.snapshot.clone_with_only_committee_caches())
+    }
+
+    /// If the snapshot with `block_root` has a pre-state with a slot no later than `slot`, return a
+    /// full clone of it (including the tree hash cache) so a block can be produced upon it.
+    pub fn get_pre_state_for_block_production(
+        &self,
+        block_root: Hash256,
+        slot: Slot,
+    ) -> Option<BeaconState<T>> {
+        self.snapshots
+            .iter()
+            .find(|item| item.beacon_block_root() == block_root)
+            .and_then(|item| item.pre_state.as_ref())
+            .filter(|pre_state| pre_state.slot <= slot)
+            .map(|pre_state| pre_state.clone_with(CloneConfig::all()))
+    }
+
+    /// Take the state of the snapshot with `block_root` so that it can be advanced to a later slot,
+    /// leaving a clone with only the committee caches in its place.
+    ///
+    /// Returns `None` if there is no such snapshot, or if it has already been advanced.
+    pub fn take_state_for_advance(&mut self, block_root: Hash256) -> Option<StateAdvance<T>> {
+        let item = self
+            .snapshots
+            .iter_mut()
+            .find(|item| item.beacon_block_root() == block_root)
+            .filter(|item| item.pre_state.is_none())?;
+
+        let cloned = item
+            .snapshot
+            .beacon_state
+            .clone_with(CloneConfig::committee_caches_only());
+
+        Some(StateAdvance {
+            state: std::mem::replace(&mut item.snapshot.beacon_state, cloned),
+            state_root: item.snapshot.beacon_state_root,
+        })
+    }
+
+    /// Store `state` as the pre-state of the snapshot with `block_root`.
+    ///
+    /// Returns `None` if there is no such snapshot (e.g., it was removed whilst `state` was being
+    /// advanced), in which case `state` is dropped.
+    pub fn update_pre_state(&mut self, block_root: Hash256, state: BeaconState<T>) -> Option<()> {
+        self.snapshots
+            .iter_mut()
+            .find(|item| item.beacon_block_root() == block_root)
+            .map(|item| item.pre_state = Some(state))
     }
 
     /// Removes all snapshots from the queue that are less than or equal to the finalized epoch.
     pub fn prune(&mut self, finalized_epoch: Epoch) {
-        self.snapshots.retain(|snapshot| {
-            snapshot.beacon_state.slot > finalized_epoch.start_slot(T::slots_per_epoch())
-        })
+        self.snapshots
+            .retain(|item| item.slot() > finalized_epoch.start_slot(T::slots_per_epoch()))
     }
 
     /// Inform the cache that the head of the beacon chain has changed.
@@ -216,4 +336,62 @@ mod test {
             "try_remove should get the correct snapshot"
         );
     }
+
+    #[test]
+    fn state_advance() {
+        let mut cache = SnapshotCache::new(CACHE_SIZE, get_snapshot(0));
+        let root = Hash256::from_low_u64_be(0);
+
+        assert!(cache
+            .take_state_for_advance(Hash256::from_low_u64_be(1))
+            .is_none());
+
+        let advance = cache
+            .take_state_for_advance(root)
+            .expect("should take the state of a snapshot that is not advanced");
+        assert_eq!(advance.state_root, root);
+
+        let mut state = advance.state;
+        state.slot += 1;
+        let advanced_slot = state.slot;
+        cache
+            .update_pre_state(root, state)
+            .expect("the snapshot should still be in the cache");
+
+        assert!(
+            cache.take_state_for_advance(root).is_none(),
+            "the state should not be advanced twice"
+        );
+        assert!(
+            cache
+                .get_pre_state_for_block_production(root, advanced_slot - 1)
+                .is_none(),
+            "the pre-state should not be used for an earlier slot"
+        );
+        assert_eq!(
+            cache
+                .get_pre_state_for_block_production(root, advanced_slot)
+                .expect("should get the pre-state")
+                .slot,
+            advanced_slot
+        );
+        assert_eq!(
+            cache
+                .get_cloned(root)
+                .expect("the snapshot should be in the cache")
+                .beacon_state
+                .slot,
+            advanced_slot - 1,
+            "get_cloned should return the un-advanced state"
+        );
+        assert_eq!(
+            cache
+                .try_remove(root)
+                .expect("the snapshot should be in the cache")
+                .pre_state
+                .slot,
+            advanced_slot,
+            "try_remove should return the advanced state"
+        );
+    }
 }
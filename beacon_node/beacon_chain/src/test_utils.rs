@@ -8,7 +8,7 @@ use crate::{
     builder::{BeaconChainBuilder, Witness},
     eth1_chain::CachingEth1Backend,
     events::NullEventHandler,
-    BeaconChain, BeaconChainTypes, BlockError, ChainConfig, StateSkipConfig,
+    BeaconChain, BeaconChainTypes, BlockError, BlockProductionPolicy, ChainConfig, StateSkipConfig,
 };
 use futures::channel::mpsc::Receiver;
 use genesis::interop_genesis_state;
@@ -271,6 +271,24 @@ impl<E: EthSpec> BeaconChainHarness<DiskHarnessType<E>> {
         store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
         validator_keypairs: Vec<Keypair>,
         data_dir: TempDir,
+    ) -> Self {
+        Self::resume_from_disk_store_with_slot_clock(
+            eth_spec_instance,
+            store,
+            validator_keypairs,
+            data_dir,
+            None,
+        )
+    }
+
+    /// As `resume_from_disk_store`, but the resumed chain starts at the slot of `slot_clock`
+    /// rather than at genesis.
+    pub fn resume_from_disk_store_with_slot_clock(
+        eth_spec_instance: E,
+        store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+        validator_keypairs: Vec<Keypair>,
+        data_dir: TempDir,
+        slot_clock: Option<TestingSlotClock>,
     ) -> Self {
         let spec = E::default_spec();
 
@@ -288,12 +306,16 @@ impl<E: EthSpec> BeaconChainHarness<DiskHarnessType<E>> {
             .expect("should resume beacon chain from db")
             .dummy_eth1_backend()
             .expect("should build dummy backend")
-            .null_event_handler()
-            .testing_slot_clock(Duration::from_secs(1))
-            .expect("should configure testing slot clock")
-            .shutdown_sender(shutdown_tx)
-            .build()
-            .expect("should build");
+            .null_event_handler();
+        let chain = match slot_clock {
+            Some(slot_clock) => chain.slot_clock(slot_clock),
+            None => chain
+                .testing_slot_clock(Duration::from_secs(1))
+                .expect("should configure testing slot clock"),
+        }
+        .shutdown_sender(shutdown_tx)
+        .build()
+        .expect("should build");
 
         Self {
             spec: chain.spec.clone(),
@@ -410,7 +432,13 @@ where
 
         let (block, state) = self
             .chain
-            .produce_block_on_state(state, slot, randao_reveal, Some(graffiti))
+            .produce_block_on_state(
+                state,
+                slot,
+                randao_reveal,
+                Some(graffiti),
+                BlockProductionPolicy::default(),
+            )
             .unwrap();
 
         let signed_block = block.sign(
@@ -875,6 +875,140 @@ fn unaggregated_gossip_verification() {
     );
 }
 
+/// Tests the batch verification of attestations and aggregates from the gossip network, ensuring
+/// that a single invalid signature does not cause the rest of the batch to be rejected.
+#[test]
+fn batch_gossip_verification() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    // Extend the chain out a few epochs so we have some chain depth to play with.
+    harness.extend_chain(
+        MainnetEthSpec::slots_per_epoch() as usize * 3 - 1,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    // Advance into a slot where there have not been blocks or attestations produced.
+    harness.advance_slot();
+
+    let chain = &harness.chain;
+    let head = chain.head().expect("should get head");
+    let current_slot = chain.slot().expect("should get slot");
+
+    let committee = head
+        .beacon_state
+        .get_beacon_committee(current_slot, 0)
+        .expect("should get committee")
+        .committee
+        .to_vec();
+    assert!(committee.len() >= 8, "the test requires a larger committee");
+
+    let subnet_id = SubnetId::compute_subnet_for_attestation_data::<E>(
+        &chain
+            .produce_unaggregated_attestation(current_slot, 0)
+            .expect("should produce attestation")
+            .data,
+        head.beacon_state
+            .get_committee_count_at_slot(current_slot)
+            .expect("should get committee count"),
+        &chain.spec,
+    )
+    .expect("should get subnet_id");
+
+    // Produces an attestation for the committee member at `position`, signed by the validator at
+    // `signer_position`.
+    let attestation = |position: usize, signer_position: usize| {
+        let mut attestation = chain
+            .produce_unaggregated_attestation(current_slot, 0)
+            .expect("should produce attestation");
+        attestation
+            .sign(
+                &generate_deterministic_keypair(committee[signer_position]).sk,
+                position,
+                &head.beacon_state.fork,
+                chain.genesis_validators_root,
+                &chain.spec,
+            )
+            .expect("should sign attestation");
+        attestation
+    };
+
+    /*
+     * Unaggregated attestations.
+     */
+
+    let results = chain
+        .batch_verify_unaggregated_attestations_for_gossip(
+            vec![
+                attestation(0, 0),
+                attestation(1, 1),
+                attestation(2, 2),
+                // Signed by the wrong validator.
+                attestation(3, 4),
+                // A duplicate of an earlier attestation in the same batch.
+                attestation(0, 0),
+            ]
+            .into_iter()
+            .map(|attestation| (attestation, Some(subnet_id))),
+        )
+        .expect("should verify batch");
+
+    assert_eq!(results.len(), 5);
+    for result in &results[0..3] {
+        assert!(result.is_ok(), "valid attestation should be verified");
+    }
+    assert!(matches!(results[3], Err(AttnError::InvalidSignature)));
+    assert!(matches!(
+        results[4],
+        Err(AttnError::PriorAttestationKnown { validator_index, .. })
+            if validator_index == committee[0] as u64
+    ));
+
+    let results = chain
+        .batch_verify_unaggregated_attestations_for_gossip(
+            vec![attestation(4, 4), attestation(5, 5)]
+                .into_iter()
+                .map(|attestation| (attestation, Some(subnet_id))),
+        )
+        .expect("should verify batch");
+
+    assert!(
+        results.iter().all(Result::is_ok),
+        "a valid batch should be verified"
+    );
+
+    /*
+     * Aggregated attestations.
+     */
+
+    let (valid_aggregate, _aggregator_index, _aggregator_sk) =
+        get_valid_aggregated_attestation(chain, attestation(6, 6));
+
+    let mut invalid_aggregate = valid_aggregate.clone();
+    invalid_aggregate.signature = generate_deterministic_keypair(committee[7])
+        .sk
+        .sign(Hash256::from_low_u64_be(42));
+
+    let results = chain
+        .batch_verify_aggregated_attestations_for_gossip(
+            vec![
+                invalid_aggregate,
+                valid_aggregate.clone(),
+                valid_aggregate.clone(),
+            ]
+            .into_iter(),
+        )
+        .expect("should verify batch");
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[0], Err(AttnError::InvalidSignature)));
+    assert!(results[1].is_ok(), "valid aggregate should be verified");
+    assert!(matches!(
+        results[2],
+        Err(AttnError::AttestationAlreadyKnown(_))
+    ));
+}
+
 /// Ensures that an attestation that skips epochs can still be processed.
 ///
 /// This also checks that we can do a state lookup if we don't get a hit from the shuffling cache.
@@ -11,7 +11,7 @@ use sloggers::{null::NullLoggerBuilder, Build};
 use std::sync::Arc;
 use store::{HotColdDB, LevelDB, StoreConfig};
 use tempfile::{tempdir, TempDir};
-use types::{EthSpec, Keypair, MinimalEthSpec};
+use types::{Attestation, EthSpec, Keypair, MinimalEthSpec};
 
 type E = MinimalEthSpec;
 
@@ -80,6 +80,10 @@ fn finalizes_after_resuming_from_db() {
         .chain
         .persist_op_pool()
         .expect("should persist the op pool");
+    harness
+        .chain
+        .persist_naive_aggregation_pool()
+        .expect("should persist the naive aggregation pool");
     harness
         .chain
         .persist_eth1_cache()
@@ -137,6 +141,92 @@ fn finalizes_after_resuming_from_db() {
     );
 }
 
+#[test]
+fn restores_operation_pools_after_restart() {
+    let validator_count = 16;
+
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let harness = BeaconChainHarness::new_with_disk_store(
+        MinimalEthSpec,
+        store.clone(),
+        KEYPAIRS[0..validator_count].to_vec(),
+    );
+
+    harness.advance_slot();
+
+    harness.extend_chain(
+        (MinimalEthSpec::slots_per_epoch() * 2) as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+
+    assert!(
+        harness.chain.op_pool.num_attestations() > 0,
+        "the op pool should not be empty"
+    );
+    let naive_attestations = naive_aggregation_pool_contents(&harness.chain);
+    assert!(
+        !naive_attestations.is_empty(),
+        "the naive aggregation pool should not be empty"
+    );
+
+    harness
+        .chain
+        .persist_head_and_fork_choice()
+        .expect("should persist the head and fork choice");
+    harness
+        .chain
+        .persist_op_pool()
+        .expect("should persist the op pool");
+    harness
+        .chain
+        .persist_naive_aggregation_pool()
+        .expect("should persist the naive aggregation pool");
+
+    let data_dir = harness.data_dir;
+    let original_chain = harness.chain;
+
+    // Resume at the current slot, so that no restored attestation is too old or too new.
+    let resumed_harness = BeaconChainHarness::resume_from_disk_store_with_slot_clock(
+        MinimalEthSpec,
+        store,
+        KEYPAIRS[0..validator_count].to_vec(),
+        data_dir,
+        Some(original_chain.slot_clock.clone()),
+    );
+
+    assert_eq!(
+        original_chain.op_pool, resumed_harness.chain.op_pool,
+        "op_pool should be restored"
+    );
+
+    let restored_attestations = naive_aggregation_pool_contents(&resumed_harness.chain);
+    assert_eq!(
+        naive_attestations.len(),
+        restored_attestations.len(),
+        "every naive aggregation pool attestation should be restored"
+    );
+    for attestation in &naive_attestations {
+        assert!(
+            restored_attestations.contains(attestation),
+            "naive aggregation pool should be restored"
+        );
+    }
+}
+
+fn naive_aggregation_pool_contents<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> Vec<Attestation<T::EthSpec>> {
+    chain
+        .naive_aggregation_pool
+        .read()
+        .iter()
+        .cloned()
+        .collect()
+}
+
 /// Checks that two chains are the same, for the purpose of this tests.
 ///
 /// Several fields that are hard/impossible to check are ignored (e.g., the store).
@@ -156,8 +246,8 @@ fn assert_chains_pretty_much_the_same<T: BeaconChainTypes>(a: &BeaconChain<T>, b
 
     let slot = a.slot().unwrap();
     assert!(
-        a.fork_choice.write().get_head(slot).unwrap()
-            == b.fork_choice.write().get_head(slot).unwrap(),
+        a.fork_choice.write().get_head(slot, &a.spec).unwrap()
+            == b.fork_choice.write().get_head(slot, &b.spec).unwrap(),
         "fork_choice heads should be equal"
     );
 }
@@ -616,3 +616,54 @@ fn produces_and_processes_with_genesis_skip_slots() {
         run_skip_slot_test(i)
     }
 }
+
+#[test]
+fn imports_blocks_upon_advanced_head_state() {
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch();
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    // Build up to the last slot of the first epoch, so that the first state advance crosses an
+    // epoch boundary.
+    harness.extend_chain(
+        (slots_per_epoch - 1) as usize,
+        BlockStrategy::OnCanonicalHead,
+        AttestationStrategy::AllValidators,
+    );
+    assert_eq!(harness.chain.slot().unwrap(), slots_per_epoch - 1);
+
+    // Advance the head state into the next epoch, then import a block upon it. Advancing twice
+    // should be a no-op.
+    harness.chain.advance_head_state().unwrap();
+    harness.chain.advance_head_state().unwrap();
+
+    let slot = Slot::new(slots_per_epoch);
+    let (block_hash, _, _) = harness
+        .add_block_at_slot(slot, harness.get_current_state())
+        .unwrap();
+    let head_info = harness.chain.head_info().unwrap();
+    assert_eq!(head_info.block_root, Hash256::from(block_hash));
+    assert_eq!(head_info.slot, slot);
+
+    // Advance the head state by a slot, then import a block one slot later. The advanced state
+    // is now a skipped slot and must be stored.
+    harness.chain.advance_head_state().unwrap();
+
+    let skipped_slot = slot + 1;
+    let slot = slot + 2;
+    let (block_hash, _, _) = harness
+        .add_block_at_slot(slot, harness.get_current_state())
+        .unwrap();
+    let head = harness.chain.head().unwrap();
+    assert_eq!(head.beacon_block_root, Hash256::from(block_hash));
+    assert_eq!(head.beacon_block.slot(), slot);
+
+    let skipped_state_root = *head.beacon_state.get_state_root(skipped_slot).unwrap();
+    assert!(
+        harness
+            .chain
+            .get_state(&skipped_state_root, Some(skipped_slot))
+            .unwrap()
+            .is_some(),
+        "the state of the skipped slot should be stored"
+    );
+}
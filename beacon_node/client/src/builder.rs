@@ -16,7 +16,9 @@ use eth2_libp2p::NetworkGlobals;
 use genesis::{interop_genesis_state, Eth1GenesisService};
 use network::{NetworkConfig, NetworkMessage, NetworkService};
 use parking_lot::Mutex;
-use slog::{debug, info};
+use slasher::Slasher;
+use slasher_service::SlasherService;
+use slog::{debug, info, o};
 use ssz::Decode;
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
@@ -63,6 +65,8 @@ pub struct ClientBuilder<T: BeaconChainTypes> {
     http_api_config: http_api::Config,
     http_metrics_config: http_metrics::Config,
     websocket_listen_addr: Option<SocketAddr>,
+    #[allow(clippy::type_complexity)]
+    head_events: Option<Arc<Mutex<Bus<SignedBeaconBlockHash>>>>,
     eth_spec_instance: T::EthSpec,
 }
 
@@ -96,6 +100,7 @@ where
             http_api_config: <_>::default(),
             http_metrics_config: <_>::default(),
             websocket_listen_addr: None,
+            head_events: None,
             eth_spec_instance,
         }
     }
@@ -127,6 +132,7 @@ where
         let disabled_forks = config.disabled_forks.clone();
         let chain_config = config.chain.clone();
         let graffiti = config.graffiti;
+        let slasher_config = config.slasher.clone();
 
         let store =
             store.ok_or_else(|| "beacon_chain_start_method requires a store".to_string())?;
@@ -145,6 +151,17 @@ where
             .disabled_forks(disabled_forks)
             .graffiti(graffiti);
 
+        let builder = if let Some(slasher_config) = slasher_config {
+            let slasher = Slasher::open(
+                slasher_config,
+                context.log().new(o!("service" => "slasher")),
+            )
+            .map_err(|e| format!("Unable to open slasher database: {:?}", e))?;
+            builder.slasher(Arc::new(slasher))
+        } else {
+            builder
+        };
+
         let chain_exists = builder
             .store_contains_beacon_chain()
             .unwrap_or_else(|_| false);
@@ -267,6 +284,28 @@ where
         Ok(self)
     }
 
+    /// Immediately starts the slasher service, if the beacon chain has a slasher.
+    pub fn slasher_service(self) -> Result<Self, String> {
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or_else(|| "slasher service requires a beacon chain")?;
+
+        if beacon_chain.slasher.is_none() {
+            return Ok(self);
+        }
+
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or_else(|| "slasher requires a runtime_context")?
+            .service_context("slasher_service_ctxt".into());
+
+        SlasherService::new(beacon_chain).run(&context.executor)?;
+
+        Ok(self)
+    }
+
     /// Provides configuration for the HTTP API.
     pub fn http_api_config(mut self, config: http_api::Config) -> Self {
         self.http_api_config = config;
@@ -334,6 +373,8 @@ where
                 chain: self.beacon_chain.clone(),
                 network_tx: self.network_send.clone(),
                 network_globals: self.network_globals.clone(),
+                log_filter: runtime_context.log_filter.clone(),
+                head_events: self.head_events.clone(),
                 log: log.clone(),
             });
 
@@ -472,6 +513,7 @@ where
         self.websocket_listen_addr = listening_addr;
         let (tee_event_handler, bus) = TeeEventHandler::new(log, sender)?;
         self.event_handler = Some(tee_event_handler);
+        self.head_events = Some(bus.clone());
         Ok((self, bus))
     }
 }
@@ -643,7 +685,16 @@ where
     TColdStore: ItemStore<TEthSpec> + 'static,
 {
     /// Specifies that the slot clock should read the time from the computers system clock.
-    pub fn system_time_slot_clock(mut self) -> Result<Self, String> {
+    pub fn system_time_slot_clock(self) -> Result<Self, String> {
+        self.system_time_slot_clock_with_skew(0)
+    }
+
+    /// Specifies that the slot clock should read the time from the computers system clock, offset
+    /// by `skew_millis`.
+    ///
+    /// A positive skew makes the clock run ahead of the system clock, whilst a negative skew makes
+    /// it lag behind. Only intended for testing.
+    pub fn system_time_slot_clock_with_skew(mut self, skew_millis: i64) -> Result<Self, String> {
         let beacon_chain_builder = self
             .beacon_chain_builder
             .as_ref()
@@ -658,9 +709,20 @@ where
             .clone()
             .ok_or_else(|| "system_time_slot_clock requires a chain spec".to_string())?;
 
+        // A clock that runs ahead is equivalent to one with an earlier genesis, and vice versa.
+        let genesis_duration = Duration::from_secs(genesis_time);
+        let skew = Duration::from_millis(skew_millis.abs() as u64);
+        let genesis_duration = if skew_millis >= 0 {
+            genesis_duration
+                .checked_sub(skew)
+                .ok_or_else(|| "Slot clock skew exceeds genesis time".to_string())?
+        } else {
+            genesis_duration + skew
+        };
+
         let slot_clock = SystemTimeSlotClock::new(
             spec.genesis_slot,
-            Duration::from_secs(genesis_time),
+            genesis_duration,
             Duration::from_millis(spec.milliseconds_per_slot),
         );
 
@@ -67,6 +67,12 @@ pub struct Config {
     pub eth1: eth1::Config,
     pub http_api: http_api::Config,
     pub http_metrics: http_metrics::Config,
+    /// Configuration for the slasher, which is disabled if `None`.
+    pub slasher: Option<slasher::Config>,
+    /// Offsets the slot clock from the system clock by this many milliseconds, simulating a node
+    /// with an inaccurate clock. Only intended for testing.
+    #[serde(skip)]
+    pub slot_clock_skew_millis: i64,
 }
 
 impl Default for Config {
@@ -89,6 +95,8 @@ impl Default for Config {
             graffiti: Graffiti::default(),
             http_api: <_>::default(),
             http_metrics: <_>::default(),
+            slasher: None,
+            slot_clock_skew_millis: 0,
         }
     }
 }
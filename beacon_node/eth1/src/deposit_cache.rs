@@ -122,6 +122,11 @@ impl DepositCache {
         self.logs.is_empty()
     }
 
+    /// Returns the block number at which the deposit contract was deployed.
+    pub fn deposit_contract_deploy_block(&self) -> u64 {
+        self.deposit_contract_deploy_block
+    }
+
     /// Returns the block number for the most recent deposit in the cache.
     pub fn latest_block_number(&self) -> Option<u64> {
         self.logs.last().map(|log| log.block_number)
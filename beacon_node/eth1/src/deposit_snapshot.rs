@@ -0,0 +1,242 @@
+//! Provides a portable file format for the `DepositCache`.
+//!
+//! A snapshot allows a new node to bootstrap its deposit cache from a file instead of
+//! downloading every deposit log since the deployment of the deposit contract.
+
+use crate::deposit_cache::{DepositCache, SszDepositCache};
+use crate::http::{get_deposit_count, get_deposit_root};
+use crate::Config;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use types::Hash256;
+
+/// The version of the snapshot file format.
+///
+/// It is stored as a prefix (SSZ `u64`) to the SSZ-encoded snapshot. Increment this whenever the
+/// format changes.
+pub const DEPOSIT_SNAPSHOT_VERSION: u64 = 1;
+
+/// Timeout when reading the deposit root and count from the eth1 node.
+const VERIFY_TIMEOUT_MILLIS: u64 = 15_000;
+
+/// All deposit logs up to and including the eth1 block `block_number`, along with the deposit
+/// root and deposit count of the deposit contract at that block.
+#[derive(Encode, Decode, Clone)]
+pub struct DepositSnapshot {
+    pub block_number: u64,
+    pub deposit_root: Hash256,
+    pub deposit_count: u64,
+    deposit_cache: SszDepositCache,
+}
+
+impl DepositSnapshot {
+    /// Creates a snapshot of all deposits in `cache` as at the eth1 block `block_number`.
+    ///
+    /// All deposits in `cache` must have been included at or before `block_number`.
+    pub fn from_deposit_cache(cache: &DepositCache, block_number: u64) -> Result<Self, String> {
+        if cache
+            .latest_block_number()
+            .map_or(false, |latest| latest > block_number)
+        {
+            return Err(format!(
+                "Deposit cache contains deposits after block {}",
+                block_number
+            ));
+        }
+
+        let deposit_root = cache
+            .get_deposit_root_from_cache(block_number)
+            .ok_or_else(|| format!("No deposit root known for block {}", block_number))?;
+
+        Ok(Self {
+            block_number,
+            deposit_root,
+            deposit_count: cache.len() as u64,
+            deposit_cache: SszDepositCache::from_deposit_cache(cache),
+        })
+    }
+
+    /// Rebuilds the `DepositCache`, checking that its deposit root and count match those of the
+    /// snapshot.
+    pub fn to_deposit_cache(&self) -> Result<DepositCache, String> {
+        let cache = self.deposit_cache.to_deposit_cache()?;
+
+        if cache.len() as u64 != self.deposit_count {
+            return Err(format!(
+                "Snapshot contains {} deposits but claims {}",
+                cache.len(),
+                self.deposit_count
+            ));
+        }
+
+        let deposit_root = cache.get_deposit_root_from_cache(self.block_number);
+        if deposit_root != Some(self.deposit_root) {
+            return Err(format!(
+                "Snapshot deposit root {:?} does not match its deposits ({:?})",
+                self.deposit_root, deposit_root
+            ));
+        }
+
+        Ok(cache)
+    }
+
+    /// Checks the deposit root and count against those reported by the deposit contract on the
+    /// eth1 node in `config`.
+    ///
+    /// The contract is queried at `self.block_number`, so the eth1 node must still have the state
+    /// of that block. Nodes which are not archive nodes prune the state of old blocks.
+    pub async fn verify(&self, config: &Config) -> Result<(), String> {
+        let timeout = Duration::from_millis(VERIFY_TIMEOUT_MILLIS);
+
+        let deposit_root = get_deposit_root(
+            &config.endpoint,
+            &config.deposit_contract_address,
+            self.block_number,
+            timeout,
+        )
+        .await
+        .map_err(|e| {
+            format!(
+                "Unable to query the deposit contract at block {}, the eth1 node may have \
+                 pruned its state (try an archive node): {}",
+                self.block_number, e
+            )
+        })?;
+        if deposit_root != Some(self.deposit_root) {
+            return Err(format!(
+                "Snapshot deposit root {:?} does not match the eth1 node ({:?}) at block {}",
+                self.deposit_root, deposit_root, self.block_number
+            ));
+        }
+
+        let deposit_count = get_deposit_count(
+            &config.endpoint,
+            &config.deposit_contract_address,
+            self.block_number,
+            timeout,
+        )
+        .await?;
+        if deposit_count != Some(self.deposit_count) {
+            return Err(format!(
+                "Snapshot deposit count {} does not match the eth1 node ({:?}) at block {}",
+                self.deposit_count, deposit_count, self.block_number
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Encodes `self` with a version prefix.
+    pub fn as_file_bytes(&self) -> Vec<u8> {
+        let mut bytes = DEPOSIT_SNAPSHOT_VERSION.as_ssz_bytes();
+        bytes.append(&mut self.as_ssz_bytes());
+        bytes
+    }
+
+    /// Decodes bytes produced by `Self::as_file_bytes`.
+    pub fn from_file_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let version_len = <u64 as Decode>::ssz_fixed_len();
+        if bytes.len() < version_len {
+            return Err("Deposit snapshot is too short".to_string());
+        }
+
+        let version = u64::from_ssz_bytes(&bytes[..version_len])
+            .map_err(|e| format!("Unable to decode deposit snapshot version: {:?}", e))?;
+        if version != DEPOSIT_SNAPSHOT_VERSION {
+            return Err(format!(
+                "Unsupported deposit snapshot version {}, expected {}",
+                version, DEPOSIT_SNAPSHOT_VERSION
+            ));
+        }
+
+        Self::from_ssz_bytes(&bytes[version_len..])
+            .map_err(|e| format!("Unable to decode deposit snapshot: {:?}", e))
+    }
+
+    /// Writes `self` to the file at `path`, replacing any existing file.
+    pub fn to_file(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.as_file_bytes())
+            .map_err(|e| format!("Unable to write {:?}: {}", path, e))
+    }
+
+    /// Reads a snapshot from the file at `path`.
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| format!("Unable to read {:?}: {}", path, e))?;
+        Self::from_file_bytes(&bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deposit_log::tests::EXAMPLE_LOG;
+    use crate::http::Log;
+    use crate::DepositLog;
+    use types::{EthSpec, MainnetEthSpec};
+
+    fn deposit_cache(num_logs: u64) -> DepositCache {
+        let spec = MainnetEthSpec::default_spec();
+        let mut cache = DepositCache::default();
+
+        for i in 0..num_logs {
+            let log = Log {
+                block_number: 42 + i,
+                data: EXAMPLE_LOG.to_vec(),
+            };
+            let mut log = DepositLog::from_log(&log, &spec).expect("should decode log");
+            log.index = i;
+            cache.insert_log(log).expect("should insert log");
+        }
+
+        cache
+    }
+
+    #[test]
+    fn round_trip() {
+        let cache = deposit_cache(8);
+        let snapshot = DepositSnapshot::from_deposit_cache(&cache, 100).expect("should snapshot");
+
+        assert_eq!(snapshot.deposit_count, 8);
+
+        let decoded = DepositSnapshot::from_file_bytes(&snapshot.as_file_bytes())
+            .expect("should decode snapshot");
+        let restored = decoded.to_deposit_cache().expect("should rebuild cache");
+
+        assert_eq!(restored.len(), cache.len());
+        assert_eq!(
+            restored.get_deposit_root_from_cache(100),
+            cache.get_deposit_root_from_cache(100)
+        );
+    }
+
+    #[test]
+    fn deposits_after_block_number_are_rejected() {
+        let cache = deposit_cache(8);
+
+        assert!(DepositSnapshot::from_deposit_cache(&cache, 45).is_err());
+    }
+
+    #[test]
+    fn mismatched_deposit_root_is_rejected() {
+        let cache = deposit_cache(8);
+        let mut snapshot =
+            DepositSnapshot::from_deposit_cache(&cache, 100).expect("should snapshot");
+        snapshot.deposit_root = Hash256::repeat_byte(42);
+
+        assert!(snapshot.to_deposit_cache().is_err());
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let cache = deposit_cache(1);
+        let snapshot = DepositSnapshot::from_deposit_cache(&cache, 100).expect("should snapshot");
+
+        let mut bytes = (DEPOSIT_SNAPSHOT_VERSION + 1).as_ssz_bytes();
+        bytes.append(&mut snapshot.as_ssz_bytes());
+
+        assert!(DepositSnapshot::from_file_bytes(&bytes).is_err());
+    }
+}
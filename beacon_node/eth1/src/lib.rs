@@ -4,6 +4,7 @@ extern crate lazy_static;
 mod block_cache;
 mod deposit_cache;
 mod deposit_log;
+mod deposit_snapshot;
 pub mod http;
 mod inner;
 mod metrics;
@@ -12,6 +13,7 @@ mod service;
 pub use block_cache::{BlockCache, Eth1Block};
 pub use deposit_cache::DepositCache;
 pub use deposit_log::DepositLog;
+pub use deposit_snapshot::{DepositSnapshot, DEPOSIT_SNAPSHOT_VERSION};
 pub use inner::SszEth1Cache;
 pub use service::{
     BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Service, DEFAULT_NETWORK_ID,
@@ -6,7 +6,7 @@ use crate::{
         get_block, get_block_number, get_deposit_logs_in_range, get_network_id, Eth1NetworkId, Log,
     },
     inner::{DepositUpdater, Inner},
-    DepositLog,
+    DepositLog, DepositSnapshot,
 };
 use futures::{future::TryFutureExt, stream, stream::TryStreamExt, StreamExt};
 use parking_lot::{RwLock, RwLockReadGuard};
@@ -184,6 +184,51 @@ impl Service {
         &self.inner.deposit_cache
     }
 
+    /// Returns a snapshot of the deposit cache, up to the last eth1 block that was processed.
+    pub fn deposit_snapshot(&self) -> Result<DepositSnapshot, String> {
+        let deposits = self.deposits().read();
+        let block_number = deposits
+            .last_processed_block
+            .ok_or_else(|| "The deposit cache has not processed any blocks".to_string())?;
+
+        DepositSnapshot::from_deposit_cache(&deposits.cache, block_number)
+    }
+
+    /// Replaces the deposit cache with the deposits in `snapshot`.
+    ///
+    /// The snapshot is not checked against the eth1 node, see `DepositSnapshot::verify`. Returns
+    /// an error if the snapshot is for a different deposit contract deployment or if the deposit
+    /// cache is already at or beyond the snapshot.
+    pub fn import_deposit_snapshot(&self, snapshot: &DepositSnapshot) -> Result<(), String> {
+        let cache = snapshot.to_deposit_cache()?;
+
+        let deploy_block = self.config().deposit_contract_deploy_block;
+        if cache.deposit_contract_deploy_block() != deploy_block {
+            return Err(format!(
+                "Snapshot deposit contract deploy block {} does not match configured block {}",
+                cache.deposit_contract_deploy_block(),
+                deploy_block
+            ));
+        }
+
+        let mut deposits = self.deposits().write();
+        if let Some(last_processed_block) = deposits.last_processed_block {
+            if last_processed_block >= snapshot.block_number {
+                return Err(format!(
+                    "Deposit cache has already processed block {}, snapshot is at block {}",
+                    last_processed_block, snapshot.block_number
+                ));
+            }
+        }
+
+        *deposits = DepositUpdater {
+            cache,
+            last_processed_block: Some(snapshot.block_number),
+        };
+
+        Ok(())
+    }
+
     /// Removes all blocks from the cache, except for the latest block.
     ///
     /// We don't remove the latest blocks so we don't lose track of the latest block.
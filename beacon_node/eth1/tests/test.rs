@@ -434,6 +434,90 @@ mod deposit_tree {
         assert_eq!(service.deposit_cache_len(), n);
     }
 
+    #[tokio::test]
+    async fn snapshot_round_trip() {
+        let n = 8;
+
+        let eth1 = GanacheEth1Instance::new()
+            .await
+            .expect("should start eth1 environment");
+        let deposit_contract = &eth1.deposit_contract;
+        let web3 = eth1.web3();
+
+        let start_block = get_block_number(&web3).await;
+        let config = Config {
+            endpoint: eth1.endpoint(),
+            deposit_contract_address: deposit_contract.address(),
+            deposit_contract_deploy_block: start_block,
+            lowest_cached_block_number: start_block,
+            follow_distance: 0,
+            ..Config::default()
+        };
+
+        let service = Service::new(
+            config.clone(),
+            null_logger(),
+            MainnetEthSpec::default_spec(),
+        );
+
+        for _ in 0..n {
+            deposit_contract
+                .deposit(random_deposit_data())
+                .await
+                .expect("should perform a deposit");
+        }
+
+        service
+            .update_deposit_cache()
+            .await
+            .expect("should perform update");
+
+        let snapshot = service.deposit_snapshot().expect("should export snapshot");
+        assert_eq!(snapshot.deposit_count, n);
+        snapshot
+            .verify(&config)
+            .await
+            .expect("should verify against the deposit contract");
+
+        let imported = Service::new(config, null_logger(), MainnetEthSpec::default_spec());
+        imported
+            .import_deposit_snapshot(&snapshot)
+            .expect("should import snapshot");
+
+        let block_number = snapshot.block_number;
+        let deposit_root = imported
+            .deposits()
+            .read()
+            .cache
+            .get_deposit_root_from_cache(block_number);
+        assert_eq!(
+            deposit_root,
+            service
+                .deposits()
+                .read()
+                .cache
+                .get_deposit_root_from_cache(block_number)
+        );
+        assert_eq!(
+            deposit_root,
+            blocking_deposit_root(&eth1, block_number).await
+        );
+
+        // The snapshot cannot be imported twice.
+        assert!(imported.import_deposit_snapshot(&snapshot).is_err());
+
+        // The imported cache continues from the snapshot block.
+        deposit_contract
+            .deposit(random_deposit_data())
+            .await
+            .expect("should perform a deposit");
+        imported
+            .update_deposit_cache()
+            .await
+            .expect("should update the imported cache");
+        assert_eq!(imported.deposit_cache_len(), n as usize + 1);
+    }
+
     #[tokio::test]
     async fn cache_consistency() {
         let n = 8;
@@ -1,3 +1,4 @@
+use crate::transport_faults::TransportFaults;
 use crate::types::{GossipKind, MessageData};
 use crate::{Enr, PeerIdSerialized};
 use directory::{
@@ -51,6 +52,21 @@ pub struct Config {
     /// Target number of connected peers.
     pub target_peers: usize,
 
+    /// The initial receive window of a yamux stream, in bytes.
+    pub yamux_receive_window: u32,
+
+    /// The maximum number of bytes buffered per yamux stream.
+    pub yamux_max_buffer_size: usize,
+
+    /// The maximum number of concurrent yamux streams per connection.
+    pub yamux_max_streams: usize,
+
+    /// The maximum number of concurrent mplex streams per connection.
+    pub mplex_max_streams: usize,
+
+    /// The maximum number of frames buffered per mplex stream.
+    pub mplex_max_buffer_size: usize,
+
     /// Gossipsub configuration parameters.
     #[serde(skip)]
     pub gs_config: GossipsubConfig,
@@ -80,8 +96,19 @@ pub struct Config {
     /// Attempt to construct external port mappings with UPnP.
     pub upnp_enabled: bool,
 
+    /// Subscribe to all attestation subnets for the lifetime of the node.
+    pub subscribe_all_subnets: bool,
+
+    /// Import all unaggregated attestations received on gossip, not just those from subnets that
+    /// local validators are aggregating for.
+    pub import_all_attestations: bool,
+
     /// List of extra topics to initially subscribe to as strings.
     pub topics: Vec<GossipKind>,
+
+    /// Faults to inject into the libp2p transport. Only intended for testing.
+    #[serde(skip)]
+    pub transport_faults: Option<TransportFaults>,
 }
 
 impl Default for Config {
@@ -170,6 +197,11 @@ impl Default for Config {
             enr_udp_port: None,
             enr_tcp_port: None,
             target_peers: 50,
+            yamux_receive_window: 256 * 1024,
+            yamux_max_buffer_size: 1024 * 1024,
+            yamux_max_streams: 8192,
+            mplex_max_streams: 128,
+            mplex_max_buffer_size: 32,
             gs_config,
             discv5_config,
             boot_nodes_enr: vec![],
@@ -179,7 +211,10 @@ impl Default for Config {
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
             upnp_enabled: true,
+            subscribe_all_subnets: false,
+            import_all_attestations: false,
             topics: Vec::new(),
+            transport_faults: None,
         }
     }
 }
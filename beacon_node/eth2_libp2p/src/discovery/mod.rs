@@ -5,7 +5,7 @@ pub mod enr_ext;
 // Allow external use of the lighthouse ENR builder
 pub use enr::{build_enr, create_enr_builder_from_config, use_or_load_enr, CombinedKey, Eth2Enr};
 pub use enr_ext::{peer_id_to_node_id, CombinedKeyExt, EnrExt};
-pub use libp2p::core::identity::{Keypair, PublicKey};
+pub use libp2p::core::identity::{secp256k1, Keypair, PublicKey};
 
 use crate::metrics;
 use crate::{error, Enr, NetworkConfig, NetworkGlobals, SubnetDiscovery};
@@ -12,6 +12,7 @@ mod metrics;
 mod peer_manager;
 pub mod rpc;
 mod service;
+mod transport_faults;
 pub mod types;
 
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
@@ -73,3 +74,4 @@ pub use peer_manager::{
     PeerSyncStatus, SyncInfo,
 };
 pub use service::{load_private_key, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
+pub use transport_faults::TransportFaults;
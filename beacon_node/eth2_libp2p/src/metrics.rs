@@ -13,6 +13,11 @@ lazy_static! {
         "libp2p_peer_connect_event_total",
         "Count of libp2p peer connect events (not the current number of connected peers)"
     );
+    pub static ref MUXER_CONNECTIONS: Result<IntCounterVec> = try_create_int_counter_vec(
+        "libp2p_muxer_connections_total",
+        "Count of libp2p connections established, by negotiated stream multiplexer",
+        &["muxer"]
+    );
     pub static ref PEER_DISCONNECT_EVENT_COUNT: Result<IntCounter> = try_create_int_counter(
         "libp2p_peer_disconnect_event_total",
         "Count of libp2p peer disconnect events"
@@ -2,14 +2,17 @@ use crate::behaviour::{
     save_metadata_to_disk, Behaviour, BehaviourEvent, PeerRequestId, Request, Response,
 };
 use crate::discovery::enr;
+use crate::metrics;
 use crate::multiaddr::Protocol;
 use crate::rpc::{GoodbyeReason, MetaData, RPCResponseErrorCode, RequestId};
+use crate::transport_faults::FaultyStream;
 use crate::types::{error, EnrBitfield, GossipKind};
 use crate::EnrExt;
 use crate::{NetworkConfig, NetworkGlobals, PeerAction};
 use futures::prelude::*;
 use libp2p::core::{
-    identity::Keypair, multiaddr::Multiaddr, muxing::StreamMuxerBox, transport::boxed::Boxed,
+    either::EitherOutput, identity::Keypair, multiaddr::Multiaddr, muxing::StreamMuxerBox,
+    transport::boxed::Boxed, upgrade::SelectUpgrade,
 };
 use libp2p::{
     core, noise,
@@ -102,7 +105,7 @@ impl<TSpec: EthSpec> Service<TSpec> {
 
         let mut swarm = {
             // Set up the transport - tcp/ws with noise and mplex
-            let transport = build_transport(local_keypair.clone())
+            let transport = build_transport(local_keypair.clone(), config)
                 .map_err(|e| format!("Failed to build transport: {:?}", e))?;
             // Lighthouse network behaviour
             let behaviour =
@@ -321,10 +324,13 @@ impl<TSpec: EthSpec> Service<TSpec> {
     }
 }
 
-/// The implementation supports TCP/IP, WebSockets over TCP/IP, noise as the encryption layer, and
-/// mplex as the multiplexing layer.
+/// Builds the TCP (and optionally websocket) transport, secured with noise.
+///
+/// Both yamux and mplex are offered as stream multiplexers, with yamux preferred. Mplex is kept for
+/// peers that do not support yamux.
 fn build_transport(
     local_private_key: Keypair,
+    config: &NetworkConfig,
 ) -> Result<Boxed<(PeerId, StreamMuxerBox), Error>, Error> {
     let transport = libp2p::tcp::TokioTcpConfig::new().nodelay(true);
     let transport = libp2p::dns::DnsConfig::new(transport)?;
@@ -333,12 +339,37 @@ fn build_transport(
         let trans_clone = transport.clone();
         transport.or_transport(libp2p::websocket::WsConfig::new(trans_clone))
     };
+    // Faults are only injected when explicitly configured (i.e., during simulations). Otherwise,
+    // `FaultyStream` simply passes through to the underlying connection.
+    let faults = config.transport_faults.clone();
+    let transport =
+        transport.map(move |socket, endpoint| FaultyStream::new(socket, faults.clone(), &endpoint));
+
+    let mut yamux_config = libp2p::yamux::Config::default();
+    yamux_config
+        .set_receive_window(config.yamux_receive_window)
+        .set_max_buffer_size(config.yamux_max_buffer_size)
+        .set_max_num_streams(config.yamux_max_streams);
+
+    let mut mplex_config = libp2p::mplex::MplexConfig::new();
+    mplex_config
+        .set_max_num_streams(config.mplex_max_streams)
+        .set_max_buffer_size(config.mplex_max_buffer_size);
+
     // Authentication
     Ok(transport
         .upgrade(core::upgrade::Version::V1)
         .authenticate(generate_noise_config(&local_private_key))
-        .multiplex(libp2p::mplex::MplexConfig::new())
-        .map(|(peer, muxer), _| (peer, core::muxing::StreamMuxerBox::new(muxer)))
+        // The first upgrade is preferred during negotiation.
+        .multiplex(SelectUpgrade::new(yamux_config, mplex_config))
+        .map(|(peer, muxer), _| {
+            let muxer_name = match muxer {
+                EitherOutput::First(_) => "yamux",
+                EitherOutput::Second(_) => "mplex",
+            };
+            metrics::inc_counter_vec(&metrics::MUXER_CONNECTIONS, &[muxer_name]);
+            (peer, core::muxing::StreamMuxerBox::new(muxer))
+        })
         .timeout(Duration::from_secs(10))
         .timeout(Duration::from_secs(10))
         .map_err(|err| Error::new(ErrorKind::Other, err))
@@ -476,3 +507,58 @@ fn load_or_build_metadata<E: EthSpec>(
     save_metadata_to_disk(network_dir, meta_data.clone(), &log);
     meta_data
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libp2p::core::transport::ListenerEvent;
+
+    fn muxer_connections(muxer: &str) -> u64 {
+        metrics::MUXER_CONNECTIONS
+            .as_ref()
+            .expect("metric should exist")
+            .with_label_values(&[muxer])
+            .get()
+    }
+
+    /// Two nodes built by `build_transport` should prefer yamux over mplex.
+    #[tokio::test]
+    async fn negotiates_yamux() {
+        let config = NetworkConfig::default();
+        let listener_key = Keypair::generate_secp256k1();
+        let listener_id = PeerId::from(listener_key.public());
+        let dialer_key = Keypair::generate_secp256k1();
+        let dialer_id = PeerId::from(dialer_key.public());
+
+        let yamux_before = muxer_connections("yamux");
+
+        let mut listener = build_transport(listener_key, &config)
+            .unwrap()
+            .listen_on("/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .unwrap();
+        let addr = match listener.next().await {
+            Some(Ok(ListenerEvent::NewAddress(addr))) => addr,
+            _ => panic!("listener should report its address"),
+        };
+
+        let accept = async move {
+            loop {
+                if let Some(Ok(ListenerEvent::Upgrade { upgrade, .. })) = listener.next().await {
+                    return upgrade.await.expect("inbound upgrade should succeed");
+                }
+            }
+        };
+        let dial = build_transport(dialer_key, &config)
+            .unwrap()
+            .dial(addr)
+            .unwrap();
+
+        let ((inbound_peer, _), outbound) = future::join(accept, dial).await;
+        let (outbound_peer, _) = outbound.expect("outbound upgrade should succeed");
+
+        assert_eq!(inbound_peer, dialer_id);
+        assert_eq!(outbound_peer, listener_id);
+        // One connection from each side of the upgrade.
+        assert!(muxer_connections("yamux") >= yamux_before + 2);
+    }
+}
@@ -0,0 +1,295 @@
+//! Provides a wrapper around the raw libp2p transport which can inject network faults at runtime.
+//!
+//! This is only intended for testing (e.g., the `simulator`). It allows a test harness to:
+//!
+//! - Sever connections to some set of remote TCP ports, simulating a network partition.
+//! - Delay all bytes received by this node, simulating a slow link.
+//! - Drop all bytes received by this node, simulating packet loss.
+//!
+//! Faults are applied to the raw byte stream, before noise and the stream multiplexers. As such,
+//! dropping bytes will corrupt any partially-received frames and the affected connections will
+//! eventually be closed and re-established once the fault is lifted.
+//!
+//! Partitions are only enforced on connections that *this* node has dialed, since the TCP port of
+//! an inbound connection does not identify the remote node. A partition between two nodes should
+//! therefore be applied to both of them.
+use futures::io::{AsyncRead, AsyncWrite};
+use futures::prelude::*;
+use libp2p::core::ConnectedPoint;
+use libp2p::multiaddr::Protocol;
+use parking_lot::RwLock;
+use std::collections::{HashSet, VecDeque};
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{delay_until, Delay, Instant};
+
+/// The maximum number of bytes read from the underlying stream in a single read.
+const READ_CHUNK_SIZE: usize = 8_192;
+
+#[derive(Debug, Default)]
+struct FaultState {
+    /// Connections dialed to these TCP ports are severed.
+    blocked_ports: HashSet<u16>,
+    /// All received bytes are held back for this duration before being released.
+    delay: Duration,
+    /// If `true`, all received bytes are discarded.
+    drop_all: bool,
+}
+
+/// A handle to the faults that should be applied to a node's transport.
+///
+/// The handle may be cloned and modified at any time; the changes apply to both existing and new
+/// connections.
+#[derive(Debug, Default, Clone)]
+pub struct TransportFaults {
+    state: Arc<RwLock<FaultState>>,
+}
+
+impl TransportFaults {
+    /// Sever all connections to and from the given remote TCP ports.
+    pub fn block_ports(&self, ports: impl IntoIterator<Item = u16>) {
+        self.state.write().blocked_ports.extend(ports);
+    }
+
+    /// Allow connections to all remote TCP ports again.
+    pub fn unblock_all_ports(&self) {
+        self.state.write().blocked_ports.clear();
+    }
+
+    /// Hold back all received bytes for `delay`. A `delay` of zero disables the delay.
+    pub fn set_delay(&self, delay: Duration) {
+        self.state.write().delay = delay;
+    }
+
+    /// Discard all received bytes whilst `drop_all` is `true`.
+    pub fn set_drop_all(&self, drop_all: bool) {
+        self.state.write().drop_all = drop_all;
+    }
+
+    /// Remove all faults.
+    pub fn clear(&self) {
+        *self.state.write() = FaultState::default();
+    }
+
+    fn is_blocked(&self, port: u16) -> bool {
+        self.state.read().blocked_ports.contains(&port)
+    }
+}
+
+/// Wraps a raw connection (e.g., a TCP stream) and applies the `TransportFaults` to it.
+///
+/// If no faults are provided, all operations are passed straight through to the inner stream.
+pub struct FaultyStream<S> {
+    inner: S,
+    faults: Option<TransportFaults>,
+    /// The TCP port of the remote, if this connection was dialed by us.
+    dialed_port: Option<u16>,
+    /// Bytes that have been read from `inner`, along with the time that they may be released.
+    pending: VecDeque<(Instant, Vec<u8>)>,
+    /// A timer which wakes the task when the first `pending` bytes may be released.
+    timer: Option<Pin<Box<Delay>>>,
+    /// Set to `true` once `inner` has returned EOF.
+    eof: bool,
+}
+
+impl<S> FaultyStream<S> {
+    pub fn new(inner: S, faults: Option<TransportFaults>, endpoint: &ConnectedPoint) -> Self {
+        let dialed_port = match endpoint {
+            ConnectedPoint::Dialer { address } => address.iter().find_map(|protocol| {
+                if let Protocol::Tcp(port) = protocol {
+                    Some(port)
+                } else {
+                    None
+                }
+            }),
+            ConnectedPoint::Listener { .. } => None,
+        };
+
+        Self {
+            inner,
+            faults,
+            dialed_port,
+            pending: VecDeque::new(),
+            timer: None,
+            eof: false,
+        }
+    }
+
+    /// Returns an error if this connection has been severed by a partition.
+    fn check_severed(&self) -> io::Result<()> {
+        match (&self.faults, self.dialed_port) {
+            (Some(faults), Some(port)) if faults.is_blocked(port) => Err(io::Error::new(
+                io::ErrorKind::ConnectionReset,
+                "connection severed by fault injection",
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for FaultyStream<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        let (delay, drop_all) = match &this.faults {
+            Some(faults) => {
+                let state = faults.state.read();
+                (state.delay, state.drop_all)
+            }
+            None => return Pin::new(&mut this.inner).poll_read(cx, buf),
+        };
+
+        if let Err(e) = this.check_severed() {
+            return Poll::Ready(Err(e));
+        }
+
+        if delay == Duration::from_secs(0) && !drop_all && this.pending.is_empty() {
+            return Pin::new(&mut this.inner).poll_read(cx, buf);
+        }
+
+        // Read everything that is presently available from the inner stream, so that its arrival
+        // time can be recorded.
+        while !this.eof {
+            let mut chunk = vec![0; READ_CHUNK_SIZE];
+            match Pin::new(&mut this.inner).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(0)) => this.eof = true,
+                Poll::Ready(Ok(n)) => {
+                    if !drop_all {
+                        chunk.truncate(n);
+                        this.pending.push_back((Instant::now() + delay, chunk));
+                    }
+                }
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => break,
+            }
+        }
+
+        if let Some((release_at, mut chunk)) = this.pending.pop_front() {
+            if release_at <= Instant::now() {
+                let n = std::cmp::min(buf.len(), chunk.len());
+                buf[..n].copy_from_slice(&chunk[..n]);
+                if n < chunk.len() {
+                    chunk.drain(..n);
+                    this.pending.push_front((release_at, chunk));
+                }
+                this.timer = None;
+                return Poll::Ready(Ok(n));
+            }
+
+            this.pending.push_front((release_at, chunk));
+            let mut timer = Box::pin(delay_until(release_at));
+            if timer.as_mut().poll(cx).is_ready() {
+                cx.waker().wake_by_ref();
+            }
+            this.timer = Some(timer);
+            return Poll::Pending;
+        }
+
+        if this.eof {
+            Poll::Ready(Ok(0))
+        } else {
+            // The inner stream returned `Pending`, so the task will be woken when more bytes
+            // arrive.
+            Poll::Pending
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for FaultyStream<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        if let Err(e) = this.check_severed() {
+            return Poll::Ready(Err(e));
+        }
+        Pin::new(&mut this.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        if let Err(e) = this.check_severed() {
+            return Poll::Ready(Err(e));
+        }
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_close(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::io::Cursor;
+    use libp2p::Multiaddr;
+
+    fn dialer(port: u16) -> ConnectedPoint {
+        ConnectedPoint::Dialer {
+            address: format!("/ip4/127.0.0.1/tcp/{}", port)
+                .parse::<Multiaddr>()
+                .unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn passthrough_without_faults() {
+        let mut stream = FaultyStream::new(Cursor::new(vec![1, 2, 3]), None, &dialer(9000));
+        let mut out = vec![];
+        stream.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn severs_blocked_ports() {
+        let faults = TransportFaults::default();
+        let mut stream = FaultyStream::new(
+            Cursor::new(vec![1, 2, 3]),
+            Some(faults.clone()),
+            &dialer(9000),
+        );
+
+        faults.block_ports(vec![9000]);
+        let mut out = vec![];
+        let err = stream.read_to_end(&mut out).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionReset);
+
+        faults.unblock_all_ports();
+        stream.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn delays_received_bytes() {
+        let faults = TransportFaults::default();
+        faults.set_delay(Duration::from_millis(100));
+        let mut stream = FaultyStream::new(Cursor::new(vec![1, 2, 3]), Some(faults), &dialer(9000));
+
+        let start = Instant::now();
+        let mut out = vec![];
+        stream.read_to_end(&mut out).await.unwrap();
+        assert_eq!(out, vec![1, 2, 3]);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn drops_received_bytes() {
+        let faults = TransportFaults::default();
+        faults.set_drop_all(true);
+        let mut stream = FaultyStream::new(Cursor::new(vec![1, 2, 3]), Some(faults), &dialer(9000));
+
+        let mut out = vec![];
+        stream.read_to_end(&mut out).await.unwrap();
+        assert!(out.is_empty());
+    }
+}
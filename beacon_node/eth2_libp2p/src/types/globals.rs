@@ -1,7 +1,7 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::PeerDB;
 use crate::rpc::MetaData;
-use crate::types::SyncState;
+use crate::types::{SyncState, SyncStats};
 use crate::Client;
 use crate::EnrExt;
 use crate::{Enr, GossipTopic, Multiaddr, PeerId};
@@ -29,6 +29,8 @@ pub struct NetworkGlobals<TSpec: EthSpec> {
     pub gossipsub_subscriptions: RwLock<HashSet<GossipTopic>>,
     /// The current sync status of the node.
     pub sync_state: RwLock<SyncState>,
+    /// The progress of the current long-range sync, if any.
+    pub sync_stats: RwLock<Option<SyncStats>>,
 }
 
 impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
@@ -50,6 +52,7 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
             peers: RwLock::new(PeerDB::new(trusted_peers, log)),
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
+            sync_stats: RwLock::new(None),
         }
     }
 
@@ -99,6 +102,11 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
         self.sync_state.read().clone()
     }
 
+    /// Returns the progress of the current long-range sync, if any.
+    pub fn sync_stats(&self) -> Option<SyncStats> {
+        self.sync_stats.read().clone()
+    }
+
     /// Returns a `Client` type if one is known for the `PeerId`.
     pub fn client(&self, peer_id: &PeerId) -> Client {
         self.peers
@@ -114,4 +122,9 @@ impl<TSpec: EthSpec> NetworkGlobals<TSpec> {
     pub fn set_sync_state(&self, new_state: SyncState) -> SyncState {
         std::mem::replace(&mut *self.sync_state.write(), new_state)
     }
+
+    /// Updates the progress of the current long-range sync.
+    pub fn set_sync_stats(&self, stats: Option<SyncStats>) {
+        *self.sync_stats.write() = stats;
+    }
 }
@@ -15,5 +15,5 @@ pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 pub use globals::NetworkGlobals;
 pub use pubsub::{MessageData, PubsubMessage};
 pub use subnet::SubnetDiscovery;
-pub use sync_state::SyncState;
+pub use sync_state::{SyncPeerStats, SyncState, SyncStats};
 pub use topics::{GossipEncoding, GossipKind, GossipTopic, CORE_TOPICS};
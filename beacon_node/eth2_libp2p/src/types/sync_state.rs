@@ -57,3 +57,35 @@ impl std::fmt::Display for SyncState {
         }
     }
 }
+
+/// Download and import progress of a long-range sync.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncStats {
+    /// The slot of the local head.
+    pub head_slot: Slot,
+    /// The slot that range sync is currently aiming for.
+    pub target_slot: Slot,
+    /// Blocks downloaded per second across all peers, averaged over the recent past.
+    pub download_rate: f64,
+    /// Slots imported per second, averaged over the recent past.
+    pub import_rate: f64,
+    /// The estimated number of seconds until `target_slot` is reached, if any progress is being
+    /// made.
+    pub eta_seconds: Option<u64>,
+    /// Download statistics for each peer that has served range requests.
+    pub peers: Vec<SyncPeerStats>,
+}
+
+/// The range sync download statistics of a single peer.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncPeerStats {
+    pub peer_id: String,
+    /// Moving average of the blocks per second served by this peer, if it has completed a request.
+    pub blocks_per_second: Option<f64>,
+    /// The number of requests that have been completed by this peer.
+    pub completed_requests: u64,
+    /// The number of requests that have failed or been re-assigned for being too slow.
+    pub failed_requests: u64,
+    /// The number of requests currently in flight to this peer.
+    pub active_requests: usize,
+}
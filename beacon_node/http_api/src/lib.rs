@@ -9,20 +9,23 @@ mod beacon_proposer_cache;
 mod block_id;
 mod metrics;
 mod state_id;
+mod state_proofs;
 mod validator_inclusion;
 
 use beacon_chain::{
     observed_operations::ObservationOutcome, AttestationError as AttnError, BeaconChain,
-    BeaconChainError, BeaconChainTypes,
+    BeaconChainError, BeaconChainTypes, BlockProductionError, BlockProductionPolicy,
 };
 use beacon_proposer_cache::BeaconProposerCache;
 use block_id::BlockId;
+use bus::Bus;
 use eth2::{
     types::{self as api_types, ValidatorId},
     StatusCode,
 };
 use eth2_libp2p::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
 use lighthouse_version::version_with_platform;
+use logging::{LogFilter, LogFilterHandle};
 use network::NetworkMessage;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
@@ -32,18 +35,24 @@ use ssz::Encode;
 use state_id::StateId;
 use state_processing::per_slot_processing;
 use std::borrow::Cow;
-use std::convert::TryInto;
+use std::convert::{Infallible, TryInto};
 use std::future::Future;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
-use std::sync::Arc;
-use tokio::sync::mpsc::UnboundedSender;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{mpsc::RecvTimeoutError, Arc};
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc::UnboundedSender};
+use tokio_rustls::rustls::{internal::pemfile, NoClientAuth, ServerConfig};
 use types::{
     Attestation, AttestationDuty, AttesterSlashing, CloneConfig, CommitteeCache, Epoch, EthSpec,
     Hash256, ProposerSlashing, PublicKey, RelativeEpoch, SignedAggregateAndProof,
-    SignedBeaconBlock, SignedVoluntaryExit, Slot, YamlConfig,
+    SignedBeaconBlock, SignedBeaconBlockHash, SignedVoluntaryExit, Slot, YamlConfig,
 };
 use warp::{http::Response, Filter};
-use warp_utils::task::{blocking_json_task, blocking_task};
+use warp_utils::auth::{ApiTokenFiles, ApiTokens, Scope};
+use warp_utils::encoding::Encoding;
+use warp_utils::task::{blocking_encoded_task, blocking_json_task, blocking_task};
 
 const API_PREFIX: &str = "eth";
 const API_VERSION: &str = "v1";
@@ -55,6 +64,14 @@ const API_VERSION: &str = "v1";
 /// finalized head.
 const SYNC_TOLERANCE_EPOCHS: u64 = 8;
 
+/// How often the thread forwarding head events checks whether the server has stopped whilst
+/// waiting for a new head.
+const EVENTS_DISCONNECT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The number of head events buffered for each `GET events` subscriber. A subscriber which falls
+/// further behind skips the oldest events.
+const HEAD_EVENTS_CHANNEL_CAPACITY: usize = 16;
+
 /// A wrapper around all the items required to spawn the HTTP server.
 ///
 /// The server will gracefully handle the case where any fields are `None`.
@@ -63,6 +80,10 @@ pub struct Context<T: BeaconChainTypes> {
     pub chain: Option<Arc<BeaconChain<T>>>,
     pub network_tx: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
     pub network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
+    pub log_filter: Option<LogFilterHandle>,
+    /// Receives the root of each new head block, for `GET events?topics=head`.
+    #[allow(clippy::type_complexity)]
+    pub head_events: Option<Arc<Mutex<Bus<SignedBeaconBlockHash>>>>,
     pub log: Logger,
 }
 
@@ -73,6 +94,8 @@ pub struct Config {
     pub listen_addr: Ipv4Addr,
     pub listen_port: u16,
     pub allow_origin: Option<String>,
+    pub tls_config: Option<TlsConfig>,
+    pub api_token_files: ApiTokenFiles,
 }
 
 impl Default for Config {
@@ -82,10 +105,19 @@ impl Default for Config {
             listen_addr: Ipv4Addr::new(127, 0, 0, 1),
             listen_port: 5052,
             allow_origin: None,
+            tls_config: None,
+            api_token_files: ApiTokenFiles::default(),
         }
     }
 }
 
+/// The PEM-encoded certificate and private key used to serve the API over TLS.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct TlsConfig {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
 #[derive(Debug)]
 pub enum Error {
     Warp(warp::Error),
@@ -209,7 +241,7 @@ pub fn prometheus_metrics() -> warp::filters::log::Log<impl Fn(warp::filters::lo
 pub fn serve<T: BeaconChainTypes>(
     ctx: Arc<Context<T>>,
     shutdown: impl Future<Output = ()> + Send + Sync + 'static,
-) -> Result<(SocketAddr, impl Future<Output = ()>), Error> {
+) -> Result<(SocketAddr, Pin<Box<dyn Future<Output = ()> + Send>>), Error> {
     let config = ctx.config.clone();
     let log = ctx.log.clone();
 
@@ -217,7 +249,7 @@ pub fn serve<T: BeaconChainTypes>(
     let cors_builder = {
         let builder = warp::cors()
             .allow_methods(vec!["GET", "POST"])
-            .allow_headers(vec!["Content-Type"]);
+            .allow_headers(vec!["Content-Type", "Authorization"]);
 
         warp_utils::cors::set_builder_origins(
             builder,
@@ -234,6 +266,11 @@ pub fn serve<T: BeaconChainTypes>(
         ));
     }
 
+    // Load the bearer tokens, if any.
+    let api_tokens = Arc::new(ApiTokens::from_files(&config.api_token_files)?);
+    let read_auth = warp_utils::auth::bearer_auth(api_tokens.clone(), Scope::Read);
+    let write_auth = warp_utils::auth::bearer_auth(api_tokens.clone(), Scope::Write);
+
     let eth1_v1 = warp::path(API_PREFIX).and(warp::path(API_VERSION));
 
     // Instantiate the beacon proposer cache.
@@ -273,6 +310,34 @@ pub fn serve<T: BeaconChainTypes>(
             }
         });
 
+    let inner_log_filter_handle = ctx.log_filter.clone();
+    let log_levels = warp::any()
+        .map(move || inner_log_filter_handle.clone())
+        .and_then(|log_levels: Option<LogFilterHandle>| async move {
+            match log_levels {
+                Some(log_levels) => Ok(log_levels),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "log levels cannot be changed at runtime.".to_string(),
+                )),
+            }
+        });
+
+    let inner_head_events = match (&ctx.head_events, &ctx.chain) {
+        (Some(head_events), Some(chain)) => Some(forward_head_events(head_events, chain.clone())?),
+        _ => None,
+    };
+    let head_events =
+        warp::any()
+            .map(move || inner_head_events.clone())
+            .and_then(|head_events| async move {
+                match head_events {
+                    Some(head_events) => Ok(head_events),
+                    None => Err(warp_utils::reject::custom_not_found(
+                        "head events are not enabled.".to_string(),
+                    )),
+                }
+            });
+
     // Create a `warp` filter that provides access to the beacon chain.
     let inner_ctx = ctx.clone();
     let chain_filter =
@@ -384,44 +449,51 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("root"))
         .and(warp::path::end())
-        .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || {
-                state_id
-                    .root(&chain)
-                    .map(api_types::RootData::from)
-                    .map(api_types::GenericResponse::from)
-            })
-        });
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |state_id: StateId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
+                    state_id.root(&chain).map(api_types::RootData::from)
+                })
+            },
+        );
 
     // GET beacon/states/{state_id}/fork
     let get_beacon_state_fork = beacon_states_path
         .clone()
         .and(warp::path("fork"))
         .and(warp::path::end())
-        .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || state_id.fork(&chain).map(api_types::GenericResponse::from))
-        });
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |state_id: StateId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || state_id.fork(&chain))
+            },
+        );
 
     // GET beacon/states/{state_id}/finality_checkpoints
     let get_beacon_state_finality_checkpoints = beacon_states_path
         .clone()
         .and(warp::path("finality_checkpoints"))
         .and(warp::path::end())
-        .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || {
-                state_id
-                    .map_state(&chain, |state| {
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |state_id: StateId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
+                    state_id.map_state(&chain, |state| {
                         Ok(api_types::FinalityCheckpointsData {
                             previous_justified: state.previous_justified_checkpoint,
                             current_justified: state.current_justified_checkpoint,
                             finalized: state.finalized_checkpoint,
                         })
                     })
-                    .map(api_types::GenericResponse::from)
-            })
-        });
+                })
+            },
+        );
 
     // GET beacon/states/{state_id}/validators
+    //
+    // Always JSON: `ValidatorStatus` has no SSZ representation in the API spec, so an SSZ response
+    // would be a Lighthouse-specific format that no other client could decode.
     let get_beacon_state_validators = beacon_states_path
         .clone()
         .and(warp::path("validators"))
@@ -457,6 +529,8 @@ pub fn serve<T: BeaconChainTypes>(
         });
 
     // GET beacon/states/{state_id}/validators/{validator_id}
+    //
+    // Always JSON, for the same reason as `GET beacon/states/{state_id}/validators`.
     let get_beacon_state_validators_id = beacon_states_path
         .clone()
         .and(warp::path("validators"))
@@ -513,12 +587,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::param::<Epoch>())
         .and(warp::query::<api_types::CommitteesQuery>())
         .and(warp::path::end())
+        .and(warp_utils::encoding::accept())
         .and_then(
             |state_id: StateId,
              chain: Arc<BeaconChain<T>>,
              epoch: Epoch,
-             query: api_types::CommitteesQuery| {
-                blocking_json_task(move || {
+             query: api_types::CommitteesQuery,
+             encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
                     state_id.map_state(&chain, |state| {
                         let relative_epoch =
                             RelativeEpoch::from_epoch(state.current_epoch(), epoch).map_err(
@@ -584,7 +660,7 @@ pub fn serve<T: BeaconChainTypes>(
                             }
                         }
 
-                        Ok(api_types::GenericResponse::from(response))
+                        Ok(response)
                     })
                 })
             },
@@ -603,9 +679,10 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::query::<api_types::HeadersQuery>())
         .and(warp::path::end())
         .and(chain_filter.clone())
+        .and(warp_utils::encoding::accept())
         .and_then(
-            |query: api_types::HeadersQuery, chain: Arc<BeaconChain<T>>| {
-                blocking_json_task(move || {
+            |query: api_types::HeadersQuery, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
                     let (root, block) = match (query.slot, query.parent_root) {
                         // No query parameters, return the canonical head block.
                         (None, None) => chain
@@ -665,7 +742,7 @@ pub fn serve<T: BeaconChainTypes>(
                         },
                     };
 
-                    Ok(api_types::GenericResponse::from(vec![data]))
+                    Ok(vec![data])
                 })
             },
         );
@@ -677,28 +754,31 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::param::<BlockId>())
         .and(warp::path::end())
         .and(chain_filter.clone())
-        .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || {
-                let root = block_id.root(&chain)?;
-                let block = BlockId::from_root(root).block(&chain)?;
-
-                let canonical = chain
-                    .block_root_at_slot(block.slot())
-                    .map_err(warp_utils::reject::beacon_chain_error)?
-                    .map_or(false, |canonical| root == canonical);
-
-                let data = api_types::BlockHeaderData {
-                    root,
-                    canonical,
-                    header: api_types::BlockHeaderAndSignature {
-                        message: block.message.block_header(),
-                        signature: block.signature.into(),
-                    },
-                };
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |block_id: BlockId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
+                    let root = block_id.root(&chain)?;
+                    let block = BlockId::from_root(root).block(&chain)?;
 
-                Ok(api_types::GenericResponse::from(data))
-            })
-        });
+                    let canonical = chain
+                        .block_root_at_slot(block.slot())
+                        .map_err(warp_utils::reject::beacon_chain_error)?
+                        .map_or(false, |canonical| root == canonical);
+
+                    let data = api_types::BlockHeaderData {
+                        root,
+                        canonical,
+                        header: api_types::BlockHeaderAndSignature {
+                            message: block.message.block_header(),
+                            signature: block.signature.into(),
+                        },
+                    };
+
+                    Ok(data)
+                })
+            },
+        );
 
     /*
      * beacon/blocks
@@ -709,7 +789,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("beacon"))
         .and(warp::path("blocks"))
         .and(warp::path::end())
-        .and(warp::body::json())
+        .and(warp_utils::encoding::body())
         .and(chain_filter.clone())
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
@@ -763,39 +843,45 @@ pub fn serve<T: BeaconChainTypes>(
         .and(chain_filter.clone());
 
     // GET beacon/blocks/{block_id}
-    let get_beacon_block = beacon_blocks_path.clone().and(warp::path::end()).and_then(
-        |block_id: BlockId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || block_id.block(&chain).map(api_types::GenericResponse::from))
-        },
-    );
+    let get_beacon_block = beacon_blocks_path
+        .clone()
+        .and(warp::path::end())
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |block_id: BlockId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || block_id.block(&chain))
+            },
+        );
 
     // GET beacon/blocks/{block_id}/root
     let get_beacon_block_root = beacon_blocks_path
         .clone()
         .and(warp::path("root"))
         .and(warp::path::end())
-        .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || {
-                block_id
-                    .root(&chain)
-                    .map(api_types::RootData::from)
-                    .map(api_types::GenericResponse::from)
-            })
-        });
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |block_id: BlockId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
+                    block_id.root(&chain).map(api_types::RootData::from)
+                })
+            },
+        );
 
     // GET beacon/blocks/{block_id}/attestations
     let get_beacon_block_attestations = beacon_blocks_path
         .clone()
         .and(warp::path("attestations"))
         .and(warp::path::end())
-        .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
-            blocking_json_task(move || {
-                block_id
-                    .block(&chain)
-                    .map(|block| block.message.body.attestations)
-                    .map(api_types::GenericResponse::from)
-            })
-        });
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |block_id: BlockId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
+                    block_id
+                        .block(&chain)
+                        .map(|block| block.message.body.attestations)
+                })
+            },
+        );
 
     /*
      * beacon/pool
@@ -811,7 +897,7 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("attestations"))
         .and(warp::path::end())
-        .and(warp::body::json())
+        .and(warp_utils::encoding::body())
         .and(network_tx_filter.clone())
         .and_then(
             |chain: Arc<BeaconChain<T>>,
@@ -1083,15 +1169,16 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::param::<StateId>())
         .and(warp::path::end())
         .and(chain_filter.clone())
-        .and_then(|state_id: StateId, chain: Arc<BeaconChain<T>>| {
-            blocking_task(move || {
-                state_id.map_state(&chain, |state| {
-                    Ok(warp::reply::json(&api_types::GenericResponseRef::from(
-                        &state,
-                    )))
+        .and(warp_utils::encoding::accept())
+        .and_then(
+            |state_id: StateId, chain: Arc<BeaconChain<T>>, encoding: Encoding| {
+                blocking_task(move || {
+                    state_id.map_state(&chain, |state| {
+                        Ok(warp_utils::encoding::response(encoding, state))
+                    })
                 })
-            })
-        });
+            },
+        );
 
     // GET debug/beacon/heads
     let get_debug_beacon_heads = eth1_v1
@@ -1324,9 +1411,13 @@ pub fn serve<T: BeaconChainTypes>(
         .and(not_while_syncing_filter.clone())
         .and(warp::query::<api_types::ValidatorDutiesQuery>())
         .and(chain_filter.clone())
+        .and(warp_utils::encoding::accept())
         .and_then(
-            |epoch: Epoch, query: api_types::ValidatorDutiesQuery, chain: Arc<BeaconChain<T>>| {
-                blocking_json_task(move || {
+            |epoch: Epoch,
+             query: api_types::ValidatorDutiesQuery,
+             chain: Arc<BeaconChain<T>>,
+             encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
                     let current_epoch = chain
                         .epoch()
                         .map_err(warp_utils::reject::beacon_chain_error)?;
@@ -1475,7 +1566,7 @@ pub fn serve<T: BeaconChainTypes>(
                             .collect::<Result<Vec<_>, warp::Rejection>>()?
                     };
 
-                    Ok(api_types::GenericResponse::from(duties))
+                    Ok(duties)
                 })
             },
         );
@@ -1490,15 +1581,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(not_while_syncing_filter.clone())
         .and(chain_filter.clone())
         .and(beacon_proposer_cache())
+        .and(warp_utils::encoding::accept())
         .and_then(
             |epoch: Epoch,
              chain: Arc<BeaconChain<T>>,
-             beacon_proposer_cache: Arc<Mutex<BeaconProposerCache>>| {
-                blocking_json_task(move || {
-                    beacon_proposer_cache
-                        .lock()
-                        .get_proposers(&chain, epoch)
-                        .map(api_types::GenericResponse::from)
+             beacon_proposer_cache: Arc<Mutex<BeaconProposerCache>>,
+             encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
+                    beacon_proposer_cache.lock().get_proposers(&chain, epoch)
                 })
             },
         );
@@ -1512,9 +1602,13 @@ pub fn serve<T: BeaconChainTypes>(
         .and(not_while_syncing_filter.clone())
         .and(warp::query::<api_types::ValidatorBlocksQuery>())
         .and(chain_filter.clone())
+        .and(warp_utils::encoding::accept())
         .and_then(
-            |slot: Slot, query: api_types::ValidatorBlocksQuery, chain: Arc<BeaconChain<T>>| {
-                blocking_json_task(move || {
+            |slot: Slot,
+             query: api_types::ValidatorBlocksQuery,
+             chain: Arc<BeaconChain<T>>,
+             encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
                     let randao_reveal = (&query.randao_reveal).try_into().map_err(|e| {
                         warp_utils::reject::custom_bad_request(format!(
                             "randao reveal is not valid BLS signature: {:?}",
@@ -1522,11 +1616,34 @@ pub fn serve<T: BeaconChainTypes>(
                         ))
                     })?;
 
+                    let mut policy = BlockProductionPolicy::default();
+                    policy.verify_randao =
+                        !query.skip_randao_verification.map_or(false, |flag| flag.0);
+                    policy.max_attestations = query.max_attestations;
+                    if let Some(exclude_exits) = query.exclude_exits {
+                        policy.excluded_exits.extend(exclude_exits.0);
+                    }
+                    if let Some(exclude_slashings) = query.exclude_slashings {
+                        policy.excluded_slashings.extend(exclude_slashings.0);
+                    }
+
                     chain
-                        .produce_block(randao_reveal, slot, query.graffiti.map(Into::into))
+                        .produce_block_with_policy(
+                            randao_reveal,
+                            slot,
+                            query.graffiti.map(Into::into),
+                            policy,
+                        )
                         .map(|block_and_state| block_and_state.0)
-                        .map(api_types::GenericResponse::from)
-                        .map_err(warp_utils::reject::block_production_error)
+                        .map_err(|e| match e {
+                            BlockProductionError::InvalidRandaoReveal => {
+                                warp_utils::reject::custom_bad_request(
+                                    "randao reveal is not a valid signature by the proposer"
+                                        .to_string(),
+                                )
+                            }
+                            e => warp_utils::reject::block_production_error(e),
+                        })
                 })
             },
         );
@@ -1539,13 +1656,15 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::query::<api_types::ValidatorAttestationDataQuery>())
         .and(not_while_syncing_filter.clone())
         .and(chain_filter.clone())
+        .and(warp_utils::encoding::accept())
         .and_then(
-            |query: api_types::ValidatorAttestationDataQuery, chain: Arc<BeaconChain<T>>| {
-                blocking_json_task(move || {
+            |query: api_types::ValidatorAttestationDataQuery,
+             chain: Arc<BeaconChain<T>>,
+             encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
                     chain
                         .produce_unaggregated_attestation(query.slot, query.committee_index)
                         .map(|attestation| attestation.data)
-                        .map(api_types::GenericResponse::from)
                         .map_err(warp_utils::reject::beacon_chain_error)
                 })
             },
@@ -1559,15 +1678,17 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::query::<api_types::ValidatorAggregateAttestationQuery>())
         .and(not_while_syncing_filter.clone())
         .and(chain_filter.clone())
+        .and(warp_utils::encoding::accept())
         .and_then(
-            |query: api_types::ValidatorAggregateAttestationQuery, chain: Arc<BeaconChain<T>>| {
-                blocking_json_task(move || {
+            |query: api_types::ValidatorAggregateAttestationQuery,
+             chain: Arc<BeaconChain<T>>,
+             encoding: Encoding| {
+                blocking_encoded_task(encoding, move || {
                     chain
                         .get_aggregated_attestation_by_slot_and_root(
                             query.slot,
                             &query.attestation_data_root,
                         )
-                        .map(api_types::GenericResponse::from)
                         .ok_or_else(|| {
                             warp_utils::reject::custom_not_found(
                                 "no matching aggregate found".to_string(),
@@ -1670,6 +1791,33 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET events?topics
+    let get_events = eth1_v1
+        .and(warp::path("events"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::EventQuery>())
+        .and(head_events)
+        .and_then(
+            |query: api_types::EventQuery,
+             head_events: Arc<broadcast::Sender<api_types::SseHead>>| async move {
+                if !query.topics.0.contains(&api_types::EventTopic::Head) {
+                    return Err(warp_utils::reject::custom_bad_request(
+                        "no supported topics were requested".to_string(),
+                    ));
+                }
+
+                use tokio::stream::StreamExt;
+                let stream = head_events.subscribe().filter_map(|event| {
+                    // A subscriber which has fallen behind skips the events it missed.
+                    event.ok().map(|event| {
+                        Ok::<_, Infallible>((warp::sse::event("head"), warp::sse::json(event)))
+                    })
+                });
+
+                Ok(warp::sse::reply(warp::sse::keep_alive().stream(stream)))
+            },
+        );
+
     // GET lighthouse/health
     let get_lighthouse_health = warp::path("lighthouse")
         .and(warp::path("health"))
@@ -1695,6 +1843,20 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/syncing/stats
+    let get_lighthouse_syncing_stats = warp::path("lighthouse")
+        .and(warp::path("syncing"))
+        .and(warp::path("stats"))
+        .and(warp::path::end())
+        .and(network_globals.clone())
+        .and_then(|network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    network_globals.sync_stats(),
+                ))
+            })
+        });
+
     // GET lighthouse/peers
     let get_lighthouse_peers = warp::path("lighthouse")
         .and(warp::path("peers"))
@@ -1777,6 +1939,37 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/proofs/{block_id}/finalized_checkpoint
+    let get_lighthouse_proofs_finalized_checkpoint = warp::path("lighthouse")
+        .and(warp::path("proofs"))
+        .and(warp::path::param::<BlockId>())
+        .and(warp::path("finalized_checkpoint"))
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(|block_id: BlockId, chain: Arc<BeaconChain<T>>| {
+            blocking_json_task(move || {
+                state_proofs::finalized_checkpoint_proof(&block_id, &chain)
+                    .map(api_types::GenericResponse::from)
+            })
+        });
+
+    // GET lighthouse/proofs/{block_id}/balances/{validator_id}
+    let get_lighthouse_proofs_balance = warp::path("lighthouse")
+        .and(warp::path("proofs"))
+        .and(warp::path::param::<BlockId>())
+        .and(warp::path("balances"))
+        .and(warp::path::param::<ValidatorId>())
+        .and(warp::path::end())
+        .and(chain_filter.clone())
+        .and_then(
+            |block_id: BlockId, validator_id: ValidatorId, chain: Arc<BeaconChain<T>>| {
+                blocking_json_task(move || {
+                    state_proofs::balance_proof(&block_id, &validator_id, &chain)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // GET lighthouse/beacon/states/{state_id}/ssz
     let get_lighthouse_beacon_states_ssz = warp::path("lighthouse")
         .and(warp::path("beacon"))
@@ -1801,8 +1994,50 @@ pub fn serve<T: BeaconChainTypes>(
             })
         });
 
+    // GET lighthouse/logging
+    let get_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(log_levels.clone())
+        .and_then(|log_levels: LogFilterHandle| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(
+                    eth2::lighthouse::LoggingConfig {
+                        filter: log_levels.get().to_string(),
+                    },
+                ))
+            })
+        });
+
+    // POST lighthouse/logging
+    let post_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(log_levels)
+        .and(log_filter)
+        .and_then(
+            |config: eth2::lighthouse::LoggingConfig, log_levels: LogFilterHandle, log: Logger| {
+                blocking_json_task(move || {
+                    let filter = config
+                        .filter
+                        .parse::<LogFilter>()
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+
+                    info!(log, "Updated log levels"; "filter" => filter.to_string());
+                    let response = eth2::lighthouse::LoggingConfig {
+                        filter: filter.to_string(),
+                    };
+                    log_levels.set(filter);
+
+                    Ok(api_types::GenericResponse::from(response))
+                })
+            },
+        );
+
     // Define the ultimate set of routes that will be provided to the server.
     let routes = warp::get()
+        .and(read_auth)
         .and(
             get_beacon_genesis
                 .or(get_beacon_state_root.boxed())
@@ -1836,17 +2071,23 @@ pub fn serve<T: BeaconChainTypes>(
                 .or(get_validator_blocks.boxed())
                 .or(get_validator_attestation_data.boxed())
                 .or(get_validator_aggregate_attestation.boxed())
+                .or(get_events.boxed())
                 .or(get_lighthouse_health.boxed())
                 .or(get_lighthouse_syncing.boxed())
+                .or(get_lighthouse_syncing_stats.boxed())
                 .or(get_lighthouse_peers.boxed())
                 .or(get_lighthouse_peers_connected.boxed())
                 .or(get_lighthouse_proto_array.boxed())
                 .or(get_lighthouse_validator_inclusion_global.boxed())
                 .or(get_lighthouse_validator_inclusion.boxed())
+                .or(get_lighthouse_proofs_finalized_checkpoint.boxed())
+                .or(get_lighthouse_proofs_balance.boxed())
                 .or(get_lighthouse_beacon_states_ssz.boxed())
+                .or(get_lighthouse_logging.boxed())
                 .boxed(),
         )
         .or(warp::post()
+            .and(write_auth)
             .and(
                 post_beacon_blocks
                     .or(post_beacon_pool_attestations.boxed())
@@ -1855,6 +2096,7 @@ pub fn serve<T: BeaconChainTypes>(
                     .or(post_beacon_pool_voluntary_exits.boxed())
                     .or(post_validator_aggregate_and_proofs.boxed())
                     .or(post_validator_beacon_committee_subscriptions.boxed())
+                    .or(post_lighthouse_logging.boxed())
                     .boxed(),
             )
             .boxed())
@@ -1867,22 +2109,128 @@ pub fn serve<T: BeaconChainTypes>(
         .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
         .with(cors_builder.build());
 
-    let (listening_socket, server) = warp::serve(routes).try_bind_with_graceful_shutdown(
-        SocketAddrV4::new(config.listen_addr, config.listen_port),
-        async {
-            shutdown.await;
-        },
-    )?;
+    let listen_addr = SocketAddrV4::new(config.listen_addr, config.listen_port);
+    let shutdown = async {
+        shutdown.await;
+    };
+
+    let (listening_socket, server): (_, Pin<Box<dyn Future<Output = ()> + Send>>) =
+        if let Some(tls_config) = &config.tls_config {
+            // The TLS server panics rather than returning an error if the certificate or key are
+            // invalid, or if it can't bind to the listen address, so check all three first.
+            let (cert, key) = load_tls_config(tls_config)?;
+
+            // Another process could take the port between this check and the server binding to it,
+            // but that is unlikely in practice.
+            std::net::TcpListener::bind(listen_addr)
+                .map_err(|e| format!("Unable to bind to {}: {}", listen_addr, e))?;
+
+            let (listening_socket, server) = warp::serve(routes)
+                .tls()
+                .cert(cert)
+                .key(key)
+                .bind_with_graceful_shutdown(listen_addr, shutdown);
+            (listening_socket, Box::pin(server))
+        } else {
+            let (listening_socket, server) =
+                warp::serve(routes).try_bind_with_graceful_shutdown(listen_addr, shutdown)?;
+            (listening_socket, Box::pin(server))
+        };
 
     info!(
         log,
         "HTTP API started";
         "listen_address" => listening_socket.to_string(),
+        "tls" => config.tls_config.is_some(),
+        "auth" => api_tokens.is_enabled(),
     );
 
     Ok((listening_socket, server))
 }
 
+/// Spawns a thread which reads new heads from `head_events` and sends them to every `GET events`
+/// subscriber through the returned channel.
+///
+/// The bus can only be read by blocking, so a single thread serves all subscribers rather than one
+/// per connection. The thread stops once the server has dropped the returned sender.
+fn forward_head_events<T: BeaconChainTypes>(
+    head_events: &Mutex<Bus<SignedBeaconBlockHash>>,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Arc<broadcast::Sender<api_types::SseHead>>, String> {
+    let mut reader = head_events.lock().add_rx();
+    let (tx, _) = broadcast::channel(HEAD_EVENTS_CHANNEL_CAPACITY);
+    let tx = Arc::new(tx);
+    let server_tx = Arc::downgrade(&tx);
+
+    std::thread::Builder::new()
+        .name("http_head_events".to_string())
+        .spawn(move || loop {
+            let block_root = match reader.recv_timeout(EVENTS_DISCONNECT_POLL_INTERVAL) {
+                Ok(block_root) => block_root,
+                Err(RecvTimeoutError::Timeout) if server_tx.strong_count() > 0 => continue,
+                Err(_) => break,
+            };
+
+            let head = match chain.head_info() {
+                Ok(head) if head.block_root == Hash256::from(block_root) => head,
+                // The head has already changed again, a later event will follow.
+                _ => continue,
+            };
+
+            let event = api_types::SseHead {
+                slot: head.slot,
+                block: head.block_root,
+                state: head.state_root,
+                epoch_transition: head.slot % T::EthSpec::slots_per_epoch() == 0,
+            };
+
+            match server_tx.upgrade() {
+                // Sending only fails when there are no subscribers, which is fine.
+                Some(tx) => {
+                    let _ = tx.send(event);
+                }
+                None => break,
+            }
+        })
+        .map_err(|e| format!("Unable to spawn the head events thread: {}", e))?;
+
+    Ok(tx)
+}
+
+/// Reads the PEM-encoded certificate chain and private key in `tls_config`, returning them if
+/// they can be used to build a TLS server configuration.
+fn load_tls_config(tls_config: &TlsConfig) -> Result<(Vec<u8>, Vec<u8>), String> {
+    let read = |path: &PathBuf| {
+        std::fs::read(path).map_err(|e| format!("Unable to read TLS file {:?}: {}", path, e))
+    };
+    let cert = read(&tls_config.cert)?;
+    let key = read(&tls_config.key)?;
+
+    let certs = pemfile::certs(&mut cert.as_slice())
+        .map_err(|()| format!("Unable to parse TLS certificate {:?}", tls_config.cert))?;
+    if certs.is_empty() {
+        return Err(format!("No TLS certificate found in {:?}", tls_config.cert));
+    }
+
+    // Accept the same key formats as the TLS server.
+    let mut keys = pemfile::pkcs8_private_keys(&mut key.as_slice())
+        .map_err(|()| format!("Unable to parse TLS key {:?}", tls_config.key))?;
+    if keys.is_empty() {
+        keys = pemfile::rsa_private_keys(&mut key.as_slice())
+            .map_err(|()| format!("Unable to parse TLS key {:?}", tls_config.key))?;
+    }
+    let private_key = keys
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No TLS private key found in {:?}", tls_config.key))?;
+
+    ServerConfig::new(NoClientAuth::new())
+        .set_single_cert(certs, private_key)
+        .map_err(|e| format!("Invalid TLS certificate or key: {}", e))?;
+
+    Ok((cert, key))
+}
+
 /// Publish a message to the libp2p pubsub network.
 fn publish_pubsub_message<T: EthSpec>(
     network_tx: &UnboundedSender<NetworkMessage<T>>,
@@ -0,0 +1,90 @@
+use crate::block_id::BlockId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::{
+    lighthouse::{BalanceProof, FinalizedCheckpointProof, StateMerkleProof},
+    types::ValidatorId,
+};
+use types::{BeaconState, MerkleNodes, SignedBeaconBlock};
+
+/// Returns the block identified by `block_id` and its post-state.
+fn block_and_state<T: BeaconChainTypes>(
+    block_id: &BlockId,
+    chain: &BeaconChain<T>,
+) -> Result<(SignedBeaconBlock<T::EthSpec>, BeaconState<T::EthSpec>), warp::Rejection> {
+    let block = block_id.block(chain)?;
+    let state_root = block.state_root();
+
+    let state = chain
+        .get_state(&state_root, Some(block.slot()))
+        .map_err(warp_utils::reject::beacon_chain_error)?
+        .ok_or_else(|| {
+            warp_utils::reject::custom_not_found(format!("beacon state at root {}", state_root))
+        })?;
+
+    Ok((block, state))
+}
+
+/// Produces a proof of the node at `gindex` in `state`, which must be the post-state of `block`.
+fn state_merkle_proof<T: BeaconChainTypes>(
+    block: &SignedBeaconBlock<T::EthSpec>,
+    state: &BeaconState<T::EthSpec>,
+    gindex: u64,
+) -> Result<StateMerkleProof, warp::Rejection> {
+    let to_rejection =
+        |e| warp_utils::reject::custom_server_error(format!("unable to compute proof: {:?}", e));
+
+    Ok(StateMerkleProof {
+        block_root: block.canonical_root(),
+        slot: block.slot(),
+        state_root: block.state_root(),
+        gindex,
+        leaf: state.merkle_node(gindex).map_err(to_rejection)?,
+        branch: state.compute_merkle_proof(gindex).map_err(to_rejection)?,
+    })
+}
+
+/// Returns the `finalized_checkpoint` of the post-state of a block and a proof of its inclusion
+/// in the block's `state_root`.
+pub fn finalized_checkpoint_proof<T: BeaconChainTypes>(
+    block_id: &BlockId,
+    chain: &BeaconChain<T>,
+) -> Result<FinalizedCheckpointProof, warp::Rejection> {
+    let (block, state) = block_and_state(block_id, chain)?;
+    let gindex = BeaconState::<T::EthSpec>::finalized_checkpoint_gindex();
+
+    Ok(FinalizedCheckpointProof {
+        finalized_checkpoint: state.finalized_checkpoint,
+        proof: state_merkle_proof::<T>(&block, &state, gindex)?,
+    })
+}
+
+/// Returns the balance of a validator in the post-state of a block and a proof of its inclusion
+/// in the block's `state_root`.
+pub fn balance_proof<T: BeaconChainTypes>(
+    block_id: &BlockId,
+    validator_id: &ValidatorId,
+    chain: &BeaconChain<T>,
+) -> Result<BalanceProof, warp::Rejection> {
+    let (block, mut state) = block_and_state(block_id, chain)?;
+
+    let validator_index = match validator_id {
+        ValidatorId::Index(index) => *index as usize,
+        ValidatorId::PublicKey(pubkey) => state
+            .get_validator_index(pubkey)
+            .map_err(warp_utils::reject::beacon_state_error)?
+            .ok_or_else(|| {
+                warp_utils::reject::custom_not_found(format!("unknown validator: {}", pubkey))
+            })?,
+    };
+
+    let balance = *state.balances.get(validator_index).ok_or_else(|| {
+        warp_utils::reject::custom_not_found(format!("unknown validator: {}", validator_index))
+    })?;
+    let gindex = BeaconState::<T::EthSpec>::balance_gindex(validator_index);
+
+    Ok(BalanceProof {
+        validator_index: validator_index as u64,
+        balance,
+        proof: state_merkle_proof::<T>(&block, &state, gindex)?,
+    })
+}
@@ -2,6 +2,7 @@ use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
     BeaconChain, StateSkipConfig,
 };
+use bus::Bus;
 use discv5::enr::{CombinedKey, EnrBuilder};
 use environment::null_logger;
 use eth2::{types::*, BeaconNodeHttpClient, Url};
@@ -10,12 +11,18 @@ use eth2_libp2p::{
     types::{EnrBitfield, SyncState},
     Enr, EnrExt, NetworkGlobals, PeerId,
 };
-use http_api::{Config, Context};
+use http_api::{Config, Context, TlsConfig};
+use logging::LogFilterHandle;
+use merkle_proof::{generalized_index_depth, verify_merkle_proof};
 use network::NetworkMessage;
+use parking_lot::Mutex;
 use state_processing::per_slot_processing;
 use std::convert::TryInto;
+use std::fs;
 use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tempfile::tempdir;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tree_hash::TreeHash;
@@ -24,6 +31,7 @@ use types::{
     EthSpec, Hash256, Keypair, MainnetEthSpec, RelativeEpoch, SelectionProof, SignedRoot, Slot,
 };
 use warp::http::StatusCode;
+use warp_utils::auth::ApiTokenFiles;
 
 type E = MainnetEthSpec;
 
@@ -36,6 +44,8 @@ const TCP_PORT: u16 = 42;
 const UDP_PORT: u16 = 42;
 const SEQ_NUMBER: u64 = 0;
 const EXTERNAL_ADDR: &str = "/ip4/0.0.0.0";
+const READ_TOKEN: &str = "read-token";
+const WRITE_TOKEN: &str = "write-token";
 
 /// Skipping the slots around the epoch boundary allows us to check that we're obtaining states
 /// from skipped slots for the finalized and justified checkpoints (instead of the state from the
@@ -50,6 +60,7 @@ const SKIPPED_SLOTS: &[u64] = &[
 struct ApiTester {
     chain: Arc<BeaconChain<EphemeralHarnessType<E>>>,
     client: BeaconNodeHttpClient,
+    server_url: Url,
     next_block: SignedBeaconBlock<E>,
     attestations: Vec<Attestation<E>>,
     attester_slashing: AttesterSlashing<E>,
@@ -60,10 +71,30 @@ struct ApiTester {
     network_rx: mpsc::UnboundedReceiver<NetworkMessage<E>>,
     local_enr: Enr,
     external_peer_id: PeerId,
+    head_events: Arc<Mutex<Bus<SignedBeaconBlockHash>>>,
 }
 
 impl ApiTester {
     pub fn new() -> Self {
+        Self::new_with_api_token_files(ApiTokenFiles::default())
+    }
+
+    pub fn new_with_api_token_files(api_token_files: ApiTokenFiles) -> Self {
+        Self::new_with_config(api_token_files, vec![])
+    }
+
+    /// Returns a tester whose chain excludes operations for `block_production_deny_list` from
+    /// the blocks it produces.
+    pub fn new_with_block_production_deny_list(
+        block_production_deny_list: Vec<PublicKeyBytes>,
+    ) -> Self {
+        Self::new_with_config(ApiTokenFiles::default(), block_production_deny_list)
+    }
+
+    fn new_with_config(
+        api_token_files: ApiTokenFiles,
+        block_production_deny_list: Vec<PublicKeyBytes>,
+    ) -> Self {
         let mut harness = BeaconChainHarness::new(
             MainnetEthSpec,
             generate_deterministic_keypairs(VALIDATOR_COUNT),
@@ -122,6 +153,7 @@ impl ApiTester {
         //
         // This allows for testing voluntary exits without building out a massive chain.
         harness.chain.spec.shard_committee_period = 2;
+        harness.chain.config.block_production_deny_list = block_production_deny_list;
 
         let chain = Arc::new(harness.chain);
 
@@ -167,16 +199,22 @@ impl ApiTester {
 
         *network_globals.sync_state.write() = SyncState::Synced;
 
+        let head_events = Arc::new(Mutex::new(Bus::new(E::slots_per_epoch() as usize)));
+
         let context = Arc::new(Context {
             config: Config {
                 enabled: true,
                 listen_addr: Ipv4Addr::new(127, 0, 0, 1),
                 listen_port: 0,
                 allow_origin: None,
+                tls_config: None,
+                api_token_files,
             },
             chain: Some(chain.clone()),
             network_tx: Some(network_tx),
             network_globals: Some(Arc::new(network_globals)),
+            log_filter: Some(LogFilterHandle::default()),
+            head_events: Some(head_events.clone()),
             log,
         });
         let ctx = context.clone();
@@ -189,18 +227,18 @@ impl ApiTester {
 
         tokio::spawn(async { server.await });
 
-        let client = BeaconNodeHttpClient::new(
-            Url::parse(&format!(
-                "http://{}:{}",
-                listening_socket.ip(),
-                listening_socket.port()
-            ))
-            .unwrap(),
-        );
+        let server_url = Url::parse(&format!(
+            "http://{}:{}",
+            listening_socket.ip(),
+            listening_socket.port()
+        ))
+        .unwrap();
+        let client = BeaconNodeHttpClient::new(server_url.clone());
 
         Self {
             chain,
             client,
+            server_url,
             next_block,
             attestations,
             attester_slashing,
@@ -211,9 +249,19 @@ impl ApiTester {
             network_rx,
             local_enr: enr_clone,
             external_peer_id: peer_id,
+            head_events,
         }
     }
 
+    /// Returns a client which presents `api_token` to the server.
+    fn client_with_api_token(&self, api_token: &str) -> BeaconNodeHttpClient {
+        let client = BeaconNodeHttpClient::client_builder(Some(api_token), None)
+            .unwrap()
+            .build()
+            .unwrap();
+        BeaconNodeHttpClient::from_components(self.server_url.clone(), client)
+    }
+
     fn skip_slots(self, count: u64) -> Self {
         for _ in 0..count {
             self.chain
@@ -310,6 +358,51 @@ impl ApiTester {
         }
     }
 
+    pub async fn test_api_token_auth(mut self) -> Self {
+        let read_client = self.client_with_api_token(READ_TOKEN);
+        let write_client = self.client_with_api_token(WRITE_TOKEN);
+
+        // Requests without a token are rejected.
+        assert_eq!(
+            self.client
+                .get_beacon_genesis()
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(403)
+        );
+
+        // The read token may only read.
+        read_client.get_beacon_genesis().await.unwrap();
+        assert_eq!(
+            read_client
+                .post_beacon_blocks(&self.next_block)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(403)
+        );
+        assert!(
+            self.network_rx.try_recv().is_err(),
+            "unauthorized blocks should not be sent to network"
+        );
+
+        // The write token may read and write.
+        write_client.get_beacon_genesis().await.unwrap();
+        write_client
+            .post_beacon_blocks(&self.next_block)
+            .await
+            .unwrap();
+        assert!(
+            self.network_rx.try_recv().is_ok(),
+            "valid blocks should be sent to network"
+        );
+
+        self
+    }
+
     pub async fn test_beacon_genesis(self) -> Self {
         let result = self.client.get_beacon_genesis().await.unwrap().data;
 
@@ -719,6 +812,22 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_beacon_blocks_ssz_valid(mut self) -> Self {
+        let next_block = &self.next_block;
+
+        self.client
+            .post_beacon_blocks_ssz(next_block)
+            .await
+            .unwrap();
+
+        assert!(
+            self.network_rx.try_recv().is_ok(),
+            "valid blocks should be sent to network"
+        );
+
+        self
+    }
+
     pub async fn test_post_beacon_blocks_invalid(mut self) -> Self {
         let mut next_block = self.next_block.clone();
         next_block.message.proposer_index += 1;
@@ -785,6 +894,22 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_beacon_pool_attestations_ssz_valid(mut self) -> Self {
+        for attestation in &self.attestations {
+            self.client
+                .post_beacon_pool_attestations_ssz(attestation)
+                .await
+                .unwrap();
+
+            assert!(
+                self.network_rx.try_recv().is_ok(),
+                "valid attestation should be sent to network"
+            );
+        }
+
+        self
+    }
+
     pub async fn test_post_beacon_pool_attestations_invalid(mut self) -> Self {
         for attestation in &self.attestations {
             let mut attestation = attestation.clone();
@@ -1106,6 +1231,23 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_debug_beacon_states_ssz(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            let result = self
+                .client
+                .get_debug_beacon_states_ssz(state_id)
+                .await
+                .unwrap();
+
+            let mut expected = self.get_state(state_id);
+            expected.as_mut().map(|state| state.drop_all_caches());
+
+            assert_eq!(result, expected, "{:?}", state_id);
+        }
+
+        self
+    }
+
     pub async fn test_get_debug_beacon_heads(self) -> Self {
         let result = self
             .client
@@ -1274,6 +1416,157 @@ impl ApiTester {
         self
     }
 
+    /// Checks that each endpoint supporting SSZ returns the same data as JSON.
+    pub async fn test_ssz_responses(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            assert_eq!(
+                self.client
+                    .get_beacon_states_root_ssz(state_id)
+                    .await
+                    .unwrap(),
+                self.client
+                    .get_beacon_states_root(state_id)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data),
+                "{:?}",
+                state_id
+            );
+            assert_eq!(
+                self.client
+                    .get_beacon_states_fork_ssz(state_id)
+                    .await
+                    .unwrap(),
+                self.client
+                    .get_beacon_states_fork(state_id)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data),
+                "{:?}",
+                state_id
+            );
+            assert_eq!(
+                self.client
+                    .get_beacon_states_finality_checkpoints_ssz(state_id)
+                    .await
+                    .unwrap(),
+                self.client
+                    .get_beacon_states_finality_checkpoints(state_id)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data),
+                "{:?}",
+                state_id
+            );
+
+            let epoch = self.chain.epoch().unwrap();
+            assert_eq!(
+                self.client
+                    .get_beacon_states_committees_ssz(state_id, epoch, None, None)
+                    .await
+                    .ok(),
+                self.client
+                    .get_beacon_states_committees(state_id, epoch, None, None)
+                    .await
+                    .ok()
+                    .map(|res| res.map(|res| res.data)),
+                "{:?}",
+                state_id
+            );
+        }
+
+        for block_id in self.interesting_block_ids() {
+            assert_eq!(
+                self.client.get_beacon_blocks_ssz(block_id).await.unwrap(),
+                self.client
+                    .get_beacon_blocks(block_id)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data),
+                "{:?}",
+                block_id
+            );
+            assert_eq!(
+                self.client
+                    .get_beacon_blocks_root_ssz(block_id)
+                    .await
+                    .unwrap(),
+                self.client
+                    .get_beacon_blocks_root(block_id)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data),
+                "{:?}",
+                block_id
+            );
+            assert_eq!(
+                self.client
+                    .get_beacon_blocks_attestations_ssz::<E>(block_id)
+                    .await
+                    .unwrap(),
+                self.client
+                    .get_beacon_blocks_attestations(block_id)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data),
+                "{:?}",
+                block_id
+            );
+            assert_eq!(
+                self.client
+                    .get_beacon_headers_block_id_ssz(block_id)
+                    .await
+                    .unwrap(),
+                self.client
+                    .get_beacon_headers_block_id(block_id)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data),
+                "{:?}",
+                block_id
+            );
+        }
+
+        assert_eq!(
+            self.client
+                .get_beacon_headers_ssz(None, None)
+                .await
+                .unwrap(),
+            self.client
+                .get_beacon_headers(None, None)
+                .await
+                .unwrap()
+                .map(|res| res.data)
+        );
+
+        let epoch = self.chain.epoch().unwrap();
+        let indices = (0..self.validator_keypairs.len() as u64).collect::<Vec<_>>();
+        assert_eq!(
+            self.client
+                .get_validator_duties_attester_ssz(epoch, Some(&indices))
+                .await
+                .unwrap(),
+            self.client
+                .get_validator_duties_attester(epoch, Some(&indices))
+                .await
+                .unwrap()
+                .data
+        );
+        assert_eq!(
+            self.client
+                .get_validator_duties_proposer_ssz(epoch)
+                .await
+                .unwrap(),
+            self.client
+                .get_validator_duties_proposer(epoch)
+                .await
+                .unwrap()
+                .data
+        );
+
+        self
+    }
+
     pub async fn test_block_production(self) -> Self {
         let fork = self.chain.head_info().unwrap().fork;
         let genesis_validators_root = self.chain.genesis_validators_root;
@@ -1331,6 +1624,140 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_block_production_policy(self) -> Self {
+        let fork = self.chain.head_info().unwrap().fork;
+        let genesis_validators_root = self.chain.genesis_validators_root;
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let proposer_pubkey_bytes = self
+            .client
+            .get_validator_duties_proposer(epoch)
+            .await
+            .unwrap()
+            .data
+            .into_iter()
+            .find(|duty| duty.slot == slot)
+            .map(|duty| duty.pubkey)
+            .unwrap();
+        let proposer_pubkey = (&proposer_pubkey_bytes).try_into().unwrap();
+        let proposer_index = self
+            .validator_keypairs
+            .iter()
+            .position(|kp| kp.pk == proposer_pubkey)
+            .unwrap();
+
+        // Sign the randao reveal with a key that does not belong to the proposer.
+        let wrong_sk =
+            &self.validator_keypairs[(proposer_index + 1) % self.validator_keypairs.len()].sk;
+        let randao_reveal: SignatureBytes = {
+            let domain =
+                self.chain
+                    .spec
+                    .get_domain(epoch, Domain::Randao, &fork, genesis_validators_root);
+            let message = epoch.signing_root(domain);
+            wrong_sk.sign(message).into()
+        };
+
+        assert_eq!(
+            self.client
+                .get_validator_blocks::<E>(slot, randao_reveal.clone(), None)
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+
+        let options = BlockProductionOptions {
+            skip_randao_verification: true,
+            max_attestations: Some(0),
+            ..BlockProductionOptions::default()
+        };
+        let block = self
+            .client
+            .get_validator_blocks_with_options::<E>(slot, randao_reveal, None, &options)
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(block.slot, slot);
+        assert!(block.body.attestations.is_empty());
+
+        self
+    }
+
+    /// Adds an exit and slashings to the op pool, produces a block and checks whether they were
+    /// included.
+    pub async fn test_block_production_operations(
+        self,
+        options: BlockProductionOptions,
+        expect_included: bool,
+    ) -> Self {
+        self.client
+            .post_beacon_pool_voluntary_exits(&self.voluntary_exit)
+            .await
+            .unwrap();
+        self.client
+            .post_beacon_pool_proposer_slashings(&self.proposer_slashing)
+            .await
+            .unwrap();
+        self.client
+            .post_beacon_pool_attester_slashings(&self.attester_slashing)
+            .await
+            .unwrap();
+
+        let fork = self.chain.head_info().unwrap().fork;
+        let genesis_validators_root = self.chain.genesis_validators_root;
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        // The randao reveal is not under test, so it is not signed by the proposer.
+        let randao_reveal: SignatureBytes = {
+            let domain =
+                self.chain
+                    .spec
+                    .get_domain(epoch, Domain::Randao, &fork, genesis_validators_root);
+            let message = epoch.signing_root(domain);
+            self.validator_keypairs[0].sk.sign(message).into()
+        };
+        let options = BlockProductionOptions {
+            skip_randao_verification: true,
+            ..options
+        };
+
+        let block = self
+            .client
+            .get_validator_blocks_with_options::<E>(slot, randao_reveal, None, &options)
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(
+            block.body.voluntary_exits.contains(&self.voluntary_exit),
+            expect_included,
+            "voluntary exit"
+        );
+        assert_eq!(
+            block
+                .body
+                .proposer_slashings
+                .contains(&self.proposer_slashing),
+            expect_included,
+            "proposer slashing"
+        );
+        assert_eq!(
+            block
+                .body
+                .attester_slashings
+                .contains(&self.attester_slashing),
+            expect_included,
+            "attester slashing"
+        );
+
+        self
+    }
+
     pub async fn test_get_validator_attestation_data(self) -> Self {
         let mut state = self.chain.head_beacon_state().unwrap();
         let slot = state.slot;
@@ -1353,6 +1780,14 @@ impl ApiTester {
                 .data;
 
             assert_eq!(result, expected);
+
+            let ssz_result = self
+                .client
+                .get_validator_attestation_data_ssz(slot, index)
+                .await
+                .unwrap();
+
+            assert_eq!(ssz_result, expected);
         }
 
         self
@@ -1379,9 +1814,20 @@ impl ApiTester {
             .unwrap()
             .data;
 
+        let ssz_result = self
+            .client
+            .get_validator_aggregate_attestation_ssz::<E>(
+                attestation.data.slot,
+                attestation.data.tree_hash_root(),
+            )
+            .await
+            .unwrap()
+            .unwrap();
+
         let expected = attestation;
 
         assert_eq!(result, expected);
+        assert_eq!(ssz_result, expected);
 
         self
     }
@@ -1546,6 +1992,20 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_syncing_stats(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_syncing_stats()
+            .await
+            .unwrap()
+            .data;
+
+        // The test harness never starts a range sync.
+        assert_eq!(result, None);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_proto_array(self) -> Self {
         self.client.get_lighthouse_proto_array().await.unwrap();
 
@@ -1588,6 +2048,264 @@ impl ApiTester {
 
         self
     }
+
+    pub async fn test_get_events_head(self) -> Self {
+        let head = self.chain.head_info().unwrap();
+
+        // All subscribers are served from the same channel.
+        let mut streams = vec![
+            self.client.get_events_head().await.unwrap(),
+            self.client.get_events_head().await.unwrap(),
+        ];
+
+        // Stale roots are not sent.
+        self.head_events
+            .lock()
+            .broadcast(Hash256::repeat_byte(42).into());
+        self.head_events.lock().broadcast(head.block_root.into());
+
+        for stream in &mut streams {
+            assert_eq!(
+                stream.next().await.unwrap(),
+                Some(SseHead {
+                    slot: head.slot,
+                    block: head.block_root,
+                    state: head.state_root,
+                    epoch_transition: head.slot % E::slots_per_epoch() == 0,
+                })
+            );
+        }
+
+        self
+    }
+
+    pub async fn test_lighthouse_logging(self) -> Self {
+        let filter = |filter: &str| eth2::lighthouse::LoggingConfig {
+            filter: filter.to_string(),
+        };
+
+        assert_eq!(
+            self.client.get_lighthouse_logging().await.unwrap().data,
+            filter("info")
+        );
+
+        assert_eq!(
+            self.client
+                .post_lighthouse_logging(&filter("warn, network=debug"))
+                .await
+                .unwrap()
+                .data,
+            filter("warn,network=debug")
+        );
+        assert_eq!(
+            self.client.get_lighthouse_logging().await.unwrap().data,
+            filter("warn,network=debug")
+        );
+
+        assert_eq!(
+            self.client
+                .post_lighthouse_logging(&filter("network=loud"))
+                .await
+                .unwrap_err()
+                .status()
+                .map(Into::into),
+            Some(400)
+        );
+        assert_eq!(
+            self.client.get_lighthouse_logging().await.unwrap().data,
+            filter("warn,network=debug")
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_proofs(self) -> Self {
+        let verify = |proof: &eth2::lighthouse::StateMerkleProof| {
+            let depth = generalized_index_depth(proof.gindex).unwrap();
+            verify_merkle_proof(
+                proof.leaf,
+                &proof.branch,
+                depth,
+                (proof.gindex - (1 << depth)) as usize,
+                proof.state_root,
+            )
+        };
+
+        for block_id in self.interesting_block_ids() {
+            let result = self
+                .client
+                .get_lighthouse_proofs_finalized_checkpoint(block_id)
+                .await
+                .unwrap()
+                .map(|res| res.data);
+
+            let block = match self.get_block(block_id) {
+                Some(block) => block,
+                None => {
+                    assert_eq!(result, None, "{:?}", block_id);
+                    continue;
+                }
+            };
+            let state = self
+                .chain
+                .get_state(&block.state_root(), Some(block.slot()))
+                .unwrap()
+                .unwrap();
+
+            let result = result.unwrap();
+            assert_eq!(result.finalized_checkpoint, state.finalized_checkpoint);
+            assert_eq!(result.proof.block_root, block.canonical_root());
+            assert_eq!(result.proof.state_root, block.state_root());
+            assert_eq!(
+                result.proof.leaf,
+                state.finalized_checkpoint.tree_hash_root()
+            );
+            assert!(verify(&result.proof), "{:?}", block_id);
+
+            for validator_id in &[
+                ValidatorId::Index(0),
+                ValidatorId::PublicKey((&self.validator_keypairs[1].pk).into()),
+            ] {
+                let result = self
+                    .client
+                    .get_lighthouse_proofs_balance(block_id, validator_id)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .data;
+
+                let index = result.validator_index as usize;
+                let offset = (index % 4) * 8;
+                assert_eq!(result.balance, state.balances[index]);
+                assert_eq!(
+                    &result.proof.leaf.as_bytes()[offset..offset + 8],
+                    &result.balance.to_le_bytes()
+                );
+                assert!(verify(&result.proof), "{:?}", block_id);
+            }
+
+            let unknown_validator = ValidatorId::Index(VALIDATOR_COUNT as u64);
+            assert_eq!(
+                self.client
+                    .get_lighthouse_proofs_balance(block_id, &unknown_validator)
+                    .await
+                    .unwrap(),
+                None
+            );
+        }
+
+        self
+    }
+}
+
+#[tokio::test(core_threads = 2)]
+async fn api_token_auth() {
+    let dir = tempdir().unwrap();
+    let read = dir.path().join("read-token");
+    let write = dir.path().join("write-token");
+    fs::write(&read, format!("{}\n", READ_TOKEN)).unwrap();
+    fs::write(&write, WRITE_TOKEN).unwrap();
+
+    ApiTester::new_with_api_token_files(ApiTokenFiles {
+        read: Some(read),
+        write: Some(write),
+    })
+    .test_api_token_auth()
+    .await;
+}
+
+/// Returns a context without a beacon chain which serves the API over TLS with the certificate and
+/// key at `cert_path` and `key_path`.
+fn tls_context(cert_path: PathBuf, key_path: PathBuf) -> Arc<Context<EphemeralHarnessType<E>>> {
+    Arc::new(Context {
+        config: Config {
+            enabled: true,
+            listen_addr: Ipv4Addr::new(127, 0, 0, 1),
+            listen_port: 0,
+            allow_origin: None,
+            tls_config: Some(TlsConfig {
+                cert: cert_path,
+                key: key_path,
+            }),
+            api_token_files: ApiTokenFiles::default(),
+        },
+        chain: None,
+        network_tx: None,
+        network_globals: None,
+        log_filter: None,
+        head_events: None,
+        log: null_logger().unwrap(),
+    })
+}
+
+/// Starts the server with a TLS certificate and key containing `cert` and `key`, returning the
+/// error.
+fn serve_with_invalid_tls(cert: Option<&str>, key: Option<&str>) -> String {
+    let dir = tempdir().unwrap();
+    let cert_path = dir.path().join("cert.pem");
+    let key_path = dir.path().join("key.pem");
+    if let Some(cert) = cert {
+        fs::write(&cert_path, cert).unwrap();
+    }
+    if let Some(key) = key {
+        fs::write(&key_path, key).unwrap();
+    }
+
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let shutdown = async {
+        let _ = shutdown_rx.await;
+    };
+
+    match http_api::serve(tls_context(cert_path, key_path), shutdown) {
+        Ok(_) => panic!("server should not start with an invalid TLS configuration"),
+        Err(e) => format!("{:?}", e),
+    }
+}
+
+#[tokio::test(core_threads = 2)]
+async fn tls_config_is_checked_before_serving() {
+    let error = serve_with_invalid_tls(None, None);
+    assert!(error.contains("Unable to read TLS file"), "{}", error);
+
+    let error = serve_with_invalid_tls(Some("not a certificate"), Some("not a key"));
+    assert!(error.contains("No TLS certificate found"), "{}", error);
+}
+
+/// Serves the API with the self-signed certificate in `tests/tls` (valid for `localhost` and
+/// `127.0.0.1` until 2126) and checks that a client which trusts it can make requests.
+#[tokio::test(core_threads = 2)]
+async fn tls_round_trip() {
+    let tls_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("tls");
+    let cert_path = tls_dir.join("cert.pem");
+    let context = tls_context(cert_path.clone(), tls_dir.join("key.pem"));
+
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let shutdown = async {
+        let _ = shutdown_rx.await;
+    };
+    let (listening_socket, server) = http_api::serve(context, shutdown).unwrap();
+    tokio::spawn(server);
+
+    let server_url = Url::parse(&format!("https://localhost:{}", listening_socket.port())).unwrap();
+    let ca_cert = fs::read(&cert_path).unwrap();
+    let client = BeaconNodeHttpClient::client_builder(None, Some(&ca_cert))
+        .unwrap()
+        .build()
+        .unwrap();
+    let client = BeaconNodeHttpClient::from_components(server_url.clone(), client);
+
+    assert_eq!(
+        client.get_node_version().await.unwrap().data.version,
+        lighthouse_version::version_with_platform()
+    );
+
+    // A client which does not trust the certificate cannot connect.
+    BeaconNodeHttpClient::new(server_url)
+        .get_node_version()
+        .await
+        .unwrap_err();
 }
 
 #[tokio::test(core_threads = 2)]
@@ -1651,6 +2369,11 @@ async fn post_beacon_blocks_valid() {
     ApiTester::new().test_post_beacon_blocks_valid().await;
 }
 
+#[tokio::test(core_threads = 2)]
+async fn post_beacon_blocks_ssz_valid() {
+    ApiTester::new().test_post_beacon_blocks_ssz_valid().await;
+}
+
 #[tokio::test(core_threads = 2)]
 async fn post_beacon_blocks_invalid() {
     ApiTester::new().test_post_beacon_blocks_invalid().await;
@@ -1686,6 +2409,13 @@ async fn beacon_pools_post_attestations_valid() {
         .await;
 }
 
+#[tokio::test(core_threads = 2)]
+async fn beacon_pools_post_attestations_ssz_valid() {
+    ApiTester::new()
+        .test_post_beacon_pool_attestations_ssz_valid()
+        .await;
+}
+
 #[tokio::test(core_threads = 2)]
 async fn beacon_pools_post_attestations_invalid() {
     ApiTester::new()
@@ -1751,10 +2481,17 @@ async fn debug_get() {
     ApiTester::new()
         .test_get_debug_beacon_states()
         .await
+        .test_get_debug_beacon_states_ssz()
+        .await
         .test_get_debug_beacon_heads()
         .await;
 }
 
+#[tokio::test(core_threads = 2)]
+async fn ssz_get() {
+    ApiTester::new().test_ssz_responses().await;
+}
+
 #[tokio::test(core_threads = 2)]
 async fn node_get() {
     ApiTester::new()
@@ -1811,6 +2548,44 @@ async fn block_production_with_skip_slots() {
         .await;
 }
 
+#[tokio::test(core_threads = 2)]
+async fn block_production_policy() {
+    ApiTester::new().test_block_production_policy().await;
+}
+
+#[tokio::test(core_threads = 2)]
+async fn block_production_includes_operations() {
+    ApiTester::new()
+        .test_block_production_operations(BlockProductionOptions::default(), true)
+        .await;
+}
+
+#[tokio::test(core_threads = 2)]
+async fn block_production_excluded_operations() {
+    // The attester slashing slashes validators 0 and 1, the proposer slashing validator 2 and
+    // the exit is for validator 3.
+    let options = BlockProductionOptions {
+        exclude_exits: vec![3],
+        exclude_slashings: vec![1, 2],
+        ..BlockProductionOptions::default()
+    };
+    ApiTester::new()
+        .test_block_production_operations(options, false)
+        .await;
+}
+
+#[tokio::test(core_threads = 2)]
+async fn block_production_deny_list() {
+    let deny_list = generate_deterministic_keypairs(4)
+        .iter()
+        .skip(1)
+        .map(|keypair| keypair.pk.clone().into())
+        .collect();
+    ApiTester::new_with_block_production_deny_list(deny_list)
+        .test_block_production_operations(BlockProductionOptions::default(), false)
+        .await;
+}
+
 #[tokio::test(core_threads = 2)]
 async fn get_validator_attestation_data() {
     ApiTester::new().test_get_validator_attestation_data().await;
@@ -1876,6 +2651,11 @@ async fn get_validator_beacon_committee_subscriptions() {
         .await;
 }
 
+#[tokio::test(core_threads = 2)]
+async fn get_events_head() {
+    ApiTester::new().test_get_events_head().await;
+}
+
 #[tokio::test(core_threads = 2)]
 async fn lighthouse_endpoints() {
     ApiTester::new()
@@ -1883,6 +2663,8 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_syncing_stats()
+        .await
         .test_get_lighthouse_proto_array()
         .await
         .test_get_lighthouse_validator_inclusion()
@@ -1890,5 +2672,9 @@ async fn lighthouse_endpoints() {
         .test_get_lighthouse_validator_inclusion_global()
         .await
         .test_get_lighthouse_beacon_states_ssz()
+        .await
+        .test_get_lighthouse_proofs()
+        .await
+        .test_lighthouse_logging()
         .await;
 }
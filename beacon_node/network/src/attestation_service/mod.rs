@@ -10,10 +10,10 @@ use std::time::{Duration, Instant};
 
 use futures::prelude::*;
 use rand::seq::SliceRandom;
-use slog::{debug, error, o, trace, warn};
+use slog::{debug, error, info, o, trace, warn};
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2_libp2p::SubnetDiscovery;
+use eth2_libp2p::{NetworkConfig, SubnetDiscovery};
 use hashset_delay::HashSetDelay;
 use slot_clock::SlotClock;
 use types::{Attestation, EthSpec, Slot, SubnetId, ValidatorSubscription};
@@ -89,6 +89,14 @@ pub struct AttestationService<T: BeaconChainTypes> {
     /// The waker for the current thread.
     waker: Option<std::task::Waker>,
 
+    /// The network service subscribes to every subnet at startup, so this service must not
+    /// subscribe to or unsubscribe from any subnet itself.
+    subscribe_all_subnets: bool,
+
+    /// Process all attestations, regardless of whether a local validator is aggregating for the
+    /// subnet.
+    import_all_attestations: bool,
+
     /// The logger for the attestation service.
     log: slog::Logger,
 }
@@ -96,9 +104,20 @@ pub struct AttestationService<T: BeaconChainTypes> {
 impl<T: BeaconChainTypes> AttestationService<T> {
     /* Public functions */
 
-    pub fn new(beacon_chain: Arc<BeaconChain<T>>, log: &slog::Logger) -> Self {
+    pub fn new(
+        beacon_chain: Arc<BeaconChain<T>>,
+        config: &NetworkConfig,
+        log: &slog::Logger,
+    ) -> Self {
         let log = log.new(o!("service" => "attestation_service"));
 
+        if config.subscribe_all_subnets {
+            info!(log, "Subscribing to all attestation subnets");
+        }
+        if config.import_all_attestations {
+            info!(log, "Importing all unaggregated attestations");
+        }
+
         // calculate the random subnet duration from the spec constants
         let spec = &beacon_chain.spec;
         let slot_duration = beacon_chain.slot_clock.slot_duration();
@@ -124,6 +143,8 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             aggregate_validators_on_subnet: HashSetDelay::new(default_timeout),
             known_validators: HashSetDelay::new(last_seen_val_timeout),
             waker: None,
+            subscribe_all_subnets: config.subscribe_all_subnets,
+            import_all_attestations: config.import_all_attestations,
             log,
         }
     }
@@ -218,6 +239,11 @@ impl<T: BeaconChainTypes> AttestationService<T> {
             }
         }
 
+        // When subscribed to all subnets, peers for every subnet are already being maintained.
+        if self.subscribe_all_subnets {
+            subnets_to_discover.clear();
+        }
+
         if let Err(e) = self.discover_peers_request(
             subnets_to_discover
                 .into_iter()
@@ -235,11 +261,17 @@ impl<T: BeaconChainTypes> AttestationService<T> {
 
     /// Checks if we have subscribed aggregate validators for the subnet. If not, checks the gossip
     /// verification, re-propagates and returns false.
+    ///
+    /// Always returns true if the service was configured to import all attestations.
     pub fn should_process_attestation(
         &self,
         subnet: SubnetId,
         attestation: &Attestation<T::EthSpec>,
     ) -> bool {
+        if self.import_all_attestations {
+            return true;
+        }
+
         let exact_subnet = ExactSubnet {
             subnet_id: subnet,
             slot: attestation.data.slot,
@@ -366,7 +398,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     ///
     /// This also updates the ENR to indicate our long-lived subscription to the subnet
     fn add_known_validator(&mut self, validator_index: u64) {
-        if self.known_validators.get(&validator_index).is_none() {
+        if self.known_validators.get(&validator_index).is_none() && !self.subscribe_all_subnets {
             // New validator has subscribed
             // Subscribe to random topics and update the ENR if needed.
 
@@ -445,6 +477,11 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     /// can be unsubscribed at any time by inactive validators). If we are
     /// still subscribed at the time the event fires, we don't re-subscribe.
     fn handle_subscriptions(&mut self, exact_subnet: ExactSubnet) {
+        // We are permanently subscribed to every subnet.
+        if self.subscribe_all_subnets {
+            return;
+        }
+
         // Check if the subnet currently exists as a long-lasting random subnet
         if let Some(expiry) = self.random_subnets.get(&exact_subnet.subnet_id) {
             // we are subscribed via a random subnet, if this is to expire during the time we need
@@ -480,8 +517,9 @@ impl<T: BeaconChainTypes> AttestationService<T> {
     /// Unsubscription events are added, even if we are subscribed to long-lived random subnets. If
     /// a random subnet is present, we do not unsubscribe from it.
     fn handle_unsubscriptions(&mut self, exact_subnet: ExactSubnet) {
-        // Check if the subnet currently exists as a long-lasting random subnet
-        if self.random_subnets.contains(&exact_subnet.subnet_id) {
+        // Check if the subnet currently exists as a long-lasting random subnet, or if we are
+        // permanently subscribed to every subnet
+        if self.subscribe_all_subnets || self.random_subnets.contains(&exact_subnet.subnet_id) {
             return;
         }
 
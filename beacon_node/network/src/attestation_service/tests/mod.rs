@@ -17,7 +17,9 @@ mod tests {
     use store::config::StoreConfig;
     use store::{HotColdDB, MemoryStore};
     use tempfile::tempdir;
-    use types::{CommitteeIndex, EthSpec, MinimalEthSpec};
+    use types::{
+        AggregateSignature, AttestationData, BitList, CommitteeIndex, EthSpec, MinimalEthSpec,
+    };
 
     const SLOT_DURATION_MILLIS: u64 = 400;
 
@@ -91,11 +93,17 @@ mod tests {
     }
 
     fn get_attestation_service() -> AttestationService<TestBeaconChainType> {
+        get_attestation_service_with_config(&NetworkConfig::default())
+    }
+
+    fn get_attestation_service_with_config(
+        config: &NetworkConfig,
+    ) -> AttestationService<TestBeaconChainType> {
         let log = get_logger();
 
         let beacon_chain = CHAIN.chain.clone();
 
-        AttestationService::new(beacon_chain, &log)
+        AttestationService::new(beacon_chain, config, &log)
     }
 
     fn get_subscription(
@@ -430,4 +438,52 @@ mod tests {
         assert_eq!(enr_add_count, 64);
         assert_eq!(unexpected_msg_count, 0);
     }
+
+    #[tokio::test]
+    async fn subscribe_all_subnets_emits_no_subscription_events() {
+        let committee_count = 1;
+
+        let config = NetworkConfig {
+            subscribe_all_subnets: true,
+            ..NetworkConfig::default()
+        };
+        let mut attestation_service = get_attestation_service_with_config(&config);
+        let current_slot = attestation_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+
+        // Well into the future, so that discovery would normally be requested.
+        let subscriptions = get_subscriptions(4, current_slot + Slot::new(10), committee_count);
+
+        attestation_service
+            .validator_subscriptions(subscriptions)
+            .unwrap();
+
+        // The network service is subscribed to every subnet, so no subscriptions, ENR updates or
+        // discovery requests are required.
+        let events = get_events(&mut attestation_service, None, 1).await;
+        assert_eq!(events, vec![]);
+        assert_eq!(attestation_service.subscription_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn import_all_attestations_processes_every_subnet() {
+        let attestation = Attestation {
+            aggregation_bits: BitList::with_capacity(1).unwrap(),
+            data: AttestationData::default(),
+            signature: AggregateSignature::empty(),
+        };
+
+        let attestation_service = get_attestation_service();
+        assert!(!attestation_service.should_process_attestation(SubnetId::new(0), &attestation));
+
+        let config = NetworkConfig {
+            import_all_attestations: true,
+            ..NetworkConfig::default()
+        };
+        let attestation_service = get_attestation_service_with_config(&config);
+        assert!(attestation_service.should_process_attestation(SubnetId::new(0), &attestation));
+    }
 }
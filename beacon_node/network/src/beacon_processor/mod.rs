@@ -34,11 +34,15 @@
 //! Whenever the manager receives a notification that a worker has finished a parcel of work, it
 //! checks the queues to see if there are more parcels of work that can be spawned in a new worker
 //! task.
+//!
+//! When there is more than one attestation (or aggregate) in a queue, up to a fixed number of them
+//! are given to a single worker so that their signatures can be verified as a batch.
 
 use crate::{metrics, service::NetworkMessage, sync::SyncMessage};
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
 use eth2_libp2p::{MessageId, NetworkGlobals, PeerId};
 use slog::{crit, debug, error, trace, warn, Logger};
+use std::cmp;
 use std::collections::VecDeque;
 use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
@@ -48,7 +52,7 @@ use types::{
     Attestation, AttesterSlashing, EthSpec, Hash256, ProposerSlashing, SignedAggregateAndProof,
     SignedBeaconBlock, SignedVoluntaryExit, SubnetId,
 };
-use worker::Worker;
+use worker::{GossipAggregatePackage, GossipAttestationPackage, Worker};
 
 mod chain_segment;
 mod worker;
@@ -74,6 +78,14 @@ const MAX_UNAGGREGATED_ATTESTATION_QUEUE_LEN: usize = 16_384;
 /// start dropping them.
 const MAX_AGGREGATED_ATTESTATION_QUEUE_LEN: usize = 1_024;
 
+/// The maximum number of queued `Attestation` objects that will be given to a single worker for
+/// batch signature verification.
+const MAX_GOSSIP_ATTESTATION_BATCH_SIZE: usize = 64;
+
+/// The maximum number of queued `SignedAggregateAndProof` objects that will be given to a single
+/// worker for batch signature verification.
+const MAX_GOSSIP_AGGREGATE_BATCH_SIZE: usize = 64;
+
 /// The maximum number of queued `SignedBeaconBlock` objects received on gossip that will be stored
 /// before we start dropping them.
 const MAX_GOSSIP_BLOCK_QUEUE_LEN: usize = 1_024;
@@ -332,11 +344,17 @@ pub enum Work<E: EthSpec> {
         subnet_id: SubnetId,
         should_import: bool,
     },
+    GossipAttestationBatch {
+        packages: Vec<GossipAttestationPackage<E>>,
+    },
     GossipAggregate {
         message_id: MessageId,
         peer_id: PeerId,
         aggregate: Box<SignedAggregateAndProof<E>>,
     },
+    GossipAggregateBatch {
+        packages: Vec<GossipAggregatePackage<E>>,
+    },
     GossipBlock {
         message_id: MessageId,
         peer_id: PeerId,
@@ -372,7 +390,9 @@ impl<E: EthSpec> Work<E> {
     fn str_id(&self) -> &'static str {
         match self {
             Work::GossipAttestation { .. } => "gossip_attestation",
+            Work::GossipAttestationBatch { .. } => "gossip_attestation_batch",
             Work::GossipAggregate { .. } => "gossip_aggregate",
+            Work::GossipAggregateBatch { .. } => "gossip_aggregate_batch",
             Work::GossipBlock { .. } => "gossip_block",
             Work::GossipVoluntaryExit { .. } => "gossip_voluntary_exit",
             Work::GossipProposerSlashing { .. } => "gossip_proposer_slashing",
@@ -522,6 +542,11 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                     // We don't check the `work.drop_during_sync` here. We assume that if it made
                     // it into the queue at any point then we should process it.
                     None if can_spawn => {
+                        let aggregate_batch_size =
+                            cmp::min(aggregate_queue.len(), MAX_GOSSIP_AGGREGATE_BATCH_SIZE);
+                        let attestation_batch_size =
+                            cmp::min(attestation_queue.len(), MAX_GOSSIP_ATTESTATION_BATCH_SIZE);
+
                         // Check for chain segments first, they're the most efficient way to get
                         // blocks into the system.
                         if let Some(item) = chain_segment_queue.pop() {
@@ -538,8 +563,58 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         // since we assume that aggregates are more valuable to local validators
                         // and effectively give us more information with less signature
                         // verification time.
+                        //
+                        // If there are multiple items in a queue, verify them as a batch.
+                        } else if aggregate_batch_size > 1 {
+                            let mut packages = Vec::with_capacity(aggregate_batch_size);
+                            for _ in 0..aggregate_batch_size {
+                                if let Some(Work::GossipAggregate {
+                                    message_id,
+                                    peer_id,
+                                    aggregate,
+                                }) = aggregate_queue.pop()
+                                {
+                                    packages.push(GossipAggregatePackage {
+                                        message_id,
+                                        peer_id,
+                                        aggregate,
+                                    });
+                                } else {
+                                    error!(self.log, "Invalid item in aggregate queue");
+                                }
+                            }
+                            self.spawn_worker(
+                                idle_tx.clone(),
+                                Work::GossipAggregateBatch { packages },
+                            );
                         } else if let Some(item) = aggregate_queue.pop() {
                             self.spawn_worker(idle_tx.clone(), item);
+                        } else if attestation_batch_size > 1 {
+                            let mut packages = Vec::with_capacity(attestation_batch_size);
+                            for _ in 0..attestation_batch_size {
+                                if let Some(Work::GossipAttestation {
+                                    message_id,
+                                    peer_id,
+                                    attestation,
+                                    subnet_id,
+                                    should_import,
+                                }) = attestation_queue.pop()
+                                {
+                                    packages.push(GossipAttestationPackage {
+                                        message_id,
+                                        peer_id,
+                                        attestation,
+                                        subnet_id,
+                                        should_import,
+                                    });
+                                } else {
+                                    error!(self.log, "Invalid item in attestation queue");
+                                }
+                            }
+                            self.spawn_worker(
+                                idle_tx.clone(),
+                                Work::GossipAttestationBatch { packages },
+                            );
                         } else if let Some(item) = attestation_queue.pop() {
                             self.spawn_worker(idle_tx.clone(), item);
                         // Check slashings after all other consensus messages so we prioritize
@@ -590,6 +665,14 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                             _ if can_spawn => self.spawn_worker(idle_tx.clone(), work),
                             Work::GossipAttestation { .. } => attestation_queue.push(work),
                             Work::GossipAggregate { .. } => aggregate_queue.push(work),
+                            // Batches are only created from the contents of the queues, they
+                            // should never arrive as a new work event.
+                            Work::GossipAttestationBatch { .. }
+                            | Work::GossipAggregateBatch { .. } => error!(
+                                self.log,
+                                "Unexpected batch work event";
+                                "work_id" => work_id
+                            ),
                             Work::GossipBlock { .. } => {
                                 gossip_block_queue.push(work, work_id, &self.log)
                             }
@@ -744,6 +827,12 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         subnet_id,
                         should_import,
                     ),
+                    /*
+                     * Batched unaggregated attestation verification.
+                     */
+                    Work::GossipAttestationBatch { packages } => {
+                        worker.process_gossip_attestation_batch(packages)
+                    }
                     /*
                      * Aggregated attestation verification.
                      */
@@ -752,6 +841,12 @@ impl<T: BeaconChainTypes> BeaconProcessor<T> {
                         peer_id,
                         aggregate,
                     } => worker.process_gossip_aggregate(message_id, peer_id, *aggregate),
+                    /*
+                     * Batched aggregated attestation verification.
+                     */
+                    Work::GossipAggregateBatch { packages } => {
+                        worker.process_gossip_aggregate_batch(packages)
+                    }
                     /*
                      * Verification for beacon blocks received on gossip.
                      */
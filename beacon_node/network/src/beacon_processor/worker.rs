@@ -4,7 +4,10 @@ use super::{
 };
 use crate::{metrics, service::NetworkMessage, sync::SyncMessage};
 use beacon_chain::{
-    attestation_verification::Error as AttnError, observed_operations::ObservationOutcome,
+    attestation_verification::{
+        Error as AttnError, VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
+    },
+    observed_operations::ObservationOutcome,
     BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
 };
 use eth2_libp2p::{MessageAcceptance, MessageId, PeerAction, PeerId};
@@ -13,10 +16,28 @@ use ssz::Encode;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use types::{
-    Attestation, AttesterSlashing, Hash256, ProposerSlashing, SignedAggregateAndProof,
+    Attestation, AttesterSlashing, EthSpec, Hash256, ProposerSlashing, SignedAggregateAndProof,
     SignedBeaconBlock, SignedVoluntaryExit, SubnetId,
 };
 
+/// Data for an unaggregated attestation which is verified as part of a batch.
+#[derive(Debug)]
+pub struct GossipAttestationPackage<E: EthSpec> {
+    pub message_id: MessageId,
+    pub peer_id: PeerId,
+    pub attestation: Box<Attestation<E>>,
+    pub subnet_id: SubnetId,
+    pub should_import: bool,
+}
+
+/// Data for an aggregated attestation which is verified as part of a batch.
+#[derive(Debug)]
+pub struct GossipAggregatePackage<E: EthSpec> {
+    pub message_id: MessageId,
+    pub peer_id: PeerId,
+    pub aggregate: Box<SignedAggregateAndProof<E>>,
+}
+
 /// Contains the context necessary to import blocks, attestations, etc to the beacon chain.
 pub struct Worker<T: BeaconChainTypes> {
     pub chain: Arc<BeaconChain<T>>,
@@ -43,10 +64,98 @@ impl<T: BeaconChainTypes> Worker<T> {
     ) {
         let beacon_block_root = attestation.data.beacon_block_root;
 
-        let attestation = match self
+        let result = self
+            .chain
+            .verify_unaggregated_attestation_for_gossip(attestation, Some(subnet_id));
+
+        self.process_gossip_attestation_result(
+            result,
+            message_id,
+            peer_id,
+            beacon_block_root,
+            should_import,
+        );
+    }
+
+    /// Process a batch of unaggregated attestations received from the gossip network, verifying
+    /// their signatures as a single batch.
+    ///
+    /// Each attestation is otherwise processed as per `Self::process_gossip_attestation`.
+    pub fn process_gossip_attestation_batch(
+        self,
+        packages: Vec<GossipAttestationPackage<T::EthSpec>>,
+    ) {
+        let beacon_block_roots = packages
+            .iter()
+            .map(|package| package.attestation.data.beacon_block_root)
+            .collect::<Vec<_>>();
+
+        let (metadata, attestations): (Vec<_>, Vec<_>) = packages
+            .into_iter()
+            .map(|package| {
+                (
+                    (package.message_id, package.peer_id, package.should_import),
+                    (*package.attestation, Some(package.subnet_id)),
+                )
+            })
+            .unzip();
+
+        let results = match self
             .chain
-            .verify_unaggregated_attestation_for_gossip(attestation, Some(subnet_id))
+            .batch_verify_unaggregated_attestations_for_gossip(attestations.into_iter())
         {
+            Ok(results) => results,
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Batch unagg. attn verification failed";
+                    "error" => format!("{:?}", e)
+                );
+                for (message_id, peer_id, _) in metadata {
+                    self.propagate_validation_result(
+                        message_id,
+                        peer_id,
+                        MessageAcceptance::Ignore,
+                    );
+                }
+                return;
+            }
+        };
+
+        if results.len() != metadata.len() {
+            crit!(
+                self.log,
+                "Batch attestation result mismatch";
+                "results" => results.len(),
+                "attestations" => metadata.len(),
+            );
+        }
+
+        for ((result, (message_id, peer_id, should_import)), beacon_block_root) in results
+            .into_iter()
+            .zip(metadata.into_iter())
+            .zip(beacon_block_roots.into_iter())
+        {
+            self.process_gossip_attestation_result(
+                result,
+                message_id,
+                peer_id,
+                beacon_block_root,
+                should_import,
+            );
+        }
+    }
+
+    /// Handles the result of the gossip verification of an unaggregated attestation.
+    fn process_gossip_attestation_result(
+        &self,
+        result: Result<VerifiedUnaggregatedAttestation<T>, AttnError>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        beacon_block_root: Hash256,
+        should_import: bool,
+    ) {
+        let attestation = match result {
             Ok(attestation) => attestation,
             Err(e) => {
                 self.handle_attestation_verification_failure(
@@ -119,10 +228,77 @@ impl<T: BeaconChainTypes> Worker<T> {
     ) {
         let beacon_block_root = aggregate.message.aggregate.data.beacon_block_root;
 
-        let aggregate = match self
+        let result = self
             .chain
-            .verify_aggregated_attestation_for_gossip(aggregate)
+            .verify_aggregated_attestation_for_gossip(aggregate);
+
+        self.process_gossip_aggregate_result(result, message_id, peer_id, beacon_block_root);
+    }
+
+    /// Process a batch of aggregated attestations received from the gossip network, verifying
+    /// their signatures as a single batch.
+    ///
+    /// Each aggregate is otherwise processed as per `Self::process_gossip_aggregate`.
+    pub fn process_gossip_aggregate_batch(self, packages: Vec<GossipAggregatePackage<T::EthSpec>>) {
+        let beacon_block_roots = packages
+            .iter()
+            .map(|package| package.aggregate.message.aggregate.data.beacon_block_root)
+            .collect::<Vec<_>>();
+
+        let (metadata, aggregates): (Vec<_>, Vec<_>) = packages
+            .into_iter()
+            .map(|package| ((package.message_id, package.peer_id), *package.aggregate))
+            .unzip();
+
+        let results = match self
+            .chain
+            .batch_verify_aggregated_attestations_for_gossip(aggregates.into_iter())
+        {
+            Ok(results) => results,
+            Err(e) => {
+                error!(
+                    self.log,
+                    "Batch agg. attn verification failed";
+                    "error" => format!("{:?}", e)
+                );
+                for (message_id, peer_id) in metadata {
+                    self.propagate_validation_result(
+                        message_id,
+                        peer_id,
+                        MessageAcceptance::Ignore,
+                    );
+                }
+                return;
+            }
+        };
+
+        if results.len() != metadata.len() {
+            crit!(
+                self.log,
+                "Batch aggregate result mismatch";
+                "results" => results.len(),
+                "aggregates" => metadata.len(),
+            );
+        }
+
+        for ((result, (message_id, peer_id)), beacon_block_root) in results
+            .into_iter()
+            .zip(metadata.into_iter())
+            .zip(beacon_block_roots.into_iter())
         {
+            self.process_gossip_aggregate_result(result, message_id, peer_id, beacon_block_root);
+        }
+    }
+
+    /// Handles the result of the gossip verification of an aggregated attestation.
+    fn process_gossip_aggregate_result(
+        &self,
+        result: Result<VerifiedAggregatedAttestation<T>, AttnError>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        beacon_block_root: Hash256,
+    ) {
+        let aggregate = match result {
             Ok(aggregate) => aggregate,
             Err(e) => {
                 // Report the failure to gossipsub
@@ -8,7 +8,7 @@ use crate::{error, metrics};
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::{
     rpc::{GoodbyeReason, RPCResponseErrorCode, RequestId},
-    Gossipsub, Libp2pEvent, PeerAction, PeerRequestId, PubsubMessage, Request, Response,
+    Eth2Enr, Gossipsub, Libp2pEvent, PeerAction, PeerRequestId, PubsubMessage, Request, Response,
 };
 use eth2_libp2p::{
     types::GossipKind, BehaviourEvent, GossipTopic, MessageId, NetworkGlobals, PeerId, TopicHash,
@@ -20,7 +20,7 @@ use std::{collections::HashMap, net::SocketAddr, sync::Arc, time::Duration};
 use store::HotColdDB;
 use tokio::sync::mpsc;
 use tokio::time::Delay;
-use types::{EthSpec, ValidatorSubscription};
+use types::{EthSpec, SubnetId, ValidatorSubscription};
 
 mod tests;
 
@@ -108,6 +108,8 @@ pub struct NetworkService<T: BeaconChainTypes> {
     /// Keeps track of if discovery is auto-updating or not. This is used to inform us if we should
     /// update the UDP socket of discovery if the UPnP mappings get established.
     discovery_auto_update: bool,
+    /// Subscribe to all the attestation subnets along with the core topics.
+    subscribe_all_subnets: bool,
     /// A delay that expires when a new fork takes place.
     next_fork_update: Option<Delay>,
     /// A timer for updating various network metrics.
@@ -178,7 +180,8 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         )?;
 
         // attestation service
-        let attestation_service = AttestationService::new(beacon_chain.clone(), &network_log);
+        let attestation_service =
+            AttestationService::new(beacon_chain.clone(), config, &network_log);
 
         // create a timer for updating network metrics
         let metrics_update = tokio::time::interval(Duration::from_secs(METRIC_UPDATE_INTERVAL));
@@ -195,6 +198,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             network_globals: network_globals.clone(),
             upnp_mappings: (None, None),
             discovery_auto_update: config.discv5_config.enr_update,
+            subscribe_all_subnets: config.subscribe_all_subnets,
             next_fork_update,
             metrics_update,
             log: network_log,
@@ -340,6 +344,27 @@ fn spawn_service<T: BeaconChainTypes>(
                                     warn!(service.log, "Could not subscribe to topic"; "topic" => format!("{}",topic_kind));
                                 }
                             }
+                            if service.subscribe_all_subnets {
+                                let attnets = service.network_globals.local_enr().bitfield::<T::EthSpec>().ok();
+                                for subnet_id in 0..service.beacon_chain.spec.attestation_subnet_count {
+                                    let subnet_id = SubnetId::new(subnet_id);
+                                    let topic_kind = GossipKind::Attestation(subnet_id);
+                                    // Advertise every subnet in the ENR `attnets` bitfield, even
+                                    // those which were subscribed to for a validator duty.
+                                    let advertised = attnets.as_ref().and_then(|attnets| attnets.get(*subnet_id as usize).ok());
+                                    if advertised != Some(true) {
+                                        service.libp2p.swarm.update_enr_subnet(subnet_id, true);
+                                    }
+                                    if already_subscribed.contains(&topic_kind) {
+                                        continue;
+                                    }
+                                    if service.libp2p.swarm.subscribe_kind(topic_kind.clone()) {
+                                        subscribed_topics.push(topic_kind);
+                                    } else {
+                                        warn!(service.log, "Could not subscribe to topic"; "topic" => format!("{}", topic_kind));
+                                    }
+                                }
+                            }
                             if !subscribed_topics.is_empty() {
                                 info!(service.log, "Subscribed to topics"; "topics" => format!("{:?}", subscribed_topics));
                             }
@@ -41,7 +41,7 @@ use crate::beacon_processor::{ProcessId, WorkEvent as BeaconWorkEvent};
 use crate::service::NetworkMessage;
 use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
 use eth2_libp2p::rpc::{methods::MAX_REQUEST_BLOCKS, BlocksByRootRequest, GoodbyeReason};
-use eth2_libp2p::types::{NetworkGlobals, SyncState};
+use eth2_libp2p::types::{NetworkGlobals, SyncState, SyncStats};
 use eth2_libp2p::{PeerAction, PeerId};
 use fnv::FnvHashMap;
 use lru_cache::LRUCache;
@@ -49,8 +49,10 @@ use slog::{crit, debug, error, info, trace, warn, Logger};
 use smallvec::SmallVec;
 use ssz_types::VariableList;
 use std::boxed::Box;
+use std::collections::VecDeque;
 use std::ops::Sub;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -68,6 +70,10 @@ const PARENT_FAIL_TOLERANCE: usize = 5;
 /// canonical chain to its head once the peer connects. A chain should not appear where it's depth
 /// is further back than the most recent head slot.
 const PARENT_DEPTH_TOLERANCE: usize = SLOT_IMPORT_TOLERANCE * 2;
+/// How often slow range sync batches are re-assigned and the sync stats are updated.
+const SYNC_TICK_INTERVAL: Duration = Duration::from_secs(5);
+/// The number of head slot samples, one per tick, used to compute the import rate.
+const IMPORT_RATE_SAMPLES: usize = 12;
 
 #[derive(Debug)]
 /// A message than can be sent to the sync manager thread.
@@ -179,6 +185,9 @@ pub struct SyncManager<T: BeaconChainTypes> {
     /// A multi-threaded, non-blocking processor for applying messages to the beacon chain.
     beacon_processor_send: mpsc::Sender<BeaconWorkEvent<T::EthSpec>>,
 
+    /// Recent samples of the local head slot, used to estimate the time left to sync.
+    head_slot_samples: VecDeque<(Instant, Slot)>,
+
     /// The logger for the import manager.
     log: Logger,
 }
@@ -233,6 +242,7 @@ pub fn spawn<T: BeaconChainTypes>(
         failed_chains: LRUCache::new(500),
         single_block_lookups: FnvHashMap::default(),
         beacon_processor_send,
+        head_slot_samples: VecDeque::with_capacity(IMPORT_RATE_SAMPLES),
         log: log.clone(),
     };
 
@@ -727,6 +737,67 @@ impl<T: BeaconChainTypes> SyncManager<T> {
         }
     }
 
+    /// Publishes the download rate and estimated time to completion of an ongoing long-range
+    /// sync.
+    fn update_sync_stats(&mut self) {
+        let target_slot = match self.network_globals.sync_state() {
+            SyncState::SyncingFinalized { target_slot, .. }
+            | SyncState::SyncingHead { target_slot, .. } => target_slot,
+            SyncState::Synced | SyncState::Stalled => {
+                self.head_slot_samples.clear();
+                self.network_globals.set_sync_stats(None);
+                return;
+            }
+        };
+
+        let now = Instant::now();
+        let head_slot = self.chain.best_slot().unwrap_or_else(|_| Slot::new(0));
+        if self.head_slot_samples.len() >= IMPORT_RATE_SAMPLES {
+            self.head_slot_samples.pop_front();
+        }
+        self.head_slot_samples.push_back((now, head_slot));
+
+        let import_rate = match (
+            self.head_slot_samples.front(),
+            self.head_slot_samples.back(),
+        ) {
+            (Some((first_time, first_slot)), Some((last_time, last_slot))) => {
+                let elapsed = last_time
+                    .saturating_duration_since(*first_time)
+                    .as_secs_f64();
+                if elapsed > 0.0 {
+                    last_slot.saturating_sub(*first_slot).as_u64() as f64 / elapsed
+                } else {
+                    0.0
+                }
+            }
+            _ => 0.0,
+        };
+        let eta_seconds = if import_rate > 0.0 {
+            Some((target_slot.saturating_sub(head_slot).as_u64() as f64 / import_rate) as u64)
+        } else {
+            None
+        };
+
+        let peer_quality = self.network.peer_quality_mut();
+        let stats = SyncStats {
+            head_slot,
+            target_slot,
+            download_rate: peer_quality.download_rate(now),
+            import_rate,
+            eta_seconds,
+            peers: peer_quality.peer_stats(),
+        };
+        debug!(self.log, "Sync progress";
+            "head_slot" => head_slot,
+            "target_slot" => target_slot,
+            "download_rate" => format!("{:.2} blocks/s", stats.download_rate),
+            "import_rate" => format!("{:.2} slots/s", import_rate),
+            "eta_seconds" => ?eta_seconds,
+        );
+        self.network_globals.set_sync_stats(Some(stats));
+    }
+
     /* Processing State Functions */
     // These functions are called in the main poll function to transition the state of the sync
     // manager
@@ -906,9 +977,22 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
     /// The main driving future for the sync manager.
     async fn main(&mut self) {
+        let mut sync_tick = tokio::time::interval(SYNC_TICK_INTERVAL);
+
         // process any inbound messages
         loop {
-            if let Some(sync_message) = self.input_channel.recv().await {
+            let sync_message = tokio::select! {
+                sync_message = self.input_channel.recv() => sync_message,
+                _ = sync_tick.tick() => {
+                    // re-assign slow batches and publish the sync progress
+                    self.range_sync.retry_slow_batches(&mut self.network);
+                    self.update_sync_state();
+                    self.update_sync_stats();
+                    continue;
+                }
+            };
+
+            if let Some(sync_message) = sync_message {
                 match sync_message {
                     SyncMessage::AddPeer(peer_id, info) => {
                         self.add_peer(peer_id, info);
@@ -1,7 +1,7 @@
 //! Provides network functionality for the Syncing thread. This fundamentally wraps a network
 //! channel and stores a global RPC ID to perform requests.
 
-use super::range_sync::{BatchId, ChainId};
+use super::range_sync::{BatchId, ChainId, PeerQualityTracker};
 use super::RequestId as SyncRequestId;
 use crate::router::processor::status_message;
 use crate::service::NetworkMessage;
@@ -11,6 +11,7 @@ use eth2_libp2p::{Client, NetworkGlobals, PeerAction, PeerId, Request};
 use fnv::FnvHashMap;
 use slog::{debug, trace, warn};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 use types::EthSpec;
 
@@ -29,6 +30,9 @@ pub struct SyncNetworkContext<T: EthSpec> {
     /// BlocksByRange requests made by range syncing chains.
     range_requests: FnvHashMap<SyncRequestId, (ChainId, BatchId)>,
 
+    /// The download performance of the peers serving BlocksByRange requests.
+    peer_quality: PeerQualityTracker,
+
     /// Logger for the `SyncNetworkContext`.
     log: slog::Logger,
 }
@@ -44,6 +48,7 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             network_globals,
             request_id: 1,
             range_requests: FnvHashMap::default(),
+            peer_quality: PeerQualityTracker::default(),
             log,
         }
     }
@@ -95,8 +100,10 @@ impl<T: EthSpec> SyncNetworkContext<T> {
             "count" => request.count,
             "peer" => %peer_id,
         );
-        let req_id = self.send_rpc_request(peer_id, Request::BlocksByRange(request))?;
+        let req_id = self.send_rpc_request(peer_id.clone(), Request::BlocksByRange(request))?;
         self.range_requests.insert(req_id, (chain_id, batch_id));
+        self.peer_quality
+            .on_request(peer_id, req_id, Instant::now());
         Ok(req_id)
     }
 
@@ -115,6 +122,15 @@ impl<T: EthSpec> SyncNetworkContext<T> {
         }
     }
 
+    /// Returns the download performance of the peers serving BlocksByRange requests.
+    pub fn peer_quality(&self) -> &PeerQualityTracker {
+        &self.peer_quality
+    }
+
+    pub fn peer_quality_mut(&mut self) -> &mut PeerQualityTracker {
+        &mut self.peer_quality
+    }
+
     pub fn blocks_by_root_request(
         &mut self,
         peer_id: PeerId,
@@ -177,3 +193,26 @@ impl<T: EthSpec> SyncNetworkContext<T> {
         })
     }
 }
+
+#[cfg(test)]
+impl<T: EthSpec> SyncNetworkContext<T> {
+    /// Returns a context which is not connected to a network service, along with the receiver
+    /// of the messages it sends.
+    pub fn for_testing(log: slog::Logger) -> (Self, mpsc::UnboundedReceiver<NetworkMessage<T>>) {
+        use eth2_libp2p::discv5::enr::{CombinedKey, EnrBuilder};
+        use eth2_libp2p::rpc::methods::MetaData;
+
+        let enr_key = CombinedKey::generate_secp256k1();
+        let enr = EnrBuilder::new("v4")
+            .build(&enr_key)
+            .expect("should build enr");
+        let meta_data = MetaData {
+            seq_number: 0,
+            attnets: Default::default(),
+        };
+        let network_globals = Arc::new(NetworkGlobals::new(enr, 0, 0, meta_data, vec![], &log));
+        let (network_send, network_recv) = mpsc::unbounded_channel();
+
+        (Self::new(network_send, network_globals, log), network_recv)
+    }
+}
@@ -226,6 +226,22 @@ impl<T: EthSpec> BatchInfo<T> {
         }
     }
 
+    /// Abandons the current download so the batch can be requested from a different peer,
+    /// without counting it as a failed attempt.
+    pub fn download_reassigned(&mut self, logger: &Logger) {
+        match self.state.poison() {
+            BatchState::Downloading(..) => {
+                // drop the blocks
+                self.state = BatchState::AwaitingDownload;
+            }
+            BatchState::Poisoned => unreachable!("Poisoned batch"),
+            other => {
+                crit!(logger, "Download reassigned for batch in wrong state"; "state" => ?other);
+                self.state = other
+            }
+        }
+    }
+
     pub fn start_downloading_from_peer(
         &mut self,
         peer: PeerId,
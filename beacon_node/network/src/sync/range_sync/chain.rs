@@ -7,8 +7,10 @@ use eth2_libp2p::{PeerAction, PeerId};
 use fnv::FnvHashMap;
 use rand::seq::SliceRandom;
 use slog::{crit, debug, o, warn};
+use std::cmp::Ordering;
 use std::collections::{btree_map::Entry, BTreeMap, HashSet};
 use std::hash::{Hash, Hasher};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc::Sender;
 use types::{Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
 
@@ -23,6 +25,11 @@ pub const EPOCHS_PER_BATCH: u64 = 8;
 /// The maximum number of batches to queue before requesting more.
 const BATCH_BUFFER_SIZE: u8 = 5;
 
+/// A batch that has been downloading for longer than this is re-assigned to a faster peer, if one
+/// is available. A peer slowly streaming blocks may never trigger the RPC response timeout, so
+/// this prevents a single slow peer from stalling the chain.
+const SLOW_BATCH_THRESHOLD: Duration = Duration::from_secs(15);
+
 /// A return type for functions that act on a `Chain` which informs the caller whether the chain
 /// has been completed and should be removed or to be kept if further processing is
 /// required.
@@ -820,14 +827,26 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         let failed_peers = batch.failed_peers();
 
         let new_peer = {
+            let peer_quality = network.peer_quality();
             let mut priorized_peers = self
                 .peers
                 .iter()
-                .map(|(peer, requests)| (failed_peers.contains(peer), requests.len(), peer))
+                .map(|(peer, requests)| {
+                    (
+                        failed_peers.contains(peer),
+                        requests.len(),
+                        peer_quality.score(peer),
+                        peer,
+                    )
+                })
                 .collect::<Vec<_>>();
-            // Sort peers prioritizing unrelated peers with less active requests.
-            priorized_peers.sort_unstable();
-            priorized_peers.get(0).map(|&(_, _, peer)| peer.clone())
+            // Sort peers prioritizing unrelated peers with less active requests, then faster peers.
+            priorized_peers.sort_unstable_by(|a, b| {
+                (a.0, a.1)
+                    .cmp(&(b.0, b.1))
+                    .then_with(|| b.2.partial_cmp(&a.2).unwrap_or(Ordering::Equal))
+            });
+            priorized_peers.get(0).map(|&(_, _, _, peer)| peer.clone())
         };
 
         if let Some(peer) = new_peer {
@@ -838,6 +857,78 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
     }
 
+    /// Re-assigns batches that have been downloading for longer than `SLOW_BATCH_THRESHOLD` to
+    /// the best peer with spare capacity. The slow request counts against the peer's quality but
+    /// not as a failed download attempt of the batch.
+    pub fn retry_slow_batches(
+        &mut self,
+        network: &mut SyncNetworkContext<T::EthSpec>,
+        now: Instant,
+    ) -> ProcessingResult {
+        if !self.is_syncing() {
+            return ProcessingResult::KeepChain;
+        }
+
+        let slow_batches = self
+            .batches
+            .iter()
+            .filter_map(|(batch_id, batch)| match batch.state() {
+                BatchState::Downloading(peer, _, request_id) => network
+                    .peer_quality()
+                    .request_duration(*request_id, now)
+                    .filter(|duration| *duration > SLOW_BATCH_THRESHOLD)
+                    .map(|_| (*batch_id, peer.clone(), *request_id)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (batch_id, slow_peer, request_id) in slow_batches {
+            let failed_peers = match self.batches.get(&batch_id) {
+                Some(batch) => batch.failed_peers(),
+                None => continue,
+            };
+
+            let new_peer = {
+                let peer_quality = network.peer_quality();
+                self.peers
+                    .keys()
+                    .filter(|peer| {
+                        **peer != slow_peer
+                            && !failed_peers.contains(*peer)
+                            && peer_quality.active_requests(peer)
+                                < peer_quality.max_concurrent_requests(peer)
+                    })
+                    .max_by(|a, b| {
+                        peer_quality
+                            .score(a)
+                            .partial_cmp(&peer_quality.score(b))
+                            .unwrap_or(Ordering::Equal)
+                    })
+                    .cloned()
+            };
+
+            if let Some(new_peer) = new_peer {
+                debug!(self.log, "Re-assigning slow batch"; "epoch" => batch_id,
+                    "slow_peer" => %slow_peer, "new_peer" => %new_peer);
+                // The slow request cannot be cancelled, so it stays registered with the peer
+                // quality tracker until it terminates.
+                network.peer_quality_mut().on_abandoned(request_id);
+                if let Some(active_requests) = self.peers.get_mut(&slow_peer) {
+                    active_requests.remove(&batch_id);
+                }
+                if let Some(batch) = self.batches.get_mut(&batch_id) {
+                    batch.download_reassigned(&self.log);
+                }
+                if let ProcessingResult::RemoveChain = self.send_batch(network, batch_id, new_peer)
+                {
+                    return ProcessingResult::RemoveChain;
+                }
+            }
+        }
+
+        ProcessingResult::KeepChain
+    }
+
     /// Requests the batch asigned to the given id from a given peer.
     pub fn send_batch(
         &mut self,
@@ -901,6 +992,9 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
     /// Attempts to request the next required batches from the peer pool if the chain is syncing. It will exhaust the peer
     /// pool and left over batches until the batch buffer is reached or all peers are exhausted.
+    ///
+    /// Peers are allowed as many concurrent batches as their download quality permits, with the
+    /// fastest peers being assigned batches first.
     fn request_batches(
         &mut self,
         network: &mut SyncNetworkContext<T::EthSpec>,
@@ -911,20 +1005,48 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // find the next pending batch and request it from the peer
 
-        // randomize the peers for load balancing
+        // randomize the peers for load balancing, then order them by quality
         let mut rng = rand::thread_rng();
-        let mut idle_peers = self
+        let peer_quality = network.peer_quality();
+        let mut available_peers = self
             .peers
-            .iter()
-            .filter_map(|(peer, requests)| {
-                if requests.is_empty() {
-                    Some(peer.clone())
+            .keys()
+            .filter_map(|peer| {
+                let spare = peer_quality
+                    .max_concurrent_requests(peer)
+                    .saturating_sub(peer_quality.active_requests(peer));
+                if spare > 0 {
+                    Some((peer.clone(), spare))
                 } else {
                     None
                 }
             })
             .collect::<Vec<_>>();
-        idle_peers.shuffle(&mut rng);
+        available_peers.shuffle(&mut rng);
+        available_peers.sort_by(|(a, _), (b, _)| {
+            peer_quality
+                .score(b)
+                .partial_cmp(&peer_quality.score(a))
+                .unwrap_or(Ordering::Equal)
+        });
+
+        // Hand out batches in rounds, so that every available peer is given a batch before the
+        // fastest peers are given additional ones.
+        let rounds = available_peers
+            .iter()
+            .map(|(_, spare)| *spare)
+            .max()
+            .unwrap_or(0);
+        let mut idle_peers = (0..rounds)
+            .flat_map(|round| {
+                available_peers
+                    .iter()
+                    .filter(move |(_, spare)| *spare > round)
+                    .map(|(peer, _)| peer.clone())
+            })
+            .collect::<Vec<_>>();
+        // peers are popped from the back
+        idle_peers.reverse();
 
         // check if we have the batch for our optimistic start. If not, request it first.
         // We wait for this batch before requesting any other batches.
@@ -1040,3 +1162,144 @@ impl<T: BeaconChainTypes> slog::KV for SyncingChain<T> {
         slog::Result::Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::EphemeralHarnessType;
+    use slog::Logger;
+    use tokio::sync::mpsc;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+    type TestChain = SyncingChain<EphemeralHarnessType<E>>;
+
+    /// A request id which is never assigned by the network context.
+    const FAKE_REQUEST_ID: RequestId = RequestId::max_value();
+
+    fn null_logger() -> Logger {
+        Logger::root(slog::Discard, o!())
+    }
+
+    /// Returns a chain with `batches` batches to download from `peer`, which is not syncing.
+    fn new_chain(
+        peer: PeerId,
+        batches: u64,
+        beacon_processor_send: Sender<BeaconWorkEvent<E>>,
+    ) -> TestChain {
+        let target_head_slot =
+            Epoch::new(batches * EPOCHS_PER_BATCH).start_slot(E::slots_per_epoch()) - 1;
+        SyncingChain::new(
+            Epoch::new(0),
+            target_head_slot,
+            Hash256::repeat_byte(1),
+            peer,
+            beacon_processor_send,
+            &null_logger(),
+        )
+    }
+
+    /// Registers a completed request that makes `peer` look fast enough to download the maximum
+    /// number of batches at once.
+    fn make_fast(network: &mut SyncNetworkContext<E>, peer: &PeerId, now: Instant) {
+        let peer_quality = network.peer_quality_mut();
+        peer_quality.on_request(peer.clone(), FAKE_REQUEST_ID, now);
+        for _ in 0..256 {
+            peer_quality.on_block(FAKE_REQUEST_ID);
+        }
+        peer_quality.on_completed(FAKE_REQUEST_ID, now + Duration::from_secs(1));
+    }
+
+    fn downloading_peer(chain: &TestChain, batch_id: BatchId) -> (PeerId, RequestId) {
+        match chain.batches.get(&batch_id).map(BatchInfo::state) {
+            Some(BatchState::Downloading(peer, _, request_id)) => (peer.clone(), *request_id),
+            _ => panic!("batch {} should be downloading", batch_id),
+        }
+    }
+
+    #[test]
+    fn fast_peers_are_given_more_batches() {
+        let (mut network, _network_recv) = SyncNetworkContext::for_testing(null_logger());
+        let (beacon_processor_send, _beacon_processor_recv) = mpsc::channel(16);
+        let fast = PeerId::random();
+        let unknown = PeerId::random();
+        make_fast(&mut network, &fast, Instant::now());
+
+        let mut chain = new_chain(fast.clone(), 16, beacon_processor_send);
+        assert!(chain.add_peer(&mut network, unknown.clone()) == ProcessingResult::KeepChain);
+        assert!(
+            chain.start_syncing(&mut network, Epoch::new(0), Epoch::new(0))
+                == ProcessingResult::KeepChain
+        );
+
+        // Every peer is given a batch before the fast peer is given additional ones.
+        assert_eq!(chain.peers[&unknown].len(), 1);
+        assert_eq!(chain.peers[&fast].len(), 3);
+        assert_eq!(downloading_peer(&chain, Epoch::new(0)).0, fast);
+        assert_eq!(
+            downloading_peer(&chain, Epoch::new(EPOCHS_PER_BATCH)).0,
+            unknown
+        );
+        assert_eq!(network.peer_quality().active_requests(&fast), 3);
+        assert_eq!(network.peer_quality().active_requests(&unknown), 1);
+    }
+
+    #[test]
+    fn slow_batches_are_reassigned() {
+        let (mut network, _network_recv) = SyncNetworkContext::for_testing(null_logger());
+        let (beacon_processor_send, _beacon_processor_recv) = mpsc::channel(16);
+        let fast = PeerId::random();
+        let slow = PeerId::random();
+        let now = Instant::now();
+        make_fast(&mut network, &fast, now);
+
+        // Only two batches, so that the fast peer has spare capacity.
+        let mut chain = new_chain(fast.clone(), 2, beacon_processor_send);
+        assert!(chain.add_peer(&mut network, slow.clone()) == ProcessingResult::KeepChain);
+        assert!(
+            chain.start_syncing(&mut network, Epoch::new(0), Epoch::new(0))
+                == ProcessingResult::KeepChain
+        );
+
+        let slow_batch = Epoch::new(EPOCHS_PER_BATCH);
+        let (peer, slow_request_id) = downloading_peer(&chain, slow_batch);
+        assert_eq!(peer, slow);
+
+        // Nothing is re-assigned before the threshold.
+        assert!(chain.retry_slow_batches(&mut network, now) == ProcessingResult::KeepChain);
+        assert_eq!(
+            downloading_peer(&chain, slow_batch),
+            (slow.clone(), slow_request_id)
+        );
+
+        let later = now + SLOW_BATCH_THRESHOLD + Duration::from_secs(1);
+        assert!(chain.retry_slow_batches(&mut network, later) == ProcessingResult::KeepChain);
+
+        // The batch of the fast peer has no other peer with spare capacity, so it is kept.
+        assert_eq!(downloading_peer(&chain, Epoch::new(0)).0, fast);
+        // The batch of the slow peer is moved to the fast peer.
+        let (peer, new_request_id) = downloading_peer(&chain, slow_batch);
+        assert_eq!(peer, fast);
+        assert_ne!(new_request_id, slow_request_id);
+        assert!(chain.peers[&slow].is_empty());
+        assert_eq!(chain.peers[&fast].len(), 2);
+
+        // The slow peer is penalised, but is still serving the abandoned request.
+        assert_eq!(network.peer_quality().active_requests(&slow), 1);
+        assert_eq!(network.peer_quality().active_requests(&fast), 2);
+        let slow_stats = network
+            .peer_quality()
+            .peer_stats()
+            .into_iter()
+            .find(|stats| stats.peer_id == slow.to_string())
+            .expect("slow peer should have stats");
+        assert_eq!(slow_stats.failed_requests, 1);
+
+        // A late response to the abandoned request is ignored.
+        assert!(
+            chain.on_block_response(&mut network, slow_batch, &slow, slow_request_id, None)
+                == ProcessingResult::KeepChain
+        );
+        assert_eq!(downloading_peer(&chain, slow_batch), (fast, new_request_id));
+    }
+}
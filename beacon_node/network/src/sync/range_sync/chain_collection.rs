@@ -13,25 +13,36 @@ use eth2_libp2p::PeerId;
 use fnv::FnvHashMap;
 use slog::{debug, error};
 use smallvec::SmallVec;
+use std::cmp::Reverse;
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use types::EthSpec;
 use types::{Epoch, Hash256, Slot};
 
+/// The number of finalized chains to sync at a time.
+const PARALLEL_FINALIZED_CHAINS: usize = 2;
+
 /// The number of head syncing chains to sync at a time.
 const PARALLEL_HEAD_CHAINS: usize = 2;
 
 /// The state of the long range/batch sync.
-#[derive(Clone)]
-pub enum RangeSyncState {
-    /// A finalized chain is being synced.
-    Finalized(u64),
-    /// There are no finalized chains and we are syncing one more head chains.
-    Head(SmallVec<[u64; PARALLEL_HEAD_CHAINS]>),
-    /// There are no head or finalized chains and no long range sync is in progress.
-    Idle,
+///
+/// Finalized and head chains are synced at the same time. Peers are shared between all syncing
+/// chains by the peer quality scheduling, so a chain with slow peers does not hold back the
+/// others. When no chains are syncing, the sync is idle.
+#[derive(Clone, Default)]
+pub struct RangeSyncState {
+    /// The finalized chains being synced.
+    finalized: SmallVec<[ChainId; PARALLEL_FINALIZED_CHAINS]>,
+    /// The head chains being synced.
+    head: SmallVec<[ChainId; PARALLEL_HEAD_CHAINS]>,
+}
+
+impl RangeSyncState {
+    fn is_syncing(&self, id: &ChainId) -> bool {
+        self.finalized.contains(id) || self.head.contains(id)
+    }
 }
 
 /// A collection of finalized and head chains currently being processed.
@@ -54,55 +65,16 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
             beacon_chain,
             finalized_chains: FnvHashMap::default(),
             head_chains: FnvHashMap::default(),
-            state: RangeSyncState::Idle,
+            state: RangeSyncState::default(),
             log,
         }
     }
 
     /// Updates the Syncing state of the collection after a chain is removed.
     fn on_chain_removed(&mut self, id: &ChainId, was_syncing: bool) {
-        match self.state {
-            RangeSyncState::Finalized(ref syncing_id) => {
-                if syncing_id == id {
-                    // the finalized chain that was syncing was removed
-                    debug_assert!(was_syncing);
-                    let syncing_head_ids: SmallVec<[u64; PARALLEL_HEAD_CHAINS]> = self
-                        .head_chains
-                        .iter()
-                        .filter(|(_id, chain)| chain.is_syncing())
-                        .map(|(id, _)| *id)
-                        .collect();
-                    self.state = if syncing_head_ids.is_empty() {
-                        RangeSyncState::Idle
-                    } else {
-                        RangeSyncState::Head(syncing_head_ids)
-                    };
-                } else {
-                    debug_assert!(!was_syncing);
-                }
-            }
-            RangeSyncState::Head(ref mut syncing_head_ids) => {
-                if let Some(index) = syncing_head_ids
-                    .iter()
-                    .enumerate()
-                    .find(|(_, &chain_id)| &chain_id == id)
-                    .map(|(i, _)| i)
-                {
-                    // a syncing head chain was removed
-                    debug_assert!(was_syncing);
-                    syncing_head_ids.swap_remove(index);
-                    if syncing_head_ids.is_empty() {
-                        self.state = RangeSyncState::Idle;
-                    }
-                } else {
-                    debug_assert!(!was_syncing);
-                }
-            }
-            RangeSyncState::Idle => {
-                // the removed chain should not be syncing
-                debug_assert!(!was_syncing)
-            }
-        }
+        debug_assert_eq!(self.state.is_syncing(id), was_syncing);
+        self.state.finalized.retain(|syncing_id| syncing_id != id);
+        self.state.head.retain(|syncing_id| syncing_id != id);
     }
 
     /// Calls `func` on every chain of the collection. If the result is
@@ -178,15 +150,9 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
 
     /// Updates the state of the chain collection.
     ///
-    /// This removes any out-dated chains, swaps to any higher priority finalized chains and
-    /// updates the state of the collection. This starts head chains syncing if any are required to
-    /// do so.
-    pub fn update(
-        &mut self,
-        network: &mut SyncNetworkContext<T::EthSpec>,
-        awaiting_head_peers: &mut HashMap<PeerId, PeerSyncInfo>,
-        beacon_processor_send: &mpsc::Sender<BeaconWorkEvent<T::EthSpec>>,
-    ) {
+    /// This removes any out-dated chains, then starts syncing the best finalized and head chains,
+    /// stopping any others.
+    pub fn update(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) {
         let (local_finalized_epoch, local_head_epoch) =
             match PeerSyncInfo::from_chain(&self.beacon_chain) {
                 None => {
@@ -203,200 +169,112 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
             };
 
         // Remove any outdated finalized/head chains
-        self.purge_outdated_chains(awaiting_head_peers);
-
-        // Choose the best finalized chain if one needs to be selected.
-        self.update_finalized_chains(network, local_finalized_epoch, local_head_epoch);
-
-        if !matches!(self.state, RangeSyncState::Finalized(_)) {
-            // Handle head syncing chains if there are no finalized chains left.
-            self.update_head_chains(
-                network,
-                local_finalized_epoch,
-                local_head_epoch,
-                awaiting_head_peers,
-                beacon_processor_send,
-            );
-        }
+        self.purge_outdated_chains();
+
+        self.state.finalized = Self::sync_best_chains(
+            &mut self.finalized_chains,
+            PARALLEL_FINALIZED_CHAINS,
+            network,
+            local_finalized_epoch,
+            local_head_epoch,
+            &self.log,
+        )
+        .into_iter()
+        .collect();
+
+        self.state.head = Self::sync_best_chains(
+            &mut self.head_chains,
+            PARALLEL_HEAD_CHAINS,
+            network,
+            local_finalized_epoch,
+            local_head_epoch,
+            &self.log,
+        )
+        .into_iter()
+        .collect();
     }
 
+    /// Returns the type of the chains being synced along with the range of slots they cover.
+    ///
+    /// Finalized chains take precedence when both types of chain are syncing.
     pub fn state(&self) -> Result<Option<(RangeSyncType, Slot /* from */, Slot /* to */)>, String> {
-        match self.state {
-            RangeSyncState::Finalized(ref syncing_id) => {
-                let chain = self
-                    .finalized_chains
-                    .get(syncing_id)
-                    .ok_or(format!("Finalized syncing chain not found: {}", syncing_id))?;
-                Ok(Some((
-                    RangeSyncType::Finalized,
-                    chain.start_epoch.start_slot(T::EthSpec::slots_per_epoch()),
-                    chain.target_head_slot,
-                )))
-            }
-            RangeSyncState::Head(ref syncing_head_ids) => {
-                let mut range: Option<(Slot, Slot)> = None;
-                for id in syncing_head_ids {
-                    let chain = self
-                        .head_chains
-                        .get(id)
-                        .ok_or(format!("Head syncing chain not found: {}", id))?;
-                    let start = chain.start_epoch.start_slot(T::EthSpec::slots_per_epoch());
-                    let target = chain.target_head_slot;
-
-                    range = range
-                        .map(|(min_start, max_slot)| (min_start.min(start), max_slot.max(target)))
-                        .or(Some((start, target)));
-                }
-                let (start_slot, target_slot) =
-                    range.ok_or_else(|| "Syncing head with empty head ids".to_string())?;
-                Ok(Some((RangeSyncType::Head, start_slot, target_slot)))
-            }
-            RangeSyncState::Idle => Ok(None),
-        }
-    }
-
-    /// This looks at all current finalized chains and decides if a new chain should be prioritised
-    /// or not.
-    fn update_finalized_chains(
-        &mut self,
-        network: &mut SyncNetworkContext<T::EthSpec>,
-        local_epoch: Epoch,
-        local_head_epoch: Epoch,
-    ) {
-        // Find the chain with most peers and check if it is already syncing
-        if let Some((mut new_id, peers)) = self
-            .finalized_chains
-            .iter()
-            .max_by_key(|(_, chain)| chain.available_peers())
-            .map(|(id, chain)| (*id, chain.available_peers()))
-        {
-            let mut old_id = None;
-            if let RangeSyncState::Finalized(syncing_id) = self.state {
-                if syncing_id == new_id {
-                    // best chain is already syncing
-                    old_id = Some(None);
-                } else {
-                    // chains are different, check that they don't have the same number of peers
-                    if let Some(syncing_chain) = self.finalized_chains.get_mut(&syncing_id) {
-                        if syncing_chain.available_peers() > peers {
-                            syncing_chain.stop_syncing();
-                            old_id = Some(Some(syncing_id));
-                        } else {
-                            // chains have the same number of peers, pick the currently syncing
-                            // chain to avoid unnecesary switchings and try to advance it
-                            new_id = syncing_id;
-                            old_id = Some(None);
-                        }
-                    }
-                }
-            }
-
-            let chain = self
-                .finalized_chains
-                .get_mut(&new_id)
-                .expect("Chain exists");
-
-            match old_id {
-                Some(Some(old_id)) => debug!(self.log, "Switching finalized chains";
-                    "old_id" => old_id, &chain),
-                None => debug!(self.log, "Syncing new chain"; &chain),
-                Some(None) => {
-                    // this is the same chain. We try to advance it.
-                }
-            }
-
-            // update the state to a new finalized state
-            self.state = RangeSyncState::Finalized(new_id);
+        let (sync_type, ids, chains) = if !self.state.finalized.is_empty() {
+            (
+                RangeSyncType::Finalized,
+                &self.state.finalized[..],
+                &self.finalized_chains,
+            )
+        } else if !self.state.head.is_empty() {
+            (RangeSyncType::Head, &self.state.head[..], &self.head_chains)
+        } else {
+            return Ok(None);
+        };
 
-            if let ProcessingResult::RemoveChain =
-                chain.start_syncing(network, local_epoch, local_head_epoch)
-            {
-                // this happens only if sending a batch over the `network` fails a lot
-                error!(self.log, "Chain removed while switching chains");
-                self.finalized_chains.remove(&new_id);
-                self.on_chain_removed(&new_id, true);
-            }
+        let mut range: Option<(Slot, Slot)> = None;
+        for id in ids {
+            let chain = chains
+                .get(id)
+                .ok_or_else(|| format!("{:?} syncing chain not found: {}", sync_type, id))?;
+            let start = chain.start_epoch.start_slot(T::EthSpec::slots_per_epoch());
+            let target = chain.target_head_slot;
+
+            range = range
+                .map(|(min_start, max_slot)| (min_start.min(start), max_slot.max(target)))
+                .or(Some((start, target)));
         }
+        let (start_slot, target_slot) =
+            range.ok_or_else(|| "Syncing with empty chain ids".to_string())?;
+        Ok(Some((sync_type, start_slot, target_slot)))
     }
 
-    /// Start syncing any head chains if required.
-    fn update_head_chains(
-        &mut self,
+    /// Starts syncing up to `max_syncing` of `chains` and stops the others, returning the ids of
+    /// the syncing chains.
+    ///
+    /// Chains with more peers are preferred. Between chains with the same number of peers, those
+    /// that are already syncing are preferred to avoid unnecessary switching.
+    fn sync_best_chains(
+        chains: &mut FnvHashMap<ChainId, SyncingChain<T>>,
+        max_syncing: usize,
         network: &mut SyncNetworkContext<T::EthSpec>,
         local_epoch: Epoch,
         local_head_epoch: Epoch,
-        awaiting_head_peers: &mut HashMap<PeerId, PeerSyncInfo>,
-        beacon_processor_send: &mpsc::Sender<BeaconWorkEvent<T::EthSpec>>,
-    ) {
-        // Include the awaiting head peers
-        for (peer_id, peer_sync_info) in awaiting_head_peers.drain() {
-            self.add_peer_or_create_chain(
-                local_epoch,
-                peer_sync_info.head_root,
-                peer_sync_info.head_slot,
-                peer_id,
-                RangeSyncType::Head,
-                beacon_processor_send,
-                network,
-            );
-        }
-
-        if self.head_chains.is_empty() {
-            // There are no finalized chains, update the state.
-            self.state = RangeSyncState::Idle;
-            return;
-        }
-
-        // Order chains by available peers, if two chains have the same number of peers, prefer one
-        // that is already syncing
-        let mut preferred_ids = self
-            .head_chains
+        log: &slog::Logger,
+    ) -> Vec<ChainId> {
+        let mut preferred_ids = chains
             .iter()
-            .map(|(id, chain)| (chain.available_peers(), !chain.is_syncing(), *id))
+            .map(|(id, chain)| (Reverse(chain.available_peers()), !chain.is_syncing(), *id))
             .collect::<Vec<_>>();
         preferred_ids.sort_unstable();
 
-        let mut syncing_chains = SmallVec::<[u64; PARALLEL_HEAD_CHAINS]>::new();
+        let mut syncing_ids = Vec::with_capacity(max_syncing);
         for (_, _, id) in preferred_ids {
-            let chain = self.head_chains.get_mut(&id).expect("known chain");
-            if syncing_chains.len() < PARALLEL_HEAD_CHAINS {
-                // start this chain if it's not already syncing
+            let chain = chains.get_mut(&id).expect("known chain");
+            if syncing_ids.len() < max_syncing {
                 if !chain.is_syncing() {
-                    debug!(self.log, "New head chain started syncing"; &chain);
+                    debug!(log, "Chain started syncing"; &chain);
                 }
                 if let ProcessingResult::RemoveChain =
                     chain.start_syncing(network, local_epoch, local_head_epoch)
                 {
-                    self.head_chains.remove(&id);
-                    error!(self.log, "Chain removed while switching head chains"; "id" => id);
+                    // this happens only if sending a batch over the `network` fails a lot
+                    error!(log, "Chain removed while starting to sync"; "id" => id);
+                    chains.remove(&id);
                 } else {
-                    syncing_chains.push(id);
+                    syncing_ids.push(id);
                 }
-            } else {
-                // stop any other chain
+            } else if chain.is_syncing() {
+                debug!(log, "Chain stopped syncing"; &chain);
                 chain.stop_syncing();
             }
         }
 
-        self.state = if syncing_chains.is_empty() {
-            RangeSyncState::Idle
-        } else {
-            RangeSyncState::Head(syncing_chains)
-        };
-    }
-
-    /// Returns if `true` if any finalized chains exist, `false` otherwise.
-    pub fn is_finalizing_sync(&self) -> bool {
-        !self.finalized_chains.is_empty()
+        syncing_ids
     }
 
     /// Removes any outdated finalized or head chains.
     /// This removes chains with no peers, or chains whose start block slot is less than our current
-    /// finalized block slot. Peers that would create outdated chains are removed too.
-    pub fn purge_outdated_chains(
-        &mut self,
-        awaiting_head_peers: &mut HashMap<PeerId, PeerSyncInfo>,
-    ) {
+    /// finalized block slot.
+    pub fn purge_outdated_chains(&mut self) {
         let local_info = match PeerSyncInfo::from_chain(&self.beacon_chain) {
             Some(local) => local,
             None => {
@@ -420,11 +298,6 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
                 || beacon_chain.fork_choice.read().contains_block(target_root)
         };
 
-        // Retain only head peers that remain relevant
-        awaiting_head_peers.retain(|_peer_id, peer_sync_info| {
-            !is_outdated(&peer_sync_info.head_slot, &peer_sync_info.head_root)
-        });
-
         // Remove chains that are out-dated
         let mut removed_chains = Vec::new();
         self.finalized_chains.retain(|id, chain| {
@@ -504,3 +377,85 @@ impl<T: BeaconChainTypes> ChainCollection<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+    use slog::{o, Logger};
+    use types::{test_utils::generate_deterministic_keypairs, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+    type TestTypes = EphemeralHarnessType<E>;
+
+    #[test]
+    fn finalized_and_head_chains_sync_in_parallel() {
+        let beacon_chain = Arc::new(
+            BeaconChainHarness::new(MinimalEthSpec, generate_deterministic_keypairs(8)).chain,
+        );
+        let log = Logger::root(slog::Discard, o!());
+        let (mut network, _network_recv) = SyncNetworkContext::for_testing(log.clone());
+        let (beacon_processor_send, _beacon_processor_recv) = mpsc::channel(16);
+        let mut chains = ChainCollection::new(beacon_chain, log);
+
+        let target_slot = Slot::new(64 * E::slots_per_epoch());
+        let mut add_chain = |chains: &mut ChainCollection<TestTypes>, byte, peers, sync_type| {
+            let root = Hash256::repeat_byte(byte);
+            for _ in 0..peers {
+                chains.add_peer_or_create_chain(
+                    Epoch::new(0),
+                    root,
+                    target_slot,
+                    PeerId::random(),
+                    sync_type,
+                    &beacon_processor_send,
+                    &mut network,
+                );
+            }
+            SyncingChain::<TestTypes>::id(&root, &target_slot)
+        };
+
+        // More finalized chains than can be synced at once, with the first having the most peers.
+        let best_finalized = add_chain(&mut chains, 1, 2, RangeSyncType::Finalized);
+        let other_finalized = [
+            add_chain(&mut chains, 2, 1, RangeSyncType::Finalized),
+            add_chain(&mut chains, 3, 1, RangeSyncType::Finalized),
+        ];
+        let head = add_chain(&mut chains, 4, 1, RangeSyncType::Head);
+
+        chains.update(&mut network);
+
+        assert!(matches!(
+            chains.state(),
+            Ok(Some((RangeSyncType::Finalized, _, _)))
+        ));
+        assert_eq!(chains.state.finalized.len(), PARALLEL_FINALIZED_CHAINS);
+        assert!(chains.state.finalized.contains(&best_finalized));
+        // The head chain is synced alongside the finalized chains.
+        assert_eq!(&chains.state.head[..], &[head]);
+        assert!(chains.head_chains[&head].is_syncing());
+
+        let stopped = other_finalized
+            .iter()
+            .filter(|id| !chains.finalized_chains[*id].is_syncing())
+            .collect::<Vec<_>>();
+        assert_eq!(stopped.len(), 1);
+        assert!(!chains.state.is_syncing(stopped[0]));
+
+        // Once a syncing finalized chain is removed, the stopped chain starts syncing.
+        chains.call_all(|chain| {
+            if chain.get_id() == best_finalized {
+                ProcessingResult::RemoveChain
+            } else {
+                ProcessingResult::KeepChain
+            }
+        });
+        chains.update(&mut network);
+
+        for id in &other_finalized {
+            assert!(chains.finalized_chains[id].is_syncing());
+            assert!(chains.state.finalized.contains(id));
+        }
+        assert_eq!(&chains.state.head[..], &[head]);
+    }
+}
@@ -4,10 +4,12 @@
 mod batch;
 mod chain;
 mod chain_collection;
+mod peer_quality;
 mod range;
 mod sync_type;
 
 pub use batch::BatchInfo;
 pub use chain::{BatchId, ChainId, EPOCHS_PER_BATCH};
+pub use peer_quality::PeerQualityTracker;
 pub use range::RangeSync;
 pub use sync_type::RangeSyncType;
@@ -0,0 +1,373 @@
+//! Tracks how quickly and reliably each peer serves `BlocksByRange` requests.
+//!
+//! Range sync uses this to decide how many batches a peer may download concurrently, to prefer
+//! fast peers when assigning batches and to detect batches that are taking too long to download
+//! so they can be re-assigned before the request times out.
+
+use crate::sync::RequestId;
+use eth2_libp2p::types::SyncPeerStats;
+use eth2_libp2p::PeerId;
+use fnv::FnvHashMap;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The weight given to the most recent request when updating a peer's average throughput.
+const THROUGHPUT_SMOOTHING_FACTOR: f64 = 0.3;
+
+/// The throughput assumed for peers that have not yet completed a request. This is optimistic so
+/// that new peers are given a chance to prove themselves.
+const DEFAULT_BLOCKS_PER_SECOND: f64 = 50.0;
+
+/// A peer is allowed one additional concurrent batch for every multiple of this throughput.
+const BLOCKS_PER_SECOND_PER_EXTRA_REQUEST: f64 = 50.0;
+
+/// The maximum number of batches a single peer may download concurrently.
+const MAX_CONCURRENT_REQUESTS_PER_PEER: usize = 3;
+
+/// Peers with a reliability below this value are limited to a single concurrent request.
+const MIN_RELIABILITY_FOR_EXTRA_REQUESTS: f64 = 0.75;
+
+/// The period over which the aggregate download rate is measured.
+const DOWNLOAD_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// The download performance of a single peer.
+#[derive(Default)]
+struct PeerQuality {
+    /// Moving average of the blocks per second served, if a non-empty request has completed.
+    blocks_per_second: Option<f64>,
+    /// The number of successfully completed requests.
+    completed_requests: u64,
+    /// The number of requests that errored or were re-assigned for being too slow.
+    failed_requests: u64,
+}
+
+impl PeerQuality {
+    /// The fraction of requests that succeeded, with a prior of one successful request.
+    fn reliability(&self) -> f64 {
+        (self.completed_requests + 1) as f64
+            / (self.completed_requests + self.failed_requests + 1) as f64
+    }
+
+    /// The expected number of blocks per second this peer will serve, discounted by its
+    /// reliability.
+    fn score(&self) -> f64 {
+        self.blocks_per_second.unwrap_or(DEFAULT_BLOCKS_PER_SECOND) * self.reliability()
+    }
+}
+
+/// A `BlocksByRange` request that is in flight.
+struct ActiveRequest {
+    peer_id: PeerId,
+    started: Instant,
+    blocks: usize,
+    /// The batch of this request was re-assigned to another peer. The request is still in flight,
+    /// but its outcome no longer affects the peer's quality.
+    abandoned: bool,
+}
+
+/// Tracks the throughput and error rate of the peers serving range sync requests.
+#[derive(Default)]
+pub struct PeerQualityTracker {
+    peers: FnvHashMap<PeerId, PeerQuality>,
+    active_requests: FnvHashMap<RequestId, ActiveRequest>,
+    /// The completion time and number of blocks of recent successful requests.
+    recent_downloads: VecDeque<(Instant, usize)>,
+}
+
+impl PeerQualityTracker {
+    /// Registers a new request sent to `peer_id`.
+    pub fn on_request(&mut self, peer_id: PeerId, request_id: RequestId, now: Instant) {
+        self.peers.entry(peer_id.clone()).or_default();
+        self.active_requests.insert(
+            request_id,
+            ActiveRequest {
+                peer_id,
+                started: now,
+                blocks: 0,
+                abandoned: false,
+            },
+        );
+    }
+
+    /// Registers a block received for a request.
+    pub fn on_block(&mut self, request_id: RequestId) {
+        if let Some(request) = self.active_requests.get_mut(&request_id) {
+            request.blocks += 1;
+        }
+    }
+
+    /// Registers the successful termination of a request, updating the peer's throughput.
+    pub fn on_completed(&mut self, request_id: RequestId, now: Instant) {
+        if let Some(request) = self
+            .active_requests
+            .remove(&request_id)
+            .filter(|request| !request.abandoned)
+        {
+            let quality = self.peers.entry(request.peer_id).or_default();
+            quality.completed_requests += 1;
+
+            // Empty batches say nothing about the peer's bandwidth.
+            if request.blocks > 0 {
+                let elapsed = now
+                    .saturating_duration_since(request.started)
+                    .as_secs_f64()
+                    .max(0.001);
+                let rate = request.blocks as f64 / elapsed;
+                quality.blocks_per_second = Some(match quality.blocks_per_second {
+                    Some(average) => average + THROUGHPUT_SMOOTHING_FACTOR * (rate - average),
+                    None => rate,
+                });
+                self.recent_downloads.push_back((now, request.blocks));
+            }
+        }
+        self.prune_recent_downloads(now);
+    }
+
+    /// Registers a request that failed.
+    pub fn on_failed(&mut self, request_id: RequestId) {
+        if let Some(request) = self
+            .active_requests
+            .remove(&request_id)
+            .filter(|request| !request.abandoned)
+        {
+            self.peers
+                .entry(request.peer_id)
+                .or_default()
+                .failed_requests += 1;
+        }
+    }
+
+    /// Registers a request whose batch was re-assigned because the peer was too slow.
+    ///
+    /// The peer is penalised immediately, but the request keeps counting towards the peer's
+    /// active requests until it terminates, since the peer is still serving it.
+    pub fn on_abandoned(&mut self, request_id: RequestId) {
+        if let Some(request) = self.active_requests.get_mut(&request_id) {
+            if !request.abandoned {
+                request.abandoned = true;
+                self.peers
+                    .entry(request.peer_id.clone())
+                    .or_default()
+                    .failed_requests += 1;
+            }
+        }
+    }
+
+    /// Forgets a peer and its in-flight requests.
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.peers.remove(peer_id);
+        self.active_requests
+            .retain(|_, request| &request.peer_id != peer_id);
+    }
+
+    /// Returns how long the given request has been in flight, if it is known.
+    pub fn request_duration(&self, request_id: RequestId, now: Instant) -> Option<Duration> {
+        self.active_requests
+            .get(&request_id)
+            .map(|request| now.saturating_duration_since(request.started))
+    }
+
+    /// Returns the number of requests in flight to a peer, across all chains.
+    pub fn active_requests(&self, peer_id: &PeerId) -> usize {
+        self.active_requests
+            .values()
+            .filter(|request| &request.peer_id == peer_id)
+            .count()
+    }
+
+    /// Returns the expected blocks per second of a peer, discounted by its error rate. Higher is
+    /// better.
+    pub fn score(&self, peer_id: &PeerId) -> f64 {
+        self.peers
+            .get(peer_id)
+            .map(PeerQuality::score)
+            .unwrap_or(DEFAULT_BLOCKS_PER_SECOND)
+    }
+
+    /// Returns the number of batches a peer may download at the same time.
+    ///
+    /// Unknown and unreliable peers get a single batch, fast and reliable peers get more.
+    pub fn max_concurrent_requests(&self, peer_id: &PeerId) -> usize {
+        match self.peers.get(peer_id) {
+            Some(quality) if quality.reliability() >= MIN_RELIABILITY_FOR_EXTRA_REQUESTS => {
+                let blocks_per_second = quality.blocks_per_second.unwrap_or(0.0);
+                let extra = (blocks_per_second / BLOCKS_PER_SECOND_PER_EXTRA_REQUEST) as usize;
+                (1 + extra).min(MAX_CONCURRENT_REQUESTS_PER_PEER)
+            }
+            _ => 1,
+        }
+    }
+
+    /// Returns the number of blocks per second downloaded from all peers over the recent past.
+    pub fn download_rate(&mut self, now: Instant) -> f64 {
+        self.prune_recent_downloads(now);
+        let blocks: usize = self.recent_downloads.iter().map(|(_, blocks)| blocks).sum();
+        blocks as f64 / DOWNLOAD_RATE_WINDOW.as_secs_f64()
+    }
+
+    /// Returns the download statistics of every known peer, fastest first.
+    pub fn peer_stats(&self) -> Vec<SyncPeerStats> {
+        let mut stats = self
+            .peers
+            .iter()
+            .map(|(peer_id, quality)| SyncPeerStats {
+                peer_id: peer_id.to_string(),
+                blocks_per_second: quality.blocks_per_second,
+                completed_requests: quality.completed_requests,
+                failed_requests: quality.failed_requests,
+                active_requests: self.active_requests(peer_id),
+            })
+            .collect::<Vec<_>>();
+        stats.sort_by(|a, b| {
+            b.blocks_per_second
+                .unwrap_or(0.0)
+                .partial_cmp(&a.blocks_per_second.unwrap_or(0.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        stats
+    }
+
+    fn prune_recent_downloads(&mut self, now: Instant) {
+        while let Some((completed, _)) = self.recent_downloads.front() {
+            if now.saturating_duration_since(*completed) > DOWNLOAD_RATE_WINDOW {
+                self.recent_downloads.pop_front();
+            } else {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn complete_request(
+        tracker: &mut PeerQualityTracker,
+        peer_id: &PeerId,
+        request_id: RequestId,
+        blocks: usize,
+        duration: Duration,
+        start: Instant,
+    ) {
+        tracker.on_request(peer_id.clone(), request_id, start);
+        for _ in 0..blocks {
+            tracker.on_block(request_id);
+        }
+        tracker.on_completed(request_id, start + duration);
+    }
+
+    #[test]
+    fn unknown_peers_get_a_single_request() {
+        let tracker = PeerQualityTracker::default();
+        let peer_id = PeerId::random();
+
+        assert_eq!(tracker.max_concurrent_requests(&peer_id), 1);
+        assert_eq!(tracker.score(&peer_id), DEFAULT_BLOCKS_PER_SECOND);
+    }
+
+    #[test]
+    fn fast_peers_get_more_requests() {
+        let mut tracker = PeerQualityTracker::default();
+        let fast = PeerId::random();
+        let slow = PeerId::random();
+        let now = Instant::now();
+
+        complete_request(&mut tracker, &fast, 1, 256, Duration::from_secs(1), now);
+        complete_request(&mut tracker, &slow, 2, 256, Duration::from_secs(32), now);
+
+        assert_eq!(
+            tracker.max_concurrent_requests(&fast),
+            MAX_CONCURRENT_REQUESTS_PER_PEER
+        );
+        assert_eq!(tracker.max_concurrent_requests(&slow), 1);
+        assert!(tracker.score(&fast) > tracker.score(&slow));
+    }
+
+    #[test]
+    fn failures_reduce_score() {
+        let mut tracker = PeerQualityTracker::default();
+        let reliable = PeerId::random();
+        let unreliable = PeerId::random();
+        let now = Instant::now();
+
+        complete_request(&mut tracker, &reliable, 1, 256, Duration::from_secs(1), now);
+        complete_request(
+            &mut tracker,
+            &unreliable,
+            2,
+            256,
+            Duration::from_secs(1),
+            now,
+        );
+        tracker.on_request(unreliable.clone(), 3, now);
+        tracker.on_failed(3);
+
+        assert!(tracker.score(&reliable) > tracker.score(&unreliable));
+        assert_eq!(tracker.max_concurrent_requests(&unreliable), 1);
+    }
+
+    #[test]
+    fn empty_batches_do_not_affect_throughput() {
+        let mut tracker = PeerQualityTracker::default();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        complete_request(&mut tracker, &peer_id, 1, 0, Duration::from_secs(30), now);
+
+        assert_eq!(tracker.score(&peer_id), DEFAULT_BLOCKS_PER_SECOND);
+        assert_eq!(tracker.peer_stats()[0].completed_requests, 1);
+    }
+
+    #[test]
+    fn download_rate_only_counts_recent_requests() {
+        let mut tracker = PeerQualityTracker::default();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        complete_request(&mut tracker, &peer_id, 1, 60, Duration::from_secs(1), now);
+        assert_eq!(tracker.download_rate(now + Duration::from_secs(1)), 1.0);
+        assert_eq!(
+            tracker.download_rate(now + DOWNLOAD_RATE_WINDOW + Duration::from_secs(2)),
+            0.0
+        );
+    }
+
+    #[test]
+    fn request_duration_is_tracked_until_completion() {
+        let mut tracker = PeerQualityTracker::default();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        tracker.on_request(peer_id, 1, now);
+        assert_eq!(
+            tracker.request_duration(1, now + Duration::from_secs(5)),
+            Some(Duration::from_secs(5))
+        );
+        tracker.on_completed(1, now + Duration::from_secs(6));
+        assert_eq!(tracker.request_duration(1, now), None);
+    }
+
+    #[test]
+    fn abandoned_requests_stay_active_until_terminated() {
+        let mut tracker = PeerQualityTracker::default();
+        let peer_id = PeerId::random();
+        let now = Instant::now();
+
+        tracker.on_request(peer_id.clone(), 1, now);
+        tracker.on_abandoned(1);
+        tracker.on_abandoned(1);
+
+        // The peer is still serving the request, so it must count towards its capacity.
+        assert_eq!(tracker.active_requests(&peer_id), 1);
+        assert_eq!(tracker.peer_stats()[0].failed_requests, 1);
+
+        // The late completion of an abandoned request is not counted as a success.
+        tracker.on_block(1);
+        tracker.on_completed(1, now + Duration::from_secs(30));
+        assert_eq!(tracker.active_requests(&peer_id), 0);
+        assert_eq!(tracker.peer_stats()[0].completed_requests, 0);
+        assert_eq!(tracker.peer_stats()[0].failed_requests, 1);
+        assert_eq!(tracker.score(&peer_id), DEFAULT_BLOCKS_PER_SECOND * 0.5);
+    }
+}
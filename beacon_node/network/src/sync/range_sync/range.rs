@@ -19,25 +19,30 @@
 //!  need to be downloaded.
 //!
 //!  A few interesting notes about finalized chain syncing:
-//!  - Only one finalized chain can sync at a time.
-//!  - The finalized chain with the largest peer pool takes priority.
+//!  - A small number of finalized chains sync in parallel.
+//!  - The finalized chains with the largest peer pools take priority.
 //!  - As one finalized chain completes, others are checked to see if we they can be continued,
 //!  otherwise they are removed.
 //!
 //!  ## Head Chain Sync
 //!
-//!  If a peer joins and there is no active finalized chains being synced, and it's head is beyond
-//!  our `SLOT_IMPORT_TOLERANCE` a chain is formed starting from this peers finalized epoch (this
-//!  has been necessarily downloaded by our node, otherwise we would start a finalized chain sync)
-//!  to this peers head slot. Any other peers that match this head slot and head root, are added to
-//!  this chain's peer pool, which will be downloaded in parallel.
+//!  If a peer joins and it's head is beyond our `SLOT_IMPORT_TOLERANCE` a chain is formed starting
+//!  from this peers finalized epoch (this has been necessarily downloaded by our node, otherwise we
+//!  would start a finalized chain sync) to this peers head slot. Any other peers that match this
+//!  head slot and head root, are added to this chain's peer pool, which will be downloaded in
+//!  parallel.
 //!
-//!  Unlike finalized chains, head chains can be synced in parallel.
+//!  Head chains sync in parallel, both with each other and with any finalized chains.
 //!
 //!  ## Batch Syncing
 //!
 //!  Each chain is downloaded in batches of blocks. The batched blocks are processed sequentially
 //!  and further batches are requested as current blocks are being processed.
+//!
+//!  The throughput and error rate of each peer is tracked across all chains. Fast and reliable
+//!  peers are given batches first and may download several batches at once, while unknown or
+//!  unreliable peers download one batch at a time. Batches that take too long to download are
+//!  periodically re-assigned to a better peer before the request times out.
 
 use super::chain::ChainId;
 use super::chain_collection::ChainCollection;
@@ -49,9 +54,9 @@ use crate::sync::PeerSyncInfo;
 use crate::sync::RequestId;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2_libp2p::PeerId;
-use slog::{debug, error, trace};
-use std::collections::HashMap;
+use slog::{debug, error};
 use std::sync::Arc;
+use std::time::Instant;
 use tokio::sync::mpsc;
 use types::{Epoch, EthSpec, SignedBeaconBlock, Slot};
 
@@ -61,9 +66,6 @@ use types::{Epoch, EthSpec, SignedBeaconBlock, Slot};
 pub struct RangeSync<T: BeaconChainTypes> {
     /// The beacon chain for processing.
     beacon_chain: Arc<BeaconChain<T>>,
-    /// Last known sync info of our useful connected peers. We use this information to create Head
-    /// chains after all finalized chains have ended.
-    awaiting_head_peers: HashMap<PeerId, PeerSyncInfo>,
     /// A collection of chains that need to be downloaded. This stores any head or finalized chains
     /// that need to be downloaded.
     chains: ChainCollection<T>,
@@ -82,7 +84,6 @@ impl<T: BeaconChainTypes> RangeSync<T> {
         RangeSync {
             beacon_chain: beacon_chain.clone(),
             chains: ChainCollection::new(beacon_chain, log.clone()),
-            awaiting_head_peers: HashMap::new(),
             beacon_processor_send,
             log,
         }
@@ -128,10 +129,9 @@ impl<T: BeaconChainTypes> RangeSync<T> {
             RangeSyncType::Finalized => {
                 // Finalized chain search
                 debug!(self.log, "Finalization sync peer joined"; "peer_id" => %peer_id);
-                self.awaiting_head_peers.remove(&peer_id);
 
-                // Note: We keep current head chains. These can continue syncing whilst we complete
-                // this new finalized chain.
+                // Note: We keep current head chains. These continue syncing alongside the
+                // finalized chains.
 
                 self.chains.add_peer_or_create_chain(
                     local_info.finalized_epoch,
@@ -143,27 +143,13 @@ impl<T: BeaconChainTypes> RangeSync<T> {
                     network,
                 );
 
-                self.chains.update(
-                    network,
-                    &mut self.awaiting_head_peers,
-                    &self.beacon_processor_send,
-                );
+                self.chains.update(network);
             }
             RangeSyncType::Head => {
-                // This peer requires a head chain sync
-
-                if self.chains.is_finalizing_sync() {
-                    // If there are finalized chains to sync, finish these first, before syncing head
-                    // chains.
-                    trace!(self.log, "Waiting for finalized sync to complete";
-                        "peer_id" => %peer_id, "awaiting_head_peers" => &self.awaiting_head_peers.len());
-                    self.awaiting_head_peers.insert(peer_id, remote_info);
-                    return;
-                }
+                // This peer requires a head chain sync, which runs alongside any finalized chains.
 
-                // if the peer existed in any other head chain, remove it.
+                // if the peer existed in any other chain, remove it.
                 self.remove_peer(network, &peer_id);
-                self.awaiting_head_peers.remove(&peer_id);
 
                 // The new peer has the same finalized (earlier filters should prevent a peer with an
                 // earlier finalized chain from reaching here).
@@ -179,11 +165,7 @@ impl<T: BeaconChainTypes> RangeSync<T> {
                     &self.beacon_processor_send,
                     network,
                 );
-                self.chains.update(
-                    network,
-                    &mut self.awaiting_head_peers,
-                    &self.beacon_processor_send,
-                );
+                self.chains.update(network);
             }
         }
     }
@@ -199,6 +181,15 @@ impl<T: BeaconChainTypes> RangeSync<T> {
         request_id: RequestId,
         beacon_block: Option<SignedBeaconBlock<T::EthSpec>>,
     ) {
+        // register the response with the peer's download quality
+        if beacon_block.is_some() {
+            network.peer_quality_mut().on_block(request_id);
+        } else {
+            network
+                .peer_quality_mut()
+                .on_completed(request_id, Instant::now());
+        }
+
         // get the chain and batch for which this response belongs
         if let Some((chain_id, batch_id)) =
             network.blocks_by_range_response(request_id, beacon_block.is_none())
@@ -211,11 +202,7 @@ impl<T: BeaconChainTypes> RangeSync<T> {
                     if let Some(_removed_chain) = removed_chain {
                         debug!(self.log, "Chain removed after block response"; "sync_type" => ?sync_type, "chain_id" => chain_id);
                         // update the state of the collection
-                        self.chains.update(
-                            network,
-                            &mut self.awaiting_head_peers,
-                            &self.beacon_processor_send,
-                        );
+                        self.chains.update(network);
                     }
                 }
                 Err(_) => {
@@ -243,11 +230,7 @@ impl<T: BeaconChainTypes> RangeSync<T> {
             }
             Ok((Some(_removed_chain), sync_type)) => {
                 debug!(self.log, "Chain removed after processing result"; "chain" => chain_id, "sync_type" => ?sync_type);
-                self.chains.update(
-                    network,
-                    &mut self.awaiting_head_peers,
-                    &self.beacon_processor_send,
-                );
+                self.chains.update(network);
             }
 
             Err(_) => {
@@ -263,8 +246,7 @@ impl<T: BeaconChainTypes> RangeSync<T> {
         network: &mut SyncNetworkContext<T::EthSpec>,
         peer_id: &PeerId,
     ) {
-        // if the peer is in the awaiting head mapping, remove it
-        self.awaiting_head_peers.remove(peer_id);
+        network.peer_quality_mut().remove_peer(peer_id);
 
         // remove the peer from any peer pool, failing its batches
         self.remove_peer(network, peer_id);
@@ -282,11 +264,20 @@ impl<T: BeaconChainTypes> RangeSync<T> {
             debug!(self.log, "Chain removed after removing peer"; "sync_type" => ?sync_type, "chain" => removed_chain.get_id());
             // update the state of the collection
         }
-        self.chains.update(
-            network,
-            &mut self.awaiting_head_peers,
-            &self.beacon_processor_send,
-        );
+        self.chains.update(network);
+    }
+
+    /// Re-assigns batches that are taking too long to download from their peer to a faster peer.
+    ///
+    /// This is called periodically by the sync manager.
+    pub fn retry_slow_batches(&mut self, network: &mut SyncNetworkContext<T::EthSpec>) {
+        for (removed_chain, sync_type) in self
+            .chains
+            .call_all(|chain| chain.retry_slow_batches(network, Instant::now()))
+        {
+            debug!(self.log, "Chain removed while retrying slow batches"; "sync_type" => ?sync_type, "chain" => removed_chain.get_id());
+        }
+        self.chains.update(network);
     }
 
     /// An RPC error has occurred.
@@ -299,6 +290,8 @@ impl<T: BeaconChainTypes> RangeSync<T> {
         peer_id: PeerId,
         request_id: RequestId,
     ) {
+        network.peer_quality_mut().on_failed(request_id);
+
         // get the chain and batch for which this response belongs
         if let Some((chain_id, batch_id)) = network.blocks_by_range_response(request_id, true) {
             // check that this request is pending
@@ -309,11 +302,7 @@ impl<T: BeaconChainTypes> RangeSync<T> {
                     if let Some(removed_chain) = removed_chain {
                         debug!(self.log, "Chain removed on rpc error"; "sync_type" => ?sync_type, "chain" => removed_chain.get_id());
                         // update the state of the collection
-                        self.chains.update(
-                            network,
-                            &mut self.awaiting_head_peers,
-                            &self.beacon_processor_send,
-                        );
+                        self.chains.update(network);
                     }
                 }
                 Err(_) => {
@@ -95,6 +95,24 @@ impl<T: EthSpec> OperationPool<T> {
         self.attestations.read().values().map(Vec::len).sum()
     }
 
+    /// Number of attestations in the pool that are recent enough to be included in a block
+    /// built on `state`, i.e. those that target its current or previous epoch and have satisfied
+    /// the minimum inclusion delay.
+    pub fn num_includable_attestations(&self, state: &BeaconState<T>, spec: &ChainSpec) -> usize {
+        let prev_epoch = state.previous_epoch();
+        let current_epoch = state.current_epoch();
+        self.attestations
+            .read()
+            .values()
+            .flat_map(|attestations| attestations.iter())
+            .filter(|attestation| {
+                (attestation.data.target.epoch == prev_epoch
+                    || attestation.data.target.epoch == current_epoch)
+                    && attestation.data.slot + spec.min_attestation_inclusion_delay <= state.slot
+            })
+            .count()
+    }
+
     /// Get a list of attestations for inclusion in a block.
     ///
     /// The `validity_filter` is a closure that provides extra filtering of the attestations
@@ -167,6 +185,23 @@ impl<T: EthSpec> OperationPool<T> {
         });
     }
 
+    /// Remove attestations whose committee is unknown to `state`, or whose aggregation bits don't
+    /// match the size of their committee.
+    ///
+    /// This is used to validate attestations restored from disk against the head state.
+    pub fn prune_invalid_attestations(&self, state: &BeaconState<T>) {
+        self.attestations.write().retain(|_, attestations| {
+            attestations.retain(|att| {
+                state
+                    .get_beacon_committee(att.data.slot, att.data.index)
+                    .map_or(false, |committee| {
+                        committee.committee.len() == att.aggregation_bits.len()
+                    })
+            });
+            !attestations.is_empty()
+        });
+    }
+
     /// Insert a proposer slashing into the pool.
     pub fn insert_proposer_slashing(
         &self,
@@ -194,9 +229,14 @@ impl<T: EthSpec> OperationPool<T> {
     /// This function computes both types of slashings together, because
     /// attester slashings may be invalidated by proposer slashings included
     /// earlier in the block.
+    ///
+    /// Slashings rejected by `proposer_slashing_filter` or `attester_slashing_filter` are never
+    /// considered, so they don't take the place of other slashings in the block.
     pub fn get_slashings(
         &self,
         state: &BeaconState<T>,
+        proposer_slashing_filter: impl Fn(&ProposerSlashing) -> bool,
+        attester_slashing_filter: impl Fn(&AttesterSlashing<T>) -> bool,
         spec: &ChainSpec,
     ) -> (Vec<ProposerSlashing>, Vec<AttesterSlashing<T>>) {
         let proposer_slashings = filter_limit_operations(
@@ -206,6 +246,7 @@ impl<T: EthSpec> OperationPool<T> {
                     .validators
                     .get(slashing.signed_header_1.message.proposer_index as usize)
                     .map_or(false, |validator| !validator.slashed)
+                    && proposer_slashing_filter(slashing)
             },
             T::MaxProposerSlashings::to_usize(),
         );
@@ -220,7 +261,9 @@ impl<T: EthSpec> OperationPool<T> {
         let reader = self.attester_slashings.read();
 
         let relevant_attester_slashings = reader.iter().flat_map(|(slashing, fork)| {
-            if *fork == state.fork.previous_version || *fork == state.fork.current_version {
+            if (*fork == state.fork.previous_version || *fork == state.fork.current_version)
+                && attester_slashing_filter(slashing)
+            {
                 AttesterSlashingMaxCover::new(&slashing, &to_be_slashed, state, spec)
             } else {
                 None
@@ -283,14 +326,18 @@ impl<T: EthSpec> OperationPool<T> {
     }
 
     /// Get a list of voluntary exits for inclusion in a block.
+    ///
+    /// Exits rejected by `filter` are never considered, so they don't take the place of other
+    /// exits in the block.
     pub fn get_voluntary_exits(
         &self,
         state: &BeaconState<T>,
+        filter: impl Fn(&SignedVoluntaryExit) -> bool,
         spec: &ChainSpec,
     ) -> Vec<SignedVoluntaryExit> {
         filter_limit_operations(
             self.voluntary_exits.read().values(),
-            |exit| verify_exit(state, exit, VerifySignatures::False, spec).is_ok(),
+            |exit| filter(exit) && verify_exit(state, exit, VerifySignatures::False, spec).is_ok(),
             T::MaxVoluntaryExits::to_usize(),
         )
     }
@@ -667,6 +714,47 @@ mod release_tests {
         assert_eq!(op_pool.num_attestations(), committees.len());
     }
 
+    /// Attestations that don't match a committee in the state should be pruned.
+    #[test]
+    fn attestation_prune_invalid() {
+        let (ref mut state, ref keypairs, ref spec) = attestation_test_state::<MainnetEthSpec>(1);
+
+        let op_pool = OperationPool::new();
+
+        let slot = state.slot - 1;
+        let committees = state
+            .get_beacon_committees_at_slot(slot)
+            .unwrap()
+            .into_iter()
+            .map(BeaconCommittee::into_owned)
+            .collect::<Vec<_>>();
+
+        for bc in &committees {
+            let att = signed_attestation(
+                &bc.committee,
+                bc.index,
+                keypairs,
+                ..,
+                slot,
+                state,
+                spec,
+                None,
+            );
+            op_pool
+                .insert_attestation(att, &state.fork, state.genesis_validators_root, spec)
+                .unwrap();
+        }
+
+        op_pool.prune_invalid_attestations(state);
+        assert_eq!(op_pool.num_attestations(), committees.len());
+
+        // Once the attestations are from an epoch that the state has no committees for, they
+        // should be pruned.
+        state.slot += 3 * MainnetEthSpec::slots_per_epoch();
+        op_pool.prune_invalid_attestations(state);
+        assert_eq!(op_pool.num_attestations(), 0);
+    }
+
     /// Adding lots of attestations that only intersect pairwise should lead to two aggregate
     /// attestations.
     #[test]
@@ -990,7 +1078,10 @@ mod release_tests {
         op_pool.insert_proposer_slashing(slashing2.clone().validate(state, spec).unwrap());
 
         // Should only get the second slashing back.
-        assert_eq!(op_pool.get_slashings(state, spec).0, vec![slashing2]);
+        assert_eq!(
+            op_pool.get_slashings(state, |_| true, |_| true, spec).0,
+            vec![slashing2]
+        );
     }
 
     // Sanity check on the pruning of proposer slashings
@@ -1001,7 +1092,10 @@ mod release_tests {
         let slashing = ctxt.proposer_slashing(0);
         op_pool.insert_proposer_slashing(slashing.clone().validate(state, spec).unwrap());
         op_pool.prune_proposer_slashings(state);
-        assert_eq!(op_pool.get_slashings(state, spec).0, vec![slashing]);
+        assert_eq!(
+            op_pool.get_slashings(state, |_| true, |_| true, spec).0,
+            vec![slashing]
+        );
     }
 
     // Sanity check on the pruning of attester slashings
@@ -1013,7 +1107,63 @@ mod release_tests {
         op_pool
             .insert_attester_slashing(slashing.clone().validate(state, spec).unwrap(), state.fork);
         op_pool.prune_attester_slashings(state, state.fork);
-        assert_eq!(op_pool.get_slashings(state, spec).1, vec![slashing]);
+        assert_eq!(
+            op_pool.get_slashings(state, |_| true, |_| true, spec).1,
+            vec![slashing]
+        );
+    }
+
+    // Filtered proposer slashings should not take the place of other slashings.
+    #[test]
+    fn proposer_slashing_filter_applied_before_limit() {
+        let ctxt = TestContext::new();
+        let (op_pool, state, spec) = (&ctxt.op_pool, &ctxt.state, &ctxt.spec);
+        let max_slashings = <MainnetEthSpec as EthSpec>::MaxProposerSlashings::to_u64();
+
+        for proposer_index in 0..=max_slashings {
+            let slashing = ctxt.proposer_slashing(proposer_index);
+            op_pool.insert_proposer_slashing(slashing.validate(state, spec).unwrap());
+        }
+
+        let (proposer_slashings, _) = op_pool.get_slashings(
+            state,
+            |slashing| slashing.signed_header_1.message.proposer_index != 0,
+            |_| true,
+            spec,
+        );
+        let proposers = proposer_slashings
+            .iter()
+            .map(|slashing| slashing.signed_header_1.message.proposer_index)
+            .collect::<Vec<_>>();
+        assert_eq!(proposers.len() as u64, max_slashings);
+        assert!(!proposers.contains(&0));
+    }
+
+    // Filtered attester slashings should not take the place of other slashings.
+    #[test]
+    fn attester_slashing_filter_applied_before_limit() {
+        let ctxt = TestContext::new();
+        let (op_pool, state, spec) = (&ctxt.op_pool, &ctxt.state, &ctxt.spec);
+
+        let slashing_1 = ctxt.attester_slashing(&[1, 2, 3, 4, 5, 6]);
+        let slashing_2 = ctxt.attester_slashing(&[7, 8]);
+        let slashing_3 = ctxt.attester_slashing(&[9, 10]);
+        for slashing in &[&slashing_1, &slashing_2, &slashing_3] {
+            op_pool.insert_attester_slashing(
+                (*slashing).clone().validate(state, spec).unwrap(),
+                state.fork,
+            );
+        }
+
+        let (_, attester_slashings) = op_pool.get_slashings(
+            state,
+            |_| true,
+            |slashing| !slashing.attestation_1.attesting_indices.contains(&1),
+            spec,
+        );
+        assert_eq!(attester_slashings.len(), 2);
+        assert!(attester_slashings.contains(&slashing_2));
+        assert!(attester_slashings.contains(&slashing_3));
     }
 
     // Check that we get maximum coverage for attester slashings (highest qty of validators slashed)
@@ -1044,7 +1194,7 @@ mod release_tests {
             state.fork,
         );
 
-        let best_slashings = op_pool.get_slashings(state, spec);
+        let best_slashings = op_pool.get_slashings(state, |_| true, |_| true, spec);
         assert_eq!(best_slashings.1, vec![slashing_4, slashing_3]);
     }
 
@@ -1076,7 +1226,7 @@ mod release_tests {
             state.fork,
         );
 
-        let best_slashings = op_pool.get_slashings(state, spec);
+        let best_slashings = op_pool.get_slashings(state, |_| true, |_| true, spec);
         assert_eq!(best_slashings.1, vec![slashing_1, slashing_3]);
     }
 
@@ -1105,7 +1255,7 @@ mod release_tests {
             state.fork,
         );
 
-        let best_slashings = op_pool.get_slashings(state, spec);
+        let best_slashings = op_pool.get_slashings(state, |_| true, |_| true, spec);
         assert_eq!(best_slashings.1, vec![a_slashing_1, a_slashing_3]);
     }
 
@@ -1133,7 +1283,7 @@ mod release_tests {
             state.fork,
         );
 
-        let best_slashings = op_pool.get_slashings(state, spec);
+        let best_slashings = op_pool.get_slashings(state, |_| true, |_| true, spec);
         assert_eq!(best_slashings.1, vec![slashing_1, slashing_3]);
     }
 
@@ -1164,7 +1314,7 @@ mod release_tests {
             state.fork,
         );
 
-        let best_slashings = op_pool.get_slashings(state, spec);
+        let best_slashings = op_pool.get_slashings(state, |_| true, |_| true, spec);
         assert_eq!(best_slashings.1, vec![slashing_2, slashing_3]);
     }
 }
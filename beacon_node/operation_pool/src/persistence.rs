@@ -2,11 +2,19 @@ use crate::attestation_id::AttestationId;
 use crate::OperationPool;
 use parking_lot::RwLock;
 use serde_derive::{Deserialize, Serialize};
-use ssz::{Decode, Encode};
+use ssz::{Decode, DecodeError, Encode};
 use ssz_derive::{Decode, Encode};
 use store::{DBColumn, Error as StoreError, StoreItem};
 use types::*;
 
+/// The version of the on-disk format of `PersistedOperationPool`.
+///
+/// Version 1 is the original format, which was stored without a version prefix. Every later
+/// version is stored as the version number (SSZ `u64`) followed by the SSZ-encoded pool. Increment
+/// this whenever the persisted format changes, and add a conversion from the old format to
+/// `from_store_bytes`.
+pub const OP_POOL_VERSION: u64 = 2;
+
 /// SSZ-serializable version of `OperationPool`.
 ///
 /// Operations are stored in arbitrary order, so it's not a good idea to compare instances
@@ -98,10 +106,76 @@ impl<T: EthSpec> StoreItem for PersistedOperationPool<T> {
     }
 
     fn as_store_bytes(&self) -> Vec<u8> {
-        self.as_ssz_bytes()
+        let mut bytes = OP_POOL_VERSION.as_ssz_bytes();
+        bytes.append(&mut self.as_ssz_bytes());
+        bytes
     }
 
     fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
-        Self::from_ssz_bytes(bytes).map_err(Into::into)
+        let version_len = <u64 as Decode>::ssz_fixed_len();
+        let prefixed_version = bytes
+            .get(..version_len)
+            .map(u64::from_ssz_bytes)
+            .transpose()?;
+
+        match prefixed_version {
+            Some(OP_POOL_VERSION) => {
+                Self::from_ssz_bytes(&bytes[version_len..]).map_err(Into::into)
+            }
+            // The unversioned format begins with two non-zero SSZ offsets, so the upper half of
+            // its prefix is never zero and can't be mistaken for a version number.
+            Some(version) if version <= u64::from(u32::max_value()) => {
+                Err(DecodeError::BytesInvalid(format!(
+                    "Unsupported op pool version {}, expected {}",
+                    version, OP_POOL_VERSION
+                ))
+                .into())
+            }
+            // Version 1 has the same layout as version 2, without the prefix.
+            _ => Self::from_ssz_bytes(bytes).map_err(Into::into),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn persisted_pool() -> PersistedOperationPool<E> {
+        PersistedOperationPool::from_operation_pool(&OperationPool::new())
+    }
+
+    #[test]
+    fn store_bytes_are_versioned() {
+        let pool = persisted_pool();
+        let bytes = pool.as_store_bytes();
+
+        assert_eq!(bytes[..8], OP_POOL_VERSION.as_ssz_bytes()[..]);
+        assert_eq!(
+            PersistedOperationPool::<E>::from_store_bytes(&bytes).unwrap(),
+            pool
+        );
+    }
+
+    #[test]
+    fn unversioned_pool_is_decoded() {
+        let pool = persisted_pool();
+        let bytes = pool.as_ssz_bytes();
+
+        assert_eq!(
+            PersistedOperationPool::<E>::from_store_bytes(&bytes).unwrap(),
+            pool
+        );
+    }
+
+    #[test]
+    fn unknown_version_is_rejected() {
+        let mut bytes = (OP_POOL_VERSION + 1).as_ssz_bytes();
+        bytes.append(&mut persisted_pool().as_ssz_bytes());
+
+        assert!(PersistedOperationPool::<E>::from_store_bytes(&bytes).is_err());
     }
 }
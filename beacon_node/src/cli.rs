@@ -8,6 +8,7 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
         .about("The primary component which connects to the Ethereum 2.0 P2P network and \
                 downloads, verifies and stores blocks. Provides a HTTP API for querying \
                 the beacon chain and publishing messages to the network.")
+        .subcommand(crate::eth1_cache::cli_app())
         /*
          * Configuration directory locations.
          */
@@ -81,6 +82,22 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .help("Disables UPnP support. Setting this will prevent Lighthouse from attempting to automatically establish external port mappings.")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("subscribe-all-subnets")
+                .long("subscribe-all-subnets")
+                .help("Subscribe to all attestation subnets and advertise them in the local ENR. \
+                      This greatly increases bandwidth and CPU usage, but gives the node a full view \
+                      of unaggregated attestations.")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("import-all-attestations")
+                .long("import-all-attestations")
+                .help("Import and aggregate all unaggregated attestations received on gossip, \
+                      rather than only those for subnets that local validators are aggregating for. \
+                      Most useful in combination with --subscribe-all-subnets.")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("enr-udp-port")
                 .long("enr-udp-port")
@@ -177,6 +194,41 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                     address of this server (e.g., http://localhost:5052).")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("http-tls-cert")
+                .long("http-tls-cert")
+                .value_name("PATH")
+                .help("Serve the RESTful HTTP API over TLS using this PEM-encoded certificate. \
+                    Requires --http-tls-key.")
+                .requires("http-tls-key")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-tls-key")
+                .long("http-tls-key")
+                .value_name("PATH")
+                .help("The PEM-encoded private key of the certificate supplied to \
+                    --http-tls-cert.")
+                .requires("http-tls-cert")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-read-token-file")
+                .long("http-read-token-file")
+                .value_name("PATH")
+                .help("Require GET requests to the RESTful HTTP API to present the token in \
+                    this file (or the write token) in an \"Authorization: Bearer\" header.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("http-write-token-file")
+                .long("http-write-token-file")
+                .value_name("PATH")
+                .help("Require POST requests to the RESTful HTTP API to present the token in \
+                    this file in an \"Authorization: Bearer\" header. If only \
+                    --http-read-token-file is supplied, POST requests require the read token.")
+                .takes_value(true),
+        )
         /* Prometheus metrics HTTP server related arguments */
         .arg(
             Arg::with_name("metrics")
@@ -323,6 +375,17 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .default_value("700")
         )
+        .arg(
+            Arg::with_name("block-production-deny-list")
+                .long("block-production-deny-list")
+                .help(
+                    "A comma-separated list of 0x-prefixed validator public keys. Voluntary exits \
+                    and slashings for these validators will never be included in blocks produced \
+                    by this beacon node."
+                )
+                .value_name("PUBKEYS")
+                .takes_value(true)
+        )
         .arg(
             Arg::with_name("wss-checkpoint")
                 .long("wss-checkpoint")
@@ -333,4 +396,48 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .value_name("WSS_CHECKPOINT")
                 .takes_value(true)
         )
+
+        /*
+         * Slasher.
+         */
+        .arg(
+            Arg::with_name("slasher")
+                .long("slasher")
+                .help(
+                    "Run a slasher alongside the beacon node. Attestations and blocks seen on the \
+                     network or in imported blocks are checked for slashable offences, and any \
+                     slashings found are added to the operation pool."
+                )
+                .takes_value(false)
+        )
+        .arg(
+            Arg::with_name("slasher-dir")
+                .long("slasher-dir")
+                .help(
+                    "Set the slasher's database directory. Defaults to `slasher_db` in the \
+                     data directory."
+                )
+                .value_name("DIR")
+                .takes_value(true)
+                .requires("slasher")
+        )
+        .arg(
+            Arg::with_name("slasher-history-length")
+                .long("slasher-history-length")
+                .help(
+                    "The number of epochs of history that the slasher keeps. Must be a multiple \
+                     of 16 and cannot be changed once the slasher database has been created."
+                )
+                .value_name("EPOCHS")
+                .takes_value(true)
+                .requires("slasher")
+        )
+        .arg(
+            Arg::with_name("slasher-update-period")
+                .long("slasher-update-period")
+                .help("The number of seconds between each batch of slasher processing.")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .requires("slasher")
+        )
 }
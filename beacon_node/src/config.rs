@@ -14,6 +14,9 @@ use std::net::{TcpListener, UdpSocket};
 use std::path::PathBuf;
 use types::{ChainSpec, Checkpoint, Epoch, EthSpec, Hash256, GRAFFITI_BYTES_LEN};
 
+/// The name of the slasher's database directory, inside the beacon node data directory.
+const DEFAULT_SLASHER_DIR: &str = "slasher_db";
+
 /// Gets the fully-initialized global client.
 ///
 /// The top-level `clap` arguments should be provided as `cli_args`.
@@ -120,6 +123,18 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.allow_origin = Some(allow_origin.to_string());
     }
 
+    if let Some(cert) = clap_utils::parse_optional(cli_args, "http-tls-cert")? {
+        client_config.http_api.tls_config = Some(http_api::TlsConfig {
+            cert,
+            key: clap_utils::parse_required(cli_args, "http-tls-key")?,
+        });
+    }
+
+    client_config.http_api.api_token_files.read =
+        clap_utils::parse_optional(cli_args, "http-read-token-file")?;
+    client_config.http_api.api_token_files.write =
+        clap_utils::parse_optional(cli_args, "http-write-token-file")?;
+
     /*
      * Prometheus metrics HTTP server
      */
@@ -348,6 +363,47 @@ pub fn get_config<E: EthSpec>(
         };
     }
 
+    if let Some(deny_list) = cli_args.value_of("block-production-deny-list") {
+        client_config.chain.block_production_deny_list = deny_list
+            .split(',')
+            .map(|pubkey| {
+                pubkey.trim().parse().map_err(|e| {
+                    format!(
+                        "Invalid pubkey {} in block-production-deny-list: {:?}",
+                        pubkey, e
+                    )
+                })
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    if cli_args.is_present("slasher") {
+        let slasher_dir = if let Some(slasher_dir) = cli_args.value_of("slasher-dir") {
+            PathBuf::from(slasher_dir)
+        } else {
+            client_config.data_dir.join(DEFAULT_SLASHER_DIR)
+        };
+
+        let mut slasher_config = slasher::Config::new(slasher_dir);
+
+        if let Some(history_length) =
+            clap_utils::parse_optional(cli_args, "slasher-history-length")?
+        {
+            slasher_config.history_length = history_length;
+        }
+
+        if let Some(update_period) = clap_utils::parse_optional(cli_args, "slasher-update-period")?
+        {
+            slasher_config.update_period = update_period;
+        }
+
+        slasher_config
+            .validate()
+            .map_err(|e| format!("Invalid slasher configuration: {:?}", e))?;
+
+        client_config.slasher = Some(slasher_config);
+    }
+
     Ok(client_config)
 }
 
@@ -520,6 +576,14 @@ pub fn set_network_config(
         config.upnp_enabled = false;
     }
 
+    if cli_args.is_present("subscribe-all-subnets") {
+        config.subscribe_all_subnets = true;
+    }
+
+    if cli_args.is_present("import-all-attestations") {
+        config.import_all_attestations = true;
+    }
+
     Ok(())
 }
 
@@ -0,0 +1,156 @@
+//! Provides the `eth1` subcommand of the beacon node, which exports and imports the eth1 deposit
+//! cache stored in the beacon node database.
+//!
+//! The beacon node must not be running whilst these commands are used.
+
+use beacon_chain::eth1_chain::{CachingEth1Backend, Eth1Chain, SszEth1};
+use beacon_chain::ETH1_CACHE_DB_KEY;
+use clap::{App, Arg, ArgMatches, SubCommand};
+use client::ClientConfig;
+use environment::RuntimeContext;
+use eth1::DepositSnapshot;
+use slog::info;
+use std::path::PathBuf;
+use store::{HotColdDB, LevelDB};
+use types::EthSpec;
+
+pub const CMD: &str = "eth1";
+pub const EXPORT_CMD: &str = "export-cache";
+pub const IMPORT_CMD: &str = "import-cache";
+pub const OUTPUT_FLAG: &str = "output";
+pub const INPUT_FLAG: &str = "input";
+
+pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
+    SubCommand::with_name(CMD)
+        .about(
+            "Exports or imports the eth1 deposit cache of the beacon node database. The beacon \
+            node must not be running.",
+        )
+        .subcommand(
+            SubCommand::with_name(EXPORT_CMD)
+                .about(
+                    "Writes all deposit logs known to the beacon node, along with the deposit \
+                    root and eth1 block number, to an SSZ file.",
+                )
+                .arg(
+                    Arg::with_name(OUTPUT_FLAG)
+                        .long(OUTPUT_FLAG)
+                        .value_name("FILE")
+                        .help("The path of the deposit cache file to create.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name(IMPORT_CMD)
+                .about(
+                    "Loads a deposit cache file created by the export-cache command into the \
+                    beacon node database. The deposit root and count in the file are checked \
+                    against the deposit contract on the eth1 node before importing. The check \
+                    queries the contract at the eth1 block of the file, so the eth1 node must \
+                    retain the state of that block (e.g., an archive node) unless the file is \
+                    recent.",
+                )
+                .arg(
+                    Arg::with_name(INPUT_FLAG)
+                        .long(INPUT_FLAG)
+                        .value_name("FILE")
+                        .help("The path of the deposit cache file to import.")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+}
+
+pub async fn run<E: EthSpec>(
+    matches: &ArgMatches<'_>,
+    client_config: ClientConfig,
+    context: RuntimeContext<E>,
+) -> Result<(), String> {
+    let log = context.log().clone();
+    let spec = context.eth2_config().spec.clone();
+
+    let store = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &client_config.create_db_path()?,
+        &client_config.create_freezer_db_path()?,
+        client_config.store.clone(),
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {:?}", e))?;
+
+    let persisted_backend = store
+        .get_item::<SszEth1>(&ETH1_CACHE_DB_KEY)
+        .map_err(|e| format!("DB error whilst reading eth1 cache: {:?}", e))?
+        .map(|persisted| {
+            Eth1Chain::<CachingEth1Backend<E>, E>::from_ssz_container(
+                &persisted,
+                client_config.eth1.clone(),
+                &log,
+                spec.clone(),
+            )
+            .map(Eth1Chain::into_backend)
+        })
+        .transpose()?;
+
+    match matches.subcommand() {
+        (EXPORT_CMD, Some(matches)) => {
+            let path: PathBuf = clap_utils::parse_required(matches, OUTPUT_FLAG)?;
+            let backend = persisted_backend
+                .ok_or_else(|| "The database does not contain an eth1 cache".to_string())?;
+
+            let snapshot = backend.core.deposit_snapshot()?;
+            snapshot.to_file(&path)?;
+
+            info!(
+                log,
+                "Exported eth1 deposit cache";
+                "path" => ?path,
+                "deposit_count" => snapshot.deposit_count,
+                "deposit_root" => ?snapshot.deposit_root,
+                "eth1_block" => snapshot.block_number,
+            );
+        }
+        (IMPORT_CMD, Some(matches)) => {
+            let path: PathBuf = clap_utils::parse_required(matches, INPUT_FLAG)?;
+            let snapshot = DepositSnapshot::from_file(&path)?;
+
+            info!(
+                log,
+                "Verifying eth1 deposit cache";
+                "endpoint" => &client_config.eth1.endpoint,
+                "deposit_count" => snapshot.deposit_count,
+                "deposit_root" => ?snapshot.deposit_root,
+                "eth1_block" => snapshot.block_number,
+            );
+            snapshot.verify(&client_config.eth1).await?;
+
+            let backend = persisted_backend.unwrap_or_else(|| {
+                CachingEth1Backend::new(client_config.eth1.clone(), log.clone(), spec.clone())
+            });
+            backend.core.import_deposit_snapshot(&snapshot)?;
+
+            store
+                .put_item(
+                    &ETH1_CACHE_DB_KEY,
+                    &Eth1Chain::new(backend).as_ssz_container(),
+                )
+                .map_err(|e| format!("DB error whilst writing eth1 cache: {:?}", e))?;
+
+            info!(
+                log,
+                "Imported eth1 deposit cache";
+                "path" => ?path,
+                "deposit_count" => snapshot.deposit_count,
+            );
+        }
+        (unknown, _) => {
+            return Err(format!(
+                "{} does not have a {} command. See --help",
+                CMD, unknown
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -3,6 +3,7 @@ extern crate clap;
 
 mod cli;
 mod config;
+pub mod eth1_cache;
 
 pub use beacon_chain;
 pub use cli::cli_app;
@@ -115,7 +116,7 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         };
 
         let (builder, _events) = builder
-            .system_time_slot_clock()?
+            .system_time_slot_clock_with_skew(client_config.slot_clock_skew_millis)?
             .tee_event_handler(client_config.websocket_server.clone())?;
 
         // Inject the executor into the discv5 network config.
@@ -127,6 +128,7 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             .network(&client_config.network)
             .await?
             .notifier()?
+            .slasher_service()?
             .http_api_config(client_config.http_api.clone())
             .http_metrics_config(client_config.http_metrics.clone())
             .build()
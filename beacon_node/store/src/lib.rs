@@ -140,6 +140,7 @@ pub enum DBColumn {
     /// For persisting in-memory state to the database.
     BeaconChain,
     OpPool,
+    NaiveAggregationPool,
     Eth1Cache,
     ForkChoice,
     /// For the table mapping restore point numbers to state roots.
@@ -151,6 +152,20 @@ pub enum DBColumn {
     BeaconHistoricalRoots,
     BeaconRandaoMixes,
     DhtEnrs,
+    /// For the slasher's own metadata, such as the configuration it was created with.
+    SlasherMetadata,
+    /// For the slasher's min-target span arrays.
+    SlasherMinTargets,
+    /// For the slasher's max-target span arrays.
+    SlasherMaxTargets,
+    /// For the mapping from (validator, target epoch) to the attestation signed by that validator.
+    SlasherAttesterRecords,
+    /// For the indexed attestations referenced by `SlasherAttesterRecords`.
+    SlasherIndexedAttestations,
+    /// For the list of indexed attestations stored for each target epoch.
+    SlasherEpochAttestations,
+    /// For the mapping from (slot, proposer) to a signed block header.
+    SlasherProposers,
 }
 
 impl Into<&'static str> for DBColumn {
@@ -162,6 +177,7 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconState => "ste",
             DBColumn::BeaconChain => "bch",
             DBColumn::OpPool => "opo",
+            DBColumn::NaiveAggregationPool => "nap",
             DBColumn::Eth1Cache => "etc",
             DBColumn::ForkChoice => "frk",
             DBColumn::BeaconRestorePoint => "brp",
@@ -171,6 +187,13 @@ impl Into<&'static str> for DBColumn {
             DBColumn::BeaconHistoricalRoots => "bhr",
             DBColumn::BeaconRandaoMixes => "brm",
             DBColumn::DhtEnrs => "dht",
+            DBColumn::SlasherMetadata => "smd",
+            DBColumn::SlasherMinTargets => "smn",
+            DBColumn::SlasherMaxTargets => "smx",
+            DBColumn::SlasherAttesterRecords => "sar",
+            DBColumn::SlasherIndexedAttestations => "sia",
+            DBColumn::SlasherEpochAttestations => "sea",
+            DBColumn::SlasherProposers => "spr",
         }
     }
 }
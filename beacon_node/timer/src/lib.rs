@@ -4,12 +4,18 @@
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use futures::stream::StreamExt;
-use slog::info;
+use parking_lot::Mutex;
+use slog::{debug, error, info};
 use slot_clock::SlotClock;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::{interval_at, Instant};
 
+/// The fraction of a slot before the start of the next slot at which the head state is advanced.
+///
+/// E.g., a value of 4 with a 12 second slot advances the state 3 seconds before the next slot.
+const STATE_ADVANCE_SLOT_FRACTION: u32 = 4;
+
 /// Spawns a timer service which periodically executes tasks for the beacon chain
 pub fn spawn_timer<T: BeaconChainTypes>(
     executor: task_executor::TaskExecutor,
@@ -17,21 +23,64 @@ pub fn spawn_timer<T: BeaconChainTypes>(
     milliseconds_per_slot: u64,
 ) -> Result<(), &'static str> {
     let log = executor.log();
-    let start_instant = Instant::now()
-        + beacon_chain
-            .slot_clock
-            .duration_to_next_slot()
-            .ok_or_else(|| "slot_notifier unable to determine time to next slot")?;
+    let duration_to_next_slot = beacon_chain
+        .slot_clock
+        .duration_to_next_slot()
+        .ok_or_else(|| "slot_notifier unable to determine time to next slot")?;
+    let start_instant = Instant::now() + duration_to_next_slot;
 
     // Warning: `interval_at` panics if `milliseconds_per_slot` = 0.
-    let mut interval = interval_at(start_instant, Duration::from_millis(milliseconds_per_slot));
+    let slot_duration = Duration::from_millis(milliseconds_per_slot);
+    let mut interval = interval_at(start_instant, slot_duration);
+    let per_slot_chain = beacon_chain.clone();
     let timer_future = async move {
         while interval.next().await.is_some() {
-            beacon_chain.per_slot_task();
+            per_slot_chain.per_slot_task();
         }
     };
 
     executor.spawn(timer_future, "timer");
+
+    // Advance the head state towards the end of each slot, so it is ready for the next one.
+    let advance_lead = slot_duration / STATE_ADVANCE_SLOT_FRACTION;
+    let advance_start = if duration_to_next_slot > advance_lead {
+        Instant::now() + (duration_to_next_slot - advance_lead)
+    } else {
+        start_instant + (slot_duration - advance_lead)
+    };
+    let mut advance_interval = interval_at(advance_start, slot_duration);
+    let advance_executor = executor.clone();
+    let advance_log = log.clone();
+    // Prevents a new state advance from starting whilst a previous one is still running.
+    let advance_lock = Arc::new(Mutex::new(()));
+    let advance_future = async move {
+        while advance_interval.next().await.is_some() {
+            let beacon_chain = beacon_chain.clone();
+            let log = advance_log.clone();
+            let lock = advance_lock.clone();
+            advance_executor.spawn_blocking(
+                move || {
+                    let _guard = if let Some(guard) = lock.try_lock() {
+                        guard
+                    } else {
+                        debug!(log, "State advance is still running from previous slot");
+                        return;
+                    };
+
+                    if let Err(e) = beacon_chain.advance_head_state() {
+                        error!(
+                            log,
+                            "Failed to advance head state";
+                            "error" => format!("{:?}", e)
+                        );
+                    }
+                },
+                "state_advance",
+            );
+        }
+    };
+
+    executor.spawn(advance_future, "state_advance_timer");
     info!(log, "Timer service started");
 
     Ok(())
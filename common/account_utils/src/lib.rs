@@ -1,6 +1,9 @@
 //! Provides functions that are used for key/account management across multiple crates in the
 //! Lighthouse project.
 
+#[macro_use]
+extern crate lazy_static;
+
 use eth2_keystore::Keystore;
 use eth2_wallet::{
     bip39::{Language, Mnemonic, MnemonicType},
@@ -15,6 +18,7 @@ use std::os::unix::fs::PermissionsExt;
 use std::path::{Path, PathBuf};
 use zeroize::Zeroize;
 
+pub mod password_source;
 pub mod validator_definitions;
 
 pub use eth2_keystore;
@@ -76,6 +80,39 @@ pub fn create_with_600_perms<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()
     Ok(())
 }
 
+/// Replaces the contents of `path` with `bytes`, leaving the file with `600 (-rw-------)`
+/// permissions.
+///
+/// The bytes are written to a temporary file alongside `path` which is then renamed over it, so
+/// readers will see either the old or the new contents but never a partially written file.
+pub fn write_with_600_perms_atomically<P: AsRef<Path>>(
+    path: P,
+    bytes: &[u8],
+) -> Result<(), io::Error> {
+    let path = path.as_ref();
+
+    let mut tmp_file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?
+        .to_os_string();
+    tmp_file_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_file_name);
+
+    let mut file = File::create(&tmp_path)?;
+
+    let mut perm = file.metadata()?.permissions();
+
+    perm.set_mode(0o600);
+
+    file.set_permissions(perm)?;
+
+    file.write_all(bytes)?;
+
+    file.sync_all()?;
+
+    fs::rename(&tmp_path, path)
+}
+
 /// Generates a random alphanumeric password of length `DEFAULT_PASSWORD_LEN`.
 pub fn random_password() -> PlainText {
     rand::thread_rng()
@@ -0,0 +1,211 @@
+//! Sources of keystore passwords which are read when needed and never stored on disk.
+
+use crate::strip_off_newlines;
+use eth2_wallet::PlainText;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::fs::File;
+use std::io::{self, Read};
+use std::mem::ManuallyDrop;
+use std::os::unix::io::{FromRawFd, RawFd};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::Mutex;
+
+lazy_static! {
+    /// The contents of each file descriptor which has been read.
+    ///
+    /// A descriptor can only be read to the end once, so its contents are kept in memory for when
+    /// the password is needed again (e.g., when the validator is re-enabled).
+    static ref FD_CONTENTS: Mutex<HashMap<RawFd, PlainText>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The environment variable is not set or is not valid unicode.
+    UnableToReadEnvVar(String, env::VarError),
+    /// There was an error reading from the file descriptor.
+    UnableToReadFd(RawFd, io::Error),
+    /// The file descriptor is close-on-exec, so it was opened by this process rather than
+    /// inherited from its parent.
+    FdNotInherited(RawFd),
+    /// The file descriptor is a terminal, which should be prompted instead.
+    FdIsTerminal(RawFd),
+    /// The command could not be started.
+    UnableToRunCommand(String, io::Error),
+    /// The command exited unsuccessfully.
+    CommandFailed(String, ExitStatus),
+}
+
+/// Where to find the password for a keystore.
+///
+/// Trailing newlines are removed from the password, as they are for password files.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PasswordSource {
+    /// The value of the environment variable `name`.
+    Env { name: String },
+    /// Everything which can be read from the file descriptor `fd`, which must be inherited from
+    /// the parent process (e.g., a pipe). Use `0` for stdin, unless it is a terminal.
+    ///
+    /// The descriptor is read the first time the password is needed and is not closed.
+    Fd { fd: RawFd },
+    /// The standard output of `program`, which is run with `args` without a shell. Standard
+    /// error is passed through to the validator client's.
+    Command {
+        program: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        args: Vec<String>,
+    },
+}
+
+impl PasswordSource {
+    /// Reads the password from `self`.
+    pub fn read(&self) -> Result<PlainText, Error> {
+        match self {
+            PasswordSource::Env { name } => env::var(name)
+                .map(|password| strip_off_newlines(password.into_bytes()).into())
+                .map_err(|e| Error::UnableToReadEnvVar(name.clone(), e)),
+            PasswordSource::Fd { fd } => {
+                let mut fd_contents = FD_CONTENTS
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+                if let Some(password) = fd_contents.get(fd) {
+                    return Ok(password.clone());
+                }
+
+                check_inherited_fd(*fd)?;
+
+                // Never close the descriptor, since it is not owned by this function.
+                let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(*fd) });
+                let mut bytes = vec![];
+                file.read_to_end(&mut bytes)
+                    .map_err(|e| Error::UnableToReadFd(*fd, e))?;
+
+                let password = PlainText::from(strip_off_newlines(bytes));
+                fd_contents.insert(*fd, password.clone());
+                Ok(password)
+            }
+            PasswordSource::Command { program, args } => {
+                let output = Command::new(program)
+                    .args(args)
+                    .stdin(Stdio::null())
+                    .stderr(Stdio::inherit())
+                    .output()
+                    .map_err(|e| Error::UnableToRunCommand(program.clone(), e))?;
+
+                let password = PlainText::from(strip_off_newlines(output.stdout));
+
+                if output.status.success() {
+                    Ok(password)
+                } else {
+                    Err(Error::CommandFailed(program.clone(), output.status))
+                }
+            }
+        }
+    }
+}
+
+/// Checks that `fd` is open, was inherited from the parent process and is not a terminal, so that
+/// it is safe to read from.
+///
+/// Every descriptor opened by the Rust standard library is close-on-exec, so one without that flag
+/// must have been inherited.
+fn check_inherited_fd(fd: RawFd) -> Result<(), Error> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFD) };
+    if flags < 0 {
+        return Err(Error::UnableToReadFd(fd, io::Error::last_os_error()));
+    }
+    if flags & libc::FD_CLOEXEC != 0 {
+        return Err(Error::FdNotInherited(fd));
+    }
+    if unsafe { libc::isatty(fd) } == 1 {
+        return Err(Error::FdIsTerminal(fd));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::os::unix::io::AsRawFd;
+
+    #[test]
+    fn env() {
+        env::set_var("PASSWORD_SOURCE_TEST", "hunter2\n");
+
+        let source = PasswordSource::Env {
+            name: "PASSWORD_SOURCE_TEST".to_string(),
+        };
+        assert_eq!(source.read().unwrap().as_bytes(), b"hunter2");
+
+        let missing = PasswordSource::Env {
+            name: "PASSWORD_SOURCE_TEST_MISSING".to_string(),
+        };
+        assert!(missing.read().is_err());
+    }
+
+    #[test]
+    fn command() {
+        let source = PasswordSource::Command {
+            program: "echo".to_string(),
+            args: vec!["hunter2".to_string()],
+        };
+        assert_eq!(source.read().unwrap().as_bytes(), b"hunter2");
+
+        let failing = PasswordSource::Command {
+            program: "false".to_string(),
+            args: vec![],
+        };
+        assert!(matches!(failing.read(), Err(Error::CommandFailed(..))));
+    }
+
+    #[test]
+    fn fd() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let [read_fd, write_fd] = fds;
+
+        let mut writer = unsafe { File::from_raw_fd(write_fd) };
+        writer.write_all(b"hunter2\n").unwrap();
+        drop(writer);
+
+        // The contents are kept after the first read, and the descriptor is left open. It is never
+        // closed, so that its number is not reused by the other tests whilst in `FD_CONTENTS`.
+        let source = PasswordSource::Fd { fd: read_fd };
+        assert_eq!(source.read().unwrap().as_bytes(), b"hunter2");
+        assert_eq!(source.read().unwrap().as_bytes(), b"hunter2");
+        assert!(unsafe { libc::fcntl(read_fd, libc::F_GETFD) } >= 0);
+    }
+
+    #[test]
+    fn fd_must_be_inherited() {
+        // Files opened by this process are close-on-exec.
+        let file = File::open("/dev/null").unwrap();
+        let source = PasswordSource::Fd {
+            fd: file.as_raw_fd(),
+        };
+        assert!(matches!(source.read(), Err(Error::FdNotInherited(_))));
+
+        let closed = PasswordSource::Fd { fd: 1_000_000 };
+        assert!(matches!(closed.read(), Err(Error::UnableToReadFd(..))));
+    }
+
+    #[test]
+    fn yaml() {
+        let source: PasswordSource =
+            serde_yaml::from_str("type: command\nprogram: vault\nargs: [kv, get, secret]").unwrap();
+        assert_eq!(
+            source,
+            PasswordSource::Command {
+                program: "vault".to_string(),
+                args: vec!["kv".to_string(), "get".to_string(), "secret".to_string()],
+            }
+        );
+
+        let source: PasswordSource = serde_yaml::from_str("type: fd\nfd: 3").unwrap();
+        assert_eq!(source, PasswordSource::Fd { fd: 3 });
+    }
+}
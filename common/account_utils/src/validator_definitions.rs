@@ -3,7 +3,10 @@
 //! Serves as the source-of-truth of which validators this validator client should attempt (or not
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
 
-use crate::{create_with_600_perms, default_keystore_password_path, ZeroizeString};
+use crate::{
+    default_keystore_password_path, password_source::PasswordSource,
+    write_with_600_perms_atomically, ZeroizeString,
+};
 use directory::ensure_dir_exists;
 use eth2_keystore::Keystore;
 use regex::Regex;
@@ -55,6 +58,10 @@ pub enum SigningDefinition {
         voting_keystore_password_path: Option<PathBuf>,
         #[serde(skip_serializing_if = "Option::is_none")]
         voting_keystore_password: Option<ZeroizeString>,
+        /// Read the password from this source each time the validator client starts, rather
+        /// than from a file. Takes precedence over `voting_keystore_password_path`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        voting_keystore_password_source: Option<PasswordSource>,
     },
 }
 
@@ -98,6 +105,7 @@ impl ValidatorDefinition {
                 voting_keystore_path,
                 voting_keystore_password_path: None,
                 voting_keystore_password,
+                voting_keystore_password_source: None,
             },
         })
     }
@@ -219,6 +227,7 @@ impl ValidatorDefinitions {
                         voting_keystore_path,
                         voting_keystore_password_path,
                         voting_keystore_password: None,
+                        voting_keystore_password_source: None,
                     },
                 })
             })
@@ -234,16 +243,12 @@ impl ValidatorDefinitions {
     /// Encodes `self` as a YAML string it writes it to the `CONFIG_FILENAME` file in the
     /// `validators_dir` directory.
     ///
-    /// Will create a new file if it does not exist or over-write any existing file.
+    /// Will create a new file if it does not exist or atomically replace any existing file.
     pub fn save<P: AsRef<Path>>(&self, validators_dir: P) -> Result<(), Error> {
         let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
         let bytes = serde_yaml::to_vec(self).map_err(Error::UnableToEncodeFile)?;
 
-        if config_path.exists() {
-            fs::write(config_path, &bytes).map_err(Error::UnableToWriteFile)
-        } else {
-            create_with_600_perms(&config_path, &bytes).map_err(Error::UnableToWriteFile)
-        }
+        write_with_600_perms_atomically(&config_path, &bytes).map_err(Error::UnableToWriteFile)
     }
 
     /// Adds a new `ValidatorDefinition` to `self`.
@@ -251,6 +256,15 @@ impl ValidatorDefinitions {
         self.0.push(def)
     }
 
+    /// Removes and returns the `ValidatorDefinition` for `voting_public_key`, if any.
+    pub fn remove(&mut self, voting_public_key: &PublicKey) -> Option<ValidatorDefinition> {
+        let index = self
+            .0
+            .iter()
+            .position(|def| def.voting_public_key == *voting_public_key)?;
+        Some(self.0.remove(index))
+    }
+
     /// Returns a slice of all `ValidatorDefinition` in `self`.
     pub fn as_slice(&self) -> &[ValidatorDefinition] {
         self.0.as_slice()
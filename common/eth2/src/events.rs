@@ -0,0 +1,131 @@
+//! A client for the server-sent events (SSE) returned by `GET events`.
+
+use crate::types::SseHead;
+use crate::Error;
+use reqwest::Response;
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// The longest we will wait for the server to send anything on the stream.
+///
+/// The server sends a keep-alive comment every 15 seconds, so a silent stream has most likely
+/// been dropped somewhere between us and the server.
+pub const EVENT_STREAM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A single event parsed from a `text/event-stream` body.
+#[derive(Debug, Clone, PartialEq)]
+struct RawEvent {
+    event: String,
+    data: String,
+}
+
+/// The stream of head events returned by `BeaconNodeHttpClient::get_events_head`.
+///
+/// Any events other than `head` are ignored.
+pub struct HeadEventStream {
+    response: Response,
+    buffer: String,
+}
+
+impl HeadEventStream {
+    pub(crate) fn new(response: Response) -> Self {
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Waits for the next head event, returning `None` once the server closes the connection.
+    ///
+    /// Returns `Error::EventStreamTimeout` if nothing is received for `EVENT_STREAM_TIMEOUT`, in
+    /// which case the caller should subscribe again.
+    pub async fn next(&mut self) -> Result<Option<SseHead>, Error> {
+        loop {
+            while let Some(event) = next_event(&mut self.buffer) {
+                if event.event == "head" {
+                    return serde_json::from_str(&event.data)
+                        .map(Some)
+                        .map_err(Error::InvalidJson);
+                }
+            }
+
+            let chunk = timeout(EVENT_STREAM_TIMEOUT, self.response.chunk())
+                .await
+                .map_err(|_| Error::EventStreamTimeout)?
+                .map_err(Error::Reqwest)?;
+
+            match chunk {
+                Some(chunk) => self
+                    .buffer
+                    .push_str(&String::from_utf8_lossy(&chunk).replace("\r\n", "\n")),
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Removes the first complete event from `buffer` and returns it.
+///
+/// Comments (e.g., keep-alives) and events without data are skipped.
+fn next_event(buffer: &mut String) -> Option<RawEvent> {
+    while let Some(end) = buffer.find("\n\n") {
+        let block = buffer[..end].to_string();
+        buffer.replace_range(..end + 2, "");
+
+        let mut event = String::from("message");
+        let mut data = vec![];
+
+        for line in block.lines() {
+            let (field, value) = match line.find(':') {
+                Some(0) => continue,
+                Some(i) => (&line[..i], line[i + 1..].trim_start_matches(' ')),
+                None => (line, ""),
+            };
+
+            match field {
+                "event" => event = value.to_string(),
+                "data" => data.push(value),
+                _ => {}
+            }
+        }
+
+        if !data.is_empty() {
+            return Some(RawEvent {
+                event,
+                data: data.join("\n"),
+            });
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_events() {
+        let mut buffer = String::from(
+            ":\n\nevent:head\ndata:{\"slot\":\"1\"}\n\nevent: other\ndata: a\ndata: b\n\ndata: ",
+        );
+
+        assert_eq!(
+            next_event(&mut buffer),
+            Some(RawEvent {
+                event: "head".to_string(),
+                data: "{\"slot\":\"1\"}".to_string(),
+            })
+        );
+        assert_eq!(
+            next_event(&mut buffer),
+            Some(RawEvent {
+                event: "other".to_string(),
+                data: "a\nb".to_string(),
+            })
+        );
+        // The final event is incomplete.
+        assert_eq!(next_event(&mut buffer), None);
+        assert_eq!(buffer, "data: ");
+    }
+}
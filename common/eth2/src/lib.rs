@@ -7,18 +7,22 @@
 //! Eventually it would be ideal to publish this crate on crates.io, however we have some local
 //! dependencies preventing this presently.
 
+mod events;
 #[cfg(feature = "lighthouse")]
 pub mod lighthouse;
 pub mod lighthouse_vc;
 pub mod types;
 
 use self::types::*;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, CONTENT_TYPE};
 use reqwest::{IntoUrl, Response};
 use serde::{de::DeserializeOwned, Serialize};
+use ssz::{Decode, Encode};
 use std::convert::TryFrom;
 use std::fmt;
 
 use eth2_libp2p::PeerId;
+pub use events::HeadEventStream;
 pub use reqwest;
 pub use reqwest::{StatusCode, Url};
 
@@ -42,6 +46,8 @@ pub enum Error {
     InvalidJson(serde_json::Error),
     /// The server returned an invalid SSZ response.
     InvalidSsz(ssz::DecodeError),
+    /// The server sent nothing on an event stream (not even a keep-alive) for too long.
+    EventStreamTimeout,
 }
 
 impl Error {
@@ -57,6 +63,7 @@ impl Error {
             Error::MissingSignatureHeader => None,
             Error::InvalidJson(_) => None,
             Error::InvalidSsz(_) => None,
+            Error::EventStreamTimeout => None,
         }
     }
 }
@@ -87,6 +94,35 @@ impl BeaconNodeHttpClient {
         Self { client, server }
     }
 
+    /// Returns a `reqwest::ClientBuilder` for a client which sends `api_token` as a bearer token
+    /// with every request and trusts the PEM-encoded `tls_ca_cert` in addition to the system root
+    /// certificates.
+    ///
+    /// Use `Self::from_components` to create a `BeaconNodeHttpClient` from the built client.
+    pub fn client_builder(
+        api_token: Option<&str>,
+        tls_ca_cert: Option<&[u8]>,
+    ) -> Result<reqwest::ClientBuilder, Error> {
+        let mut builder = reqwest::Client::builder();
+
+        if let Some(api_token) = api_token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", api_token))
+                .map_err(|_| Error::InvalidSecret("API token is not a valid header".to_string()))?;
+            value.set_sensitive(true);
+
+            let mut headers = HeaderMap::new();
+            headers.insert(AUTHORIZATION, value);
+            builder = builder.default_headers(headers);
+        }
+
+        if let Some(tls_ca_cert) = tls_ca_cert {
+            let cert = reqwest::Certificate::from_pem(tls_ca_cert).map_err(Error::Reqwest)?;
+            builder = builder.add_root_certificate(cert);
+        }
+
+        Ok(builder)
+    }
+
     /// Return the path with the standard `/eth1/v1` prefix applied.
     fn eth_path(&self) -> Result<Url, Error> {
         let mut path = self.server.clone();
@@ -137,6 +173,71 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
+    /// Perform a HTTP POST request, returning a JSON response.
+    async fn post_with_response<T: Serialize, U: IntoUrl, V: DeserializeOwned>(
+        &self,
+        url: U,
+        body: &T,
+    ) -> Result<V, Error> {
+        let response = self
+            .client
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        ok_or_error(response)
+            .await?
+            .json()
+            .await
+            .map_err(Error::Reqwest)
+    }
+
+    /// Perform a HTTP GET request for an SSZ response.
+    async fn get_ssz<T: Decode, U: IntoUrl>(&self, url: U) -> Result<T, Error> {
+        let response = self
+            .client
+            .get(url)
+            .header(ACCEPT, SSZ_CONTENT_TYPE)
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        let bytes = ok_or_error(response)
+            .await?
+            .bytes()
+            .await
+            .map_err(Error::Reqwest)?;
+        T::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz)
+    }
+
+    /// Perform a HTTP GET request for an SSZ response, returning `None` on a 404 error.
+    async fn get_ssz_opt<T: Decode, U: IntoUrl>(&self, url: U) -> Result<Option<T>, Error> {
+        match self.get_ssz(url).await {
+            Ok(data) => Ok(Some(data)),
+            Err(err) => {
+                if err.status() == Some(StatusCode::NOT_FOUND) {
+                    Ok(None)
+                } else {
+                    Err(err)
+                }
+            }
+        }
+    }
+
+    /// Perform a HTTP POST request with an SSZ body.
+    async fn post_ssz<T: Encode, U: IntoUrl>(&self, url: U, body: &T) -> Result<(), Error> {
+        let response = self
+            .client
+            .post(url)
+            .header(CONTENT_TYPE, SSZ_CONTENT_TYPE)
+            .body(body.as_ssz_bytes())
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        ok_or_error(response).await?;
+        Ok(())
+    }
+
     /// `GET beacon/genesis`
     ///
     /// ## Errors
@@ -160,6 +261,25 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
     ) -> Result<Option<GenericResponse<RootData>>, Error> {
+        let path = self.beacon_states_path(state_id, "root")?;
+
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/states/{state_id}/root` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_root_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<RootData>, Error> {
+        let path = self.beacon_states_path(state_id, "root")?;
+
+        self.get_ssz_opt(path).await
+    }
+
+    /// Returns the URL for `GET beacon/states/{state_id}/{endpoint}`.
+    fn beacon_states_path(&self, state_id: StateId, endpoint: &str) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -167,9 +287,9 @@ impl BeaconNodeHttpClient {
             .push("beacon")
             .push("states")
             .push(&state_id.to_string())
-            .push("root");
+            .push(endpoint);
 
-        self.get_opt(path).await
+        Ok(path)
     }
 
     /// `GET beacon/states/{state_id}/fork`
@@ -179,18 +299,23 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
     ) -> Result<Option<GenericResponse<Fork>>, Error> {
-        let mut path = self.eth_path()?;
-
-        path.path_segments_mut()
-            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
-            .push("beacon")
-            .push("states")
-            .push(&state_id.to_string())
-            .push("fork");
+        let path = self.beacon_states_path(state_id, "fork")?;
 
         self.get_opt(path).await
     }
 
+    /// `GET beacon/states/{state_id}/fork` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_fork_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<Fork>, Error> {
+        let path = self.beacon_states_path(state_id, "fork")?;
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `GET beacon/states/{state_id}/finality_checkpoints`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -198,18 +323,23 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
     ) -> Result<Option<GenericResponse<FinalityCheckpointsData>>, Error> {
-        let mut path = self.eth_path()?;
-
-        path.path_segments_mut()
-            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
-            .push("beacon")
-            .push("states")
-            .push(&state_id.to_string())
-            .push("finality_checkpoints");
+        let path = self.beacon_states_path(state_id, "finality_checkpoints")?;
 
         self.get_opt(path).await
     }
 
+    /// `GET beacon/states/{state_id}/finality_checkpoints` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_finality_checkpoints_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<FinalityCheckpointsData>, Error> {
+        let path = self.beacon_states_path(state_id, "finality_checkpoints")?;
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `GET beacon/states/{state_id}/validators`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -217,14 +347,7 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
     ) -> Result<Option<GenericResponse<Vec<ValidatorData>>>, Error> {
-        let mut path = self.eth_path()?;
-
-        path.path_segments_mut()
-            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
-            .push("beacon")
-            .push("states")
-            .push(&state_id.to_string())
-            .push("validators");
+        let path = self.beacon_states_path(state_id, "validators")?;
 
         self.get_opt(path).await
     }
@@ -239,6 +362,34 @@ impl BeaconNodeHttpClient {
         slot: Option<Slot>,
         index: Option<u64>,
     ) -> Result<Option<GenericResponse<Vec<CommitteeData>>>, Error> {
+        let path = self.beacon_states_committees_path(state_id, epoch, slot, index)?;
+
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/states/{state_id}/committees?slot,index` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_committees_ssz(
+        &self,
+        state_id: StateId,
+        epoch: Epoch,
+        slot: Option<Slot>,
+        index: Option<u64>,
+    ) -> Result<Option<Vec<CommitteeData>>, Error> {
+        let path = self.beacon_states_committees_path(state_id, epoch, slot, index)?;
+
+        self.get_ssz_opt(path).await
+    }
+
+    /// Returns the URL for `GET beacon/states/{state_id}/committees?slot,index`.
+    fn beacon_states_committees_path(
+        &self,
+        state_id: StateId,
+        epoch: Epoch,
+        slot: Option<Slot>,
+        index: Option<u64>,
+    ) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -259,7 +410,7 @@ impl BeaconNodeHttpClient {
                 .append_pair("index", &index.to_string());
         }
 
-        self.get_opt(path).await
+        Ok(path)
     }
 
     /// `GET beacon/states/{state_id}/validators/{validator_id}`
@@ -291,6 +442,30 @@ impl BeaconNodeHttpClient {
         slot: Option<Slot>,
         parent_root: Option<Hash256>,
     ) -> Result<Option<GenericResponse<Vec<BlockHeaderData>>>, Error> {
+        let path = self.beacon_headers_path(slot, parent_root)?;
+
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/headers?slot,parent_root` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_headers_ssz(
+        &self,
+        slot: Option<Slot>,
+        parent_root: Option<Hash256>,
+    ) -> Result<Option<Vec<BlockHeaderData>>, Error> {
+        let path = self.beacon_headers_path(slot, parent_root)?;
+
+        self.get_ssz_opt(path).await
+    }
+
+    /// Returns the URL for `GET beacon/headers?slot,parent_root`.
+    fn beacon_headers_path(
+        &self,
+        slot: Option<Slot>,
+        parent_root: Option<Hash256>,
+    ) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -308,7 +483,7 @@ impl BeaconNodeHttpClient {
                 .append_pair("parent_root", &format!("{:?}", root));
         }
 
-        self.get_opt(path).await
+        Ok(path)
     }
 
     /// `GET beacon/headers/{block_id}`
@@ -318,6 +493,25 @@ impl BeaconNodeHttpClient {
         &self,
         block_id: BlockId,
     ) -> Result<Option<GenericResponse<BlockHeaderData>>, Error> {
+        let path = self.beacon_headers_block_id_path(block_id)?;
+
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/headers/{block_id}` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_headers_block_id_ssz(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<BlockHeaderData>, Error> {
+        let path = self.beacon_headers_block_id_path(block_id)?;
+
+        self.get_ssz_opt(path).await
+    }
+
+    /// Returns the URL for `GET beacon/headers/{block_id}`.
+    fn beacon_headers_block_id_path(&self, block_id: BlockId) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -326,7 +520,7 @@ impl BeaconNodeHttpClient {
             .push("headers")
             .push(&block_id.to_string());
 
-        self.get_opt(path).await
+        Ok(path)
     }
 
     /// `POST beacon/blocks`
@@ -336,6 +530,27 @@ impl BeaconNodeHttpClient {
         &self,
         block: &SignedBeaconBlock<T>,
     ) -> Result<(), Error> {
+        let path = self.beacon_blocks_submission_path()?;
+
+        self.post(path, block).await?;
+
+        Ok(())
+    }
+
+    /// `POST beacon/blocks` with an SSZ body.
+    pub async fn post_beacon_blocks_ssz<T: EthSpec>(
+        &self,
+        block: &SignedBeaconBlock<T>,
+    ) -> Result<(), Error> {
+        let path = self.beacon_blocks_submission_path()?;
+
+        self.post_ssz(path, block).await?;
+
+        Ok(())
+    }
+
+    /// Returns the URL for `POST beacon/blocks`.
+    fn beacon_blocks_submission_path(&self) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -343,9 +558,7 @@ impl BeaconNodeHttpClient {
             .push("beacon")
             .push("blocks");
 
-        self.post(path, block).await?;
-
-        Ok(())
+        Ok(path)
     }
 
     /// `GET beacon/blocks`
@@ -355,15 +568,42 @@ impl BeaconNodeHttpClient {
         &self,
         block_id: BlockId,
     ) -> Result<Option<GenericResponse<SignedBeaconBlock<T>>>, Error> {
+        let path = self.beacon_blocks_path(block_id, None)?;
+
+        self.get_opt(path).await
+    }
+
+    /// `GET beacon/blocks` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_blocks_ssz<T: EthSpec>(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<SignedBeaconBlock<T>>, Error> {
+        let path = self.beacon_blocks_path(block_id, None)?;
+
+        self.get_ssz_opt(path).await
+    }
+
+    /// Returns the URL for `GET beacon/blocks/{block_id}`, with `/{endpoint}` appended if an
+    /// `endpoint` is given.
+    fn beacon_blocks_path(&self, block_id: BlockId, endpoint: Option<&str>) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
-        path.path_segments_mut()
-            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
-            .push("beacon")
-            .push("blocks")
-            .push(&block_id.to_string());
+        {
+            let mut segments = path
+                .path_segments_mut()
+                .map_err(|()| Error::InvalidUrl(self.server.clone()))?;
+            segments
+                .push("beacon")
+                .push("blocks")
+                .push(&block_id.to_string());
+            if let Some(endpoint) = endpoint {
+                segments.push(endpoint);
+            }
+        }
 
-        self.get_opt(path).await
+        Ok(path)
     }
 
     /// `GET beacon/blocks/{block_id}/root`
@@ -373,18 +613,23 @@ impl BeaconNodeHttpClient {
         &self,
         block_id: BlockId,
     ) -> Result<Option<GenericResponse<RootData>>, Error> {
-        let mut path = self.eth_path()?;
-
-        path.path_segments_mut()
-            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
-            .push("beacon")
-            .push("blocks")
-            .push(&block_id.to_string())
-            .push("root");
+        let path = self.beacon_blocks_path(block_id, Some("root"))?;
 
         self.get_opt(path).await
     }
 
+    /// `GET beacon/blocks/{block_id}/root` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_blocks_root_ssz(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<RootData>, Error> {
+        let path = self.beacon_blocks_path(block_id, Some("root"))?;
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `GET beacon/blocks/{block_id}/attestations`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -392,40 +637,49 @@ impl BeaconNodeHttpClient {
         &self,
         block_id: BlockId,
     ) -> Result<Option<GenericResponse<Vec<Attestation<T>>>>, Error> {
-        let mut path = self.eth_path()?;
-
-        path.path_segments_mut()
-            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
-            .push("beacon")
-            .push("blocks")
-            .push(&block_id.to_string())
-            .push("attestations");
+        let path = self.beacon_blocks_path(block_id, Some("attestations"))?;
 
         self.get_opt(path).await
     }
 
+    /// `GET beacon/blocks/{block_id}/attestations` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_blocks_attestations_ssz<T: EthSpec>(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<Attestation<T>>>, Error> {
+        let path = self.beacon_blocks_path(block_id, Some("attestations"))?;
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `POST beacon/pool/attestations`
     pub async fn post_beacon_pool_attestations<T: EthSpec>(
         &self,
         attestation: &Attestation<T>,
     ) -> Result<(), Error> {
-        let mut path = self.eth_path()?;
-
-        path.path_segments_mut()
-            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
-            .push("beacon")
-            .push("pool")
-            .push("attestations");
+        let path = self.beacon_pool_attestations_path()?;
 
         self.post(path, attestation).await?;
 
         Ok(())
     }
 
-    /// `GET beacon/pool/attestations`
-    pub async fn get_beacon_pool_attestations<T: EthSpec>(
+    /// `POST beacon/pool/attestations` with an SSZ body.
+    pub async fn post_beacon_pool_attestations_ssz<T: EthSpec>(
         &self,
-    ) -> Result<GenericResponse<Vec<Attestation<T>>>, Error> {
+        attestation: &Attestation<T>,
+    ) -> Result<(), Error> {
+        let path = self.beacon_pool_attestations_path()?;
+
+        self.post_ssz(path, attestation).await?;
+
+        Ok(())
+    }
+
+    /// Returns the URL for `GET` and `POST beacon/pool/attestations`.
+    fn beacon_pool_attestations_path(&self) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -434,6 +688,15 @@ impl BeaconNodeHttpClient {
             .push("pool")
             .push("attestations");
 
+        Ok(path)
+    }
+
+    /// `GET beacon/pool/attestations`
+    pub async fn get_beacon_pool_attestations<T: EthSpec>(
+        &self,
+    ) -> Result<GenericResponse<Vec<Attestation<T>>>, Error> {
+        let path = self.beacon_pool_attestations_path()?;
+
         self.get(path).await
     }
 
@@ -666,6 +929,25 @@ impl BeaconNodeHttpClient {
         &self,
         state_id: StateId,
     ) -> Result<Option<GenericResponse<BeaconState<T>>>, Error> {
+        let path = self.debug_beacon_states_path(state_id)?;
+
+        self.get_opt(path).await
+    }
+
+    /// `GET debug/beacon/states/{state_id}` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_debug_beacon_states_ssz<T: EthSpec>(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<BeaconState<T>>, Error> {
+        let path = self.debug_beacon_states_path(state_id)?;
+
+        self.get_ssz_opt(path).await
+    }
+
+    /// Returns the URL for `GET debug/beacon/states/{state_id}`.
+    fn debug_beacon_states_path(&self, state_id: StateId) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -675,7 +957,7 @@ impl BeaconNodeHttpClient {
             .push("states")
             .push(&state_id.to_string());
 
-        self.get_opt(path).await
+        Ok(path)
     }
 
     /// `GET debug/beacon/heads`
@@ -703,6 +985,32 @@ impl BeaconNodeHttpClient {
         epoch: Epoch,
         index: Option<&[u64]>,
     ) -> Result<GenericResponse<Vec<AttesterData>>, Error> {
+        let path = self.validator_duties_attester_path(epoch, index)?;
+
+        self.get(path).await
+    }
+
+    /// `GET validator/duties/attester/{epoch}?index` as SSZ.
+    ///
+    /// ## Note
+    ///
+    /// The `index` query parameter accepts a list of validator indices.
+    pub async fn get_validator_duties_attester_ssz(
+        &self,
+        epoch: Epoch,
+        index: Option<&[u64]>,
+    ) -> Result<Vec<AttesterData>, Error> {
+        let path = self.validator_duties_attester_path(epoch, index)?;
+
+        self.get_ssz(path).await
+    }
+
+    /// Returns the URL for `GET validator/duties/attester/{epoch}?index`.
+    fn validator_duties_attester_path(
+        &self,
+        epoch: Epoch,
+        index: Option<&[u64]>,
+    ) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -713,15 +1021,11 @@ impl BeaconNodeHttpClient {
             .push(&epoch.to_string());
 
         if let Some(index) = index {
-            let string = index
-                .iter()
-                .map(|i| i.to_string())
-                .collect::<Vec<_>>()
-                .join(",");
-            path.query_pairs_mut().append_pair("index", &string);
+            path.query_pairs_mut()
+                .append_pair("index", &join_indices(index));
         }
 
-        self.get(path).await
+        Ok(path)
     }
 
     /// `GET validator/duties/proposer/{epoch}`
@@ -729,6 +1033,23 @@ impl BeaconNodeHttpClient {
         &self,
         epoch: Epoch,
     ) -> Result<GenericResponse<Vec<ProposerData>>, Error> {
+        let path = self.validator_duties_proposer_path(epoch)?;
+
+        self.get(path).await
+    }
+
+    /// `GET validator/duties/proposer/{epoch}` as SSZ.
+    pub async fn get_validator_duties_proposer_ssz(
+        &self,
+        epoch: Epoch,
+    ) -> Result<Vec<ProposerData>, Error> {
+        let path = self.validator_duties_proposer_path(epoch)?;
+
+        self.get_ssz(path).await
+    }
+
+    /// Returns the URL for `GET validator/duties/proposer/{epoch}`.
+    fn validator_duties_proposer_path(&self, epoch: Epoch) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -738,7 +1059,7 @@ impl BeaconNodeHttpClient {
             .push("proposer")
             .push(&epoch.to_string());
 
-        self.get(path).await
+        Ok(path)
     }
 
     /// `GET validator/duties/attester/{epoch}?index`
@@ -752,6 +1073,50 @@ impl BeaconNodeHttpClient {
         randao_reveal: SignatureBytes,
         graffiti: Option<&Graffiti>,
     ) -> Result<GenericResponse<BeaconBlock<T>>, Error> {
+        self.get_validator_blocks_with_options(
+            slot,
+            randao_reveal,
+            graffiti,
+            &BlockProductionOptions::default(),
+        )
+        .await
+    }
+
+    /// `GET validator/blocks/{slot}` with additional control over the operations in the block.
+    pub async fn get_validator_blocks_with_options<T: EthSpec>(
+        &self,
+        slot: Slot,
+        randao_reveal: SignatureBytes,
+        graffiti: Option<&Graffiti>,
+        options: &BlockProductionOptions,
+    ) -> Result<GenericResponse<BeaconBlock<T>>, Error> {
+        let path = self.validator_blocks_path(slot, randao_reveal, graffiti, options)?;
+
+        self.get(path).await
+    }
+
+    /// `GET validator/blocks/{slot}` as SSZ, with additional control over the operations in the
+    /// block.
+    pub async fn get_validator_blocks_ssz<T: EthSpec>(
+        &self,
+        slot: Slot,
+        randao_reveal: SignatureBytes,
+        graffiti: Option<&Graffiti>,
+        options: &BlockProductionOptions,
+    ) -> Result<BeaconBlock<T>, Error> {
+        let path = self.validator_blocks_path(slot, randao_reveal, graffiti, options)?;
+
+        self.get_ssz(path).await
+    }
+
+    /// Returns the URL for `GET validator/blocks/{slot}` with the given query parameters.
+    fn validator_blocks_path(
+        &self,
+        slot: Slot,
+        randao_reveal: SignatureBytes,
+        graffiti: Option<&Graffiti>,
+        options: &BlockProductionOptions,
+    ) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -768,7 +1133,29 @@ impl BeaconNodeHttpClient {
                 .append_pair("graffiti", &graffiti.to_string());
         }
 
-        self.get(path).await
+        if options.skip_randao_verification {
+            path.query_pairs_mut()
+                .append_pair("skip_randao_verification", "true");
+        }
+
+        if let Some(max_attestations) = options.max_attestations {
+            path.query_pairs_mut()
+                .append_pair("max_attestations", &max_attestations.to_string());
+        }
+
+        if !options.exclude_exits.is_empty() {
+            path.query_pairs_mut()
+                .append_pair("exclude_exits", &join_indices(&options.exclude_exits));
+        }
+
+        if !options.exclude_slashings.is_empty() {
+            path.query_pairs_mut().append_pair(
+                "exclude_slashings",
+                &join_indices(&options.exclude_slashings),
+            );
+        }
+
+        Ok(path)
     }
 
     /// `GET validator/attestation_data?slot,committee_index`
@@ -777,6 +1164,28 @@ impl BeaconNodeHttpClient {
         slot: Slot,
         committee_index: CommitteeIndex,
     ) -> Result<GenericResponse<AttestationData>, Error> {
+        let path = self.validator_attestation_data_path(slot, committee_index)?;
+
+        self.get(path).await
+    }
+
+    /// `GET validator/attestation_data?slot,committee_index` as SSZ.
+    pub async fn get_validator_attestation_data_ssz(
+        &self,
+        slot: Slot,
+        committee_index: CommitteeIndex,
+    ) -> Result<AttestationData, Error> {
+        let path = self.validator_attestation_data_path(slot, committee_index)?;
+
+        self.get_ssz(path).await
+    }
+
+    /// Returns the URL for `GET validator/attestation_data?slot,committee_index`.
+    fn validator_attestation_data_path(
+        &self,
+        slot: Slot,
+        committee_index: CommitteeIndex,
+    ) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -788,7 +1197,7 @@ impl BeaconNodeHttpClient {
             .append_pair("slot", &slot.to_string())
             .append_pair("committee_index", &committee_index.to_string());
 
-        self.get(path).await
+        Ok(path)
     }
 
     /// `GET validator/attestation_attestation?slot,attestation_data_root`
@@ -797,6 +1206,30 @@ impl BeaconNodeHttpClient {
         slot: Slot,
         attestation_data_root: Hash256,
     ) -> Result<Option<GenericResponse<Attestation<T>>>, Error> {
+        let path = self.validator_aggregate_attestation_path(slot, attestation_data_root)?;
+
+        self.get_opt(path).await
+    }
+
+    /// `GET validator/aggregate_attestation?slot,attestation_data_root` as SSZ.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_validator_aggregate_attestation_ssz<T: EthSpec>(
+        &self,
+        slot: Slot,
+        attestation_data_root: Hash256,
+    ) -> Result<Option<Attestation<T>>, Error> {
+        let path = self.validator_aggregate_attestation_path(slot, attestation_data_root)?;
+
+        self.get_ssz_opt(path).await
+    }
+
+    /// Returns the URL for `GET validator/aggregate_attestation?slot,attestation_data_root`.
+    fn validator_aggregate_attestation_path(
+        &self,
+        slot: Slot,
+        attestation_data_root: Hash256,
+    ) -> Result<Url, Error> {
         let mut path = self.eth_path()?;
 
         path.path_segments_mut()
@@ -811,7 +1244,7 @@ impl BeaconNodeHttpClient {
                 &format!("{:?}", attestation_data_root),
             );
 
-        self.get_opt(path).await
+        Ok(path)
     }
 
     /// `POST validator/aggregate_and_proofs`
@@ -847,6 +1280,31 @@ impl BeaconNodeHttpClient {
 
         Ok(())
     }
+
+    /// `GET events?topics=head`
+    ///
+    /// The response is held open indefinitely, so a client with a request timeout will see the
+    /// stream end once the timeout elapses.
+    pub async fn get_events_head(&self) -> Result<HeadEventStream, Error> {
+        let mut path = self.eth_path()?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("events");
+
+        path.query_pairs_mut()
+            .append_pair("topics", &EventTopic::Head.to_string());
+
+        let response = self
+            .client
+            .get(path)
+            .header(ACCEPT, "text/event-stream")
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+
+        Ok(HeadEventStream::new(ok_or_error(response).await?))
+    }
 }
 
 /// Returns `Ok(response)` if the response is a `200 OK` response. Otherwise, creates an
@@ -862,3 +1320,12 @@ async fn ok_or_error(response: Response) -> Result<Response, Error> {
         Err(Error::StatusCode(status))
     }
 }
+
+/// Formats a list of validator indices as a comma-separated query parameter.
+fn join_indices(indices: &[u64]) -> String {
+    indices
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -2,7 +2,10 @@
 
 use crate::{
     ok_or_error,
-    types::{BeaconState, Epoch, EthSpec, GenericResponse, ValidatorId},
+    types::{
+        BeaconState, BlockId, Checkpoint, Epoch, EthSpec, GenericResponse, Hash256, Slot,
+        ValidatorId,
+    },
     BeaconNodeHttpClient, Error, StateId, StatusCode,
 };
 use proto_array::core::ProtoArray;
@@ -10,7 +13,10 @@ use reqwest::IntoUrl;
 use serde::{Deserialize, Serialize};
 use ssz::Decode;
 
-pub use eth2_libp2p::{types::SyncState, PeerInfo};
+pub use eth2_libp2p::{
+    types::{SyncPeerStats, SyncState, SyncStats},
+    PeerInfo,
+};
 
 /// Information returned by `peers` and `connected_peers`.
 // TODO: this should be deserializable..
@@ -23,6 +29,13 @@ pub struct Peer<T: EthSpec> {
     pub peer_info: PeerInfo<T>,
 }
 
+/// The log levels of a Lighthouse process, as used by `lighthouse/logging`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// A default level followed by per-module levels (e.g., `info,network=debug`).
+    pub filter: String,
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -74,6 +87,44 @@ pub struct ValidatorInclusionData {
     pub is_previous_epoch_head_attester: bool,
 }
 
+/// A Merkle branch proving a node in the `BeaconState` committed to by a block's `state_root`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateMerkleProof {
+    /// The root of the block whose `state_root` the proof is against.
+    pub block_root: Hash256,
+    /// The slot of the block.
+    pub slot: Slot,
+    /// The `state_root` of the block, i.e., the root of the Merkle tree.
+    pub state_root: Hash256,
+    /// The generalized index of `leaf` in the `BeaconState`.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gindex: u64,
+    /// The node being proven.
+    pub leaf: Hash256,
+    /// The sister nodes of `leaf`, in "bottom-up" order.
+    pub branch: Vec<Hash256>,
+}
+
+/// Proves the `finalized_checkpoint` of the state of a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FinalizedCheckpointProof {
+    pub finalized_checkpoint: Checkpoint,
+    /// Proves `finalized_checkpoint.tree_hash_root()`.
+    pub proof: StateMerkleProof,
+}
+
+/// Proves the balance of a validator in the state of a block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BalanceProof {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub balance: u64,
+    /// Proves the 32-byte chunk of `balances` which contains `balance`, packed alongside the
+    /// balances of up to three other validators.
+    pub proof: StateMerkleProof,
+}
+
 #[cfg(target_os = "linux")]
 use {procinfo::pid, psutil::process::Process};
 
@@ -191,6 +242,48 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/syncing/stats`
+    pub async fn get_lighthouse_syncing_stats(
+        &self,
+    ) -> Result<GenericResponse<Option<SyncStats>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("syncing")
+            .push("stats");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/logging`
+    pub async fn get_lighthouse_logging(&self) -> Result<GenericResponse<LoggingConfig>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/logging`
+    pub async fn post_lighthouse_logging(
+        &self,
+        config: &LoggingConfig,
+    ) -> Result<GenericResponse<LoggingConfig>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("logging");
+
+        self.post_with_response(path, config).await
+    }
+
     /*
      * Note:
      *
@@ -266,4 +359,40 @@ impl BeaconNodeHttpClient {
             .map(|bytes| BeaconState::from_ssz_bytes(&bytes).map_err(Error::InvalidSsz))
             .transpose()
     }
+
+    /// `GET lighthouse/proofs/{block_id}/finalized_checkpoint`
+    pub async fn get_lighthouse_proofs_finalized_checkpoint(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<GenericResponse<FinalizedCheckpointProof>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proofs")
+            .push(&block_id.to_string())
+            .push("finalized_checkpoint");
+
+        self.get_opt(path).await
+    }
+
+    /// `GET lighthouse/proofs/{block_id}/balances/{validator_id}`
+    pub async fn get_lighthouse_proofs_balance(
+        &self,
+        block_id: BlockId,
+        validator_id: &ValidatorId,
+    ) -> Result<Option<GenericResponse<BalanceProof>>, Error> {
+        let mut path = self.server.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("proofs")
+            .push(&block_id.to_string())
+            .push("balances")
+            .push(&validator_id.to_string());
+
+        self.get_opt(path).await
+    }
 }
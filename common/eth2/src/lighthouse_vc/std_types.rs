@@ -0,0 +1,116 @@
+//! Types for the standard keymanager API (`/eth/v1/keystores`), shared by all Eth2 validator
+//! clients.
+
+use account_utils::ZeroizeString;
+use eth2_keystore::Keystore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use types::PublicKeyBytes;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ListKeystoresResponse {
+    pub data: Vec<SingleKeystoreResponse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SingleKeystoreResponse {
+    pub validating_pubkey: PublicKeyBytes,
+    pub derivation_path: Option<String>,
+    #[serde(default)]
+    pub readonly: bool,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportKeystoresRequest {
+    pub keystores: Vec<KeystoreJsonStr>,
+    /// The password for each of `keystores`, in the same order.
+    pub passwords: Vec<ZeroizeString>,
+    /// A JSON-encoded EIP-3076 slashing protection interchange.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slashing_protection: Option<String>,
+}
+
+/// A keystore which is encoded as a JSON string, rather than a JSON object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeystoreJsonStr(pub Keystore);
+
+impl Serialize for KeystoreJsonStr {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let json = self
+            .0
+            .to_json_string()
+            .map_err(|e| serde::ser::Error::custom(format!("{:?}", e)))?;
+        serializer.serialize_str(&json)
+    }
+}
+
+impl<'de> Deserialize<'de> for KeystoreJsonStr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let json = String::deserialize(deserializer)?;
+        Keystore::from_json_str(&json)
+            .map(Self)
+            .map_err(|e| serde::de::Error::custom(format!("invalid keystore: {:?}", e)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportKeystoresResponse {
+    pub data: Vec<Status<ImportKeystoreStatus>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportKeystoreStatus {
+    Imported,
+    Duplicate,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeleteKeystoresRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeleteKeystoresResponse {
+    pub data: Vec<Status<DeleteKeystoreStatus>>,
+    /// A JSON-encoded EIP-3076 slashing protection interchange for the requested keys.
+    pub slashing_protection: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteKeystoreStatus {
+    /// The key was active and has been deleted.
+    Deleted,
+    /// The key was not active, but slashing protection data for it was found.
+    NotActive,
+    /// Neither the key nor any slashing protection data for it was found.
+    NotFound,
+    Error,
+}
+
+/// The outcome of an operation on a single keystore.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Status<T> {
+    pub status: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl<T> Status<T> {
+    pub fn ok(status: T) -> Self {
+        Self {
+            status,
+            message: None,
+        }
+    }
+
+    pub fn error(status: T, message: String) -> Self {
+        Self {
+            status,
+            message: Some(message),
+        }
+    }
+}
@@ -2,7 +2,7 @@ use account_utils::ZeroizeString;
 use eth2_keystore::Keystore;
 use serde::{Deserialize, Serialize};
 
-pub use crate::lighthouse::Health;
+pub use crate::lighthouse::{Health, LoggingConfig};
 pub use crate::types::{GenericResponse, VersionData};
 pub use types::*;
 
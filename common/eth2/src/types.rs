@@ -3,12 +3,16 @@
 
 use eth2_libp2p::{ConnectionDirection, Enr, Multiaddr, PeerConnectionStatus};
 use serde::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
 use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
 
 pub use types::*;
 
+/// The media type of SSZ-encoded request and response bodies.
+pub const SSZ_CONTENT_TYPE: &str = "application/ssz";
+
 /// An API error serializable to JSON.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ErrorMessage {
@@ -147,7 +151,7 @@ impl<'a, T: Serialize> From<&'a T> for GenericResponseRef<'a, T> {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct RootData {
     pub root: Hash256,
 }
@@ -158,7 +162,7 @@ impl From<Hash256> for RootData {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct FinalityCheckpointsData {
     pub previous_justified: Checkpoint,
     pub current_justified: Checkpoint,
@@ -275,7 +279,7 @@ pub struct CommitteesQuery {
     pub index: Option<u64>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct CommitteeData {
     #[serde(with = "serde_utils::quoted_u64")]
     pub index: u64,
@@ -290,13 +294,13 @@ pub struct HeadersQuery {
     pub parent_root: Option<Hash256>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct BlockHeaderAndSignature {
     pub message: BeaconBlockHeader,
     pub signature: SignatureBytes,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct BlockHeaderData {
     pub root: Hash256,
     pub canonical: bool,
@@ -364,12 +368,30 @@ impl<T: FromStr> TryFrom<String> for QueryVec<T> {
     }
 }
 
+/// A boolean query parameter which is `true` if it is present without a value, e.g.
+/// `?skip_randao_verification`.
+#[derive(Clone, Copy, PartialEq, Debug, Deserialize)]
+#[serde(try_from = "String")]
+pub struct QueryFlag(pub bool);
+
+impl TryFrom<String> for QueryFlag {
+    type Error = String;
+
+    fn try_from(string: String) -> Result<Self, Self::Error> {
+        match string.as_str() {
+            "" | "true" => Ok(Self(true)),
+            "false" => Ok(Self(false)),
+            _ => Err("unable to parse flag".to_string()),
+        }
+    }
+}
+
 #[derive(Clone, Deserialize)]
 pub struct ValidatorDutiesQuery {
     pub index: Option<QueryVec<u64>>,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct AttesterData {
     pub pubkey: PublicKeyBytes,
     #[serde(with = "serde_utils::quoted_u64")]
@@ -385,16 +407,34 @@ pub struct AttesterData {
     pub slot: Slot,
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Encode, Decode)]
 pub struct ProposerData {
     pub pubkey: PublicKeyBytes,
     pub slot: Slot,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Deserialize)]
 pub struct ValidatorBlocksQuery {
     pub randao_reveal: SignatureBytes,
     pub graffiti: Option<Graffiti>,
+    pub skip_randao_verification: Option<QueryFlag>,
+    pub max_attestations: Option<usize>,
+    pub exclude_exits: Option<QueryVec<u64>>,
+    pub exclude_slashings: Option<QueryVec<u64>>,
+}
+
+/// Optional parameters for `GET validator/blocks/{slot}` that control which operations are
+/// included in the block.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BlockProductionOptions {
+    /// Do not check that the randao reveal was signed by the proposer.
+    pub skip_randao_verification: bool,
+    /// Include at most this many attestations.
+    pub max_attestations: Option<usize>,
+    /// Do not include voluntary exits for these validator indices.
+    pub exclude_exits: Vec<u64>,
+    /// Do not include slashings for these validator indices.
+    pub exclude_slashings: Vec<u64>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -493,6 +533,45 @@ impl FromStr for PeerDirection {
     }
 }
 
+/// A topic which may be subscribed to at `GET events`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventTopic {
+    Head,
+}
+
+impl FromStr for EventTopic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "head" => Ok(EventTopic::Head),
+            _ => Err(format!("unsupported event topic: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for EventTopic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EventTopic::Head => write!(f, "head"),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+pub struct EventQuery {
+    pub topics: QueryVec<EventTopic>,
+}
+
+/// The data of a `head` event, sent when the head of the canonical chain changes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SseHead {
+    pub slot: Slot,
+    pub block: Hash256,
+    pub state: Hash256,
+    pub epoch_transition: bool,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -504,4 +583,15 @@ mod tests {
             QueryVec(vec![0_u64, 1, 2])
         );
     }
+
+    #[test]
+    fn query_flag() {
+        assert_eq!(QueryFlag::try_from("".to_string()), Ok(QueryFlag(true)));
+        assert_eq!(QueryFlag::try_from("true".to_string()), Ok(QueryFlag(true)));
+        assert_eq!(
+            QueryFlag::try_from("false".to_string()),
+            Ok(QueryFlag(false))
+        );
+        assert!(QueryFlag::try_from("yes".to_string()).is_err());
+    }
 }
@@ -199,6 +199,13 @@ pub fn inc_counter_vec(int_counter_vec: &Result<IntCounterVec>, name: &[&str]) {
     }
 }
 
+/// Increments the `int_counter_vec` with the given `name` by `value`.
+pub fn inc_counter_vec_by(int_counter_vec: &Result<IntCounterVec>, name: &[&str], value: i64) {
+    if let Some(counter) = get_int_counter(int_counter_vec, name) {
+        counter.inc_by(value)
+    }
+}
+
 /// If `histogram_vec.is_ok()`, returns a histogram with the given `name`.
 pub fn get_histogram(histogram_vec: &Result<HistogramVec>, name: &[&str]) -> Option<Histogram> {
     if let Ok(histogram_vec) = histogram_vec {
@@ -0,0 +1,219 @@
+//! Per-module log levels, in the style of `RUST_LOG`, which may be changed at runtime.
+//!
+//! A filter is a comma-separated list of directives. A directive is either a bare level, which
+//! sets the default, or `module=level`, which sets the level for a module and its children
+//! (e.g., `info,network=debug,beacon_chain::fork_choice=trace`).
+
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// A default log level with per-module overrides.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogFilter {
+    default: Level,
+    /// Sorted by descending module path length so the most specific directive matches first.
+    directives: Vec<(String, Level)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            default: Level::Info,
+            directives: vec![],
+        }
+    }
+}
+
+impl LogFilter {
+    /// Returns the level which applies to records from `module`.
+    pub fn level_for(&self, module: &str) -> Level {
+        self.directives
+            .iter()
+            .find(|(prefix, _)| {
+                module.starts_with(prefix.as_str())
+                    && (module.len() == prefix.len() || module[prefix.len()..].starts_with("::"))
+            })
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// Returns `true` if a record at `level` from `module` should be logged.
+    pub fn is_enabled(&self, level: Level, module: &str) -> bool {
+        level.is_at_least(self.level_for(module))
+    }
+}
+
+impl FromStr for LogFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut filter = Self::default();
+
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let mut parts = directive.splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some(level), None) => filter.default = parse_level(level)?,
+                (Some(module), Some(level)) if !module.trim().is_empty() => {
+                    let module = module.trim().to_string();
+                    let level = parse_level(level)?;
+                    filter
+                        .directives
+                        .retain(|(existing, _)| *existing != module);
+                    filter.directives.push((module, level));
+                }
+                _ => return Err(format!("Invalid log directive: {:?}", directive)),
+            }
+        }
+
+        filter
+            .directives
+            .sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+
+        Ok(filter)
+    }
+}
+
+impl fmt::Display for LogFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", level_name(self.default))?;
+        for (module, level) in &self.directives {
+            write!(f, ",{}={}", module, level_name(*level))?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a level using the same names as the `--debug-level` flag.
+fn parse_level(s: &str) -> Result<Level, String> {
+    match s.trim().to_lowercase().as_str() {
+        "trace" => Ok(Level::Trace),
+        "debug" => Ok(Level::Debug),
+        "info" => Ok(Level::Info),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(format!("Unknown log level: {}", unknown)),
+    }
+}
+
+fn level_name(level: Level) -> &'static str {
+    match level {
+        Level::Trace => "trace",
+        Level::Debug => "debug",
+        Level::Info => "info",
+        Level::Warning => "warn",
+        Level::Error => "error",
+        Level::Critical => "crit",
+    }
+}
+
+/// A shared, mutable `LogFilter`.
+///
+/// Cloning the handle does not clone the filter, so a change made through one handle applies to
+/// every logger built with any of its clones.
+///
+/// Uses a `std` lock since `slog` requires drains to be `RefUnwindSafe`.
+#[derive(Debug, Clone, Default)]
+pub struct LogFilterHandle(Arc<RwLock<LogFilter>>);
+
+impl LogFilterHandle {
+    pub fn new(filter: LogFilter) -> Self {
+        Self(Arc::new(RwLock::new(filter)))
+    }
+
+    /// Returns a copy of the current filter.
+    pub fn get(&self) -> LogFilter {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the current filter.
+    pub fn set(&self, filter: LogFilter) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = filter;
+    }
+
+    pub fn is_enabled(&self, level: Level, module: &str) -> bool {
+        self.0
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .is_enabled(level, module)
+    }
+}
+
+/// A `Drain` which only passes on records permitted by a `LogFilterHandle`.
+pub struct RuntimeLevelFilter<D> {
+    drain: D,
+    filter: LogFilterHandle,
+}
+
+impl<D> RuntimeLevelFilter<D> {
+    pub fn new(drain: D, filter: LogFilterHandle) -> Self {
+        Self { drain, filter }
+    }
+}
+
+impl<D: Drain> Drain for RuntimeLevelFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if self.filter.is_enabled(record.level(), record.module()) {
+            self.drain.log(record, values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        let filter: LogFilter = "network=debug, beacon_chain=trace,warn".parse().unwrap();
+        assert_eq!(filter.to_string(), "warn,beacon_chain=trace,network=debug");
+        assert_eq!(filter.to_string().parse::<LogFilter>().unwrap(), filter);
+
+        assert_eq!("".parse::<LogFilter>().unwrap(), LogFilter::default());
+        assert!("network=loud".parse::<LogFilter>().is_err());
+        assert!("=debug".parse::<LogFilter>().is_err());
+    }
+
+    #[test]
+    fn most_specific_directive_wins() {
+        let filter: LogFilter = "info,network=debug,network::service=crit".parse().unwrap();
+
+        assert_eq!(filter.level_for("beacon_chain"), Level::Info);
+        assert_eq!(filter.level_for("network"), Level::Debug);
+        assert_eq!(filter.level_for("network::router"), Level::Debug);
+        assert_eq!(filter.level_for("network::service"), Level::Critical);
+        assert_eq!(filter.level_for("network::service::inner"), Level::Critical);
+        // Only whole path segments match.
+        assert_eq!(filter.level_for("network_utils"), Level::Info);
+
+        assert!(filter.is_enabled(Level::Debug, "network::router"));
+        assert!(!filter.is_enabled(Level::Trace, "network::router"));
+        assert!(!filter.is_enabled(Level::Debug, "beacon_chain"));
+    }
+
+    #[test]
+    fn later_directive_replaces_earlier() {
+        let filter: LogFilter = "network=debug,network=error".parse().unwrap();
+        assert_eq!(filter.level_for("network"), Level::Error);
+        assert_eq!(filter.to_string(), "info,network=error");
+    }
+
+    #[test]
+    fn handle_is_shared() {
+        let handle = LogFilterHandle::default();
+        let clone = handle.clone();
+
+        clone.set("debug".parse().unwrap());
+        assert!(handle.is_enabled(Level::Debug, "network"));
+        assert_eq!(handle.get(), clone.get());
+    }
+}
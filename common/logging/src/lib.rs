@@ -1,6 +1,12 @@
 #[macro_use]
 extern crate lazy_static;
 
+mod filter;
+mod rotation;
+
+pub use filter::{LogFilter, LogFilterHandle, RuntimeLevelFilter};
+pub use rotation::{RotatingFile, RotationConfig};
+
 use lighthouse_metrics::{
     inc_counter, try_create_int_counter, IntCounter, Result as MetricsResult,
 };
@@ -0,0 +1,302 @@
+//! A log file which is rotated when it grows too large or too old.
+//!
+//! Rotated files are named `<path>.1`, `<path>.2`, ... (with a `.gz` suffix when compressed),
+//! where `<path>.1` is the most recent. Only the newest `max_files` are kept.
+
+use flate2::{write::GzEncoder, Compression};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Determines when a `RotatingFile` is rotated and how many old files are kept.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotationConfig {
+    /// Rotate once the file reaches this many bytes.
+    pub max_size: Option<u64>,
+    /// Rotate once the file has been written to for this long.
+    pub max_age: Option<Duration>,
+    /// The number of rotated files to keep.
+    pub max_files: usize,
+    /// Gzip rotated files.
+    pub compress: bool,
+}
+
+impl Default for RotationConfig {
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            max_age: None,
+            max_files: 5,
+            compress: false,
+        }
+    }
+}
+
+impl RotationConfig {
+    /// Returns `true` if the file will ever be rotated.
+    pub fn is_enabled(&self) -> bool {
+        self.max_size.is_some() || self.max_age.is_some()
+    }
+}
+
+pub struct RotatingFile {
+    path: PathBuf,
+    config: RotationConfig,
+    file: File,
+    size: u64,
+    opened_at: Instant,
+    /// Gzips the most recently rotated file, off the write path.
+    compression: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl RotatingFile {
+    /// Opens a new, empty log file at `path`, first rotating any existing file.
+    pub fn open(path: PathBuf, config: RotationConfig) -> io::Result<Self> {
+        let compression = if path.exists() {
+            rotate_files(&path, &config)?
+        } else {
+            None
+        };
+
+        Ok(Self {
+            file: create(&path)?,
+            path,
+            config,
+            size: 0,
+            opened_at: Instant::now(),
+            compression,
+        })
+    }
+
+    fn should_rotate(&self) -> bool {
+        self.size > 0
+            && (self.config.max_size.map_or(false, |max| self.size >= max)
+                || self
+                    .config
+                    .max_age
+                    .map_or(false, |max| self.opened_at.elapsed() >= max))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        // The previous file must be compressed before the rotated files are shifted along.
+        self.wait_for_compression();
+        self.compression = rotate_files(&self.path, &self.config)?;
+        self.file = create(&self.path)?;
+        self.size = 0;
+        self.opened_at = Instant::now();
+        Ok(())
+    }
+
+    /// Blocks until any in-progress compression of a rotated file has finished.
+    fn wait_for_compression(&mut self) {
+        if let Some(handle) = self.compression.take() {
+            match handle.join() {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => eprintln!("Unable to compress log file {:?}: {}", self.path, e),
+                Err(_) => eprintln!("Log compression thread for {:?} panicked", self.path),
+            }
+        }
+    }
+}
+
+impl Drop for RotatingFile {
+    fn drop(&mut self) {
+        self.wait_for_compression();
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.file.write(buf)?;
+        self.size += n as u64;
+
+        // Only rotate at the end of a line so that records are never split across files.
+        if buf[..n].ends_with(b"\n") && self.should_rotate() {
+            // A failure to rotate should not stop logging, so keep writing to the current file.
+            if let Err(e) = self.rotate() {
+                eprintln!("Unable to rotate log file {:?}: {}", self.path, e);
+                self.opened_at = Instant::now();
+            }
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn create(path: &Path) -> io::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)
+}
+
+/// Returns the path of the `n`th most recent rotated file.
+fn rotated_path(path: &Path, n: usize, compress: bool) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    if compress {
+        name.push(".gz");
+    }
+    name.into()
+}
+
+/// Moves the file at `path` to `<path>.1`, shifting existing rotated files along and deleting
+/// any beyond `config.max_files`.
+///
+/// When compressing, `<path>.1` is gzipped to `<path>.1.gz` on a new thread whose handle is
+/// returned, so that a large file does not block logging.
+fn rotate_files(
+    path: &Path,
+    config: &RotationConfig,
+) -> io::Result<Option<JoinHandle<io::Result<()>>>> {
+    if config.max_files == 0 {
+        return fs::remove_file(path).map(|()| None);
+    }
+
+    let oldest = rotated_path(path, config.max_files, config.compress);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+
+    for n in (1..config.max_files).rev() {
+        let from = rotated_path(path, n, config.compress);
+        if from.exists() {
+            fs::rename(&from, rotated_path(path, n + 1, config.compress))?;
+        }
+    }
+
+    let uncompressed = rotated_path(path, 1, false);
+    fs::rename(path, &uncompressed)?;
+
+    if config.compress {
+        Ok(Some(thread::spawn(move || compress(&uncompressed))))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Gzips the file at `path` to `<path>.gz` and removes the original.
+fn compress(path: &Path) -> io::Result<()> {
+    let mut compressed = path.as_os_str().to_owned();
+    compressed.push(".gz");
+
+    let mut encoder = GzEncoder::new(File::create(&compressed)?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use tempfile::tempdir;
+
+    fn config(max_size: u64, max_files: usize, compress: bool) -> RotationConfig {
+        RotationConfig {
+            max_size: Some(max_size),
+            max_age: None,
+            max_files,
+            compress,
+        }
+    }
+
+    #[test]
+    fn rotates_by_size() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        let mut file = RotatingFile::open(path.clone(), config(6, 2, false)).unwrap();
+
+        for line in &["first\n", "second\n", "third\n", "fourth\n"] {
+            // Partial lines never trigger a rotation.
+            file.write_all(&line.as_bytes()[..3]).unwrap();
+            file.write_all(&line.as_bytes()[3..]).unwrap();
+        }
+        file.write_all(b"x\n").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "x\n");
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 1, false)).unwrap(),
+            "fourth\n"
+        );
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 2, false)).unwrap(),
+            "third\n"
+        );
+        assert!(!rotated_path(&path, 3, false).exists());
+    }
+
+    #[test]
+    fn rotates_existing_file_on_open() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        fs::write(&path, "previous run\n").unwrap();
+
+        let mut file = RotatingFile::open(path.clone(), config(1024, 1, true)).unwrap();
+        file.write_all(b"this run\n").unwrap();
+        file.wait_for_compression();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "this run\n");
+        assert!(!rotated_path(&path, 1, false).exists());
+
+        let mut decompressed = String::new();
+        GzDecoder::new(File::open(rotated_path(&path, 1, true)).unwrap())
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, "previous run\n");
+    }
+
+    #[test]
+    fn compresses_in_background() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        let mut file = RotatingFile::open(path.clone(), config(6, 2, true)).unwrap();
+
+        for line in &["first\n", "second\n", "third\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        drop(file);
+
+        for (n, expected) in &[(1, "third\n"), (2, "second\n")] {
+            assert!(!rotated_path(&path, *n, false).exists());
+            let mut decompressed = String::new();
+            GzDecoder::new(File::open(rotated_path(&path, *n, true)).unwrap())
+                .read_to_string(&mut decompressed)
+                .unwrap();
+            assert_eq!(&decompressed, expected);
+        }
+        assert!(!rotated_path(&path, 3, true).exists());
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        let mut file = RotatingFile::open(
+            path.clone(),
+            RotationConfig {
+                max_age: Some(Duration::from_secs(0)),
+                ..RotationConfig::default()
+            },
+        )
+        .unwrap();
+
+        file.write_all(b"first\n").unwrap();
+        file.write_all(b"second\n").unwrap();
+
+        assert!(fs::read_to_string(&path).unwrap().is_empty());
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 2, false)).unwrap(),
+            "first\n"
+        );
+    }
+}
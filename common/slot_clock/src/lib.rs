@@ -53,6 +53,16 @@ pub trait SlotClock: Send + Sync + Sized {
     /// Returns the duration from now until `slot`.
     fn duration_to_slot(&self, slot: Slot) -> Option<Duration>;
 
+    /// Returns the duration between UNIX epoch and the start of `slot`.
+    fn start_of(&self, slot: Slot) -> Option<Duration>;
+
+    /// Returns the duration between the start of `slot` and now.
+    ///
+    /// Returns `None` if now is prior to the start of `slot`.
+    fn duration_since_start_of(&self, slot: Slot) -> Option<Duration> {
+        self.now_duration()?.checked_sub(self.start_of(slot)?)
+    }
+
     /// Returns the duration until the next slot.
     fn duration_to_next_slot(&self) -> Option<Duration>;
 
@@ -50,6 +50,20 @@ impl TaskExecutor {
         }
     }
 
+    /// Clones the task executor, replacing the exit future with `exit`.
+    ///
+    /// Tasks spawned on the returned executor are shut down when `exit` is fired, rather than the
+    /// exit future of `self`. This allows a subset of tasks (e.g., a single node in a simulation)
+    /// to be shut down without affecting the others.
+    pub fn clone_with_exit(&self, exit: exit_future::Exit) -> Self {
+        TaskExecutor {
+            handle: self.handle.clone(),
+            exit,
+            signal_tx: self.signal_tx.clone(),
+            log: self.log.clone(),
+        }
+    }
+
     /// Spawn a future on the tokio runtime wrapped in an `exit_future::Exit`. The task is canceled
     /// when the corresponding exit_future `Signal` is fired/dropped.
     ///
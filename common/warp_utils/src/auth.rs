@@ -0,0 +1,199 @@
+//! Bearer token authorization with separate read and write scopes.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use warp::{filters::BoxedFilter, Filter};
+
+/// The permission required to make a request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope {
+    /// Requests which do not modify the state of the server (e.g., `GET`).
+    Read,
+    /// Requests which may modify the state of the server (e.g., `POST`).
+    Write,
+}
+
+/// Paths to the files containing the bearer tokens of each scope.
+///
+/// The files are read when the server starts.
+#[derive(PartialEq, Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiTokenFiles {
+    pub read: Option<PathBuf>,
+    pub write: Option<PathBuf>,
+}
+
+/// The bearer tokens accepted by a HTTP server.
+///
+/// - If a read token is set, reads require either the read or the write token.
+/// - If a write token is set, writes require the write token.
+/// - If only a read token is set, writes require the read token.
+/// - If neither is set, no request requires a token.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ApiTokens {
+    pub read: Option<String>,
+    pub write: Option<String>,
+}
+
+impl ApiTokens {
+    /// Reads the tokens from the files in `files`, ignoring surrounding whitespace.
+    pub fn from_files(files: &ApiTokenFiles) -> Result<Self, String> {
+        Ok(Self {
+            read: files.read.as_deref().map(read_token).transpose()?,
+            write: files.write.as_deref().map(read_token).transpose()?,
+        })
+    }
+
+    /// Returns `true` if any request requires a token.
+    pub fn is_enabled(&self) -> bool {
+        self.read.is_some() || self.write.is_some()
+    }
+
+    /// Returns `true` if a request presenting `token` may access `scope`.
+    pub fn permits(&self, scope: Scope, token: Option<&str>) -> bool {
+        let accepted = match (scope, &self.read, &self.write) {
+            (Scope::Read, None, _) | (Scope::Write, None, None) => return true,
+            (Scope::Read, Some(read), write) => vec![Some(read), write.as_ref()],
+            (Scope::Write, _, Some(write)) => vec![Some(write)],
+            (Scope::Write, Some(read), None) => vec![Some(read)],
+        };
+
+        token.map_or(false, |token| {
+            accepted
+                .into_iter()
+                .flatten()
+                .any(|expected| constant_time_eq(expected.as_bytes(), token.as_bytes()))
+        })
+    }
+}
+
+/// Returns a filter which rejects requests that do not have an `Authorization: Bearer` header
+/// with a token permitting `scope`.
+pub fn bearer_auth(tokens: Arc<ApiTokens>, scope: Scope) -> BoxedFilter<()> {
+    warp::any()
+        .map(move || tokens.clone())
+        .and(warp::header::optional::<String>("authorization"))
+        .and_then(
+            move |tokens: Arc<ApiTokens>, header: Option<String>| async move {
+                let token = header.as_deref().and_then(|header| {
+                    if header.starts_with("Bearer ") {
+                        Some(&header["Bearer ".len()..])
+                    } else {
+                        None
+                    }
+                });
+
+                if tokens.permits(scope, token) {
+                    Ok(())
+                } else if token.is_none() {
+                    Err(crate::reject::invalid_auth(
+                        "missing bearer token".to_string(),
+                    ))
+                } else {
+                    Err(crate::reject::invalid_auth(format!(
+                        "bearer token does not permit {:?} access",
+                        scope
+                    )))
+                }
+            },
+        )
+        .untuple_one()
+        .boxed()
+}
+
+fn read_token(path: &Path) -> Result<String, String> {
+    let token = fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read API token from {:?}: {}", path, e))?
+        .trim()
+        .to_string();
+
+    if token.is_empty() {
+        Err(format!("API token file {:?} is empty", path))
+    } else {
+        Ok(token)
+    }
+}
+
+/// Compares `a` and `b` in time which depends only on their lengths.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(read: Option<&str>, write: Option<&str>) -> ApiTokens {
+        ApiTokens {
+            read: read.map(Into::into),
+            write: write.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn no_tokens() {
+        let tokens = tokens(None, None);
+
+        assert!(tokens.permits(Scope::Read, None));
+        assert!(tokens.permits(Scope::Write, None));
+    }
+
+    #[test]
+    fn read_and_write_tokens() {
+        let tokens = tokens(Some("read"), Some("write"));
+
+        assert!(!tokens.permits(Scope::Read, None));
+        assert!(tokens.permits(Scope::Read, Some("read")));
+        assert!(tokens.permits(Scope::Read, Some("write")));
+        assert!(!tokens.permits(Scope::Read, Some("other")));
+
+        assert!(!tokens.permits(Scope::Write, None));
+        assert!(!tokens.permits(Scope::Write, Some("read")));
+        assert!(tokens.permits(Scope::Write, Some("write")));
+    }
+
+    #[test]
+    fn write_token_only() {
+        let tokens = tokens(None, Some("write"));
+
+        assert!(tokens.permits(Scope::Read, None));
+        assert!(!tokens.permits(Scope::Write, None));
+        assert!(tokens.permits(Scope::Write, Some("write")));
+    }
+
+    #[test]
+    fn read_token_only() {
+        let tokens = tokens(Some("read"), None);
+
+        assert!(!tokens.permits(Scope::Read, None));
+        assert!(tokens.permits(Scope::Read, Some("read")));
+        assert!(!tokens.permits(Scope::Write, None));
+        assert!(tokens.permits(Scope::Write, Some("read")));
+    }
+
+    #[tokio::test]
+    async fn filter() {
+        let filter = bearer_auth(Arc::new(tokens(Some("read"), Some("write"))), Scope::Write);
+
+        assert!(
+            warp::test::request()
+                .header("Authorization", "Bearer write")
+                .matches(&filter)
+                .await
+        );
+        assert!(
+            !warp::test::request()
+                .header("Authorization", "Bearer read")
+                .matches(&filter)
+                .await
+        );
+        assert!(
+            !warp::test::request()
+                .header("Authorization", "write")
+                .matches(&filter)
+                .await
+        );
+        assert!(!warp::test::request().matches(&filter).await);
+    }
+}
@@ -0,0 +1,149 @@
+//! Allows HTTP requests and responses to be SSZ encoded as an alternative to JSON.
+//!
+//! The response encoding is negotiated with the `Accept` header and the request encoding is
+//! determined by the `Content-Type` header. JSON is used unless SSZ is explicitly requested.
+
+use crate::reject::custom_bad_request;
+use bytes::Bytes;
+use eth2::types::GenericResponseRef;
+pub use eth2::types::SSZ_CONTENT_TYPE;
+use serde::{de::DeserializeOwned, Serialize};
+use ssz::{Decode, Encode};
+use warp::{Filter, Rejection, Reply};
+
+pub const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// The encoding of a request or response body.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Json,
+    Ssz,
+}
+
+impl Encoding {
+    /// Returns the encoding preferred by an `Accept` header, taking quality values into account.
+    ///
+    /// SSZ is only chosen if it is preferred over both JSON and wildcards.
+    pub fn from_accept(accept: &str) -> Self {
+        let mut best: Option<(Self, f32)> = None;
+
+        for media_range in accept.split(',') {
+            let mut params = media_range.split(';');
+            let media_type = params.next().unwrap_or("").trim();
+            let quality = params
+                .filter_map(|param| {
+                    let param = param.trim();
+                    if param.starts_with("q=") {
+                        param[2..].parse::<f32>().ok()
+                    } else {
+                        None
+                    }
+                })
+                .next()
+                .unwrap_or(1.0);
+
+            let encoding = match media_type {
+                SSZ_CONTENT_TYPE => Self::Ssz,
+                JSON_CONTENT_TYPE | "application/*" | "*/*" => Self::Json,
+                _ => continue,
+            };
+
+            if quality > 0.0 && best.map_or(true, |(_, best_quality)| quality > best_quality) {
+                best = Some((encoding, quality));
+            }
+        }
+
+        best.map_or(Self::Json, |(encoding, _)| encoding)
+    }
+
+    /// Returns `true` if a `Content-Type` header indicates an SSZ body.
+    pub fn is_ssz_content_type(content_type: &str) -> bool {
+        content_type
+            .split(';')
+            .next()
+            .map_or(false, |media_type| media_type.trim() == SSZ_CONTENT_TYPE)
+    }
+}
+
+/// Extracts the response `Encoding` requested by the `Accept` header.
+pub fn accept() -> impl Filter<Extract = (Encoding,), Error = Rejection> + Copy {
+    warp::header::optional::<String>("accept").map(|accept: Option<String>| {
+        accept.map_or(Encoding::Json, |accept| Encoding::from_accept(&accept))
+    })
+}
+
+/// Extracts a request body which is SSZ encoded if the `Content-Type` is `application/ssz`, or
+/// JSON encoded otherwise.
+pub fn body<T>() -> impl Filter<Extract = (T,), Error = Rejection> + Copy
+where
+    T: Decode + DeserializeOwned + Send,
+{
+    warp::header::optional::<String>("content-type")
+        .and(warp::body::bytes())
+        .and_then(|content_type: Option<String>, bytes: Bytes| async move {
+            if content_type.map_or(false, |content_type| {
+                Encoding::is_ssz_content_type(&content_type)
+            }) {
+                T::from_ssz_bytes(&bytes)
+                    .map_err(|e| custom_bad_request(format!("body is not valid SSZ: {:?}", e)))
+            } else {
+                serde_json::from_slice(&bytes)
+                    .map_err(|e| custom_bad_request(format!("body is not valid JSON: {}", e)))
+            }
+        })
+}
+
+/// Encodes `data` as SSZ, or as JSON wrapped in a `GenericResponse`.
+pub fn response<T: Serialize + Encode>(encoding: Encoding, data: &T) -> warp::reply::Response {
+    match encoding {
+        Encoding::Json => warp::reply::json(&GenericResponseRef::from(data)).into_response(),
+        Encoding::Ssz => ssz_response(data.as_ssz_bytes()),
+    }
+}
+
+/// Returns a response containing the SSZ `bytes`.
+pub fn ssz_response(bytes: Vec<u8>) -> warp::reply::Response {
+    warp::reply::with_header(bytes, "Content-Type", SSZ_CONTENT_TYPE).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_defaults_to_json() {
+        assert_eq!(Encoding::from_accept(""), Encoding::Json);
+        assert_eq!(Encoding::from_accept("*/*"), Encoding::Json);
+        assert_eq!(Encoding::from_accept("text/html"), Encoding::Json);
+        assert_eq!(
+            Encoding::from_accept("application/json, application/ssz"),
+            Encoding::Json
+        );
+    }
+
+    #[test]
+    fn accept_prefers_ssz() {
+        assert_eq!(Encoding::from_accept("application/ssz"), Encoding::Ssz);
+        assert_eq!(
+            Encoding::from_accept("application/ssz, application/json"),
+            Encoding::Ssz
+        );
+        assert_eq!(
+            Encoding::from_accept("application/json;q=0.5, application/ssz;q=0.9"),
+            Encoding::Ssz
+        );
+        assert_eq!(
+            Encoding::from_accept("application/ssz;q=0, */*"),
+            Encoding::Json
+        );
+    }
+
+    #[test]
+    fn ssz_content_type() {
+        assert!(Encoding::is_ssz_content_type("application/ssz"));
+        assert!(Encoding::is_ssz_content_type(
+            "application/ssz; charset=utf-8"
+        ));
+        assert!(!Encoding::is_ssz_content_type("application/json"));
+    }
+}
@@ -1,4 +1,6 @@
+use crate::encoding::{self, Encoding};
 use serde::Serialize;
+use ssz::Encode;
 
 /// Execute some task in a tokio "blocking thread". These threads are ideal for long-running
 /// (blocking) tasks since they don't jam up the core executor.
@@ -19,3 +21,20 @@ where
         .await
         .map(|resp| warp::reply::json(&resp))
 }
+
+/// A convenience wrapper around `blocking_task` for use with responses that may be encoded as
+/// either JSON or SSZ.
+///
+/// JSON responses are wrapped in a `GenericResponse`.
+pub async fn blocking_encoded_task<F, T>(
+    encoding: Encoding,
+    func: F,
+) -> Result<warp::reply::Response, warp::Rejection>
+where
+    F: Fn() -> Result<T, warp::Rejection>,
+    T: Serialize + Encode,
+{
+    blocking_task(func)
+        .await
+        .map(|resp| encoding::response(encoding, &resp))
+}
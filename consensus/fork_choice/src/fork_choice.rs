@@ -1,9 +1,10 @@
 use std::marker::PhantomData;
+use std::time::Duration;
 
 use proto_array::{Block as ProtoBlock, ProtoArrayForkChoice};
 use ssz_derive::{Decode, Encode};
 use types::{
-    BeaconBlock, BeaconState, BeaconStateError, Checkpoint, Epoch, EthSpec, Hash256,
+    BeaconBlock, BeaconState, BeaconStateError, ChainSpec, Checkpoint, Epoch, EthSpec, Hash256,
     IndexedAttestation, RelativeEpoch, ShufflingId, Slot,
 };
 
@@ -15,6 +16,12 @@ use std::cmp::Ordering;
 /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#configuration
 pub const SAFE_SLOTS_TO_UPDATE_JUSTIFIED: u64 = 8;
 
+/// The number of intervals in a slot. A block is considered timely (and eligible for the
+/// proposer boost) if it arrives during the first interval of its slot.
+///
+/// https://github.com/ethereum/consensus-specs/blob/v1.1.0/specs/phase0/fork-choice.md#configuration
+pub const INTERVALS_PER_SLOT: u32 = 3;
+
 #[derive(Debug)]
 pub enum Error<T> {
     InvalidAttestation(InvalidAttestation),
@@ -210,6 +217,7 @@ fn dequeue_attestations(
 ///
 /// - Management of the justified state and caching of balances.
 /// - Queuing of attestations from the current slot.
+/// - Proposer boosting of timely blocks from the current slot.
 pub struct ForkChoice<T, E> {
     /// Storage for `ForkChoice`, modelled off the spec `Store` object.
     fc_store: T,
@@ -217,6 +225,10 @@ pub struct ForkChoice<T, E> {
     proto_array: ProtoArrayForkChoice,
     /// Attestations that arrived at the current slot and must be queued for later processing.
     queued_attestations: Vec<QueuedAttestation>,
+    /// The root of the timely block from the current slot, or the zero hash if there is none.
+    ///
+    /// Not persisted, since it is reset whenever the slot changes.
+    proposer_boost_root: Hash256,
     _phantom: PhantomData<E>,
 }
 
@@ -267,6 +279,7 @@ where
             fc_store,
             proto_array,
             queued_attestations: vec![],
+            proposer_boost_root: Hash256::zero(),
             _phantom: PhantomData,
         })
     }
@@ -284,6 +297,7 @@ where
             fc_store,
             proto_array,
             queued_attestations,
+            proposer_boost_root: Hash256::zero(),
             _phantom: PhantomData,
         }
     }
@@ -341,17 +355,27 @@ where
     /// Is equivalent to:
     ///
     /// https://github.com/ethereum/eth2.0-specs/blob/v0.12.1/specs/phase0/fork-choice.md#get_head
-    pub fn get_head(&mut self, current_slot: Slot) -> Result<Hash256, Error<T::Error>> {
+    ///
+    /// The proposer boost is applied as per:
+    ///
+    /// https://github.com/ethereum/consensus-specs/blob/v1.1.0/specs/phase0/fork-choice.md#get_latest_attesting_balance
+    pub fn get_head(
+        &mut self,
+        current_slot: Slot,
+        spec: &ChainSpec,
+    ) -> Result<Hash256, Error<T::Error>> {
         self.update_time(current_slot)?;
 
         let store = &mut self.fc_store;
 
         self.proto_array
-            .find_head(
+            .find_head::<E>(
                 store.justified_checkpoint().epoch,
                 store.justified_checkpoint().root,
                 store.finalized_checkpoint().epoch,
                 store.justified_balances(),
+                self.proposer_boost_root,
+                spec,
             )
             .map_err(Into::into)
     }
@@ -410,6 +434,7 @@ where
     /// Add `block` to the fork choice DAG.
     ///
     /// - `block_root` is the root of `block.
+    /// - `block_delay` is the time between the start of `block.slot` and the arrival of `block`.
     /// - The root of `state` matches `block.state_root`.
     ///
     /// ## Specification
@@ -430,7 +455,9 @@ where
         current_slot: Slot,
         block: &BeaconBlock<E>,
         block_root: Hash256,
+        block_delay: Duration,
         state: &BeaconState<E>,
+        spec: &ChainSpec,
     ) -> Result<(), Error<T::Error>> {
         let current_slot = self.update_time(current_slot)?;
 
@@ -533,6 +560,13 @@ where
                 .map_err(Error::BeaconStateError)?
         };
 
+        // Add proposer score boost if the block is timely.
+        let is_before_attesting_interval =
+            block_delay < Duration::from_millis(spec.milliseconds_per_slot) / INTERVALS_PER_SLOT;
+        if current_slot == block.slot && is_before_attesting_interval {
+            self.proposer_boost_root = block_root;
+        }
+
         self.fc_store
             .on_verified_block(block, block_root, state)
             .map_err(Error::AfterBlockFailed)?;
@@ -725,7 +759,10 @@ where
             let previous_slot = self.fc_store.get_current_slot();
             // Note: we are relying upon `on_tick` to update `fc_store.time` to ensure we don't
             // get stuck in a loop.
-            on_tick(&mut self.fc_store, previous_slot + 1)?
+            on_tick(&mut self.fc_store, previous_slot + 1)?;
+
+            // The proposer boost only applies during the slot of the boosted block.
+            self.proposer_boost_root = Hash256::zero();
         }
 
         // Process any attestations that might now be eligible.
@@ -790,6 +827,12 @@ where
         self.proto_array.latest_message(validator_index)
     }
 
+    /// Returns the root of the block which currently receives the proposer boost, or the zero
+    /// hash if there is none.
+    pub fn proposer_boost_root(&self) -> Hash256 {
+        self.proposer_boost_root
+    }
+
     /// Returns a reference to the underlying fork choice DAG.
     pub fn proto_array(&self) -> &ProtoArrayForkChoice {
         &self.proto_array
@@ -827,6 +870,7 @@ where
             fc_store,
             proto_array,
             queued_attestations: persisted.queued_attestations,
+            proposer_boost_root: Hash256::zero(),
             _phantom: PhantomData,
         })
     }
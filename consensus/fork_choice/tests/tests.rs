@@ -6,11 +6,12 @@ use beacon_chain::{
     StateSkipConfig,
 };
 use fork_choice::{
-    ForkChoiceStore, InvalidAttestation, InvalidBlock, QueuedAttestation,
+    ForkChoiceStore, InvalidAttestation, InvalidBlock, QueuedAttestation, INTERVALS_PER_SLOT,
     SAFE_SLOTS_TO_UPDATE_JUSTIFIED,
 };
 use std::fmt;
 use std::sync::Mutex;
+use std::time::Duration;
 use store::{MemoryStore, StoreConfig};
 use types::{
     test_utils::{generate_deterministic_keypair, generate_deterministic_keypairs},
@@ -266,11 +267,71 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                Duration::from_secs(0),
+                &state,
+                &self.harness.chain.spec,
+            )
             .unwrap();
         self
     }
 
+    /// Applies a block directly to fork choice, as if it arrived `block_delay` after the start of
+    /// its slot.
+    ///
+    /// Asserts the block was applied successfully and that it received the proposer boost if
+    /// and only if `expect_boost` is `true`.
+    pub fn apply_delayed_block_directly_to_fork_choice(
+        self,
+        block_delay: Duration,
+        expect_boost: bool,
+    ) -> Self {
+        let state = self
+            .harness
+            .chain
+            .state_at_slot(
+                self.harness.get_current_slot() - 1,
+                StateSkipConfig::WithStateRoots,
+            )
+            .unwrap();
+        let slot = self.harness.get_current_slot();
+        let (block, state) = self.harness.make_block(state, slot);
+        let block_root = block.canonical_root();
+        let mut fork_choice = self.harness.chain.fork_choice.write();
+        fork_choice
+            .on_block(
+                slot,
+                &block.message,
+                block_root,
+                block_delay,
+                &state,
+                &self.harness.chain.spec,
+            )
+            .unwrap();
+        assert_eq!(
+            fork_choice.proposer_boost_root() == block_root,
+            expect_boost,
+            "unexpected proposer boost for block with delay {:?}",
+            block_delay
+        );
+        drop(fork_choice);
+        self
+    }
+
+    /// Asserts that no block is receiving the proposer boost at the current slot.
+    pub fn assert_no_proposer_boost(self) -> Self {
+        let mut fork_choice = self.harness.chain.fork_choice.write();
+        fork_choice
+            .update_time(self.harness.chain.slot().unwrap())
+            .unwrap();
+        assert_eq!(fork_choice.proposer_boost_root(), Hash256::zero());
+        drop(fork_choice);
+        self
+    }
+
     /// Applies a block directly to fork choice, bypassing the beacon chain.
     ///
     /// Asserts that an error occurred and allows inspecting it via `comparison_func`.
@@ -300,7 +361,14 @@ impl ForkChoiceTest {
             .chain
             .fork_choice
             .write()
-            .on_block(current_slot, &block.message, block.canonical_root(), &state)
+            .on_block(
+                current_slot,
+                &block.message,
+                block.canonical_root(),
+                Duration::from_secs(0),
+                &state,
+                &self.harness.chain.spec,
+            )
             .err()
             .expect("on_block did not return an error");
         comparison_func(err);
@@ -701,6 +769,36 @@ fn invalid_block_finalized_descendant() {
         );
 }
 
+/// A block which arrives during the first interval of its slot receives the proposer boost.
+#[test]
+fn proposer_boost_timely_block() {
+    ForkChoiceTest::new()
+        .apply_blocks(2)
+        .apply_delayed_block_directly_to_fork_choice(Duration::from_secs(1), true);
+}
+
+/// A block which arrives after the first interval of its slot does not receive the proposer
+/// boost.
+#[test]
+fn proposer_boost_late_block() {
+    let spec = E::default_spec();
+    let late_delay = Duration::from_millis(spec.milliseconds_per_slot) / INTERVALS_PER_SLOT;
+
+    ForkChoiceTest::new()
+        .apply_blocks(2)
+        .apply_delayed_block_directly_to_fork_choice(late_delay, false);
+}
+
+/// The proposer boost is removed once the slot of the boosted block has passed.
+#[test]
+fn proposer_boost_reset_on_next_slot() {
+    ForkChoiceTest::new()
+        .apply_blocks(2)
+        .apply_delayed_block_directly_to_fork_choice(Duration::from_secs(0), true)
+        .skip_slot()
+        .assert_no_proposer_boost();
+}
+
 macro_rules! assert_invalid_attestation {
     ($err: tt, $($error: pat) |+ $( if $guard: expr )?) => {
         assert!(
@@ -981,6 +1079,7 @@ fn weak_subjectivity_fail_on_startup() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(Checkpoint { epoch, root }),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     ForkChoiceTest::new_with_chain_config(chain_config);
@@ -994,6 +1093,7 @@ fn weak_subjectivity_pass_on_startup() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(Checkpoint { epoch, root }),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     ForkChoiceTest::new_with_chain_config(chain_config)
@@ -1019,6 +1119,7 @@ fn weak_subjectivity_check_passes() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(checkpoint),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     ForkChoiceTest::new_with_chain_config(chain_config.clone())
@@ -1049,6 +1150,7 @@ fn weak_subjectivity_check_fails_early_epoch() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(checkpoint),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     ForkChoiceTest::new_with_chain_config(chain_config.clone())
@@ -1078,6 +1180,7 @@ fn weak_subjectivity_check_fails_late_epoch() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(checkpoint),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     ForkChoiceTest::new_with_chain_config(chain_config.clone())
@@ -1107,6 +1210,7 @@ fn weak_subjectivity_check_fails_incorrect_root() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(checkpoint),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     ForkChoiceTest::new_with_chain_config(chain_config.clone())
@@ -1143,6 +1247,7 @@ fn weak_subjectivity_check_epoch_boundary_is_skip_slot() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(checkpoint),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     // recreate the chain exactly
@@ -1184,6 +1289,7 @@ fn weak_subjectivity_check_epoch_boundary_is_skip_slot_failure() {
     let chain_config = ChainConfig {
         weak_subjectivity_checkpoint: Some(checkpoint),
         import_max_skip_slots: None,
+        ..ChainConfig::default()
     };
 
     // recreate the chain exactly
@@ -2,6 +2,7 @@ use eth2_hashing::{hash, hash32_concat, ZERO_HASHES};
 use ethereum_types::H256;
 use lazy_static::lazy_static;
 use safe_arith::ArithError;
+use std::collections::{BTreeSet, HashMap};
 
 const MAX_TREE_DEPTH: usize = 32;
 const EMPTY_SLICE: &[H256] = &[];
@@ -231,6 +232,114 @@ fn merkle_root_from_branch(leaf: H256, branch: &[H256], depth: usize, index: usi
     H256::from_slice(&merkle_root)
 }
 
+/// Returns the depth of the node at generalized index `gindex`, where the root (`gindex == 1`) has
+/// depth 0.
+///
+/// Returns `None` for `gindex == 0`, which does not address any node.
+pub fn generalized_index_depth(gindex: u64) -> Option<usize> {
+    if gindex == 0 {
+        None
+    } else {
+        Some(63 - gindex.leading_zeros() as usize)
+    }
+}
+
+/// Combine a path of generalized indices into a single generalized index.
+///
+/// Each index in `indices` is relative to the root of the subtree addressed by the previous index.
+pub fn concat_generalized_indices(indices: &[u64]) -> u64 {
+    indices.iter().fold(1, |o, &i| {
+        let depth = generalized_index_depth(i).unwrap_or(0);
+        o * (1 << depth) + (i - (1 << depth))
+    })
+}
+
+/// Returns the generalized indices of the sister nodes along the path from `gindex` to the root.
+fn branch_indices(gindex: u64) -> Vec<u64> {
+    let mut indices = vec![];
+    let mut i = gindex;
+    while i > 1 {
+        indices.push(i ^ 1);
+        i /= 2;
+    }
+    indices
+}
+
+/// Returns the generalized indices of the nodes along the path from `gindex` to the root.
+fn path_indices(gindex: u64) -> Vec<u64> {
+    let mut indices = vec![];
+    let mut i = gindex;
+    while i > 1 {
+        indices.push(i);
+        i /= 2;
+    }
+    indices
+}
+
+/// Returns the generalized indices of all the nodes required to prove the nodes at `gindices`,
+/// in the order in which they should appear in a multiproof (descending).
+pub fn get_helper_indices(gindices: &[u64]) -> Vec<u64> {
+    let mut all_helpers = BTreeSet::new();
+    let mut all_path = BTreeSet::new();
+
+    for &gindex in gindices {
+        all_helpers.extend(branch_indices(gindex));
+        all_path.extend(path_indices(gindex));
+    }
+
+    all_helpers.difference(&all_path).rev().copied().collect()
+}
+
+/// Compute the root of a Merkle tree from the `leaves` at `gindices` and the `proof` nodes, which
+/// are expected in the order given by `get_helper_indices`.
+pub fn calculate_multi_merkle_root(
+    leaves: &[H256],
+    proof: &[H256],
+    gindices: &[u64],
+) -> Result<H256, MerkleTreeError> {
+    let helper_indices = get_helper_indices(gindices);
+
+    if leaves.len() != gindices.len() || proof.len() != helper_indices.len() {
+        return Err(MerkleTreeError::Invalid);
+    }
+
+    let mut objects = gindices
+        .iter()
+        .copied()
+        .zip(leaves.iter().copied())
+        .chain(helper_indices.into_iter().zip(proof.iter().copied()))
+        .collect::<HashMap<_, _>>();
+
+    let mut keys = objects.keys().copied().collect::<Vec<_>>();
+    keys.sort_unstable_by(|a, b| b.cmp(a));
+
+    let mut pos = 0;
+    while pos < keys.len() {
+        let k = keys[pos];
+        if k > 1 && !objects.contains_key(&(k / 2)) {
+            if let (Some(left), Some(right)) = (objects.get(&(k & !1)), objects.get(&(k | 1))) {
+                let parent = H256::from_slice(&hash32_concat(left.as_bytes(), right.as_bytes()));
+                objects.insert(k / 2, parent);
+                keys.push(k / 2);
+            }
+        }
+        pos += 1;
+    }
+
+    objects.get(&1).copied().ok_or(MerkleTreeError::Invalid)
+}
+
+/// Verify a multiproof that the `leaves` exist at `gindices` in a Merkle tree rooted at `root`.
+pub fn verify_merkle_multiproof(
+    leaves: &[H256],
+    proof: &[H256],
+    gindices: &[u64],
+    root: H256,
+) -> bool {
+    calculate_multi_merkle_root(leaves, proof, gindices)
+        .map_or(false, |calculated| calculated == root)
+}
+
 impl From<ArithError> for MerkleTreeError {
     fn from(_: ArithError) -> Self {
         MerkleTreeError::ArithError
@@ -399,6 +508,63 @@ mod tests {
         assert!(!verify_merkle_proof(leaf, &[], 0, 7, junk));
     }
 
+    #[test]
+    fn concat_generalized_indices_example() {
+        assert_eq!(concat_generalized_indices(&[]), 1);
+        assert_eq!(concat_generalized_indices(&[5]), 5);
+        // Left child of the right child of node 2.
+        assert_eq!(concat_generalized_indices(&[2, 3, 2]), 0b1010);
+        assert_eq!(generalized_index_depth(0b1010), Some(3));
+        assert_eq!(generalized_index_depth(0), None);
+    }
+
+    #[test]
+    fn multiproof_small_example() {
+        let leaves = [
+            H256::from([0xAA; 32]),
+            H256::from([0xBB; 32]),
+            H256::from([0xCC; 32]),
+            H256::from([0xDD; 32]),
+        ];
+        let tree = MerkleTree::create(&leaves, 2);
+        let node_b0x = H256::from_slice(&hash32_concat(leaves[0].as_bytes(), leaves[1].as_bytes()));
+
+        // Prove leaves 0b10 and 0b11 (generalized indices 6 and 7), which only requires the left
+        // subtree root.
+        let gindices = [6, 7];
+        assert_eq!(get_helper_indices(&gindices), vec![2]);
+        assert!(verify_merkle_multiproof(
+            &leaves[2..],
+            &[node_b0x],
+            &gindices,
+            tree.hash()
+        ));
+
+        // A single-leaf multiproof is equivalent to a regular branch, reversed.
+        let (leaf, branch) = tree.generate_proof(1, 2);
+        assert_eq!(get_helper_indices(&[5]), vec![4, 3]);
+        assert!(verify_merkle_multiproof(
+            &[leaf],
+            &branch,
+            &[5],
+            tree.hash()
+        ));
+
+        // Ensure that incorrect proofs fail.
+        assert!(!verify_merkle_multiproof(
+            &leaves[2..],
+            &[leaves[0]],
+            &gindices,
+            tree.hash()
+        ));
+        assert!(!verify_merkle_multiproof(
+            &leaves[2..],
+            &[],
+            &gindices,
+            tree.hash()
+        ));
+    }
+
     #[test]
     fn push_complete_example() {
         let depth = 2;
@@ -0,0 +1,218 @@
+use super::*;
+
+pub fn get_proposer_boost_test_definition() -> ForkChoiceTestDefinition {
+    // With 64 validators of balance 10 and 32 slots per epoch, a committee weighs 20 and the
+    // proposer boost (70%) is worth 14.
+    let balances = vec![10; 64];
+    let mut ops = vec![];
+
+    // Ensure that the head starts at the finalized block.
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        expected_head: get_hash(0),
+    });
+
+    // Add a block with a hash of 2.
+    //
+    //          0
+    //         /
+    //        2
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(1),
+        root: get_hash(2),
+        parent_root: get_hash(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+
+    // Ensure that the head is 2
+    //
+    //          0
+    //         /
+    // head-> 2
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        expected_head: get_hash(2),
+    });
+
+    // Add a timely block with a hash of 1 in the next slot, which forks from the genesis block.
+    //
+    //          0
+    //         / \
+    //        2   1
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(2),
+        root: get_hash(1),
+        parent_root: get_hash(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+
+    // Ensure that the head is 1, since it has the proposer boost. Without the boost, 2 would
+    // win the tie-break due to its higher root.
+    //
+    //          0
+    //         / \
+    //        2   1 <- head (+14 boost)
+    ops.push(Operation::FindHeadWithProposerBoost {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        proposer_boost_root: get_hash(1),
+        expected_head: get_hash(1),
+    });
+
+    // Add a vote to block 2.
+    //
+    //                 0
+    //                / \
+    //    +vote ->   2   1
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 0,
+        block_root: get_hash(2),
+        target_epoch: Epoch::new(2),
+    });
+
+    // Ensure that the head is still 1, since the boost (14) outweighs a single vote (10).
+    //
+    //          0
+    //         / \
+    //        2   1 <- head
+    ops.push(Operation::FindHeadWithProposerBoost {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        proposer_boost_root: get_hash(1),
+        expected_head: get_hash(1),
+    });
+
+    // Add another vote to block 2.
+    //
+    //                 0
+    //                / \
+    //    +vote ->   2   1
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 1,
+        block_root: get_hash(2),
+        target_epoch: Epoch::new(2),
+    });
+
+    // Ensure that the head is now 2, since two votes (20) outweigh the boost (14).
+    //
+    //          0
+    //         / \
+    // head-> 2   1
+    ops.push(Operation::FindHeadWithProposerBoost {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        proposer_boost_root: get_hash(1),
+        expected_head: get_hash(2),
+    });
+
+    // Add two votes to block 1.
+    //
+    //          0
+    //         / \
+    //        2   1 <- +2 votes
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 2,
+        block_root: get_hash(1),
+        target_epoch: Epoch::new(2),
+    });
+    ops.push(Operation::ProcessAttestation {
+        validator_index: 3,
+        block_root: get_hash(1),
+        target_epoch: Epoch::new(2),
+    });
+
+    // Ensure that the head is 2 once the boost has been removed in a later slot. Both blocks
+    // have 20 weight and 2 wins the tie-break. If the boost was not removed, 1 would be the
+    // head.
+    //
+    //          0
+    //         / \
+    // head-> 2   1
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        expected_head: get_hash(2),
+    });
+
+    // Add a timely block with a hash of 3 on top of 1.
+    //
+    //          0
+    //         / \
+    //        2   1
+    //            |
+    //            3
+    ops.push(Operation::ProcessBlock {
+        slot: Slot::new(3),
+        root: get_hash(3),
+        parent_root: get_hash(1),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+    });
+
+    // Ensure that the head is 3, since the boost is propagated to its ancestors.
+    //
+    //          0
+    //         / \
+    //        2   1
+    //            |
+    //            3 <- head (+14 boost)
+    ops.push(Operation::FindHeadWithProposerBoost {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances.clone(),
+        proposer_boost_root: get_hash(3),
+        expected_head: get_hash(3),
+    });
+
+    // Ensure that the head returns to 2 once the boost has been removed from 3 and all of its
+    // ancestors.
+    //
+    //          0
+    //         / \
+    // head-> 2   1
+    //            |
+    //            3
+    ops.push(Operation::FindHead {
+        justified_epoch: Epoch::new(1),
+        justified_root: get_hash(0),
+        finalized_epoch: Epoch::new(1),
+        justified_state_balances: balances,
+        expected_head: get_hash(2),
+    });
+
+    ForkChoiceTestDefinition {
+        finalized_block_slot: Slot::new(0),
+        justified_epoch: Epoch::new(1),
+        finalized_epoch: Epoch::new(1),
+        finalized_root: get_hash(0),
+        operations: ops,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let test = get_proposer_boost_test_definition();
+        test.run();
+    }
+}
@@ -2,7 +2,7 @@ use crate::{error::Error, Block};
 use serde_derive::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use std::collections::HashMap;
-use types::{Epoch, Hash256, ShufflingId, Slot};
+use types::{ChainSpec, Epoch, EthSpec, Hash256, ShufflingId, Slot};
 
 #[derive(Clone, PartialEq, Debug, Encode, Decode, Serialize, Deserialize)]
 pub struct ProtoNode {
@@ -29,6 +29,16 @@ pub struct ProtoNode {
     best_descendant: Option<usize>,
 }
 
+/// The proposer boost that was applied during the last call to `apply_score_changes`.
+///
+/// It must be remembered so the boost can be removed from the node (and its ancestors) again
+/// before a new boost is applied.
+#[derive(PartialEq, Debug, Default, Clone, Copy, Encode, Decode, Serialize, Deserialize)]
+pub struct ProposerBoost {
+    pub root: Hash256,
+    pub score: u64,
+}
+
 #[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
 pub struct ProtoArray {
     /// Do not attempt to prune the tree unless it has at least this many nodes. Small prunes
@@ -38,6 +48,7 @@ pub struct ProtoArray {
     pub finalized_epoch: Epoch,
     pub nodes: Vec<ProtoNode>,
     pub indices: HashMap<Hash256, usize>,
+    pub previous_proposer_boost: ProposerBoost,
 }
 
 impl ProtoArray {
@@ -49,16 +60,21 @@ impl ProtoArray {
     ///
     /// For each node, the following is done:
     ///
+    /// - Remove the previous proposer boost from the node which received it, if any.
+    /// - Add the proposer boost to the node with `proposer_boost_root`, if any.
     /// - Update the node's weight with the corresponding delta.
     /// - Back-propagate each node's delta to its parents delta.
     /// - Compare the current node with the parents best-child, updating it if the current node
     /// should become the best child.
     /// - If required, update the parents best-descendant with the current node or its best-descendant.
-    pub fn apply_score_changes(
+    pub fn apply_score_changes<E: EthSpec>(
         &mut self,
         mut deltas: Vec<i64>,
         justified_epoch: Epoch,
         finalized_epoch: Epoch,
+        new_balances: &[u64],
+        proposer_boost_root: Hash256,
+        spec: &ChainSpec,
     ) -> Result<(), Error> {
         if deltas.len() != self.indices.len() {
             return Err(Error::InvalidDeltaLen {
@@ -72,6 +88,9 @@ impl ProtoArray {
             self.finalized_epoch = finalized_epoch;
         }
 
+        // The score of the proposer boost applied during this call, if any.
+        let mut proposer_score = 0;
+
         // Iterate backwards through all indices in `self.nodes`.
         for node_index in (0..self.nodes.len()).rev() {
             let node = self
@@ -86,11 +105,33 @@ impl ProtoArray {
                 continue;
             }
 
-            let node_delta = deltas
+            let mut node_delta = deltas
                 .get(node_index)
                 .copied()
                 .ok_or_else(|| Error::InvalidNodeDelta(node_index))?;
 
+            // If this node received the proposer boost last time, remove it. The removal is
+            // back-propagated to the ancestors along with the rest of the delta.
+            if self.previous_proposer_boost.root != Hash256::zero()
+                && self.previous_proposer_boost.root == node.root
+            {
+                node_delta = node_delta
+                    .checked_sub(self.previous_proposer_boost.score as i64)
+                    .ok_or_else(|| Error::DeltaOverflow(node_index))?;
+            }
+
+            // If this node is the timely block of the current slot, give it the proposer boost.
+            if let Some(proposer_score_boost) = spec.proposer_score_boost {
+                if proposer_boost_root != Hash256::zero() && proposer_boost_root == node.root {
+                    proposer_score =
+                        calculate_proposer_boost::<E>(new_balances, proposer_score_boost)
+                            .ok_or_else(|| Error::ProposerBoostOverflow(node_index))?;
+                    node_delta = node_delta
+                        .checked_add(proposer_score as i64)
+                        .ok_or_else(|| Error::DeltaOverflow(node_index))?;
+                }
+            }
+
             // Apply the delta to the node.
             if node_delta < 0 {
                 // Note: I am conflicted about whether to use `saturating_sub` or `checked_sub`
@@ -126,6 +167,11 @@ impl ProtoArray {
             }
         }
 
+        self.previous_proposer_boost = ProposerBoost {
+            root: proposer_boost_root,
+            score: proposer_score,
+        };
+
         Ok(())
     }
 
@@ -448,3 +494,30 @@ impl<'a> Iterator for Iter<'a> {
         Some(node)
     }
 }
+
+/// Returns the weight of the proposer boost, which is `proposer_score_boost` percent of the
+/// weight of a single committee (i.e., the active balance divided by the slots in an epoch).
+///
+/// Validators with a zero balance are ignored, since inactive validators are given a zero
+/// balance in `justified_balances`.
+///
+/// Returns `None` if there are no active validators or an arithmetic overflow occurs.
+pub fn calculate_proposer_boost<E: EthSpec>(
+    validator_balances: &[u64],
+    proposer_score_boost: u64,
+) -> Option<u64> {
+    let mut total_balance: u64 = 0;
+    let mut num_validators: u64 = 0;
+    for &balance in validator_balances {
+        if balance != 0 {
+            total_balance = total_balance.checked_add(balance)?;
+            num_validators = num_validators.checked_add(1)?;
+        }
+    }
+    let average_balance = total_balance.checked_div(num_validators)?;
+    let committee_size = num_validators.checked_div(E::slots_per_epoch())?;
+    let committee_weight = committee_size.checked_mul(average_balance)?;
+    committee_weight
+        .checked_mul(proposer_score_boost)?
+        .checked_div(100)
+}
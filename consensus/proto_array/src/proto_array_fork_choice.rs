@@ -1,10 +1,10 @@
 use crate::error::Error;
-use crate::proto_array::ProtoArray;
-use crate::ssz_container::SszContainer;
+use crate::proto_array::{ProposerBoost, ProtoArray};
+use crate::ssz_container::{LegacySszContainer, SszContainer};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::collections::HashMap;
-use types::{Epoch, Hash256, ShufflingId, Slot};
+use types::{ChainSpec, Epoch, EthSpec, Hash256, ShufflingId, Slot};
 
 pub const DEFAULT_PRUNE_THRESHOLD: usize = 256;
 
@@ -81,6 +81,7 @@ impl ProtoArrayForkChoice {
             finalized_epoch,
             nodes: Vec::with_capacity(1),
             indices: HashMap::with_capacity(1),
+            previous_proposer_boost: ProposerBoost::default(),
         };
 
         let block = Block {
@@ -134,12 +135,19 @@ impl ProtoArrayForkChoice {
             .map_err(|e| format!("process_block_error: {:?}", e))
     }
 
-    pub fn find_head(
+    /// Returns the head of the chain, starting the search from `justified_root`.
+    ///
+    /// If `proposer_boost_root` is not the zero hash, that block is given additional weight as
+    /// per `spec.proposer_score_boost`. The boost is only applied for this call; it is removed
+    /// again during the next call to `find_head`.
+    pub fn find_head<E: EthSpec>(
         &mut self,
         justified_epoch: Epoch,
         justified_root: Hash256,
         finalized_epoch: Epoch,
         justified_state_balances: &[u64],
+        proposer_boost_root: Hash256,
+        spec: &ChainSpec,
     ) -> Result<Hash256, String> {
         let old_balances = &mut self.balances;
 
@@ -154,7 +162,14 @@ impl ProtoArrayForkChoice {
         .map_err(|e| format!("find_head compute_deltas failed: {:?}", e))?;
 
         self.proto_array
-            .apply_score_changes(deltas, justified_epoch, finalized_epoch)
+            .apply_score_changes::<E>(
+                deltas,
+                justified_epoch,
+                finalized_epoch,
+                new_balances,
+                proposer_boost_root,
+                spec,
+            )
             .map_err(|e| format!("find_head apply_score_changes failed: {:?}", e))?;
 
         *old_balances = new_balances.to_vec();
@@ -249,6 +264,13 @@ impl ProtoArrayForkChoice {
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
         SszContainer::from_ssz_bytes(bytes)
             .map(Into::into)
+            .or_else(|e| {
+                // Fork choice persisted prior to the introduction of proposer boosting does
+                // not contain the previous proposer boost.
+                LegacySszContainer::from_ssz_bytes(bytes)
+                    .map(Into::into)
+                    .map_err(|_| e)
+            })
             .map_err(|e| format!("Failed to decode ProtoArrayForkChoice: {:?}", e))
     }
 
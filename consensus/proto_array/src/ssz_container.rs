@@ -1,5 +1,5 @@
 use crate::{
-    proto_array::{ProtoArray, ProtoNode},
+    proto_array::{ProposerBoost, ProtoArray, ProtoNode},
     proto_array_fork_choice::{ElasticList, ProtoArrayForkChoice, VoteTracker},
 };
 use ssz_derive::{Decode, Encode};
@@ -16,6 +16,21 @@ pub struct SszContainer {
     finalized_epoch: Epoch,
     nodes: Vec<ProtoNode>,
     indices: Vec<(Hash256, usize)>,
+    previous_proposer_boost: ProposerBoost,
+}
+
+/// The `SszContainer` as it was prior to the addition of `previous_proposer_boost`.
+///
+/// Only used for decoding fork choice which was persisted by an older version.
+#[derive(Encode, Decode)]
+pub struct LegacySszContainer {
+    votes: Vec<VoteTracker>,
+    balances: Vec<u64>,
+    prune_threshold: usize,
+    justified_epoch: Epoch,
+    finalized_epoch: Epoch,
+    nodes: Vec<ProtoNode>,
+    indices: Vec<(Hash256, usize)>,
 }
 
 impl From<&ProtoArrayForkChoice> for SszContainer {
@@ -30,6 +45,7 @@ impl From<&ProtoArrayForkChoice> for SszContainer {
             finalized_epoch: proto_array.finalized_epoch,
             nodes: proto_array.nodes.clone(),
             indices: proto_array.indices.iter().map(|(k, v)| (*k, *v)).collect(),
+            previous_proposer_boost: proto_array.previous_proposer_boost,
         }
     }
 }
@@ -42,6 +58,7 @@ impl From<SszContainer> for ProtoArrayForkChoice {
             finalized_epoch: from.finalized_epoch,
             nodes: from.nodes,
             indices: HashMap::from_iter(from.indices.into_iter()),
+            previous_proposer_boost: from.previous_proposer_boost,
         };
 
         Self {
@@ -51,3 +68,24 @@ impl From<SszContainer> for ProtoArrayForkChoice {
         }
     }
 }
+
+impl From<LegacySszContainer> for SszContainer {
+    fn from(from: LegacySszContainer) -> Self {
+        Self {
+            votes: from.votes,
+            balances: from.balances,
+            prune_threshold: from.prune_threshold,
+            justified_epoch: from.justified_epoch,
+            finalized_epoch: from.finalized_epoch,
+            nodes: from.nodes,
+            indices: from.indices,
+            previous_proposer_boost: ProposerBoost::default(),
+        }
+    }
+}
+
+impl From<LegacySszContainer> for ProtoArrayForkChoice {
+    fn from(from: LegacySszContainer) -> Self {
+        SszContainer::from(from).into()
+    }
+}
@@ -255,6 +255,79 @@ fn tree_hash_cache() {
     assert_eq!(root.as_bytes(), &state.tree_hash_root()[..]);
 }
 
+#[test]
+fn merkle_proofs() {
+    use crate::test_utils::{SeedableRng, TestRandom, XorShiftRng};
+    use merkle_proof::{generalized_index_depth, verify_merkle_multiproof, verify_merkle_proof};
+    use tree_hash::TreeHash;
+
+    let mut rng = XorShiftRng::from_seed([42; 16]);
+
+    let state: BeaconState<MinimalEthSpec> = BeaconState::random_for_test(&mut rng);
+    let root = state.tree_hash_root();
+
+    assert_eq!(state.merkle_node(1), Ok(root));
+    assert_eq!(
+        state.merkle_node(0),
+        Err(MerkleNodesError::InvalidGeneralizedIndex(0))
+    );
+
+    let verify = |gindex: u64, leaf: Hash256| {
+        let depth = generalized_index_depth(gindex).unwrap();
+        let branch = state.compute_merkle_proof(gindex).unwrap();
+        verify_merkle_proof(leaf, &branch, depth, (gindex - (1 << depth)) as usize, root)
+    };
+
+    /*
+     * Finalized checkpoint.
+     */
+
+    let finalized_gindex = BeaconState::<MinimalEthSpec>::finalized_checkpoint_gindex();
+    assert!(verify(
+        finalized_gindex,
+        state.finalized_checkpoint.tree_hash_root()
+    ));
+    assert!(!verify(
+        finalized_gindex,
+        state.current_justified_checkpoint.tree_hash_root()
+    ));
+
+    // Descend into the `root` field of the checkpoint.
+    assert!(verify(
+        finalized_gindex * 2 + 1,
+        state.finalized_checkpoint.root
+    ));
+
+    /*
+     * Balances.
+     */
+
+    for (i, balance) in state.balances.iter().enumerate() {
+        let gindex = BeaconState::<MinimalEthSpec>::balance_gindex(i);
+        let leaf = state.merkle_node(gindex).unwrap();
+        let offset = (i % 4) * 8;
+        assert_eq!(&leaf.as_bytes()[offset..offset + 8], &balance.to_le_bytes());
+        assert!(verify(gindex, leaf));
+    }
+
+    /*
+     * Multiproofs.
+     */
+
+    let gindices = [
+        finalized_gindex,
+        BeaconState::<MinimalEthSpec>::balance_gindex(0),
+    ];
+    let (leaves, proof) = state.compute_merkle_multiproof(&gindices).unwrap();
+    assert!(verify_merkle_multiproof(&leaves, &proof, &gindices, root));
+    assert!(!verify_merkle_multiproof(
+        &leaves,
+        &proof,
+        &gindices,
+        Hash256::zero()
+    ));
+}
+
 /// Tests committee-specific components
 #[cfg(test)]
 mod committees {
@@ -102,6 +102,9 @@ pub struct ChainSpec {
      * Fork choice
      */
     pub safe_slots_to_update_justified: u64,
+    /// The percentage of a committee's weight given to a block which arrives on time in its own
+    /// slot. `None` disables proposer boosting.
+    pub proposer_score_boost: Option<u64>,
 
     /*
      * Eth1
@@ -314,6 +317,7 @@ impl ChainSpec {
              * Fork choice
              */
             safe_slots_to_update_justified: 8,
+            proposer_score_boost: Some(70),
 
             /*
              * Eth1
@@ -509,6 +513,10 @@ pub struct YamlConfig {
     min_slashing_penalty_quotient: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     safe_slots_to_update_justified: u64,
+    // Absent from configs which predate proposer boosting, in which case the value in the
+    // `ChainSpec` is retained.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    proposer_score_boost: Option<u64>,
 
     #[serde(with = "serde_utils::u32_hex")]
     domain_beacon_proposer: u32,
@@ -563,8 +571,11 @@ pub struct YamlConfig {
     #[serde(with = "serde_utils::quoted_u64")]
     seconds_per_eth1_block: u64,
     deposit_contract_address: Address,
+    // The chain id of the eth1 network which hosts the deposit contract. It is not part of the
+    // `ChainSpec`, so it is only known when read from a testnet's config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    deposit_chain_id: Option<u64>,
     /* TODO: incorporate these into ChainSpec and turn on `serde(deny_unknown_fields)`
-    deposit_chain_id: u64,
     deposit_network_id: u64,
     */
 }
@@ -619,6 +630,7 @@ impl YamlConfig {
             min_slashing_penalty_quotient: spec.min_slashing_penalty_quotient,
             genesis_fork_version: spec.genesis_fork_version,
             safe_slots_to_update_justified: spec.safe_slots_to_update_justified,
+            proposer_score_boost: spec.proposer_score_boost,
             domain_beacon_proposer: spec.domain_beacon_proposer,
             domain_beacon_attester: spec.domain_beacon_attester,
             domain_randao: spec.domain_randao,
@@ -649,9 +661,15 @@ impl YamlConfig {
             epochs_per_random_subnet_subscription: spec.epochs_per_random_subnet_subscription,
             seconds_per_eth1_block: spec.seconds_per_eth1_block,
             deposit_contract_address: spec.deposit_contract_address,
+            deposit_chain_id: None,
         }
     }
 
+    /// The chain id of the eth1 network which hosts the deposit contract, if it is known.
+    pub fn deposit_chain_id(&self) -> Option<u64> {
+        self.deposit_chain_id
+    }
+
     pub fn from_file(filename: &Path) -> Result<Self, String> {
         let f = File::open(filename)
             .map_err(|e| format!("Error opening spec at {}: {:?}", filename.display(), e))?;
@@ -698,6 +716,9 @@ impl YamlConfig {
              * Fork Choice
              */
             safe_slots_to_update_justified: self.safe_slots_to_update_justified,
+            proposer_score_boost: self
+                .proposer_score_boost
+                .or(chain_spec.proposer_score_boost),
             /*
              * Validator
              */
@@ -825,6 +846,47 @@ mod yaml_tests {
         assert_eq!(from, yamlconfig);
     }
 
+    #[test]
+    fn deposit_chain_id() {
+        let yamlconfig = YamlConfig::from_spec::<MainnetEthSpec>(&ChainSpec::mainnet());
+        assert_eq!(yamlconfig.deposit_chain_id(), None);
+
+        let yaml = serde_yaml::to_string(&yamlconfig).expect("failed to serialize");
+        assert!(!yaml.contains("DEPOSIT_CHAIN_ID"));
+
+        let from: YamlConfig = serde_yaml::from_str(&format!("{}\nDEPOSIT_CHAIN_ID: 5\n", yaml))
+            .expect("error while deserializing");
+        assert_eq!(from.deposit_chain_id(), Some(5));
+    }
+
+    #[test]
+    fn proposer_score_boost() {
+        let spec = ChainSpec::mainnet();
+        let yaml = serde_yaml::to_string(&YamlConfig::from_spec::<MainnetEthSpec>(&spec))
+            .expect("failed to serialize");
+        assert!(yaml.contains("PROPOSER_SCORE_BOOST: 70"));
+
+        // A config without the field retains the value from the spec.
+        let without: String = yaml
+            .lines()
+            .filter(|line| !line.starts_with("PROPOSER_SCORE_BOOST"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let from: YamlConfig = serde_yaml::from_str(&without).expect("error while deserializing");
+        let applied = from
+            .apply_to_chain_spec::<MainnetEthSpec>(&spec)
+            .expect("should have applied spec");
+        assert_eq!(applied.proposer_score_boost, Some(70));
+
+        let from: YamlConfig =
+            serde_yaml::from_str(&format!("{}PROPOSER_SCORE_BOOST: 40\n", without))
+                .expect("error while deserializing");
+        let applied = from
+            .apply_to_chain_spec::<MainnetEthSpec>(&spec)
+            .expect("should have applied spec");
+        assert_eq!(applied.proposer_score_boost, Some(40));
+    }
+
     #[test]
     fn apply_to_spec() {
         let mut spec = ChainSpec::minimal();
@@ -0,0 +1,361 @@
+//! Provides access to the individual nodes of the SSZ Merkle tree of a type, allowing the
+//! generation of Merkle proofs for any node addressed by a generalized index.
+//!
+//! A generalized index of `1` refers to the root of the tree, `2` and `3` are its left and right
+//! children and so on. See the `ssz/merkle-proofs.md` document in the specs for more detail.
+#![allow(clippy::integer_arithmetic)]
+
+use crate::{
+    BeaconBlockHeader, BeaconState, Checkpoint, Epoch, Eth1Data, EthSpec, Fork, Hash256,
+    PendingAttestation, PublicKeyBytes, Slot, Validator,
+};
+use eth2_hashing::ZERO_HASHES;
+use merkle_proof::{concat_generalized_indices, generalized_index_depth, get_helper_indices};
+use ssz_types::{typenum::Unsigned, BitVector, FixedVector, VariableList};
+use std::cmp;
+use tree_hash::{MerkleHasher, TreeHash, TreeHashType, BYTES_PER_CHUNK};
+
+/// The number of fields on a `BeaconState` which are included in the tree hash.
+const NUM_BEACON_STATE_FIELDS: usize = 21;
+
+/// The index of `BeaconState.balances` amongst the fields of the state.
+const BALANCES_FIELD_INDEX: u64 = 12;
+
+/// The index of `BeaconState.finalized_checkpoint` amongst the fields of the state.
+const FINALIZED_CHECKPOINT_FIELD_INDEX: u64 = 20;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    /// The generalized index does not refer to any node (i.e., it is `0`).
+    InvalidGeneralizedIndex(u64),
+    /// The generalized index refers to a node below a leaf of the tree (e.g., below a `u64`
+    /// field or inside the zero-padding of a list).
+    IndexBelowLeaf(u64),
+    TreeHashError(tree_hash::Error),
+}
+
+impl From<tree_hash::Error> for Error {
+    fn from(e: tree_hash::Error) -> Self {
+        Error::TreeHashError(e)
+    }
+}
+
+/// Provides the nodes of the SSZ Merkle tree of `self`, addressed by generalized index.
+pub trait MerkleNodes {
+    /// Returns the node at `gindex` in the tree of `self`, where `gindex == 1` is the value
+    /// returned by `TreeHash::tree_hash_root`.
+    fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error>;
+
+    /// Returns a Merkle branch which proves the node at `gindex` against the root of `self`.
+    ///
+    /// The branch is in "bottom-up" order, so it can be checked with
+    /// `merkle_proof::verify_merkle_proof` using a depth of `generalized_index_depth(gindex)` and
+    /// an index of `gindex - 2^depth`.
+    fn compute_merkle_proof(&self, gindex: u64) -> Result<Vec<Hash256>, Error> {
+        if gindex == 0 {
+            return Err(Error::InvalidGeneralizedIndex(gindex));
+        }
+
+        let mut branch = vec![];
+        let mut index = gindex;
+        while index > 1 {
+            branch.push(self.merkle_node(index ^ 1)?);
+            index /= 2;
+        }
+
+        Ok(branch)
+    }
+
+    /// Returns the leaves at each of the `gindices` and a multiproof of those leaves against the
+    /// root of `self`.
+    ///
+    /// The proof nodes are ordered as per `merkle_proof::get_helper_indices`, so they can be
+    /// checked with `merkle_proof::verify_merkle_multiproof`.
+    fn compute_merkle_multiproof(
+        &self,
+        gindices: &[u64],
+    ) -> Result<(Vec<Hash256>, Vec<Hash256>), Error> {
+        if let Some(gindex) = gindices.iter().find(|gindex| **gindex == 0) {
+            return Err(Error::InvalidGeneralizedIndex(*gindex));
+        }
+
+        let leaves = gindices
+            .iter()
+            .map(|gindex| self.merkle_node(*gindex))
+            .collect::<Result<_, _>>()?;
+        let proof = get_helper_indices(gindices)
+            .into_iter()
+            .map(|gindex| self.merkle_node(gindex))
+            .collect::<Result<_, _>>()?;
+
+        Ok((leaves, proof))
+    }
+}
+
+/// Returns the depth of a tree which has at least `num_leaves` leaves.
+fn depth_for_leaves(num_leaves: usize) -> usize {
+    cmp::max(num_leaves, 1).next_power_of_two().trailing_zeros() as usize
+}
+
+/// Returns the node at `gindex` in a tree of `depth`, where the first `num_leaves` leaves are
+/// provided by `leaf` and all others are zero.
+///
+/// The `leaf` function is called with the index of a leaf and the generalized index of a node
+/// *relative to that leaf*, allowing the function to descend into the leaf.
+fn subtree_node<F>(depth: usize, num_leaves: usize, gindex: u64, leaf: F) -> Result<Hash256, Error>
+where
+    F: Fn(usize, u64) -> Result<Hash256, Error>,
+{
+    let node_depth =
+        generalized_index_depth(gindex).ok_or(Error::InvalidGeneralizedIndex(gindex))?;
+
+    if node_depth >= depth {
+        let below = node_depth - depth;
+        let leaf_index = ((gindex >> below) - (1 << depth)) as usize;
+        let relative_gindex = (gindex & ((1 << below) - 1)) | (1 << below);
+
+        if leaf_index < num_leaves {
+            leaf(leaf_index, relative_gindex)
+        } else if relative_gindex == 1 {
+            Ok(Hash256::zero())
+        } else {
+            Err(Error::IndexBelowLeaf(gindex))
+        }
+    } else {
+        let height = depth - node_depth;
+        let first_leaf = ((gindex - (1 << node_depth)) << height) as usize;
+
+        if first_leaf >= num_leaves {
+            return Ok(Hash256::from_slice(&ZERO_HASHES[height]));
+        }
+
+        let last_leaf = cmp::min(first_leaf + (1 << height), num_leaves);
+        let mut hasher = MerkleHasher::with_leaves(1 << height);
+        for i in first_leaf..last_leaf {
+            hasher.write(leaf(i, 1)?.as_bytes())?;
+        }
+
+        Ok(hasher.finish()?)
+    }
+}
+
+/// Returns the node at `gindex` in the tree of a container with the given `fields`.
+fn container_node(fields: &[&dyn MerkleNodes], gindex: u64) -> Result<Hash256, Error> {
+    subtree_node(
+        depth_for_leaves(fields.len()),
+        fields.len(),
+        gindex,
+        |i, relative_gindex| fields[i].merkle_node(relative_gindex),
+    )
+}
+
+/// Returns the node at `gindex` in the tree of a vector or list of `items`, excluding the length
+/// mix-in of lists.
+fn sequence_node<T>(items: &[T], max_len: usize, gindex: u64) -> Result<Hash256, Error>
+where
+    T: TreeHash + MerkleNodes,
+{
+    match T::tree_hash_type() {
+        TreeHashType::Basic => {
+            let packing_factor = T::tree_hash_packing_factor();
+            let num_leaves = (items.len() + packing_factor - 1) / packing_factor;
+            let max_leaves = (max_len + packing_factor - 1) / packing_factor;
+
+            subtree_node(
+                depth_for_leaves(max_leaves),
+                num_leaves,
+                gindex,
+                |i, relative_gindex| {
+                    if relative_gindex != 1 {
+                        return Err(Error::IndexBelowLeaf(gindex));
+                    }
+
+                    let first = i * packing_factor;
+                    let last = cmp::min(first + packing_factor, items.len());
+                    let mut chunk = [0; BYTES_PER_CHUNK];
+                    let packed = items[first..last]
+                        .iter()
+                        .flat_map(|item| item.tree_hash_packed_encoding())
+                        .collect::<Vec<_>>();
+                    chunk[..packed.len()].copy_from_slice(&packed);
+
+                    Ok(Hash256::from_slice(&chunk))
+                },
+            )
+        }
+        _ => subtree_node(
+            depth_for_leaves(max_len),
+            items.len(),
+            gindex,
+            |i, relative_gindex| {
+                if relative_gindex == 1 {
+                    Ok(items[i].tree_hash_root())
+                } else {
+                    items[i].merkle_node(relative_gindex)
+                }
+            },
+        ),
+    }
+}
+
+/// Implements `MerkleNodes` for a type which is treated as a single leaf, either because it is a
+/// basic type or because we never need to descend into it.
+macro_rules! impl_merkle_nodes_for_leaf {
+    ($type: ty) => {
+        impl MerkleNodes for $type {
+            fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error> {
+                if gindex == 1 {
+                    Ok(self.tree_hash_root())
+                } else {
+                    Err(Error::IndexBelowLeaf(gindex))
+                }
+            }
+        }
+    };
+}
+
+/// Implements `MerkleNodes` for a container type with the given fields, in order.
+macro_rules! impl_merkle_nodes_for_container {
+    ($type: ty, $($field: ident),+) => {
+        impl MerkleNodes for $type {
+            fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error> {
+                container_node(&[$(&self.$field),+], gindex)
+            }
+        }
+    };
+}
+
+impl_merkle_nodes_for_leaf!(bool);
+impl_merkle_nodes_for_leaf!(u64);
+impl_merkle_nodes_for_leaf!([u8; 4]);
+impl_merkle_nodes_for_leaf!(Slot);
+impl_merkle_nodes_for_leaf!(Epoch);
+impl_merkle_nodes_for_leaf!(Hash256);
+impl_merkle_nodes_for_leaf!(PublicKeyBytes);
+
+impl<T: EthSpec> MerkleNodes for PendingAttestation<T> {
+    fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error> {
+        if gindex == 1 {
+            Ok(self.tree_hash_root())
+        } else {
+            Err(Error::IndexBelowLeaf(gindex))
+        }
+    }
+}
+
+impl<N: Unsigned + Clone> MerkleNodes for BitVector<N> {
+    fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error> {
+        if gindex == 1 {
+            Ok(self.tree_hash_root())
+        } else {
+            Err(Error::IndexBelowLeaf(gindex))
+        }
+    }
+}
+
+impl_merkle_nodes_for_container!(Checkpoint, epoch, root);
+impl_merkle_nodes_for_container!(Fork, previous_version, current_version, epoch);
+impl_merkle_nodes_for_container!(Eth1Data, deposit_root, deposit_count, block_hash);
+impl_merkle_nodes_for_container!(
+    BeaconBlockHeader,
+    slot,
+    proposer_index,
+    parent_root,
+    state_root,
+    body_root
+);
+impl_merkle_nodes_for_container!(
+    Validator,
+    pubkey,
+    withdrawal_credentials,
+    effective_balance,
+    slashed,
+    activation_eligibility_epoch,
+    activation_epoch,
+    exit_epoch,
+    withdrawable_epoch
+);
+
+impl<T: TreeHash + MerkleNodes, N: Unsigned> MerkleNodes for FixedVector<T, N> {
+    fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error> {
+        sequence_node(&self[..], N::to_usize(), gindex)
+    }
+}
+
+impl<T: TreeHash + MerkleNodes, N: Unsigned> MerkleNodes for VariableList<T, N> {
+    fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error> {
+        // The root of a list has the data tree on the left and the length on the right.
+        subtree_node(1, 2, gindex, |i, relative_gindex| {
+            match (i, relative_gindex) {
+                (0, _) => sequence_node(&self[..], N::to_usize(), relative_gindex),
+                (_, 1) => {
+                    let mut chunk = [0; BYTES_PER_CHUNK];
+                    chunk[..8].copy_from_slice(&(self.len() as u64).to_le_bytes());
+                    Ok(Hash256::from_slice(&chunk))
+                }
+                _ => Err(Error::IndexBelowLeaf(gindex)),
+            }
+        })
+    }
+}
+
+impl<T: EthSpec> MerkleNodes for BeaconState<T> {
+    fn merkle_node(&self, gindex: u64) -> Result<Hash256, Error> {
+        let fields: [&dyn MerkleNodes; NUM_BEACON_STATE_FIELDS] = [
+            &self.genesis_time,
+            &self.genesis_validators_root,
+            &self.slot,
+            &self.fork,
+            &self.latest_block_header,
+            &self.block_roots,
+            &self.state_roots,
+            &self.historical_roots,
+            &self.eth1_data,
+            &self.eth1_data_votes,
+            &self.eth1_deposit_index,
+            &self.validators,
+            &self.balances,
+            &self.randao_mixes,
+            &self.slashings,
+            &self.previous_epoch_attestations,
+            &self.current_epoch_attestations,
+            &self.justification_bits,
+            &self.previous_justified_checkpoint,
+            &self.current_justified_checkpoint,
+            &self.finalized_checkpoint,
+        ];
+        container_node(&fields, gindex)
+    }
+}
+
+impl<T: EthSpec> BeaconState<T> {
+    /// Returns the generalized index of the field at `field_index` in the state.
+    fn field_gindex(field_index: u64) -> u64 {
+        (1 << depth_for_leaves(NUM_BEACON_STATE_FIELDS)) + field_index
+    }
+
+    /// Returns the generalized index of the `finalized_checkpoint` field of the state.
+    ///
+    /// The node at this index is the `tree_hash_root` of the `Checkpoint`.
+    pub fn finalized_checkpoint_gindex() -> u64 {
+        Self::field_gindex(FINALIZED_CHECKPOINT_FIELD_INDEX)
+    }
+
+    /// Returns the generalized index of the chunk of `balances` which contains the balance of
+    /// the validator at `validator_index`.
+    ///
+    /// Balances are packed four to a chunk, with the balance of `validator_index` stored
+    /// little-endian at byte offset `8 * (validator_index % 4)`.
+    pub fn balance_gindex(validator_index: usize) -> u64 {
+        let packing_factor = BYTES_PER_CHUNK / std::mem::size_of::<u64>();
+        let max_chunks = T::ValidatorRegistryLimit::to_usize() / packing_factor;
+        let depth = depth_for_leaves(max_chunks);
+
+        concat_generalized_indices(&[
+            Self::field_gindex(BALANCES_FIELD_INDEX),
+            // The left child of a list root is the root of the data tree.
+            2,
+            (1 << depth) + (validator_index / packing_factor) as u64,
+        ])
+    }
+}
@@ -123,6 +123,13 @@ impl<'a> KeystoreBuilder<'a> {
         self
     }
 
+    /// Build the keystore with the supplied `uuid` instead of a random one (e.g., when
+    /// re-encrypting an existing keystore).
+    pub fn uuid(mut self, uuid: Uuid) -> Self {
+        self.uuid = uuid;
+        self
+    }
+
     /// Consumes `self`, returning a `Keystore`.
     pub fn build(self) -> Result<Keystore, Error> {
         Keystore::encrypt(
@@ -397,7 +404,9 @@ fn generate_checksum(derived_key: &DerivedKey, cipher_message: &[u8]) -> [u8; HA
 }
 
 /// Derive a private key from the given `password` using the given `kdf` (key derivation function).
-fn derive_key(password: &[u8], kdf: &Kdf) -> Result<DerivedKey, Error> {
+///
+/// Also useful for other keystore formats which share the EIP-2335 KDFs (e.g., eth1 keystores).
+pub fn derive_key(password: &[u8], kdf: &Kdf) -> Result<DerivedKey, Error> {
     let mut dk = DerivedKey::zero();
 
     match &kdf {
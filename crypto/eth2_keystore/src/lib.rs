@@ -7,9 +7,10 @@ mod keystore;
 pub mod json_keystore;
 
 pub use bls::ZeroizeHash;
+pub use derived_key::DerivedKey;
 pub use eth2_key_derivation::PlainText;
 pub use keystore::{
-    decrypt, default_kdf, encrypt, keypair_from_secret, Error, Keystore, KeystoreBuilder, DKLEN,
-    HASH_SIZE, IV_SIZE, SALT_SIZE,
+    decrypt, default_kdf, derive_key, encrypt, keypair_from_secret, Error, Keystore,
+    KeystoreBuilder, DKLEN, HASH_SIZE, IV_SIZE, SALT_SIZE,
 };
 pub use uuid::Uuid;
@@ -160,3 +160,25 @@ fn custom_pbkdf2_kdf() {
 
     assert_eq!(keystore.kdf(), &my_kdf);
 }
+
+#[test]
+fn custom_uuid() {
+    let keypair = Keypair::random();
+
+    let keystore = KeystoreBuilder::new(&keypair, GOOD_PASSWORD, "".into())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    let reencrypted = KeystoreBuilder::new(&keypair, BAD_PASSWORD, "".into())
+        .unwrap()
+        .uuid(*keystore.uuid())
+        .build()
+        .unwrap();
+
+    assert_eq!(reencrypted.uuid(), keystore.uuid());
+    assert_eq!(
+        reencrypted.decrypt_keypair(BAD_PASSWORD).unwrap().pk,
+        keypair.pk
+    );
+}
@@ -1,13 +1,13 @@
 use clap::ArgMatches;
 use eth2_libp2p::{
     discovery::{build_enr, CombinedKey, CombinedKeyExt, Keypair, ENR_FILENAME},
-    NetworkConfig, NETWORK_KEY_FILENAME,
+    Enr, NetworkConfig, NETWORK_KEY_FILENAME,
 };
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::net::IpAddr;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use types::{ChainSpec, EnrForkId, Epoch, EthSpec, Hash256};
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
@@ -25,18 +25,40 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         ));
     }
 
+    let enr_fork_id = EnrForkId {
+        fork_digest: ChainSpec::compute_fork_digest(genesis_fork_version, Hash256::zero()),
+        next_fork_version: genesis_fork_version,
+        next_fork_epoch: Epoch::max_value(), // FAR_FUTURE_EPOCH
+    };
+
+    write_bootnode_enr::<T>(
+        Keypair::generate_secp256k1(),
+        ip,
+        udp_port,
+        tcp_port,
+        enr_fork_id,
+        &output_dir,
+    )?;
+
+    Ok(())
+}
+
+/// Generates an ENR for `local_keypair`, writing the key and ENR to `output_dir` in the same format
+/// as a beacon node's network directory.
+pub fn write_bootnode_enr<T: EthSpec>(
+    local_keypair: Keypair,
+    ip: IpAddr,
+    udp_port: u16,
+    tcp_port: u16,
+    enr_fork_id: EnrForkId,
+    output_dir: &Path,
+) -> Result<Enr, String> {
     let mut config = NetworkConfig::default();
     config.enr_address = Some(ip);
     config.enr_udp_port = Some(udp_port);
     config.enr_tcp_port = Some(tcp_port);
 
-    let local_keypair = Keypair::generate_secp256k1();
     let enr_key = CombinedKey::from_libp2p(&local_keypair)?;
-    let enr_fork_id = EnrForkId {
-        fork_digest: ChainSpec::compute_fork_digest(genesis_fork_version, Hash256::zero()),
-        next_fork_version: genesis_fork_version,
-        next_fork_epoch: Epoch::max_value(), // FAR_FUTURE_EPOCH
-    };
     let enr = build_enr::<T>(&enr_key, &config, enr_fork_id)
         .map_err(|e| format!("Unable to create ENR: {:?}", e))?;
 
@@ -59,5 +81,5 @@ pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
         .write_all(&secret_bytes)
         .map_err(|e| format!("Unable to write key to {}: {:?}", NETWORK_KEY_FILENAME, e))?;
 
-    Ok(())
+    Ok(enr)
 }
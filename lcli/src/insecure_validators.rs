@@ -1,6 +1,7 @@
 use clap::ArgMatches;
 use std::fs;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use validator_dir::Builder as ValidatorBuilder;
 
 pub fn run(matches: &ArgMatches) -> Result<(), String> {
@@ -18,11 +19,22 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
             .map_err(|e| format!("Unable to create secrets dir: {:?}", e))?;
     }
 
-    for i in 0..validator_count {
-        println!("Validator {}/{}", i + 1, validator_count);
+    build_insecure_validators(0..validator_count, &validators_dir, &secrets_dir)
+}
+
+/// Produces a validator directory with an INSECURE, deterministic keypair for each of the
+/// `indices`.
+pub fn build_insecure_validators(
+    indices: Range<usize>,
+    validators_dir: &Path,
+    secrets_dir: &Path,
+) -> Result<(), String> {
+    let validator_count = indices.len();
+    for (n, i) in indices.enumerate() {
+        println!("Validator {}/{}", n + 1, validator_count);
 
-        ValidatorBuilder::new(validators_dir.clone())
-            .password_dir(secrets_dir.clone())
+        ValidatorBuilder::new(validators_dir.to_path_buf())
+            .password_dir(secrets_dir)
             .store_withdrawal_keystore(false)
             .insecure_voting_keypair(i)
             .map_err(|e| format!("Unable to generate keys: {:?}", e))?
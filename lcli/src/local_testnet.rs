@@ -0,0 +1,354 @@
+//! Bootstraps a local testnet with interop validators in a single command, optionally spawning
+//! and supervising all of the beacon nodes and validator clients.
+//!
+//! Produces the following layout in `--output-dir`:
+//!
+//! ```ignore
+//! testnet/                    # The testnet dir (config.yaml, genesis.ssz, boot_enr.yaml, ...).
+//! beacon_node_{i}/beacon/     # Beacon node data dirs, preloaded with a network key and ENR.
+//! validator_client_{i}/       # Validator client data dirs (validators/ and secrets/).
+//! logs/                       # Logs for each process, if --spawn is supplied.
+//! ```
+use crate::generate_bootnode_enr::write_bootnode_enr;
+use crate::insecure_validators::build_insecure_validators;
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_path_with_default_in_home_dir, parse_required};
+use directory::{
+    DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR, DEFAULT_SECRET_DIR,
+    DEFAULT_VALIDATOR_DIR,
+};
+use eth2_libp2p::discovery::{secp256k1, Keypair};
+use eth2_testnet_config::Eth2TestnetConfig;
+use genesis::interop_genesis_state;
+use std::convert::TryFrom;
+use std::fs::{self, File};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::{test_utils::generate_deterministic_keypairs, Address, EthSpec, Slot, YamlConfig};
+
+const TESTNET_DIR: &str = "testnet";
+const LOGS_DIR: &str = "logs";
+
+/// How often the supervisor checks that all spawned processes are still running.
+const SUPERVISOR_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
+    let output_dir = parse_path_with_default_in_home_dir(
+        matches,
+        "output-dir",
+        PathBuf::from(DEFAULT_ROOT_DIR).join("local-testnet"),
+    )?;
+    let validator_count: usize = parse_required(matches, "validator-count")?;
+    let beacon_node_count: usize = parse_required(matches, "beacon-nodes")?;
+    let validator_client_count: usize = parse_required(matches, "validator-clients")?;
+    let base_port: u16 = parse_required(matches, "base-port")?;
+    let base_http_port: u16 = parse_required(matches, "base-http-port")?;
+    let genesis_time = if let Some(genesis_time) = parse_optional(matches, "genesis-time")? {
+        genesis_time
+    } else {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to get time: {:?}", e))?
+            .as_secs()
+    };
+    let overwrite_files = matches.is_present("force");
+
+    if beacon_node_count == 0 || validator_client_count == 0 {
+        return Err("There must be at least one beacon node and validator client".into());
+    }
+
+    if output_dir.exists() {
+        if overwrite_files {
+            fs::remove_dir_all(&output_dir)
+                .map_err(|e| format!("Unable to remove {:?}: {:?}", output_dir, e))?;
+        } else {
+            return Err(format!(
+                "{:?} already exists, will not overwrite. Use --force to overwrite",
+                output_dir
+            ));
+        }
+    }
+
+    let mut spec = T::default_spec();
+    spec.min_genesis_active_validator_count = validator_count as u64;
+    spec.min_genesis_time = genesis_time;
+    if let Some(v) = clap_utils::parse_ssz_optional(matches, "genesis-fork-version")? {
+        spec.genesis_fork_version = v;
+    }
+
+    println!("Building genesis state with {} validators", validator_count);
+
+    let keypairs = generate_deterministic_keypairs(validator_count);
+    let genesis_state = interop_genesis_state::<T>(&keypairs, genesis_time, &spec)?;
+    let enr_fork_id = spec.enr_fork_id(Slot::new(0), genesis_state.genesis_validators_root);
+
+    // Check that every port is representable before writing anything.
+    offset_port(base_port, beacon_node_count.saturating_sub(1))?;
+    offset_port(base_http_port, beacon_node_count.saturating_sub(1))?;
+
+    let mut boot_enrs = Vec::with_capacity(beacon_node_count);
+    for i in 0..beacon_node_count {
+        let port = offset_port(base_port, i)?;
+        let network_dir = beacon_node_dir(&output_dir, i)
+            .join(DEFAULT_BEACON_NODE_DIR)
+            .join(DEFAULT_NETWORK_DIR);
+        boot_enrs.push(write_bootnode_enr::<T>(
+            deterministic_network_keypair(i)?,
+            IpAddr::V4(Ipv4Addr::LOCALHOST),
+            port,
+            port,
+            enr_fork_id.clone(),
+            &network_dir,
+        )?);
+    }
+
+    let testnet: Eth2TestnetConfig<T> = Eth2TestnetConfig {
+        deposit_contract_address: format!("{:?}", Address::zero()),
+        deposit_contract_deploy_block: 0,
+        boot_enr: Some(boot_enrs),
+        genesis_state: Some(genesis_state),
+        yaml_config: Some(YamlConfig::from_spec::<T>(&spec)),
+    };
+    let testnet_dir = output_dir.join(TESTNET_DIR);
+    testnet.write_to_file(testnet_dir.clone(), false)?;
+
+    println!("Testnet generated at {:?}", testnet_dir);
+
+    // Split the validators into contiguous ranges, with any remainder assigned to the last
+    // validator client.
+    let validators_per_client = validator_count / validator_client_count;
+    for i in 0..validator_client_count {
+        let start = i * validators_per_client;
+        let end = if i + 1 == validator_client_count {
+            validator_count
+        } else {
+            start + validators_per_client
+        };
+
+        println!(
+            "Generating validators {}..{} for validator client {}",
+            start, end, i
+        );
+
+        let vc_dir = validator_client_dir(&output_dir, i);
+        let validators_dir = vc_dir.join(DEFAULT_VALIDATOR_DIR);
+        let secrets_dir = vc_dir.join(DEFAULT_SECRET_DIR);
+        for dir in &[&validators_dir, &secrets_dir] {
+            fs::create_dir_all(dir).map_err(|e| format!("Unable to create {:?}: {:?}", dir, e))?;
+        }
+        build_insecure_validators(start..end, &validators_dir, &secrets_dir)?;
+    }
+
+    println!("Local testnet generated at {:?}", output_dir);
+
+    if matches.is_present("spawn") {
+        let lighthouse_bin: String = parse_required(matches, "lighthouse-bin")?;
+        let debug_level: String = parse_required(matches, "debug-level")?;
+
+        let processes = Processes {
+            spec_name: T::spec_name(),
+            output_dir: &output_dir,
+            lighthouse_bin: &lighthouse_bin,
+            debug_level: &debug_level,
+        };
+        processes.spawn_and_supervise(
+            beacon_node_count,
+            validator_client_count,
+            base_port,
+            base_http_port,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns the port `offset` above `base`, or an error if it is not a valid port.
+fn offset_port(base: u16, offset: usize) -> Result<u16, String> {
+    u16::try_from(offset)
+        .ok()
+        .and_then(|offset| base.checked_add(offset))
+        .ok_or_else(|| format!("Port {} + {} is out of range", base, offset))
+}
+
+/// Returns the network keypair for the beacon node with `index`, so that the ENRs and peer ids of
+/// the testnet are reproducible.
+///
+/// These keys are trivially guessable and must never be used outside of a local testnet.
+fn deterministic_network_keypair(index: usize) -> Result<Keypair, String> {
+    let mut bytes = [0; 32];
+    bytes[24..].copy_from_slice(&(index as u64 + 1).to_be_bytes());
+    secp256k1::SecretKey::from_bytes(&mut bytes)
+        .map(|secret| Keypair::Secp256k1(secret.into()))
+        .map_err(|e| format!("Unable to create network key: {:?}", e))
+}
+
+fn beacon_node_dir(output_dir: &Path, index: usize) -> PathBuf {
+    output_dir.join(format!("beacon_node_{}", index))
+}
+
+fn validator_client_dir(output_dir: &Path, index: usize) -> PathBuf {
+    output_dir.join(format!("validator_client_{}", index))
+}
+
+/// Spawns `lighthouse` processes for a local testnet.
+struct Processes<'a> {
+    spec_name: &'a str,
+    output_dir: &'a Path,
+    lighthouse_bin: &'a str,
+    debug_level: &'a str,
+}
+
+impl<'a> Processes<'a> {
+    /// Spawns all beacon nodes and validator clients, then waits until one of them exits.
+    ///
+    /// When any process exits, all other processes are killed and an error is returned.
+    fn spawn_and_supervise(
+        &self,
+        beacon_node_count: usize,
+        validator_client_count: usize,
+        base_port: u16,
+        base_http_port: u16,
+    ) -> Result<(), String> {
+        let logs_dir = self.output_dir.join(LOGS_DIR);
+        fs::create_dir_all(&logs_dir)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", logs_dir, e))?;
+
+        let mut children: Vec<(String, Child)> = vec![];
+        let result = self.spawn_all(
+            &mut children,
+            beacon_node_count,
+            validator_client_count,
+            base_port,
+            base_http_port,
+        );
+
+        let result = result.and_then(|()| {
+            println!(
+                "Spawned {} processes, logging to {:?}. Press Ctrl-C to exit.",
+                children.len(),
+                logs_dir
+            );
+            supervise(&mut children)
+        });
+
+        for (name, child) in &mut children {
+            if let Ok(None) = child.try_wait() {
+                println!("Killing {}", name);
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+        }
+
+        result
+    }
+
+    fn spawn_all(
+        &self,
+        children: &mut Vec<(String, Child)>,
+        beacon_node_count: usize,
+        validator_client_count: usize,
+        base_port: u16,
+        base_http_port: u16,
+    ) -> Result<(), String> {
+        for i in 0..beacon_node_count {
+            let port = offset_port(base_port, i)?.to_string();
+            let http_port = offset_port(base_http_port, i)?.to_string();
+            let datadir = beacon_node_dir(self.output_dir, i);
+            let args: &[&str] = &[
+                "bn",
+                "--datadir",
+                path_str(&datadir)?,
+                "--dummy-eth1",
+                "--http",
+                "--http-port",
+                &http_port,
+                "--port",
+                &port,
+                "--enr-address",
+                "127.0.0.1",
+                "--enr-udp-port",
+                &port,
+                "--enr-tcp-port",
+                &port,
+                "--disable-enr-auto-update",
+            ];
+            let name = format!("beacon_node_{}", i);
+            children.push((name.clone(), self.spawn(&name, args)?));
+        }
+
+        for i in 0..validator_client_count {
+            // Validator clients are assigned to beacon nodes in a round-robin fashion.
+            let beacon_node = format!(
+                "http://localhost:{}",
+                offset_port(base_http_port, i % beacon_node_count)?
+            );
+            let datadir = validator_client_dir(self.output_dir, i);
+            let args: &[&str] = &[
+                "vc",
+                "--datadir",
+                path_str(&datadir)?,
+                "--beacon-node",
+                &beacon_node,
+                "--init-slashing-protection",
+            ];
+            let name = format!("validator_client_{}", i);
+            children.push((name.clone(), self.spawn(&name, args)?));
+        }
+
+        Ok(())
+    }
+
+    /// Spawns a `lighthouse` process with the given subcommand `args`, logging to
+    /// `logs/{name}.log`.
+    fn spawn(&self, name: &str, args: &[&str]) -> Result<Child, String> {
+        let log_path = self.output_dir.join(LOGS_DIR).join(format!("{}.log", name));
+        let log_file = File::create(&log_path)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", log_path, e))?;
+        let stderr = log_file
+            .try_clone()
+            .map_err(|e| format!("Unable to clone log file handle: {:?}", e))?;
+        let testnet_dir = self.output_dir.join(TESTNET_DIR);
+
+        Command::new(self.lighthouse_bin)
+            .arg("--spec")
+            .arg(self.spec_name)
+            .arg("--debug-level")
+            .arg(self.debug_level)
+            .arg("--testnet-dir")
+            .arg(&testnet_dir)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(log_file)
+            .stderr(stderr)
+            .spawn()
+            .map_err(|e| {
+                format!(
+                    "Unable to spawn {} ({}): {:?}",
+                    name, self.lighthouse_bin, e
+                )
+            })
+    }
+}
+
+/// Blocks until one of the `children` exits, returning an error describing it.
+fn supervise(children: &mut [(String, Child)]) -> Result<(), String> {
+    loop {
+        for (name, child) in children.iter_mut() {
+            if let Some(status) = child
+                .try_wait()
+                .map_err(|e| format!("Unable to check status of {}: {:?}", name, e))?
+            {
+                return Err(format!("{} exited unexpectedly with {}", name, status));
+            }
+        }
+        thread::sleep(SUPERVISOR_POLL_INTERVAL);
+    }
+}
+
+fn path_str(path: &Path) -> Result<&str, String> {
+    path.to_str()
+        .ok_or_else(|| format!("Path {:?} is not valid UTF-8", path))
+}
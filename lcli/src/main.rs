@@ -7,8 +7,10 @@ mod eth1_genesis;
 mod generate_bootnode_enr;
 mod insecure_validators;
 mod interop_genesis;
+mod local_testnet;
 mod new_testnet;
 mod parse_hex;
+mod parse_ssz;
 mod refund_deposit_contract;
 mod skip_slots;
 mod transition_blocks;
@@ -153,6 +155,74 @@ fn main() {
                         .help("SSZ encoded as 0x-prefixed hex"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("parse-ssz")
+                .about(
+                    "Converts a consensus object between SSZ, JSON and YAML. By default, the \
+                    object is printed as YAML.",
+                )
+                .arg(
+                    Arg::with_name("type")
+                        .value_name("TYPE")
+                        .takes_value(true)
+                        .required(true)
+                        .possible_values(parse_ssz::TYPES)
+                        .help("The type of the object."),
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The path of the file containing the object."),
+                )
+                .arg(
+                    Arg::with_name("input-format")
+                        .long("input-format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(parse_ssz::FORMATS)
+                        .help(
+                            "The encoding of the input file. Defaults to JSON for .json files, \
+                            YAML for .yaml and .yml files and SSZ otherwise.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("output-format")
+                        .long("output-format")
+                        .value_name("FORMAT")
+                        .takes_value(true)
+                        .possible_values(parse_ssz::FORMATS)
+                        .help(
+                            "The encoding of the output. Defaults to YAML, unless \
+                            --tree-hash-root or --state-summary is supplied, in which case the \
+                            object is not printed.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .long("output")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help(
+                            "Write the output to this file instead of stdout. Required when \
+                            the output format is SSZ.",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("tree-hash-root")
+                        .long("tree-hash-root")
+                        .help("Print the tree hash root of the object."),
+                )
+                .arg(
+                    Arg::with_name("state-summary")
+                        .long("state-summary")
+                        .help(
+                            "Print summary statistics of a BeaconState, such as the number of \
+                            active validators and the total balance.",
+                        ),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("deploy-deposit-contract")
                 .about(
@@ -480,6 +550,114 @@ fn main() {
                         .help("The directory for storing secrets."),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("local-testnet")
+                .about(
+                    "Produces a complete local testnet: a testnet dir with a genesis state for \
+                    interop validators, validator directories split across validator clients and \
+                    pre-generated boot node ENRs. Optionally spawns and supervises all of the \
+                    beacon nodes and validator clients.",
+                )
+                .arg(
+                    Arg::with_name("output-dir")
+                        .long("output-dir")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("The directory in which to create the testnet. Defaults to \
+                              ~/.lighthouse/local-testnet"),
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .long("force")
+                        .short("f")
+                        .takes_value(false)
+                        .help("Deletes any existing output directory"),
+                )
+                .arg(
+                    Arg::with_name("validator-count")
+                        .long("validator-count")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("64")
+                        .help("The number of interop validators in the genesis state."),
+                )
+                .arg(
+                    Arg::with_name("beacon-nodes")
+                        .long("beacon-nodes")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("2")
+                        .help("The number of beacon nodes."),
+                )
+                .arg(
+                    Arg::with_name("validator-clients")
+                        .long("validator-clients")
+                        .value_name("INTEGER")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("The number of validator clients. Validators are split evenly \
+                              between them."),
+                )
+                .arg(
+                    Arg::with_name("genesis-time")
+                        .long("genesis-time")
+                        .short("t")
+                        .value_name("UNIX_EPOCH")
+                        .takes_value(true)
+                        .help("The value for state.genesis_time. Defaults to now."),
+                )
+                .arg(
+                    Arg::with_name("genesis-fork-version")
+                        .long("genesis-fork-version")
+                        .value_name("HEX")
+                        .takes_value(true)
+                        .help("Used to avoid reply attacks between testnets. Recommended to set to
+                              non-default."),
+                )
+                .arg(
+                    Arg::with_name("base-port")
+                        .long("base-port")
+                        .value_name("PORT")
+                        .takes_value(true)
+                        .default_value("9000")
+                        .help("The libp2p and discovery port of the first beacon node. Each \
+                              subsequent beacon node uses the next port."),
+                )
+                .arg(
+                    Arg::with_name("base-http-port")
+                        .long("base-http-port")
+                        .value_name("PORT")
+                        .takes_value(true)
+                        .default_value("5052")
+                        .help("The HTTP API port of the first beacon node. Each subsequent \
+                              beacon node uses the next port."),
+                )
+                .arg(
+                    Arg::with_name("spawn")
+                        .long("spawn")
+                        .takes_value(false)
+                        .help("Spawns all beacon nodes and validator clients once the testnet \
+                              has been generated, logging to the logs/ directory. If any \
+                              process exits, all others are killed."),
+                )
+                .arg(
+                    Arg::with_name("lighthouse-bin")
+                        .long("lighthouse-bin")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .default_value("lighthouse")
+                        .help("The lighthouse binary used by --spawn."),
+                )
+                .arg(
+                    Arg::with_name("debug-level")
+                        .long("debug-level")
+                        .value_name("LEVEL")
+                        .takes_value(true)
+                        .possible_values(&["info", "debug", "trace", "warn", "error", "crit"])
+                        .default_value("info")
+                        .help("The debug level of the processes spawned by --spawn."),
+                )
+        )
         .get_matches();
 
     macro_rules! run_with_spec {
@@ -564,6 +742,8 @@ fn run<T: EthSpec>(
         ("pretty-hex", Some(matches)) => {
             run_parse_hex::<T>(matches).map_err(|e| format!("Failed to pretty print hex: {}", e))
         }
+        ("parse-ssz", Some(matches)) => parse_ssz::run::<T>(matches)
+            .map_err(|e| format!("Failed to run parse-ssz command: {}", e)),
         ("deploy-deposit-contract", Some(matches)) => {
             deploy_deposit_contract::run::<T>(env, matches)
                 .map_err(|e| format!("Failed to run deploy-deposit-contract command: {}", e))
@@ -586,6 +766,8 @@ fn run<T: EthSpec>(
             .map_err(|e| format!("Failed to run generate-bootnode-enr command: {}", e)),
         ("insecure-validators", Some(matches)) => insecure_validators::run(matches)
             .map_err(|e| format!("Failed to run insecure-validators command: {}", e)),
+        ("local-testnet", Some(matches)) => local_testnet::run::<T>(matches)
+            .map_err(|e| format!("Failed to run local-testnet command: {}", e)),
         (other, _) => Err(format!("Unknown subcommand {}. See --help.", other)),
     }
 }
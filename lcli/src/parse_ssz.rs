@@ -1,40 +1,380 @@
-use crate::helpers::parse_path;
+//! Converts consensus objects between SSZ, JSON and YAML.
+//!
+//! Optionally prints the `tree_hash_root` of the object and, for a `BeaconState`, some summary
+//! statistics.
+
 use clap::ArgMatches;
-use serde::Serialize;
-use ssz::Decode;
-use std::fs::File;
-use std::io::Read;
-use types::{EthSpec, SignedBeaconBlock};
+use serde::{de::DeserializeOwned, Serialize};
+use ssz::{Decode, Encode};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+use types::{
+    AggregateAndProof, Attestation, AttestationData, AttesterSlashing, BeaconBlock,
+    BeaconBlockBody, BeaconBlockHeader, BeaconState, Checkpoint, Deposit, DepositData,
+    DepositMessage, EnrForkId, Eth1Data, EthSpec, Fork, ForkData, Hash256, HistoricalBatch,
+    IndexedAttestation, PendingAttestation, ProposerSlashing, SignedAggregateAndProof,
+    SignedBeaconBlock, SignedBeaconBlockHeader, SignedVoluntaryExit, SigningData, Validator,
+    VoluntaryExit,
+};
+
+/// The names of all types supported by the `parse-ssz` command.
+pub const TYPES: &[&str] = &[
+    "AggregateAndProof",
+    "Attestation",
+    "AttestationData",
+    "AttesterSlashing",
+    "BeaconBlock",
+    "BeaconBlockBody",
+    "BeaconBlockHeader",
+    "BeaconState",
+    "Checkpoint",
+    "Deposit",
+    "DepositData",
+    "DepositMessage",
+    "EnrForkId",
+    "Eth1Data",
+    "Fork",
+    "ForkData",
+    "HistoricalBatch",
+    "IndexedAttestation",
+    "PendingAttestation",
+    "ProposerSlashing",
+    "SignedAggregateAndProof",
+    "SignedBeaconBlock",
+    "SignedBeaconBlockHeader",
+    "SignedVoluntaryExit",
+    "SigningData",
+    "Validator",
+    "VoluntaryExit",
+];
+
+/// The names of all supported encodings.
+pub const FORMATS: &[&str] = &["ssz", "json", "yaml"];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Ssz,
+    Json,
+    Yaml,
+}
+
+impl Format {
+    fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "ssz" => Ok(Format::Ssz),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            other => Err(format!("Unknown format: {}", other)),
+        }
+    }
+
+    /// Infers the format from the extension of `path`, defaulting to SSZ.
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Format::Json,
+            Some("yaml") | Some("yml") => Format::Yaml,
+            _ => Format::Ssz,
+        }
+    }
+}
+
+struct Options {
+    input: PathBuf,
+    input_format: Format,
+    output: Option<PathBuf>,
+    output_format: Option<Format>,
+    tree_hash_root: bool,
+    state_summary: bool,
+}
 
 pub fn run<T: EthSpec>(matches: &ArgMatches) -> Result<(), String> {
-    let type_str = matches
-        .value_of("type")
-        .ok_or_else(|| "No type supplied".to_string())?;
-    let path = parse_path(matches, "path")?;
+    let type_str: String = clap_utils::parse_required(matches, "type")?;
+    let input: PathBuf = clap_utils::parse_required(matches, "input")?;
+    let input_format = matches
+        .value_of("input-format")
+        .map(Format::parse)
+        .transpose()?
+        .unwrap_or_else(|| Format::from_path(&input));
+    let output = clap_utils::parse_optional(matches, "output")?;
+    let output_format = matches
+        .value_of("output-format")
+        .map(Format::parse)
+        .transpose()?;
+    let tree_hash_root = matches.is_present("tree-hash-root");
+    let state_summary = matches.is_present("state-summary");
 
-    info!("Type: {:?}", type_str);
+    if state_summary && type_str != "BeaconState" {
+        return Err("--state-summary is only supported for BeaconState".to_string());
+    }
 
-    let mut bytes = vec![];
-    let mut file = File::open(&path).map_err(|e| format!("Unable to open {:?}: {}", path, e))?;
-    file.read_to_end(&mut bytes)
-        .map_err(|e| format!("Unable to read {:?}: {}", path, e))?;
+    if output_format == Some(Format::Ssz) && output.is_none() {
+        return Err("--output is required when writing SSZ".to_string());
+    }
 
-    match type_str {
-        "SignedBeaconBlock" => decode_and_print::<SignedBeaconBlock<T>>(&bytes)?,
-        other => return Err(format!("Unknown type: {}", other)),
+    let options = Options {
+        input,
+        input_format,
+        output,
+        output_format,
+        tree_hash_root,
+        state_summary,
     };
 
-    Ok(())
+    info!("Using {} spec", T::spec_name());
+    info!("Type: {:?}", type_str);
+
+    match type_str.as_str() {
+        "AggregateAndProof" => convert::<AggregateAndProof<T>>(&options).map(drop),
+        "Attestation" => convert::<Attestation<T>>(&options).map(drop),
+        "AttestationData" => convert::<AttestationData>(&options).map(drop),
+        "AttesterSlashing" => convert::<AttesterSlashing<T>>(&options).map(drop),
+        "BeaconBlock" => convert::<BeaconBlock<T>>(&options).map(drop),
+        "BeaconBlockBody" => convert::<BeaconBlockBody<T>>(&options).map(drop),
+        "BeaconBlockHeader" => convert::<BeaconBlockHeader>(&options).map(drop),
+        "BeaconState" => {
+            let state = convert::<BeaconState<T>>(&options)?;
+            if options.state_summary {
+                print_state_summary(&state);
+            }
+            Ok(())
+        }
+        "Checkpoint" => convert::<Checkpoint>(&options).map(drop),
+        "Deposit" => convert::<Deposit>(&options).map(drop),
+        "DepositData" => convert::<DepositData>(&options).map(drop),
+        "DepositMessage" => convert::<DepositMessage>(&options).map(drop),
+        "EnrForkId" => convert::<EnrForkId>(&options).map(drop),
+        "Eth1Data" => convert::<Eth1Data>(&options).map(drop),
+        "Fork" => convert::<Fork>(&options).map(drop),
+        "ForkData" => convert::<ForkData>(&options).map(drop),
+        "HistoricalBatch" => convert::<HistoricalBatch<T>>(&options).map(drop),
+        "IndexedAttestation" => convert::<IndexedAttestation<T>>(&options).map(drop),
+        "PendingAttestation" => convert::<PendingAttestation<T>>(&options).map(drop),
+        "ProposerSlashing" => convert::<ProposerSlashing>(&options).map(drop),
+        "SignedAggregateAndProof" => convert::<SignedAggregateAndProof<T>>(&options).map(drop),
+        "SignedBeaconBlock" => convert::<SignedBeaconBlock<T>>(&options).map(drop),
+        "SignedBeaconBlockHeader" => convert::<SignedBeaconBlockHeader>(&options).map(drop),
+        "SignedVoluntaryExit" => convert::<SignedVoluntaryExit>(&options).map(drop),
+        "SigningData" => convert::<SigningData>(&options).map(drop),
+        "Validator" => convert::<Validator>(&options).map(drop),
+        "VoluntaryExit" => convert::<VoluntaryExit>(&options).map(drop),
+        other => Err(format!("Unknown type: {}", other)),
+    }
 }
 
-fn decode_and_print<T: Decode + Serialize>(bytes: &[u8]) -> Result<(), String> {
-    let item = T::from_ssz_bytes(&bytes).map_err(|e| format!("Ssz decode failed: {:?}", e))?;
+/// Decodes the input file and writes it in the output format, returning the decoded object.
+///
+/// If no output format is supplied, the object is printed as YAML unless only the tree hash root
+/// or the state summary was requested.
+fn convert<T>(options: &Options) -> Result<T, String>
+where
+    T: Encode + Decode + Serialize + DeserializeOwned + TreeHash,
+{
+    let bytes = fs::read(&options.input)
+        .map_err(|e| format!("Unable to read {:?}: {}", options.input, e))?;
+
+    let item = decode::<T>(&bytes, options.input_format)?;
+
+    let output_format = options.output_format.or_else(|| {
+        if options.tree_hash_root || options.state_summary {
+            None
+        } else {
+            Some(Format::Yaml)
+        }
+    });
+
+    if let Some(format) = output_format {
+        let encoded = encode(&item, format)?;
+
+        match &options.output {
+            Some(path) => fs::write(path, &encoded)
+                .map_err(|e| format!("Unable to write {:?}: {}", path, e))?,
+            None => {
+                let mut stdout = io::stdout();
+                stdout
+                    .write_all(&encoded)
+                    .and_then(|()| stdout.write_all(b"\n"))
+                    .map_err(|e| format!("Unable to write to stdout: {}", e))?
+            }
+        }
+    }
+
+    if options.tree_hash_root {
+        println!("tree_hash_root: {:?}", item.tree_hash_root());
+    }
+
+    Ok(item)
+}
+
+fn decode<T: Decode + DeserializeOwned>(bytes: &[u8], format: Format) -> Result<T, String> {
+    match format {
+        Format::Ssz => T::from_ssz_bytes(bytes).map_err(|e| format!("SSZ decode failed: {:?}", e)),
+        Format::Json => {
+            serde_json::from_slice(bytes).map_err(|e| format!("JSON decode failed: {}", e))
+        }
+        Format::Yaml => {
+            serde_yaml::from_slice(bytes).map_err(|e| format!("YAML decode failed: {}", e))
+        }
+    }
+}
+
+fn encode<T: Encode + Serialize>(item: &T, format: Format) -> Result<Vec<u8>, String> {
+    match format {
+        Format::Ssz => Ok(item.as_ssz_bytes()),
+        Format::Json => serde_json::to_vec_pretty(item)
+            .map_err(|e| format!("Unable to write object to JSON: {}", e)),
+        Format::Yaml => {
+            serde_yaml::to_vec(item).map_err(|e| format!("Unable to write object to YAML: {}", e))
+        }
+    }
+}
+
+/// Returns the root of the latest block applied to `state`.
+///
+/// The `state_root` of `state.latest_block_header` is zero until the next slot is processed, in
+/// which case it must be filled in with the root of `state` itself.
+fn latest_block_root<T: EthSpec>(state: &BeaconState<T>) -> Hash256 {
+    let mut header = state.latest_block_header.clone();
+    if header.state_root == Hash256::zero() {
+        header.state_root = state.canonical_root();
+    }
+    header.canonical_root()
+}
 
+fn print_state_summary<T: EthSpec>(state: &BeaconState<T>) {
+    let epoch = state.current_epoch();
+    let active_validators = state
+        .validators
+        .iter()
+        .filter(|validator| validator.is_active_at(epoch))
+        .collect::<Vec<_>>();
+    let total_active_balance = active_validators
+        .iter()
+        .map(|validator| validator.effective_balance)
+        .sum::<u64>();
+    let total_balance = state.balances.iter().sum::<u64>();
+
+    println!("slot: {}", state.slot);
+    println!("epoch: {}", epoch);
+    println!("genesis_time: {}", state.genesis_time);
+    println!("fork: {:?}", state.fork);
+    println!("latest_block_root: {:?}", latest_block_root(state));
+    println!("validators: {}", state.validators.len());
+    println!("active_validators: {}", active_validators.len());
+    println!(
+        "slashed_validators: {}",
+        state.validators.iter().filter(|v| v.slashed).count()
+    );
+    println!(
+        "exited_validators: {}",
+        state
+            .validators
+            .iter()
+            .filter(|v| v.is_exited_at(epoch))
+            .count()
+    );
+    println!("total_balance: {}", total_balance);
+    println!("total_active_balance: {}", total_active_balance);
+    println!("eth1_deposit_index: {}", state.eth1_deposit_index);
+    println!("eth1_data: {:?}", state.eth1_data);
+    println!(
+        "previous_epoch_attestations: {}",
+        state.previous_epoch_attestations.len()
+    );
     println!(
-        "{}",
-        serde_yaml::to_string(&item)
-            .map_err(|e| format!("Unable to write object to YAML: {:?}", e))?
+        "current_epoch_attestations: {}",
+        state.current_epoch_attestations.len()
     );
+    println!(
+        "previous_justified_checkpoint: {:?}",
+        state.previous_justified_checkpoint
+    );
+    println!(
+        "current_justified_checkpoint: {:?}",
+        state.current_justified_checkpoint
+    );
+    println!("finalized_checkpoint: {:?}", state.finalized_checkpoint);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Debug;
+    use types::{
+        test_utils::{test_random_instance, TestRandom},
+        MinimalEthSpec,
+    };
+
+    type E = MinimalEthSpec;
+
+    /// Checks that a random `T` survives encoding and decoding in every format.
+    fn round_trip<T>()
+    where
+        T: Encode + Decode + Serialize + DeserializeOwned + TestRandom + PartialEq + Debug,
+    {
+        let item = test_random_instance::<T>();
+        for format in &[Format::Ssz, Format::Json, Format::Yaml] {
+            let bytes = encode(&item, *format).unwrap();
+            assert_eq!(decode::<T>(&bytes, *format).unwrap(), item, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn round_trip_beacon_state() {
+        round_trip::<BeaconState<E>>();
+    }
+
+    #[test]
+    fn round_trip_beacon_block() {
+        round_trip::<BeaconBlock<E>>();
+        round_trip::<SignedBeaconBlock<E>>();
+        round_trip::<SignedBeaconBlockHeader>();
+    }
+
+    #[test]
+    fn round_trip_attestation() {
+        round_trip::<Attestation<E>>();
+        round_trip::<IndexedAttestation<E>>();
+        round_trip::<SignedAggregateAndProof<E>>();
+    }
+
+    #[test]
+    fn round_trip_slashing() {
+        round_trip::<AttesterSlashing<E>>();
+        round_trip::<ProposerSlashing>();
+    }
+
+    #[test]
+    fn round_trip_deposit() {
+        round_trip::<Deposit>();
+        round_trip::<DepositData>();
+    }
+
+    #[test]
+    fn round_trip_validator() {
+        round_trip::<Validator>();
+        round_trip::<SignedVoluntaryExit>();
+    }
+
+    #[test]
+    fn latest_block_root_fills_in_state_root() {
+        let mut state = test_random_instance::<BeaconState<E>>();
+
+        let header = state.latest_block_header.clone();
+        assert_eq!(latest_block_root(&state), header.canonical_root());
+
+        state.latest_block_header.state_root = Hash256::zero();
+        let mut expected = state.latest_block_header.clone();
+        expected.state_root = state.canonical_root();
+        assert_ne!(expected.state_root, Hash256::zero());
+        assert_eq!(latest_block_root(&state), expected.canonical_root());
+    }
 
-    Ok(())
+    #[test]
+    fn decode_rejects_invalid_input() {
+        for format in &[Format::Ssz, Format::Json, Format::Yaml] {
+            assert!(decode::<Checkpoint>(b"[", *format).is_err());
+        }
+    }
 }
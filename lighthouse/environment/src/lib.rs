@@ -15,12 +15,14 @@ use futures::channel::{
 };
 use futures::{future, StreamExt};
 
-use slog::{info, o, Drain, Level, Logger};
+use logging::{LogFilterHandle, RotatingFile, RotationConfig, RuntimeLevelFilter};
+use slog::{info, o, Drain, Logger};
 use sloggers::{null::NullLoggerBuilder, Build};
 use std::cell::RefCell;
 use std::ffi::OsStr;
-use std::fs::{rename as FsRename, OpenOptions};
-use std::path::PathBuf;
+use std::fs::{rename as FsRename, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 use task_executor::TaskExecutor;
 use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
@@ -35,6 +37,7 @@ const MAXIMUM_SHUTDOWN_TIME: u64 = 3;
 pub struct EnvironmentBuilder<E: EthSpec> {
     runtime: Option<Runtime>,
     log: Option<Logger>,
+    log_filter: Option<LogFilterHandle>,
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     testnet: Option<Eth2TestnetConfig<E>>,
@@ -46,6 +49,7 @@ impl EnvironmentBuilder<MinimalEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_filter: None,
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             testnet: None,
@@ -59,6 +63,7 @@ impl EnvironmentBuilder<MainnetEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_filter: None,
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             testnet: None,
@@ -72,6 +77,7 @@ impl EnvironmentBuilder<InteropEthSpec> {
         Self {
             runtime: None,
             log: None,
+            log_filter: None,
             eth_spec_instance: InteropEthSpec,
             eth2_config: Eth2Config::interop(),
             testnet: None,
@@ -120,9 +126,12 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
     /// The logger is "async" because it has a dedicated thread that accepts logs and then
     /// asynchronously flushes them to stdout/files/etc. This means the thread that raised the log
     /// does not have to wait for the logs to be flushed.
+    ///
+    /// The `log_filter` is either a level (e.g., `info`) or a list of per-module levels (e.g.,
+    /// `info,network=debug`) which may be changed once the logger is running.
     pub fn async_logger(
         mut self,
-        debug_level: &str,
+        log_filter: &str,
         log_format: Option<&str>,
     ) -> Result<Self, String> {
         // Setting up the initial logger format and building it.
@@ -146,51 +155,35 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let log_filter = LogFilterHandle::new(log_filter.parse()?);
+        let drain = RuntimeLevelFilter::new(drain, log_filter.clone());
 
         self.log = Some(Logger::root(drain.fuse(), o!()));
+        self.log_filter = Some(log_filter);
         Ok(self)
     }
 
     /// Sets the logger (and all child loggers) to log to a file.
+    ///
+    /// If `rotation` is enabled, the file is rotated according to it. Otherwise, any existing file
+    /// is backed up and a single file is written to indefinitely.
+    ///
+    /// See `Self::async_logger` for a description of `log_filter`.
     pub fn log_to_file(
         mut self,
         path: PathBuf,
-        debug_level: &str,
+        log_filter: &str,
         log_format: Option<&str>,
+        rotation: RotationConfig,
     ) -> Result<Self, String> {
-        // Creating a backup if the logfile already exists.
-        if path.exists() {
-            let start = SystemTime::now();
-            let timestamp = start
-                .duration_since(UNIX_EPOCH)
-                .map_err(|e| e.to_string())?
-                .as_secs();
-            let file_stem = path
-                .file_stem()
-                .ok_or_else(|| "Invalid file name".to_string())?
-                .to_str()
-                .ok_or_else(|| "Failed to create str from filename".to_string())?;
-            let file_ext = path.extension().unwrap_or_else(|| OsStr::new(""));
-            let backup_name = format!("{}_backup_{}", file_stem, timestamp);
-            let backup_path = path.with_file_name(backup_name).with_extension(file_ext);
-            FsRename(&path, &backup_path).map_err(|e| e.to_string())?;
-        }
-
-        let file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(&path)
-            .map_err(|e| format!("Unable to open logfile: {:?}", e))?;
+        let file: Box<dyn Write + Send> = if rotation.is_enabled() {
+            Box::new(
+                RotatingFile::open(path.clone(), rotation.clone())
+                    .map_err(|e| format!("Unable to open logfile: {:?}", e))?,
+            )
+        } else {
+            Box::new(open_logfile(&path)?)
+        };
 
         // Setting up the initial logger format and building it.
         let drain = if let Some(format) = log_format {
@@ -213,24 +206,22 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let drain = match debug_level {
-            "info" => drain.filter_level(Level::Info),
-            "debug" => drain.filter_level(Level::Debug),
-            "trace" => drain.filter_level(Level::Trace),
-            "warn" => drain.filter_level(Level::Warning),
-            "error" => drain.filter_level(Level::Error),
-            "crit" => drain.filter_level(Level::Critical),
-            unknown => return Err(format!("Unknown debug-level: {}", unknown)),
-        };
+        let log_filter = LogFilterHandle::new(log_filter.parse()?);
+        let drain = RuntimeLevelFilter::new(drain, log_filter.clone());
 
         let log = Logger::root(drain.fuse(), o!());
         info!(
             log,
             "Logging to file";
-            "path" => format!("{:?}", path)
+            "path" => format!("{:?}", path),
+            "max_size" => rotation.max_size,
+            "max_age_secs" => rotation.max_age.map(|age| age.as_secs()),
+            "max_files" => rotation.max_files,
+            "compress" => rotation.compress,
         );
 
         self.log = Some(log);
+        self.log_filter = Some(log_filter);
 
         Ok(self)
     }
@@ -285,6 +276,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             log: self
                 .log
                 .ok_or_else(|| "Cannot build environment without log".to_string())?,
+            log_filter: self.log_filter,
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             testnet: self.testnet,
@@ -301,6 +293,8 @@ pub struct RuntimeContext<E: EthSpec> {
     pub executor: TaskExecutor,
     pub eth_spec_instance: E,
     pub eth2_config: Eth2Config,
+    /// Allows the log levels to be changed at runtime. `None` if the logger does not support it.
+    pub log_filter: Option<LogFilterHandle>,
 }
 
 impl<E: EthSpec> RuntimeContext<E> {
@@ -312,6 +306,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             executor: self.executor.clone_with_name(service_name),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            log_filter: self.log_filter.clone(),
         }
     }
 
@@ -337,6 +332,7 @@ pub struct Environment<E: EthSpec> {
     signal: Option<exit_future::Signal>,
     exit: exit_future::Exit,
     log: Logger,
+    log_filter: Option<LogFilterHandle>,
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub testnet: Option<Eth2TestnetConfig<E>>,
@@ -362,6 +358,7 @@ impl<E: EthSpec> Environment<E> {
             ),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            log_filter: self.log_filter.clone(),
         }
     }
 
@@ -376,6 +373,7 @@ impl<E: EthSpec> Environment<E> {
             ),
             eth_spec_instance: self.eth_spec_instance.clone(),
             eth2_config: self.eth2_config.clone(),
+            log_filter: self.log_filter.clone(),
         }
     }
 
@@ -438,6 +436,34 @@ impl<E: EthSpec> Environment<E> {
     }
 }
 
+/// Opens a new logfile at `path`, first backing up any existing file.
+fn open_logfile(path: &Path) -> Result<File, String> {
+    // Creating a backup if the logfile already exists.
+    if path.exists() {
+        let start = SystemTime::now();
+        let timestamp = start
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs();
+        let file_stem = path
+            .file_stem()
+            .ok_or_else(|| "Invalid file name".to_string())?
+            .to_str()
+            .ok_or_else(|| "Failed to create str from filename".to_string())?;
+        let file_ext = path.extension().unwrap_or_else(|| OsStr::new(""));
+        let backup_name = format!("{}_backup_{}", file_stem, timestamp);
+        let backup_path = path.with_file_name(backup_name).with_extension(file_ext);
+        FsRename(path, &backup_path).map_err(|e| e.to_string())?;
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(true)
+        .open(path)
+        .map_err(|e| format!("Unable to open logfile: {:?}", e))
+}
+
 pub fn null_logger() -> Result<Logger, String> {
     let log_builder = NullLoggerBuilder;
     log_builder
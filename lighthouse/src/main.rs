@@ -4,9 +4,11 @@ use env_logger::{Builder, Env};
 use environment::EnvironmentBuilder;
 use eth2_testnet_config::{Eth2TestnetConfig, DEFAULT_HARDCODED_TESTNET};
 use lighthouse_version::VERSION;
+use logging::RotationConfig;
 use slog::{crit, info, warn};
 use std::path::PathBuf;
 use std::process::exit;
+use std::time::Duration;
 use types::EthSpec;
 use validator_client::ProductionValidatorClient;
 
@@ -66,6 +68,46 @@ fn main() {
                 )
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("logfile-max-size")
+                .long("logfile-max-size")
+                .value_name("MEGABYTES")
+                .help(
+                    "Rotate the --logfile once it reaches this size.",
+                )
+                .requires("logfile")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("logfile-max-age")
+                .long("logfile-max-age")
+                .value_name("HOURS")
+                .help(
+                    "Rotate the --logfile once it has been written to for this long.",
+                )
+                .requires("logfile")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("logfile-max-number")
+                .long("logfile-max-number")
+                .value_name("COUNT")
+                .help(
+                    "The number of rotated log files to keep. Older files are deleted. \
+                    Defaults to 5.",
+                )
+                .requires("logfile")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("logfile-compress")
+                .long("logfile-compress")
+                .help(
+                    "Compress rotated log files with gzip.",
+                )
+                .requires("logfile")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("log-format")
                 .long("log-format")
@@ -84,6 +126,18 @@ fn main() {
                 .global(true)
                 .default_value("info"),
         )
+        .arg(
+            Arg::with_name("log-filter")
+                .long("log-filter")
+                .value_name("DIRECTIVES")
+                .help(
+                    "Comma-separated per-module verbosity levels which override --debug-level \
+                    for those modules (e.g., network=debug,beacon_chain=trace). The levels can be \
+                    changed at runtime via the /lighthouse/logging HTTP API endpoint.",
+                )
+                .takes_value(true)
+                .global(true),
+        )
         .arg(
             Arg::with_name("datadir")
                 .long("datadir")
@@ -187,6 +241,11 @@ fn run<E: EthSpec>(
         .value_of("debug-level")
         .ok_or_else(|| "Expected --debug-level flag".to_string())?;
 
+    let log_filter = match matches.value_of("log-filter") {
+        Some(log_filter) => format!("{},{}", debug_level, log_filter),
+        None => debug_level.to_string(),
+    };
+
     let log_format = matches.value_of("log-format");
 
     // Parse testnet config from the `testnet` and `testnet-dir` flag in that order
@@ -206,9 +265,29 @@ fn run<E: EthSpec>(
         let path = log_path
             .parse::<PathBuf>()
             .map_err(|e| format!("Failed to parse log path: {:?}", e))?;
-        environment_builder.log_to_file(path, debug_level, log_format)?
+        let rotation = RotationConfig {
+            max_size: clap_utils::parse_optional::<u64>(matches, "logfile-max-size")?
+                .map(|megabytes| {
+                    megabytes
+                        .checked_mul(1024 * 1024)
+                        .ok_or_else(|| format!("--logfile-max-size is too large: {}", megabytes))
+                })
+                .transpose()?,
+            max_age: clap_utils::parse_optional::<u64>(matches, "logfile-max-age")?
+                .map(|hours| {
+                    hours
+                        .checked_mul(60 * 60)
+                        .map(Duration::from_secs)
+                        .ok_or_else(|| format!("--logfile-max-age is too large: {}", hours))
+                })
+                .transpose()?,
+            max_files: clap_utils::parse_optional(matches, "logfile-max-number")?
+                .unwrap_or_else(|| RotationConfig::default().max_files),
+            compress: matches.is_present("logfile-compress"),
+        };
+        environment_builder.log_to_file(path, &log_filter, log_format, rotation)?
     } else {
-        environment_builder.async_logger(debug_level, log_format)?
+        environment_builder.async_logger(&log_filter, log_format)?
     };
 
     let mut environment = builder
@@ -268,6 +347,16 @@ fn run<E: EthSpec>(
                 &context.eth2_config().spec,
                 context.log().clone(),
             )?;
+
+            if let Some(sub_matches) = matches.subcommand_matches(beacon_node::eth1_cache::CMD) {
+                // Exit as soon as the command completes, without starting the beacon node.
+                return environment.runtime().block_on(beacon_node::eth1_cache::run(
+                    sub_matches,
+                    config,
+                    context,
+                ));
+            }
+
             environment.runtime().spawn(async move {
                 if let Err(e) = ProductionBeaconNode::new(context.clone(), config).await {
                     crit!(log, "Failed to start beacon node"; "reason" => e);
@@ -3,7 +3,12 @@
 use account_manager::{
     validator::{
         create::*,
+        deposit::{self, CMD as DEPOSIT_CMD},
+        deposit_data::{self, LaunchpadDepositData, CMD as DEPOSIT_DATA_CMD},
         import::{self, CMD as IMPORT_CMD},
+        modify_keystore::{self, CMD as MODIFY_KEYSTORE_CMD},
+        recover::{FIRST_INDEX_FLAG, MNEMONIC_FLAG},
+        withdrawal::{self, CMD as WITHDRAWAL_CMD},
         CMD as VALIDATOR_CMD,
     },
     wallet::{
@@ -14,33 +19,47 @@ use account_manager::{
     CMD as ACCOUNT_CMD, WALLETS_DIR_FLAG, *,
 };
 use account_utils::{
-    eth2_keystore::KeystoreBuilder,
+    eth2_keystore::{
+        json_keystore::{Kdf, Pbkdf2, Prf},
+        Keystore, KeystoreBuilder, DKLEN,
+    },
     validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
     ZeroizeString,
 };
+use bls::get_withdrawal_credentials;
+use environment::EnvironmentBuilder;
+use eth1_test_rig::GanacheEth1Instance;
+use eth2_testnet_config::{Eth2TestnetConfig, YAML_CONFIG_FILE};
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::env;
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output, Stdio};
 use std::str::from_utf8;
+use std::sync::{Arc, Mutex};
+use std::thread;
 use tempfile::{tempdir, TempDir};
-use types::{Keypair, PublicKey};
-use validator_dir::ValidatorDir;
-
-// TODO: create tests for the `lighthouse account validator deposit` command. This involves getting
-// access to an IPC endpoint during testing or adding support for deposit submission via HTTP and
-// using ganache-cli.
+use types::{
+    Epoch, EthSpec, Hash256, Keypair, MainnetEthSpec, PublicKey, PublicKeyBytes, Validator,
+    YamlConfig,
+};
+use validator_dir::{ValidatorDir, ETH1_DEPOSIT_TX_HASH_FILE, WITHDRAWAL_KEYSTORE_FILE};
 
-/// Returns the `lighthouse account` command.
-fn account_cmd() -> Command {
+/// Returns the `lighthouse` command.
+fn lighthouse_cmd() -> Command {
     let target_dir = env!("CARGO_BIN_EXE_lighthouse");
     let path = target_dir
         .parse::<PathBuf>()
         .expect("should parse CARGO_TARGET_DIR");
 
-    let mut cmd = Command::new(path);
+    Command::new(path)
+}
+
+/// Returns the `lighthouse account` command.
+fn account_cmd() -> Command {
+    let mut cmd = lighthouse_cmd();
     cmd.arg(ACCOUNT_CMD);
     cmd
 }
@@ -487,6 +506,7 @@ fn validator_import_launchpad() {
             voting_keystore_path,
             voting_keystore_password_path: None,
             voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+            voting_keystore_password_source: None,
         },
     };
 
@@ -496,6 +516,733 @@ fn validator_import_launchpad() {
     );
 }
 
+#[test]
+fn validator_deposit_data_generate_and_verify() {
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+                            abandon abandon abandon about";
+    const PASSWORD: &str = "cats-and-dogs-and-mice";
+    const FIRST_INDEX: usize = 3;
+    const COUNT: usize = 2;
+
+    let input_dir = tempdir().unwrap();
+    let output_dir = tempdir().unwrap();
+
+    let mnemonic_path = input_dir.path().join("mnemonic");
+    let password_path = input_dir.path().join("password");
+    fs::write(&mnemonic_path, MNEMONIC).unwrap();
+    fs::write(&password_path, PASSWORD).unwrap();
+
+    let deposit_data_cmd = |subcommand: &str| {
+        let mut cmd = validator_cmd();
+        cmd.arg(DEPOSIT_DATA_CMD)
+            .arg(subcommand)
+            .arg(format!("--{}", MNEMONIC_FLAG))
+            .arg(mnemonic_path.as_os_str())
+            .arg(format!("--{}", FIRST_INDEX_FLAG))
+            .arg(FIRST_INDEX.to_string())
+            .arg(format!("--{}", deposit_data::KEYSTORE_PASSWORD_FLAG))
+            .arg(password_path.as_os_str());
+        cmd
+    };
+
+    output_result(
+        deposit_data_cmd(deposit_data::GENERATE_CMD)
+            .arg(format!("--{}", COUNT_FLAG))
+            .arg(COUNT.to_string())
+            .arg(format!("--{}", deposit_data::OUTPUT_DIR_FLAG))
+            .arg(output_dir.path().as_os_str()),
+    )
+    .unwrap();
+
+    let file_names = fs::read_dir(output_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(file_names.len(), COUNT + 1);
+
+    let deposit_data_path = output_dir.path().join(
+        file_names
+            .iter()
+            .find(|name| name.starts_with("deposit_data-"))
+            .expect("should write deposit data"),
+    );
+    let deposits: Vec<LaunchpadDepositData> =
+        serde_json::from_reader(File::open(&deposit_data_path).unwrap()).unwrap();
+    assert_eq!(deposits.len(), COUNT);
+
+    for (i, deposit) in deposits.iter().enumerate() {
+        let keystore_name = file_names
+            .iter()
+            .find(|name| {
+                name.starts_with(&format!("keystore-m_12381_3600_{}_0_0-", FIRST_INDEX + i))
+            })
+            .expect("should write keystore");
+        let keystore = Keystore::from_json_file(output_dir.path().join(keystore_name)).unwrap();
+        assert_eq!(keystore.pubkey(), deposit.pubkey);
+        assert_eq!(
+            keystore.decrypt_keypair(PASSWORD.as_bytes()).unwrap().pk,
+            keystore.public_key().unwrap()
+        );
+    }
+
+    let verify = |deposit_data_path: &Path| {
+        output_result(
+            deposit_data_cmd(deposit_data::VERIFY_CMD)
+                .arg(format!("--{}", deposit_data::DEPOSIT_DATA_FLAG))
+                .arg(deposit_data_path.as_os_str())
+                .arg(format!("--{}", deposit_data::KEYSTORES_DIR_FLAG))
+                .arg(output_dir.path().as_os_str()),
+        )
+    };
+
+    verify(&deposit_data_path).unwrap();
+
+    // Tampering with the withdrawal credentials should cause verification to fail.
+    let mut tampered = deposits.clone();
+    tampered[1].withdrawal_credentials = tampered[0].withdrawal_credentials.clone();
+    let tampered_path = input_dir.path().join("tampered.json");
+    serde_json::to_writer(File::create(&tampered_path).unwrap(), &tampered).unwrap();
+
+    verify(&tampered_path).unwrap_err();
+}
+
+#[test]
+fn validator_withdrawal_derive() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+
+    let validator = TestValidator::new(validator_dir.path(), secrets_dir.path(), wallet);
+    let created_validators = validator.create_expect_success(COUNT_FLAG, 2, true);
+
+    let output = output_result(
+        validator_cmd()
+            .arg(WITHDRAWAL_CMD)
+            .arg(withdrawal::DERIVE_CMD)
+            .arg(format!("--{}", WALLETS_DIR_FLAG))
+            .arg(validator.wallet.base_dir().into_os_string())
+            .arg(format!("--{}", WALLET_NAME_FLAG))
+            .arg(&validator.wallet.name)
+            .arg(format!("--{}", WALLET_PASSWORD_FLAG))
+            .arg(validator.wallet.password_path().into_os_string())
+            .arg(format!("--{}", COUNT_FLAG))
+            .arg("2"),
+    )
+    .unwrap();
+
+    let stdout = from_utf8(&output.stdout).expect("stdout is not utf8");
+    let derived_pubkeys = stdout
+        .lines()
+        .map(|line| {
+            let columns = line.split('\t').collect::<Vec<_>>();
+            assert_eq!(columns.len(), 4, "unexpected output: {}", line);
+            columns[2]
+                .parse::<PublicKey>()
+                .expect("should parse pubkey")
+        })
+        .collect::<Vec<_>>();
+
+    // The wallet used indices 0 and 1 when creating the validators, so re-deriving those indices
+    // should produce the stored withdrawal keys.
+    let stored_pubkeys = created_validators
+        .iter()
+        .map(|dir| dir.withdrawal_keypair(&secrets_dir).unwrap().pk)
+        .collect::<Vec<_>>();
+
+    assert_eq!(derived_pubkeys, stored_pubkeys);
+}
+
+/// A validator as returned by `MockBeaconNode`.
+#[derive(Clone)]
+struct OnChainValidator {
+    index: u64,
+    pubkey: PublicKeyBytes,
+    withdrawal_credentials: Hash256,
+}
+
+/// A beacon node which only serves `GET /eth/v1/beacon/states/head/validators/{validator_id}`.
+struct MockBeaconNode {
+    url: String,
+    validators: Arc<Mutex<Vec<OnChainValidator>>>,
+}
+
+impl MockBeaconNode {
+    fn new(validators: Vec<OnChainValidator>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let validators = Arc::new(Mutex::new(validators));
+
+        let inner_validators = validators.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+
+                let mut request_line = String::new();
+                let mut reader = BufReader::new(&mut stream);
+                reader.read_line(&mut request_line).unwrap();
+                // Discard the headers; requests from the client have no body.
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 2 {
+                    line.clear();
+                }
+
+                let path = request_line.split(' ').nth(1).unwrap_or("");
+                let validator_id = path
+                    .strip_prefix("/eth/v1/beacon/states/head/validators/")
+                    .unwrap_or("");
+                let validator = inner_validators
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .find(|validator| {
+                        validator.index.to_string() == validator_id
+                            || format!("{:?}", validator.pubkey) == validator_id
+                    })
+                    .cloned();
+
+                let (status, body) = match validator {
+                    Some(validator) => (
+                        "200 OK",
+                        serde_json::json!({
+                            "data": {
+                                "index": validator.index.to_string(),
+                                "balance": "32000000000",
+                                "status": "Unknown",
+                                "validator": Validator {
+                                    pubkey: validator.pubkey,
+                                    withdrawal_credentials: validator.withdrawal_credentials,
+                                    effective_balance: 32_000_000_000,
+                                    slashed: false,
+                                    activation_eligibility_epoch: Epoch::new(0),
+                                    activation_epoch: Epoch::new(0),
+                                    exit_epoch: Epoch::new(u64::max_value()),
+                                    withdrawable_epoch: Epoch::new(u64::max_value()),
+                                },
+                            }
+                        })
+                        .to_string(),
+                    ),
+                    None => (
+                        "404 Not Found",
+                        serde_json::json!({ "code": 404, "message": "NOT_FOUND" }).to_string(),
+                    ),
+                };
+
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        Self { url, validators }
+    }
+
+    fn set_withdrawal_credentials(&self, index: u64, withdrawal_credentials: Hash256) {
+        for validator in self.validators.lock().unwrap().iter_mut() {
+            if validator.index == index {
+                validator.withdrawal_credentials = withdrawal_credentials;
+            }
+        }
+    }
+}
+
+#[test]
+fn validator_withdrawal_status_and_check() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+
+    let validator = TestValidator::new(validator_dir.path(), secrets_dir.path(), wallet);
+    let created_validators = validator.create_expect_success(COUNT_FLAG, 2, true);
+
+    let spec = MainnetEthSpec::default_spec();
+    let withdrawal_credentials = |dir: &ValidatorDir| {
+        let withdrawal_keypair = dir.withdrawal_keypair(secrets_dir.path()).unwrap();
+        Hash256::from_slice(&get_withdrawal_credentials(
+            &withdrawal_keypair.pk,
+            spec.bls_withdrawal_prefix_byte,
+        ))
+    };
+
+    let beacon_node = MockBeaconNode::new(
+        created_validators
+            .iter()
+            .enumerate()
+            .map(|(index, dir)| OnChainValidator {
+                index: index as u64,
+                pubkey: PublicKeyBytes::from(&dir.voting_keypair(secrets_dir.path()).unwrap().pk),
+                withdrawal_credentials: withdrawal_credentials(dir),
+            })
+            .collect(),
+    );
+
+    let status = || {
+        output_result(
+            validator_cmd()
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(validator_dir.path().as_os_str())
+                .arg(WITHDRAWAL_CMD)
+                .arg(withdrawal::STATUS_CMD)
+                .arg(format!("--{}", withdrawal::BEACON_NODE_FLAG))
+                .arg(&beacon_node.url),
+        )
+    };
+    let check = |dir: &ValidatorDir, validator_id: &str| {
+        output_result(
+            validator_cmd()
+                .arg(WITHDRAWAL_CMD)
+                .arg(withdrawal::CHECK_CMD)
+                .arg(format!("--{}", withdrawal::KEYSTORE_FLAG))
+                .arg(dir.dir().join(WITHDRAWAL_KEYSTORE_FILE).as_os_str())
+                .arg(format!("--{}", withdrawal::VALIDATOR_FLAG))
+                .arg(validator_id)
+                .arg(format!("--{}", withdrawal::BEACON_NODE_FLAG))
+                .arg(&beacon_node.url),
+        )
+    };
+
+    // Every withdrawal keystore matches its on-chain credentials.
+    let output = status().unwrap();
+    let stdout = from_utf8(&output.stdout).expect("stdout is not utf8");
+    assert_eq!(stdout.lines().count(), 2, "unexpected output: {}", stdout);
+    assert!(
+        stdout.lines().all(|line| line.ends_with("\tmatch")),
+        "unexpected output: {}",
+        stdout
+    );
+
+    let voting_pubkey = format!(
+        "{:?}",
+        PublicKeyBytes::from(
+            &created_validators[1]
+                .voting_keypair(secrets_dir.path())
+                .unwrap()
+                .pk
+        )
+    );
+    check(&created_validators[0], "0").unwrap();
+    check(&created_validators[1], &voting_pubkey).unwrap();
+
+    // The keystore of one validator does not match the credentials of another.
+    check(&created_validators[0], "1").unwrap_err();
+
+    // Once the on-chain credentials of the second validator differ, it is reported as a mismatch.
+    beacon_node.set_withdrawal_credentials(1, withdrawal_credentials(&created_validators[0]));
+
+    let error = status().unwrap_err();
+    assert!(
+        error.contains("1 validators have a withdrawal keystore which does not match"),
+        "unexpected error: {}",
+        error
+    );
+    check(&created_validators[0], "0").unwrap();
+    check(&created_validators[1], &voting_pubkey).unwrap_err();
+
+    // A validator which is unknown to the beacon node cannot be checked.
+    check(&created_validators[0], "2").unwrap_err();
+}
+
+#[test]
+fn validator_modify_keystore() {
+    const PASSWORD: &str = "cats";
+
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    // One keystore with a password file and one with its password in the definitions.
+    let mut defs = ValidatorDefinitions::default();
+    let keystores = (0..2)
+        .map(|i| {
+            let keystore = KeystoreBuilder::new(&Keypair::random(), PASSWORD.as_bytes(), "".into())
+                .unwrap()
+                .description(format!("validator {}", i))
+                .build()
+                .unwrap();
+
+            let keystore_dir = validator_dir
+                .path()
+                .join(format!("0x{}", keystore.pubkey()));
+            fs::create_dir(&keystore_dir).unwrap();
+            let keystore_path = keystore_dir.join("voting-keystore.json");
+            File::create(&keystore_path)
+                .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+                .unwrap();
+
+            let mut def =
+                ValidatorDefinition::new_keystore_with_password(&keystore_path, None).unwrap();
+            if i == 0 {
+                let password_path = secrets_dir.path().join(format!("0x{}", keystore.pubkey()));
+                fs::write(&password_path, PASSWORD).unwrap();
+                def.signing_definition = SigningDefinition::LocalKeystore {
+                    voting_keystore_path: keystore_path.clone(),
+                    voting_keystore_password_path: Some(password_path),
+                    voting_keystore_password: None,
+                    voting_keystore_password_source: None,
+                };
+            } else {
+                def.signing_definition = SigningDefinition::LocalKeystore {
+                    voting_keystore_path: keystore_path.clone(),
+                    voting_keystore_password_path: None,
+                    voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+                    voting_keystore_password_source: None,
+                };
+            }
+            defs.push(def);
+
+            (keystore, keystore_path)
+        })
+        .collect::<Vec<_>>();
+    defs.save(validator_dir.path()).unwrap();
+
+    output_result(
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(MODIFY_KEYSTORE_CMD)
+            .arg(format!("--{}", modify_keystore::ALL_FLAG))
+            .arg(format!("--{}", modify_keystore::RANDOM_PASSWORD_FLAG))
+            .arg(format!("--{}", modify_keystore::KDF_FLAG))
+            .arg(modify_keystore::PBKDF2)
+            .arg(format!("--{}", modify_keystore::KDF_COST_FLAG))
+            .arg("2"),
+    )
+    .unwrap();
+
+    let defs = ValidatorDefinitions::open(validator_dir.path()).unwrap();
+    assert_eq!(defs.as_slice().len(), 2);
+
+    for (def, (old_keystore, keystore_path)) in defs.as_slice().iter().zip(keystores.iter()) {
+        let password = match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_path: Some(path),
+                voting_keystore_password: None,
+                ..
+            } => fs::read_to_string(path).unwrap(),
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password: Some(password),
+                ..
+            } => password.as_str().to_string(),
+            _ => panic!("password storage should not change"),
+        };
+        assert_ne!(password, PASSWORD, "password should have changed");
+
+        let keystore = Keystore::from_json_file(keystore_path).unwrap();
+        assert_eq!(keystore.uuid(), old_keystore.uuid());
+        assert_eq!(keystore.description(), old_keystore.description());
+        match keystore.kdf() {
+            Kdf::Pbkdf2(Pbkdf2 { c, dklen, prf, .. }) => {
+                assert_eq!((*c, *dklen, prf), (2, DKLEN, &Prf::HmacSha256))
+            }
+            other => panic!("unexpected kdf: {:?}", other),
+        }
+        assert_eq!(
+            keystore.decrypt_keypair(password.as_bytes()).unwrap().pk,
+            def.voting_public_key
+        );
+
+        // The previous keystore should be backed up unchanged.
+        let backup_path = keystore_path.with_file_name("voting-keystore.json.bak");
+        assert_eq!(
+            &Keystore::from_json_file(backup_path).unwrap(),
+            old_keystore
+        );
+    }
+
+    // The previous password file should be backed up unchanged.
+    let backup_path = secrets_dir
+        .path()
+        .join(format!("0x{}.bak", keystores[0].0.pubkey()));
+    assert_eq!(fs::read_to_string(backup_path).unwrap(), PASSWORD);
+}
+
+#[test]
+fn validator_modify_keystore_shared_password_file() {
+    const PASSWORD: &str = "cats";
+    const NEW_PASSWORDS: [&str; 2] = ["first new password", "second new password"];
+
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    // Two keystores in one directory, as from the `eth2.0-deposit-cli`, sharing a password file.
+    let password_path = secrets_dir.path().join("password.txt");
+    fs::write(&password_path, PASSWORD).unwrap();
+    let keys_dir = validator_dir.path().join("validator_keys");
+    fs::create_dir(&keys_dir).unwrap();
+
+    let mut defs = ValidatorDefinitions::default();
+    let keystore_paths = (0..2)
+        .map(|i| {
+            let keystore = KeystoreBuilder::new(&Keypair::random(), PASSWORD.as_bytes(), "".into())
+                .unwrap()
+                .build()
+                .unwrap();
+            let keystore_path =
+                keys_dir.join(format!("keystore-m_12381_3600_{}_0_0-1608000000.json", i));
+            File::create(&keystore_path)
+                .map(|mut file| keystore.to_json_writer(&mut file).unwrap())
+                .unwrap();
+
+            let mut def =
+                ValidatorDefinition::new_keystore_with_password(&keystore_path, None).unwrap();
+            def.signing_definition = SigningDefinition::LocalKeystore {
+                voting_keystore_path: keystore_path.clone(),
+                voting_keystore_password_path: Some(password_path.clone()),
+                voting_keystore_password: None,
+                voting_keystore_password_source: None,
+            };
+            defs.push(def);
+
+            keystore_path
+        })
+        .collect::<Vec<_>>();
+    defs.save(validator_dir.path()).unwrap();
+
+    // Change the password twice, so that the second run finds the first run's backups.
+    for new_password in NEW_PASSWORDS.iter() {
+        let new_password_path = secrets_dir.path().join("new-password.txt");
+        fs::write(&new_password_path, new_password).unwrap();
+
+        output_result(
+            validator_cmd()
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(validator_dir.path().as_os_str())
+                .arg(MODIFY_KEYSTORE_CMD)
+                .arg(format!("--{}", modify_keystore::ALL_FLAG))
+                .arg(format!("--{}", modify_keystore::NEW_PASSWORD_FILE_FLAG))
+                .arg(new_password_path.as_os_str())
+                .arg(format!("--{}", modify_keystore::KDF_FLAG))
+                .arg(modify_keystore::PBKDF2)
+                .arg(format!("--{}", modify_keystore::KDF_COST_FLAG))
+                .arg("2"),
+        )
+        .unwrap();
+
+        assert_eq!(fs::read_to_string(&password_path).unwrap(), *new_password);
+        for keystore_path in &keystore_paths {
+            Keystore::from_json_file(keystore_path)
+                .unwrap()
+                .decrypt_keypair(new_password.as_bytes())
+                .unwrap();
+            // The staged keystore has been swapped in.
+            assert!(!keys_dir
+                .join(format!(
+                    "{}.{}",
+                    keystore_path.file_name().unwrap().to_str().unwrap(),
+                    modify_keystore::STAGED_EXTENSION
+                ))
+                .exists());
+        }
+    }
+
+    // The first backup holds the original password, and is not replaced by the second run.
+    let backups = fs::read_dir(secrets_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .filter(|name| name.starts_with("password.txt.") && name.ends_with(".bak"))
+        .collect::<Vec<_>>();
+    assert_eq!(backups.len(), 2, "{:?}", backups);
+    assert_eq!(
+        fs::read_to_string(secrets_dir.path().join("password.txt.bak")).unwrap(),
+        PASSWORD
+    );
+    let timestamped = backups
+        .iter()
+        .find(|name| *name != "password.txt.bak")
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(secrets_dir.path().join(timestamped)).unwrap(),
+        NEW_PASSWORDS[0]
+    );
+
+    for keystore_path in &keystore_paths {
+        Keystore::from_json_file(with_bak_extension(keystore_path))
+            .unwrap()
+            .decrypt_keypair(PASSWORD.as_bytes())
+            .unwrap();
+    }
+}
+
+fn with_bak_extension(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap().to_os_string();
+    file_name.push(".bak");
+    path.with_file_name(file_name)
+}
+
+/// Writes a testnet directory for the deposit contract deployed in `eth1`, declaring that the
+/// contract is on `deposit_chain_id`.
+fn write_testnet_dir(testnet_dir: &Path, eth1: &GanacheEth1Instance, deposit_chain_id: u64) {
+    let mut spec = MainnetEthSpec::default_spec();
+    spec.deposit_contract_address = eth1
+        .deposit_contract
+        .address()
+        .trim_start_matches("0x")
+        .parse()
+        .unwrap();
+
+    Eth2TestnetConfig::<MainnetEthSpec> {
+        deposit_contract_address: eth1.deposit_contract.address(),
+        deposit_contract_deploy_block: 0,
+        boot_enr: None,
+        genesis_state: None,
+        yaml_config: Some(YamlConfig::from_spec::<MainnetEthSpec>(&spec)),
+    }
+    .force_write_to_file(testnet_dir.into())
+    .unwrap();
+
+    // The deposit chain id is not part of the `ChainSpec`, so it must be added by hand.
+    OpenOptions::new()
+        .append(true)
+        .open(testnet_dir.join(YAML_CONFIG_FILE))
+        .and_then(|mut file| write!(file, "\nDEPOSIT_CHAIN_ID: {}\n", deposit_chain_id))
+        .unwrap();
+}
+
+/// Runs `lighthouse account validator deposit` for all validators in `validator_dir`, answering
+/// each confirmation prompt with a line from `answers`. Returns whether the command succeeded
+/// and its stderr.
+fn validator_deposit(
+    testnet_dir: &Path,
+    validator_dir: &Path,
+    eth1: &GanacheEth1Instance,
+    from: &str,
+    extra_args: &[&str],
+    answers: &[&str],
+) -> (bool, String) {
+    let mut child = lighthouse_cmd()
+        .arg("--testnet-dir")
+        .arg(testnet_dir.as_os_str())
+        .arg(ACCOUNT_CMD)
+        .arg(VALIDATOR_CMD)
+        .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+        .arg(validator_dir.as_os_str())
+        .arg(DEPOSIT_CMD)
+        .arg(format!("--{}", deposit::VALIDATOR_FLAG))
+        .arg(deposit::VALIDATOR_ALL)
+        .arg(format!("--{}", deposit::ETH1_HTTP_FLAG))
+        .arg(eth1.endpoint())
+        .arg(format!("--{}", deposit::FROM_ADDRESS_FLAG))
+        .arg(from)
+        .arg(format!("--{}", STDIN_INPUTS_FLAG)) // Using tty does not work well with tests.
+        .args(extra_args)
+        .stderr(Stdio::piped())
+        .stdin(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    let stdin = child.stdin.as_mut().unwrap();
+    for answer in answers {
+        stdin.write_all(format!("{}\n", answer).as_bytes()).unwrap();
+    }
+
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.success(),
+        from_utf8(&output.stderr).unwrap().to_string(),
+    )
+}
+
+/// Requires `ganache-cli` to be installed and available on `PATH`.
+#[test]
+fn validator_deposit() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+    let testnet_dir = tempdir().unwrap();
+    let wrong_testnet_dir = tempdir().unwrap();
+
+    let mut environment = EnvironmentBuilder::mainnet()
+        .single_thread_tokio_runtime()
+        .unwrap()
+        .null_logger()
+        .unwrap()
+        .build()
+        .unwrap();
+    let (eth1, chain_id, from) = environment.runtime().block_on(async {
+        let eth1 = GanacheEth1Instance::new().await.unwrap();
+        let chain_id = eth1.ganache.chain_id().await.unwrap();
+        let from = format!("{:x}", eth1.ganache.accounts().await.unwrap()[0]);
+        (eth1, chain_id, from)
+    });
+
+    write_testnet_dir(testnet_dir.path(), &eth1, chain_id);
+    write_testnet_dir(wrong_testnet_dir.path(), &eth1, chain_id + 1);
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+    let validator = TestValidator::new(validator_dir.path(), secrets_dir.path(), wallet);
+    let validators = validator.create_expect_success(COUNT_FLAG, 3, false);
+
+    // Deposits are refused if the eth1 node is not on the chain of the deposit contract.
+    let (success, stderr) = validator_deposit(
+        wrong_testnet_dir.path(),
+        validator_dir.path(),
+        &eth1,
+        &from,
+        &[],
+        &["yes"],
+    );
+    assert!(!success, "{}", stderr);
+    assert!(
+        stderr.contains("deposit contract is on chain"),
+        "{}",
+        stderr
+    );
+    assert!(validators.iter().all(|v| !v.eth1_deposit_tx_hash_exists()));
+
+    // Each batch is confirmed separately.
+    let (success, stderr) = validator_deposit(
+        testnet_dir.path(),
+        validator_dir.path(),
+        &eth1,
+        &from,
+        &[&format!("--{}", deposit::BATCH_SIZE_FLAG), "2"],
+        &["yes", "yes"],
+    );
+    assert!(success, "{}", stderr);
+    assert_eq!(
+        stderr.matches(deposit::BATCH_CONFIRMATION_PROMPT).count(),
+        2,
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("Batch 2 of 2"), "{}", stderr);
+    assert!(validators.iter().all(|v| v.eth1_deposit_tx_hash_exists()));
+
+    // A deposit which does not reach the required confirmations in time is reported as pending,
+    // and deposits which were already sent are skipped.
+    let new_validators = validator.create_expect_success(COUNT_FLAG, 1, false);
+    let (success, stderr) = validator_deposit(
+        testnet_dir.path(),
+        validator_dir.path(),
+        &eth1,
+        &from,
+        &[
+            &format!("--{}", deposit::CONFIRMATIONS_FLAG),
+            "1000",
+            &format!("--{}", deposit::RECEIPT_TIMEOUT_FLAG),
+            "1",
+        ],
+        &["yes"],
+    );
+    assert!(!success, "{}", stderr);
+    assert!(stderr.contains("still pending"), "{}", stderr);
+    assert!(stderr.contains(ETH1_DEPOSIT_TX_HASH_FILE), "{}", stderr);
+    assert_eq!(stderr.matches("Sent deposit for").count(), 1, "{}", stderr);
+    assert!(new_validators[0].eth1_deposit_tx_hash_exists());
+}
+
 /// Check that all of the given pubkeys have been registered with slashing protection.
 fn check_slashing_protection(validator_dir: &TempDir, pubkeys: impl Iterator<Item = PublicKey>) {
     let slashing_db_path = validator_dir.path().join(SLASHING_PROTECTION_FILENAME);
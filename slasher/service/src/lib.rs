@@ -0,0 +1,154 @@
+//! Runs the slasher in the background, periodically processing the attestations and blocks queued
+//! by the beacon chain and importing any slashings found into the operation pool.
+
+use beacon_chain::observed_operations::ObservationOutcome;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use futures::stream::StreamExt;
+use slasher::Slasher;
+use slog::{debug, error, info, trace, Logger};
+use slot_clock::SlotClock;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+use task_executor::TaskExecutor;
+use tokio::time::{interval_at, Instant};
+use types::{Epoch, EthSpec};
+
+pub struct SlasherService<T: BeaconChainTypes> {
+    beacon_chain: Arc<BeaconChain<T>>,
+}
+
+impl<T: BeaconChainTypes> SlasherService<T> {
+    pub fn new(beacon_chain: Arc<BeaconChain<T>>) -> Self {
+        Self { beacon_chain }
+    }
+
+    /// Start the slasher service, returning an error if the beacon chain has no slasher.
+    pub fn run(&self, executor: &TaskExecutor) -> Result<(), String> {
+        let slasher = self
+            .beacon_chain
+            .slasher
+            .clone()
+            .ok_or_else(|| "No slasher is configured".to_string())?;
+        let log = executor.log().clone();
+        let update_period = slasher.config().update_period;
+
+        // A buffer of one means that a notification arriving while the processor is busy is
+        // dropped, rather than causing batches to pile up.
+        let (notif_sender, notif_receiver) = sync_channel(1);
+
+        executor.spawn(
+            Self::run_notifier(
+                self.beacon_chain.clone(),
+                update_period,
+                notif_sender,
+                log.clone(),
+            ),
+            "slasher_server_notifier",
+        );
+
+        let beacon_chain = self.beacon_chain.clone();
+        executor.spawn_blocking(
+            move || Self::run_processor(beacon_chain, slasher, notif_receiver, log),
+            "slasher_server_processor",
+        );
+
+        info!(executor.log(), "Slasher service started"; "update_period" => update_period);
+
+        Ok(())
+    }
+
+    /// Notify the processor of the current epoch every `update_period` seconds.
+    async fn run_notifier(
+        beacon_chain: Arc<BeaconChain<T>>,
+        update_period: u64,
+        notif_sender: SyncSender<Epoch>,
+        log: Logger,
+    ) {
+        let mut interval = interval_at(Instant::now(), Duration::from_secs(update_period));
+        while interval.next().await.is_some() {
+            if let Some(current_slot) = beacon_chain.slot_clock.now() {
+                let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
+                match notif_sender.try_send(current_epoch) {
+                    Ok(()) => (),
+                    Err(TrySendError::Full(_)) => {
+                        debug!(log, "Slasher is still processing the previous batch")
+                    }
+                    Err(TrySendError::Disconnected(_)) => break,
+                }
+            } else {
+                trace!(log, "Slasher has nothing to do: we are pre-genesis");
+            }
+        }
+    }
+
+    /// Process a batch each time a notification is received, until the notifier stops.
+    fn run_processor(
+        beacon_chain: Arc<BeaconChain<T>>,
+        slasher: Arc<Slasher<T::EthSpec>>,
+        notif_receiver: Receiver<Epoch>,
+        log: Logger,
+    ) {
+        while let Ok(current_epoch) = notif_receiver.recv() {
+            if let Err(e) = slasher.process_queued(current_epoch) {
+                error!(
+                    log,
+                    "Error during scheduled slasher processing";
+                    "epoch" => current_epoch,
+                    "error" => format!("{:?}", e)
+                );
+            }
+
+            Self::import_attester_slashings(&beacon_chain, &slasher, &log);
+            Self::import_proposer_slashings(&beacon_chain, &slasher, &log);
+        }
+    }
+
+    fn import_attester_slashings(
+        beacon_chain: &BeaconChain<T>,
+        slasher: &Slasher<T::EthSpec>,
+        log: &Logger,
+    ) {
+        for slashing in slasher.take_attester_slashings() {
+            match beacon_chain.verify_attester_slashing_for_gossip(slashing) {
+                Ok(ObservationOutcome::New(verified_slashing)) => {
+                    if let Err(e) = beacon_chain.import_attester_slashing(verified_slashing) {
+                        error!(
+                            log,
+                            "Unable to import attester slashing";
+                            "error" => format!("{:?}", e)
+                        );
+                    }
+                }
+                Ok(ObservationOutcome::AlreadyKnown) => (),
+                // This is expected if the offending validators have already been slashed.
+                Err(e) => debug!(
+                    log,
+                    "Attester slashing from slasher is not valid";
+                    "error" => format!("{:?}", e)
+                ),
+            }
+        }
+    }
+
+    fn import_proposer_slashings(
+        beacon_chain: &BeaconChain<T>,
+        slasher: &Slasher<T::EthSpec>,
+        log: &Logger,
+    ) {
+        for slashing in slasher.take_proposer_slashings() {
+            match beacon_chain.verify_proposer_slashing_for_gossip(slashing) {
+                Ok(ObservationOutcome::New(verified_slashing)) => {
+                    beacon_chain.import_proposer_slashing(verified_slashing)
+                }
+                Ok(ObservationOutcome::AlreadyKnown) => (),
+                // This is expected if the offending validator has already been slashed.
+                Err(e) => debug!(
+                    log,
+                    "Proposer slashing from slasher is not valid";
+                    "error" => format!("{:?}", e)
+                ),
+            }
+        }
+    }
+}
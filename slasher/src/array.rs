@@ -0,0 +1,247 @@
+//! Min-max span arrays for detecting surround votes.
+//!
+//! For each validator and each epoch `e` in the history the slasher stores:
+//!
+//! - `min_target[e]`: the minimum target epoch of the validator's attestations with `source > e`.
+//! - `max_target[e]`: the maximum target epoch of the validator's attestations with
+//!   `source < e < target`.
+//!
+//! A new attestation `(s, t)` surrounds an existing attestation iff `min_target[s] < t`, and is
+//! surrounded by an existing attestation iff `max_target[s] > t`.
+//!
+//! Targets are stored as `u16` distances from `e`, in chunks of `chunk_size` epochs per validator.
+//! The chunks form a ring buffer over the history and each chunk records the first epoch it covers,
+//! so that chunks left over from a previous lap of the ring buffer can be detected and reset.
+use crate::{Config, Error, SlasherDB};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use store::{DBColumn, KeyValueStoreOp};
+use types::{Epoch, EthSpec};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TargetArray {
+    Min,
+    Max,
+}
+
+impl TargetArray {
+    fn column(self) -> DBColumn {
+        match self {
+            TargetArray::Min => DBColumn::SlasherMinTargets,
+            TargetArray::Max => DBColumn::SlasherMaxTargets,
+        }
+    }
+
+    /// The distance stored for an epoch with no relevant attestations.
+    fn empty_distance(self) -> u16 {
+        match self {
+            TargetArray::Min => u16::MAX,
+            TargetArray::Max => 0,
+        }
+    }
+}
+
+/// An attestation already known to the slasher which forms a surround vote with a new attestation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SurroundVote {
+    /// The new attestation surrounds an existing attestation with this target epoch.
+    SurroundsExisting { existing_target: Epoch },
+    /// The new attestation is surrounded by an existing attestation with this target epoch.
+    SurroundedByExisting { existing_target: Epoch },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Chunk {
+    /// The first epoch covered by this chunk.
+    start_epoch: Epoch,
+    distances: Vec<u16>,
+}
+
+impl Chunk {
+    fn empty(array: TargetArray, start_epoch: Epoch, chunk_size: u64) -> Self {
+        Self {
+            start_epoch,
+            distances: vec![array.empty_distance(); chunk_size as usize],
+        }
+    }
+
+    fn from_bytes(bytes: &[u8], chunk_size: u64) -> Result<Self, Error> {
+        let expected_len = 8 + 2 * chunk_size as usize;
+        if bytes.len() != expected_len {
+            return Err(Error::InvalidChunkLength(bytes.len()));
+        }
+
+        let mut start_epoch_bytes = [0; 8];
+        start_epoch_bytes.copy_from_slice(&bytes[0..8]);
+        let distances = bytes[8..]
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+
+        Ok(Self {
+            start_epoch: Epoch::new(u64::from_le_bytes(start_epoch_bytes)),
+            distances,
+        })
+    }
+
+    fn as_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.start_epoch.as_u64().to_le_bytes().to_vec();
+        for distance in &self.distances {
+            bytes.extend_from_slice(&distance.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+/// A write-back cache of span array chunks, used while processing a batch of attestations.
+pub struct SpanArrays<'a, E: EthSpec> {
+    db: &'a SlasherDB<E>,
+    config: &'a Config,
+    chunks: HashMap<(TargetArray, u64, u64), Chunk>,
+}
+
+impl<'a, E: EthSpec> SpanArrays<'a, E> {
+    pub fn new(db: &'a SlasherDB<E>, config: &'a Config) -> Self {
+        Self {
+            db,
+            config,
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Returns the chunk covering `epoch` for `validator_index`, loading it from disk if necessary.
+    fn chunk_mut(
+        &mut self,
+        array: TargetArray,
+        validator_index: u64,
+        epoch: Epoch,
+    ) -> Result<&mut Chunk, Error> {
+        let chunk_size = self.config.chunk_size;
+        let chunk_index = (epoch.as_u64() % self.config.history_length) / chunk_size;
+        let start_epoch = Epoch::new(epoch.as_u64() - epoch.as_u64() % chunk_size);
+        let key = (array, validator_index, chunk_index);
+
+        if !self.chunks.contains_key(&key) {
+            let chunk = match self
+                .db
+                .get_chunk(array.column(), validator_index, chunk_index)?
+            {
+                Some(bytes) => Chunk::from_bytes(&bytes, chunk_size)?,
+                None => Chunk::empty(array, start_epoch, chunk_size),
+            };
+            self.chunks.insert(key, chunk);
+        }
+
+        let chunk = self.chunks.get_mut(&key).expect("chunk was inserted above");
+
+        // Reset chunks left over from a previous lap of the ring buffer.
+        if chunk.start_epoch != start_epoch {
+            *chunk = Chunk::empty(array, start_epoch, chunk_size);
+        }
+
+        Ok(chunk)
+    }
+
+    fn get_target(
+        &mut self,
+        array: TargetArray,
+        validator_index: u64,
+        epoch: Epoch,
+    ) -> Result<Option<Epoch>, Error> {
+        let offset = (epoch.as_u64() % self.config.chunk_size) as usize;
+        let distance = self.chunk_mut(array, validator_index, epoch)?.distances[offset];
+
+        if distance == array.empty_distance() {
+            Ok(None)
+        } else {
+            Ok(Some(epoch + u64::from(distance)))
+        }
+    }
+
+    fn set_target(
+        &mut self,
+        array: TargetArray,
+        validator_index: u64,
+        epoch: Epoch,
+        target: Epoch,
+    ) -> Result<(), Error> {
+        let offset = (epoch.as_u64() % self.config.chunk_size) as usize;
+        // Targets are never more than `history_length` epochs beyond `epoch`, which is checked to
+        // fit into a `u16` when the config is validated.
+        let distance = u16::try_from(target.as_u64() - epoch.as_u64()).unwrap_or(u16::MAX - 1);
+        self.chunk_mut(array, validator_index, epoch)?.distances[offset] = distance;
+        Ok(())
+    }
+
+    /// Check an attestation `(source, target)` by `validator_index` for surround votes, then
+    /// record it in the span arrays.
+    ///
+    /// Epochs prior to `lowest_epoch` are outside the history and are ignored. The `target` must
+    /// be no later than the current epoch.
+    pub fn apply_attestation(
+        &mut self,
+        validator_index: u64,
+        source: Epoch,
+        target: Epoch,
+        lowest_epoch: Epoch,
+    ) -> Result<Vec<SurroundVote>, Error> {
+        let mut surround_votes = vec![];
+
+        if source >= lowest_epoch {
+            if let Some(existing_target) =
+                self.get_target(TargetArray::Min, validator_index, source)?
+            {
+                if existing_target < target {
+                    surround_votes.push(SurroundVote::SurroundsExisting { existing_target });
+                }
+            }
+
+            if let Some(existing_target) =
+                self.get_target(TargetArray::Max, validator_index, source)?
+            {
+                if existing_target > target {
+                    surround_votes.push(SurroundVote::SurroundedByExisting { existing_target });
+                }
+            }
+        }
+
+        // Update the min targets of all epochs prior to the source, stopping at the first epoch
+        // whose min target is already at least as low (all earlier epochs will be too).
+        let mut epoch = source;
+        while epoch > lowest_epoch {
+            epoch -= 1;
+            match self.get_target(TargetArray::Min, validator_index, epoch)? {
+                Some(existing) if existing <= target => break,
+                _ => self.set_target(TargetArray::Min, validator_index, epoch, target)?,
+            }
+        }
+
+        // Update the max targets of all epochs between the source and target, stopping at the
+        // first epoch whose max target is already at least as high (all later epochs will be too).
+        let mut epoch = std::cmp::max(source + 1, lowest_epoch);
+        while epoch < target {
+            match self.get_target(TargetArray::Max, validator_index, epoch)? {
+                Some(existing) if existing >= target => break,
+                _ => self.set_target(TargetArray::Max, validator_index, epoch, target)?,
+            }
+            epoch += 1;
+        }
+
+        Ok(surround_votes)
+    }
+
+    /// Consume `self`, returning the operations required to write all modified chunks to disk.
+    pub fn into_ops(self) -> Vec<KeyValueStoreOp> {
+        self.chunks
+            .into_iter()
+            .map(|((array, validator_index, chunk_index), chunk)| {
+                SlasherDB::<E>::chunk_op(
+                    array.column(),
+                    validator_index,
+                    chunk_index,
+                    chunk.as_bytes(),
+                )
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,78 @@
+use crate::Error;
+use serde_derive::{Deserialize, Serialize};
+use ssz_derive::{Decode, Encode};
+use std::path::PathBuf;
+
+pub const DEFAULT_CHUNK_SIZE: u64 = 16;
+pub const DEFAULT_HISTORY_LENGTH: u64 = 4096;
+pub const DEFAULT_UPDATE_PERIOD: u64 = 12;
+pub const DEFAULT_MAX_QUEUED_ATTESTATIONS: usize = 262_144;
+
+/// The largest history length that can be represented by the `u16` distances stored in the span
+/// arrays.
+///
+/// A distance of `u16::MAX` is reserved to mark empty entries of the min-span array.
+pub const MAX_HISTORY_LENGTH: u64 = u16::MAX as u64 - 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    /// The directory of the slasher's database, separate from the beacon node database.
+    pub database_path: PathBuf,
+    /// The number of epochs stored in each chunk of a span array.
+    pub chunk_size: u64,
+    /// The number of epochs of history kept by the slasher.
+    pub history_length: u64,
+    /// The number of seconds between each batch of queued attestations and blocks being processed.
+    pub update_period: u64,
+    /// The maximum number of attestations held for the next batch. Attestations which arrive
+    /// while the queue is full are dropped.
+    pub max_queued_attestations: usize,
+}
+
+/// The subset of `Config` that determines the layout of the database.
+///
+/// It is stored on disk and checked each time the database is opened, since the database cannot be
+/// re-interpreted with a different layout.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct DatabaseLayout {
+    pub chunk_size: u64,
+    pub history_length: u64,
+}
+
+impl Config {
+    pub fn new(database_path: PathBuf) -> Self {
+        Self {
+            database_path,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            history_length: DEFAULT_HISTORY_LENGTH,
+            update_period: DEFAULT_UPDATE_PERIOD,
+            max_queued_attestations: DEFAULT_MAX_QUEUED_ATTESTATIONS,
+        }
+    }
+
+    /// Returns an error if `self` describes a database layout that cannot be used.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.chunk_size == 0
+            || self.history_length == 0
+            || self.history_length > MAX_HISTORY_LENGTH
+            || self.history_length % self.chunk_size != 0
+            || self.history_length <= self.chunk_size
+        {
+            Err(Error::ConfigInvalidChunkSize {
+                chunk_size: self.chunk_size,
+                history_length: self.history_length,
+            })
+        } else if self.update_period == 0 {
+            Err(Error::ConfigInvalidUpdatePeriod)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn layout(&self) -> DatabaseLayout {
+        DatabaseLayout {
+            chunk_size: self.chunk_size,
+            history_length: self.history_length,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::config::{Config, DatabaseLayout};
+use crate::Error;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::collections::HashSet;
+use std::sync::Arc;
+use store::{get_key_for_col, DBColumn, KeyValueStore, KeyValueStoreOp, LevelDB};
+use types::{
+    Epoch, EthSpec, Hash256, IndexedAttestation, ProposerSlashing, SignedBeaconBlockHeader, Slot,
+};
+
+/// The key under which the `DatabaseLayout` is stored in the `SlasherMetadata` column.
+const LAYOUT_KEY: &[u8] = b"layout";
+
+/// The attestation that a validator signed for some target epoch.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct AttesterRecord {
+    pub target_epoch: Epoch,
+    pub attestation_data_hash: Hash256,
+    pub indexed_attestation_hash: Hash256,
+}
+
+/// The hashes of all indexed attestations stored for a target epoch.
+///
+/// Stored in a ring buffer keyed by `target_epoch % history_length` so that the attestations of an
+/// epoch can be deleted once it falls out of the history.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+struct EpochAttestations {
+    target_epoch: Epoch,
+    indexed_attestation_hashes: Vec<Hash256>,
+}
+
+/// The outcome of checking a block proposal against the proposals already in the database.
+pub enum ProposalOutcome {
+    /// The proposal has not been seen before and has been stored.
+    New,
+    /// The exact same proposal has already been seen.
+    Repeat,
+    /// A different proposal from the same proposer for the same slot has already been seen.
+    Slashable(ProposerSlashing),
+}
+
+/// The slasher's on-disk database.
+///
+/// All records are stored in ring buffers indexed by epoch (or slot) modulo the history length, and
+/// each record carries the epoch (or slot) it was written for. Records that have been lapped are
+/// therefore recognised as stale and overwritten, keeping the database size bounded without needing
+/// to iterate over it.
+pub struct SlasherDB<E: EthSpec> {
+    db: LevelDB<E>,
+    config: Arc<Config>,
+}
+
+/// Returns a database key made from two big-endian `u64`s.
+fn u64_pair_key(a: u64, b: u64) -> Vec<u8> {
+    let mut key = a.to_be_bytes().to_vec();
+    key.extend_from_slice(&b.to_be_bytes());
+    key
+}
+
+fn indexed_attestation_key(target_epoch: Epoch, indexed_attestation_hash: Hash256) -> Vec<u8> {
+    let mut key = target_epoch.as_u64().to_be_bytes().to_vec();
+    key.extend_from_slice(indexed_attestation_hash.as_bytes());
+    key
+}
+
+impl<E: EthSpec> SlasherDB<E> {
+    /// Open the database at `config.database_path`, creating it if it doesn't exist.
+    ///
+    /// Returns an error if the database was created with a different layout to `config`.
+    pub fn open(config: Arc<Config>) -> Result<Self, Error> {
+        std::fs::create_dir_all(&config.database_path).map_err(Error::DatabaseIOError)?;
+        let db = LevelDB::open(&config.database_path)?;
+        let slasher_db = Self { db, config };
+        slasher_db.check_or_store_layout()?;
+        Ok(slasher_db)
+    }
+
+    fn check_or_store_layout(&self) -> Result<(), Error> {
+        let column = DBColumn::SlasherMetadata.into();
+        let layout = self.config.layout();
+
+        if let Some(bytes) = self.db.get_bytes(column, LAYOUT_KEY)? {
+            let on_disk = DatabaseLayout::from_ssz_bytes(&bytes)?;
+            if on_disk != layout {
+                return Err(Error::ConfigIncompatible {
+                    on_disk,
+                    config: layout,
+                });
+            }
+        } else {
+            self.db
+                .put_bytes_sync(column, LAYOUT_KEY, &layout.as_ssz_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Execute `ops` atomically.
+    pub fn write(&self, ops: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        self.db.do_atomically(ops).map_err(Into::into)
+    }
+
+    /// Returns the raw bytes of a span array chunk.
+    pub fn get_chunk(
+        &self,
+        column: DBColumn,
+        validator_index: u64,
+        chunk_index: u64,
+    ) -> Result<Option<Vec<u8>>, Error> {
+        self.db
+            .get_bytes(column.into(), &u64_pair_key(validator_index, chunk_index))
+            .map_err(Into::into)
+    }
+
+    /// Returns an operation which stores the raw bytes of a span array chunk.
+    pub fn chunk_op(
+        column: DBColumn,
+        validator_index: u64,
+        chunk_index: u64,
+        bytes: Vec<u8>,
+    ) -> KeyValueStoreOp {
+        let key = get_key_for_col(column.into(), &u64_pair_key(validator_index, chunk_index));
+        KeyValueStoreOp::PutKeyValue(key, bytes)
+    }
+
+    fn attester_record_key(&self, validator_index: u64, target_epoch: Epoch) -> Vec<u8> {
+        u64_pair_key(
+            validator_index,
+            target_epoch.as_u64() % self.config.history_length,
+        )
+    }
+
+    /// Returns the attester record of `validator_index` for `target_epoch`, if any.
+    pub fn get_attester_record(
+        &self,
+        validator_index: u64,
+        target_epoch: Epoch,
+    ) -> Result<Option<AttesterRecord>, Error> {
+        let key = self.attester_record_key(validator_index, target_epoch);
+        match self
+            .db
+            .get_bytes(DBColumn::SlasherAttesterRecords.into(), &key)?
+        {
+            Some(bytes) => {
+                let record = AttesterRecord::from_ssz_bytes(&bytes)?;
+                // Ignore records from a previous lap of the ring buffer.
+                if record.target_epoch == target_epoch {
+                    Ok(Some(record))
+                } else {
+                    Ok(None)
+                }
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Returns an operation which stores the attester record of `validator_index`.
+    pub fn attester_record_op(
+        &self,
+        validator_index: u64,
+        record: &AttesterRecord,
+    ) -> KeyValueStoreOp {
+        let key = get_key_for_col(
+            DBColumn::SlasherAttesterRecords.into(),
+            &self.attester_record_key(validator_index, record.target_epoch),
+        );
+        KeyValueStoreOp::PutKeyValue(key, record.as_ssz_bytes())
+    }
+
+    /// Store `attestations`, which must all have the given `target_epoch`.
+    ///
+    /// Any attestations stored for the epoch that previously occupied the same position in the
+    /// ring buffer are deleted.
+    pub fn store_indexed_attestations(
+        &self,
+        target_epoch: Epoch,
+        attestations: &[(Hash256, &IndexedAttestation<E>)],
+    ) -> Result<(), Error> {
+        let epoch_column = DBColumn::SlasherEpochAttestations.into();
+        let attestation_column = DBColumn::SlasherIndexedAttestations.into();
+        let epoch_key = (target_epoch.as_u64() % self.config.history_length).to_be_bytes();
+
+        let mut ops = vec![];
+        let mut epoch_attestations = match self.db.get_bytes(epoch_column, &epoch_key)? {
+            Some(bytes) => EpochAttestations::from_ssz_bytes(&bytes)?,
+            None => EpochAttestations {
+                target_epoch,
+                indexed_attestation_hashes: vec![],
+            },
+        };
+
+        if epoch_attestations.target_epoch != target_epoch {
+            for hash in &epoch_attestations.indexed_attestation_hashes {
+                let key = indexed_attestation_key(epoch_attestations.target_epoch, *hash);
+                ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    attestation_column,
+                    &key,
+                )));
+            }
+            epoch_attestations = EpochAttestations {
+                target_epoch,
+                indexed_attestation_hashes: vec![],
+            };
+        }
+
+        let mut known = epoch_attestations
+            .indexed_attestation_hashes
+            .iter()
+            .copied()
+            .collect::<HashSet<_>>();
+
+        for (hash, attestation) in attestations {
+            if known.insert(*hash) {
+                let key = indexed_attestation_key(target_epoch, *hash);
+                ops.push(KeyValueStoreOp::PutKeyValue(
+                    get_key_for_col(attestation_column, &key),
+                    attestation.as_ssz_bytes(),
+                ));
+                epoch_attestations.indexed_attestation_hashes.push(*hash);
+            }
+        }
+
+        ops.push(KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(epoch_column, &epoch_key),
+            epoch_attestations.as_ssz_bytes(),
+        ));
+
+        self.write(ops)
+    }
+
+    pub fn get_indexed_attestation(
+        &self,
+        target_epoch: Epoch,
+        indexed_attestation_hash: Hash256,
+    ) -> Result<IndexedAttestation<E>, Error> {
+        let key = indexed_attestation_key(target_epoch, indexed_attestation_hash);
+        let bytes = self
+            .db
+            .get_bytes(DBColumn::SlasherIndexedAttestations.into(), &key)?
+            .ok_or_else(|| Error::MissingIndexedAttestation {
+                target_epoch,
+                indexed_attestation_root: indexed_attestation_hash,
+            })?;
+        Ok(IndexedAttestation::from_ssz_bytes(&bytes)?)
+    }
+
+    fn proposal_key(&self, slot: Slot, proposer_index: u64) -> Vec<u8> {
+        let slots_of_history = self.config.history_length * E::slots_per_epoch();
+        u64_pair_key(slot.as_u64() % slots_of_history, proposer_index)
+    }
+
+    /// Check `block_header` against any proposal already stored for the same proposer and slot,
+    /// storing it if there is none.
+    pub fn check_or_insert_block_proposal(
+        &self,
+        block_header: SignedBeaconBlockHeader,
+    ) -> Result<ProposalOutcome, Error> {
+        let column = DBColumn::SlasherProposers.into();
+        let key = self.proposal_key(
+            block_header.message.slot,
+            block_header.message.proposer_index,
+        );
+
+        if let Some(bytes) = self.db.get_bytes(column, &key)? {
+            let existing = SignedBeaconBlockHeader::from_ssz_bytes(&bytes)?;
+
+            // Only compare against headers from the current lap of the ring buffer.
+            if existing.message.slot == block_header.message.slot {
+                return if existing.message == block_header.message {
+                    Ok(ProposalOutcome::Repeat)
+                } else {
+                    Ok(ProposalOutcome::Slashable(ProposerSlashing {
+                        signed_header_1: existing,
+                        signed_header_2: block_header,
+                    }))
+                };
+            }
+        }
+
+        self.db
+            .put_bytes(column, &key, &block_header.as_ssz_bytes())?;
+        Ok(ProposalOutcome::New)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::config::DatabaseLayout;
+use types::{Epoch, Hash256};
+
+#[derive(Debug)]
+pub enum Error {
+    DatabaseError(store::Error),
+    DatabaseIOError(std::io::Error),
+    SszDecodeError(ssz::DecodeError),
+    ConfigInvalidChunkSize {
+        chunk_size: u64,
+        history_length: u64,
+    },
+    ConfigInvalidUpdatePeriod,
+    /// The database was created with a different chunk size or history length.
+    ConfigIncompatible {
+        on_disk: DatabaseLayout,
+        config: DatabaseLayout,
+    },
+    /// A stored span array chunk has an unexpected length.
+    InvalidChunkLength(usize),
+    /// The span arrays refer to an attestation for which we have no record.
+    MissingAttesterRecord {
+        validator_index: u64,
+        target_epoch: Epoch,
+    },
+    /// An attester record refers to an indexed attestation that isn't stored.
+    MissingIndexedAttestation {
+        target_epoch: Epoch,
+        indexed_attestation_root: Hash256,
+    },
+}
+
+impl From<store::Error> for Error {
+    fn from(e: store::Error) -> Self {
+        Error::DatabaseError(e)
+    }
+}
+
+impl From<ssz::DecodeError> for Error {
+    fn from(e: ssz::DecodeError) -> Self {
+        Error::SszDecodeError(e)
+    }
+}
@@ -0,0 +1,20 @@
+//! A slasher which detects slashable attestations and block proposals.
+//!
+//! Attestations and blocks are queued by the beacon chain as they are verified, and processed in
+//! batches (see `Slasher::process_queued`). Double votes and double proposals are found by looking
+//! up records keyed by validator and epoch (or proposer and slot), whilst surround votes are found
+//! using min-max span arrays (see the `array` module).
+#[macro_use]
+extern crate lazy_static;
+
+mod array;
+pub mod config;
+mod database;
+mod error;
+pub mod metrics;
+mod slasher;
+
+pub use crate::slasher::Slasher;
+pub use config::{Config, MAX_HISTORY_LENGTH};
+pub use database::SlasherDB;
+pub use error::Error;
@@ -0,0 +1,36 @@
+pub use lighthouse_metrics::*;
+
+lazy_static! {
+    pub static ref SLASHER_RUN_TIME: Result<Histogram> = try_create_histogram(
+        "slasher_process_batch_time",
+        "Time taken to process a batch of blocks and attestations"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_VALID: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attestations_valid",
+        "Number of valid attestations per batch"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_DEFERRED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attestations_deferred",
+        "Number of attestations deferred to the next batch because their target is in the future"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_DROPPED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_attestations_dropped",
+        "Number of attestations dropped per batch because they are outside the history"
+    );
+    pub static ref SLASHER_NUM_ATTESTATIONS_QUEUE_FULL: Result<IntCounter> = try_create_int_counter(
+        "slasher_num_attestations_queue_full_total",
+        "Number of attestations dropped because the attestation queue was full"
+    );
+    pub static ref SLASHER_NUM_BLOCKS_PROCESSED: Result<IntGauge> = try_create_int_gauge(
+        "slasher_num_blocks_processed",
+        "Number of blocks processed per batch",
+    );
+    pub static ref SLASHER_NUM_ATTESTER_SLASHINGS: Result<IntCounter> = try_create_int_counter(
+        "slasher_num_attester_slashings_total",
+        "Number of attester slashings found by the slasher"
+    );
+    pub static ref SLASHER_NUM_PROPOSER_SLASHINGS: Result<IntCounter> = try_create_int_counter(
+        "slasher_num_proposer_slashings_total",
+        "Number of proposer slashings found by the slasher"
+    );
+}
@@ -0,0 +1,358 @@
+use crate::array::{SpanArrays, SurroundVote};
+use crate::database::{AttesterRecord, ProposalOutcome};
+use crate::{metrics, Config, Error, SlasherDB};
+use parking_lot::Mutex;
+use slog::{debug, info, Logger};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem;
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::{
+    AttestationData, AttesterSlashing, Epoch, EthSpec, Hash256, IndexedAttestation,
+    ProposerSlashing, SignedBeaconBlockHeader,
+};
+
+/// Detects slashable attestations and block proposals.
+///
+/// Items are queued via `Self::accept_attestation` and `Self::accept_block_header`, and only
+/// checked once `Self::process_queued` is called. Any slashings found are held until they are
+/// taken with `Self::take_attester_slashings` and `Self::take_proposer_slashings`.
+pub struct Slasher<E: EthSpec> {
+    db: SlasherDB<E>,
+    attestation_queue: Mutex<Vec<IndexedAttestation<E>>>,
+    block_queue: Mutex<Vec<SignedBeaconBlockHeader>>,
+    attester_slashings: Mutex<HashSet<AttesterSlashing<E>>>,
+    proposer_slashings: Mutex<Vec<ProposerSlashing>>,
+    config: Arc<Config>,
+    log: Logger,
+}
+
+/// Returns `true` if `data_1` and `data_2` form a double vote, or `data_1` surrounds `data_2`.
+fn is_slashable_attestation_data(data_1: &AttestationData, data_2: &AttestationData) -> bool {
+    (data_1 != data_2 && data_1.target.epoch == data_2.target.epoch)
+        || (data_1.source.epoch < data_2.source.epoch && data_2.target.epoch < data_1.target.epoch)
+}
+
+impl<E: EthSpec> Slasher<E> {
+    /// Open the slasher, creating its database if it doesn't already exist.
+    pub fn open(config: Config, log: Logger) -> Result<Self, Error> {
+        config.validate()?;
+        let config = Arc::new(config);
+        let db = SlasherDB::open(config.clone())?;
+        Ok(Self {
+            db,
+            attestation_queue: Mutex::new(vec![]),
+            block_queue: Mutex::new(vec![]),
+            attester_slashings: Mutex::new(HashSet::new()),
+            proposer_slashings: Mutex::new(vec![]),
+            config,
+            log,
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Queue an attestation with a valid signature for processing.
+    ///
+    /// The attestation is dropped if the queue already holds `config.max_queued_attestations`.
+    pub fn accept_attestation(&self, attestation: IndexedAttestation<E>) {
+        let mut queue = self.attestation_queue.lock();
+        if queue.len() < self.config.max_queued_attestations {
+            queue.push(attestation);
+        } else {
+            metrics::inc_counter(&metrics::SLASHER_NUM_ATTESTATIONS_QUEUE_FULL);
+        }
+    }
+
+    /// Queue a block header with a valid signature for processing.
+    pub fn accept_block_header(&self, block_header: SignedBeaconBlockHeader) {
+        self.block_queue.lock().push(block_header);
+    }
+
+    /// Take all attester slashings found so far.
+    pub fn take_attester_slashings(&self) -> HashSet<AttesterSlashing<E>> {
+        mem::take(&mut *self.attester_slashings.lock())
+    }
+
+    /// Take all proposer slashings found so far.
+    pub fn take_proposer_slashings(&self) -> Vec<ProposerSlashing> {
+        mem::take(&mut *self.proposer_slashings.lock())
+    }
+
+    /// Returns the lowest epoch for which the slasher holds history, given the `current_epoch`.
+    ///
+    /// The history is aligned to the chunks of the span arrays, so it may be up to
+    /// `chunk_size - 1` epochs shorter than `history_length`.
+    pub fn lowest_epoch(&self, current_epoch: Epoch) -> Epoch {
+        let chunk_size = self.config.chunk_size;
+        let next_chunk_start = (current_epoch.as_u64() / chunk_size + 1) * chunk_size;
+        Epoch::new(next_chunk_start.saturating_sub(self.config.history_length))
+    }
+
+    /// Check all queued blocks and attestations for slashable offences, storing them for future
+    /// checks.
+    ///
+    /// Attestations with a target later than `current_epoch` are kept in the queue until a later
+    /// call.
+    pub fn process_queued(&self, current_epoch: Epoch) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::SLASHER_RUN_TIME);
+        self.process_blocks(current_epoch)?;
+        self.process_attestations(current_epoch)?;
+        Ok(())
+    }
+
+    fn process_blocks(&self, current_epoch: Epoch) -> Result<(), Error> {
+        let lowest_epoch = self.lowest_epoch(current_epoch);
+        let block_headers = mem::take(&mut *self.block_queue.lock());
+        metrics::set_gauge(
+            &metrics::SLASHER_NUM_BLOCKS_PROCESSED,
+            block_headers.len() as i64,
+        );
+
+        for block_header in block_headers {
+            if block_header.message.slot.epoch(E::slots_per_epoch()) < lowest_epoch {
+                continue;
+            }
+
+            if let ProposalOutcome::Slashable(slashing) =
+                self.db.check_or_insert_block_proposal(block_header)?
+            {
+                let mut proposer_slashings = self.proposer_slashings.lock();
+                if !proposer_slashings.contains(&slashing) {
+                    info!(
+                        self.log,
+                        "Found proposer slashing";
+                        "proposer_index" => slashing.signed_header_1.message.proposer_index,
+                        "slot" => slashing.signed_header_1.message.slot,
+                    );
+                    metrics::inc_counter(&metrics::SLASHER_NUM_PROPOSER_SLASHINGS);
+                    proposer_slashings.push(slashing);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn process_attestations(&self, current_epoch: Epoch) -> Result<(), Error> {
+        let lowest_epoch = self.lowest_epoch(current_epoch);
+        let queued = mem::take(&mut *self.attestation_queue.lock());
+
+        let mut deferred = vec![];
+        let mut num_dropped = 0;
+        let mut seen = HashSet::new();
+        let mut attestations = vec![];
+
+        for attestation in queued {
+            let source = attestation.data.source.epoch;
+            let target = attestation.data.target.epoch;
+
+            if target > current_epoch {
+                deferred.push(attestation);
+            } else if target < lowest_epoch || source > target {
+                num_dropped += 1;
+            } else {
+                let hash = attestation.tree_hash_root();
+                if seen.insert(hash) {
+                    attestations.push((hash, attestation));
+                }
+            }
+        }
+
+        metrics::set_gauge(
+            &metrics::SLASHER_NUM_ATTESTATIONS_VALID,
+            attestations.len() as i64,
+        );
+        metrics::set_gauge(
+            &metrics::SLASHER_NUM_ATTESTATIONS_DEFERRED,
+            deferred.len() as i64,
+        );
+        metrics::set_gauge(&metrics::SLASHER_NUM_ATTESTATIONS_DROPPED, num_dropped);
+
+        debug!(
+            self.log,
+            "Processing queued attestations";
+            "valid" => attestations.len(),
+            "deferred" => deferred.len(),
+            "dropped" => num_dropped,
+        );
+
+        // Attestations which arrived during this batch take precedence over deferred ones, since
+        // they were accepted while there was room in the queue.
+        {
+            let mut queue = self.attestation_queue.lock();
+            let space = self
+                .config
+                .max_queued_attestations
+                .saturating_sub(queue.len());
+            if deferred.len() > space {
+                metrics::inc_counter_by(
+                    &metrics::SLASHER_NUM_ATTESTATIONS_QUEUE_FULL,
+                    (deferred.len() - space) as i64,
+                );
+            }
+            queue.extend(deferred.into_iter().take(space));
+        }
+
+        // Store the indexed attestations first, so that they can be found when building slashings.
+        let mut by_target_epoch = BTreeMap::<_, Vec<_>>::new();
+        for (hash, attestation) in &attestations {
+            by_target_epoch
+                .entry(attestation.data.target.epoch)
+                .or_default()
+                .push((*hash, attestation));
+        }
+        for (target_epoch, attestations) in by_target_epoch {
+            self.db
+                .store_indexed_attestations(target_epoch, &attestations)?;
+        }
+
+        // The attester records and span arrays are written together at the end of the batch, so
+        // that a failure part-way through cannot leave records whose spans were never stored.
+        let mut arrays = SpanArrays::new(&self.db, &self.config);
+        let mut records = HashMap::new();
+        for (hash, attestation) in &attestations {
+            let data_hash = attestation.data.tree_hash_root();
+            for &validator_index in attestation.attesting_indices.iter() {
+                self.process_attestation_for_validator(
+                    &mut arrays,
+                    &mut records,
+                    validator_index,
+                    attestation,
+                    *hash,
+                    data_hash,
+                    lowest_epoch,
+                )?;
+            }
+        }
+
+        let mut ops = arrays.into_ops();
+        ops.extend(records.iter().map(|((validator_index, _), record)| {
+            self.db.attester_record_op(*validator_index, record)
+        }));
+        self.db.write(ops)?;
+
+        Ok(())
+    }
+
+    /// Returns the attester record of `validator_index` for `target_epoch`, preferring a record
+    /// from the current batch (`records`) to one in the database.
+    fn get_attester_record(
+        &self,
+        records: &HashMap<(u64, Epoch), AttesterRecord>,
+        validator_index: u64,
+        target_epoch: Epoch,
+    ) -> Result<Option<AttesterRecord>, Error> {
+        match records.get(&(validator_index, target_epoch)) {
+            Some(record) => Ok(Some(record.clone())),
+            None => self.db.get_attester_record(validator_index, target_epoch),
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn process_attestation_for_validator(
+        &self,
+        arrays: &mut SpanArrays<E>,
+        records: &mut HashMap<(u64, Epoch), AttesterRecord>,
+        validator_index: u64,
+        attestation: &IndexedAttestation<E>,
+        indexed_attestation_hash: Hash256,
+        attestation_data_hash: Hash256,
+        lowest_epoch: Epoch,
+    ) -> Result<(), Error> {
+        let source = attestation.data.source.epoch;
+        let target = attestation.data.target.epoch;
+
+        match self.get_attester_record(records, validator_index, target)? {
+            // This validator's vote has already been processed, possibly as part of another
+            // aggregate.
+            Some(record) if record.attestation_data_hash == attestation_data_hash => return Ok(()),
+            Some(record) => {
+                let existing = self
+                    .db
+                    .get_indexed_attestation(target, record.indexed_attestation_hash)?;
+                self.add_attester_slashing(existing, attestation.clone(), validator_index);
+            }
+            None => {
+                records.insert(
+                    (validator_index, target),
+                    AttesterRecord {
+                        target_epoch: target,
+                        attestation_data_hash,
+                        indexed_attestation_hash,
+                    },
+                );
+            }
+        }
+
+        for surround_vote in
+            arrays.apply_attestation(validator_index, source, target, lowest_epoch)?
+        {
+            let existing_target = match surround_vote {
+                SurroundVote::SurroundsExisting { existing_target }
+                | SurroundVote::SurroundedByExisting { existing_target } => existing_target,
+            };
+            let record = self
+                .get_attester_record(records, validator_index, existing_target)?
+                .ok_or_else(|| Error::MissingAttesterRecord {
+                    validator_index,
+                    target_epoch: existing_target,
+                })?;
+            let existing = self
+                .db
+                .get_indexed_attestation(existing_target, record.indexed_attestation_hash)?;
+
+            match surround_vote {
+                SurroundVote::SurroundsExisting { .. } => {
+                    self.add_attester_slashing(attestation.clone(), existing, validator_index)
+                }
+                SurroundVote::SurroundedByExisting { .. } => {
+                    self.add_attester_slashing(existing, attestation.clone(), validator_index)
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_attester_slashing(
+        &self,
+        attestation_1: IndexedAttestation<E>,
+        attestation_2: IndexedAttestation<E>,
+        validator_index: u64,
+    ) {
+        // The span arrays and attester records only hold one attestation per validator and target
+        // epoch, so when a validator has double voted the stored attestation may not be the one
+        // that formed the surround vote.
+        if !is_slashable_attestation_data(&attestation_1.data, &attestation_2.data) {
+            debug!(
+                self.log,
+                "Ignoring non-slashable attestation pair";
+                "validator_index" => validator_index,
+                "target_1" => attestation_1.data.target.epoch,
+                "target_2" => attestation_2.data.target.epoch,
+            );
+            return;
+        }
+
+        let slashing = AttesterSlashing {
+            attestation_1,
+            attestation_2,
+        };
+
+        if self.attester_slashings.lock().insert(slashing.clone()) {
+            info!(
+                self.log,
+                "Found attester slashing";
+                "validator_index" => validator_index,
+                "source_1" => slashing.attestation_1.data.source.epoch,
+                "target_1" => slashing.attestation_1.data.target.epoch,
+                "source_2" => slashing.attestation_2.data.source.epoch,
+                "target_2" => slashing.attestation_2.data.target.epoch,
+            );
+            metrics::inc_counter(&metrics::SLASHER_NUM_ATTESTER_SLASHINGS);
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use slasher::{Config, Error, Slasher, MAX_HISTORY_LENGTH};
+use slog::{o, Discard, Logger};
+use std::path::Path;
+use tempfile::tempdir;
+use types::{
+    AggregateSignature, AttestationData, BeaconBlockHeader, Checkpoint, Epoch, Hash256,
+    IndexedAttestation, MainnetEthSpec, Signature, SignedBeaconBlockHeader, Slot,
+};
+
+type E = MainnetEthSpec;
+
+fn logger() -> Logger {
+    Logger::root(Discard, o!())
+}
+
+fn small_config(path: &Path) -> Config {
+    let mut config = Config::new(path.into());
+    config.chunk_size = 4;
+    config.history_length = 16;
+    config
+}
+
+fn indexed_att(
+    attesting_indices: &[u64],
+    source: u64,
+    target: u64,
+    target_root: u64,
+) -> IndexedAttestation<E> {
+    IndexedAttestation {
+        attesting_indices: attesting_indices.to_vec().into(),
+        data: AttestationData {
+            slot: Epoch::new(target).start_slot(E::slots_per_epoch()),
+            index: 0,
+            beacon_block_root: Hash256::zero(),
+            source: Checkpoint {
+                epoch: Epoch::new(source),
+                root: Hash256::from_low_u64_be(source),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(target),
+                root: Hash256::from_low_u64_be(target_root),
+            },
+        },
+        signature: AggregateSignature::empty(),
+    }
+}
+
+fn block_header(slot: u64, proposer_index: u64, body_root: u64) -> SignedBeaconBlockHeader {
+    SignedBeaconBlockHeader {
+        message: BeaconBlockHeader {
+            slot: Slot::new(slot),
+            proposer_index,
+            parent_root: Hash256::zero(),
+            state_root: Hash256::zero(),
+            body_root: Hash256::from_low_u64_be(body_root),
+        },
+        signature: Signature::empty(),
+    }
+}
+
+/// Process `attestations` in a single batch at `current_epoch`, returning the number of attester
+/// slashings found.
+fn process_attestations(
+    slasher: &Slasher<E>,
+    attestations: Vec<IndexedAttestation<E>>,
+    current_epoch: u64,
+) -> usize {
+    for attestation in attestations {
+        slasher.accept_attestation(attestation);
+    }
+    slasher
+        .process_queued(Epoch::new(current_epoch))
+        .expect("should process attestations");
+    slasher.take_attester_slashings().len()
+}
+
+#[test]
+fn double_vote() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    let att1 = indexed_att(&[0, 1], 0, 1, 1);
+    let att2 = indexed_att(&[1, 2], 0, 1, 2);
+
+    assert_eq!(process_attestations(&slasher, vec![att1, att2], 1), 1);
+}
+
+#[test]
+fn double_vote_across_batches() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 0, 1, 1)], 1),
+        0
+    );
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 0, 1, 2)], 2),
+        1
+    );
+}
+
+#[test]
+fn surrounding_vote() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 2, 3, 0)], 3),
+        0
+    );
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 1, 4, 0)], 4),
+        1
+    );
+}
+
+#[test]
+fn surrounding_vote_within_batch() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    let att1 = indexed_att(&[0], 2, 3, 0);
+    let att2 = indexed_att(&[0], 1, 4, 0);
+
+    assert_eq!(process_attestations(&slasher, vec![att1, att2], 4), 1);
+}
+
+#[test]
+fn attestation_queue_is_bounded() {
+    let dir = tempdir().unwrap();
+    let mut config = small_config(dir.path());
+    config.max_queued_attestations = 2;
+    let slasher = Slasher::open(config, logger()).unwrap();
+
+    // The third attestation does not fit in the queue, so only the first two are compared.
+    let atts = vec![
+        indexed_att(&[0], 0, 1, 1),
+        indexed_att(&[0], 0, 1, 2),
+        indexed_att(&[0], 0, 1, 3),
+    ];
+    assert_eq!(process_attestations(&slasher, atts, 1), 1);
+
+    // Once processed, the queue has room again.
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 0, 1, 3)], 1),
+        1
+    );
+}
+
+#[test]
+fn surrounded_vote() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 1, 6, 0)], 6),
+        0
+    );
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 2, 5, 0)], 6),
+        1
+    );
+}
+
+#[test]
+fn surround_vote_across_chunks() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[3], 5, 6, 0)], 6),
+        0
+    );
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[3], 0, 10, 0)], 10),
+        1
+    );
+}
+
+#[test]
+fn no_false_positives() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    let attestations = (1..12)
+        .map(|epoch| indexed_att(&[0, 1, 2], epoch - 1, epoch, epoch))
+        .collect::<Vec<_>>();
+
+    // Re-processing the same attestations must not produce a slashing either.
+    assert_eq!(process_attestations(&slasher, attestations.clone(), 11), 0);
+    assert_eq!(process_attestations(&slasher, attestations, 11), 0);
+
+    // A validator skipping epochs is not slashable.
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 11, 14, 14)], 14),
+        0
+    );
+}
+
+#[test]
+fn future_attestations_are_deferred() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    let att1 = indexed_att(&[0], 1, 2, 1);
+    let att2 = indexed_att(&[0], 1, 2, 2);
+
+    assert_eq!(process_attestations(&slasher, vec![att1, att2], 1), 0);
+    assert_eq!(process_attestations(&slasher, vec![], 2), 1);
+}
+
+#[test]
+fn attestations_outside_history_are_ignored() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 1, 2, 1)], 2),
+        0
+    );
+
+    // Conflicting with the first attestation, but too old to be checked.
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 1, 2, 2)], 40),
+        0
+    );
+
+    // Occupies the same positions in the ring buffer as the first attestation, but is not
+    // slashable with respect to it.
+    assert_eq!(
+        process_attestations(&slasher, vec![indexed_att(&[0], 33, 34, 3)], 40),
+        0
+    );
+}
+
+#[test]
+fn double_proposal() {
+    let dir = tempdir().unwrap();
+    let slasher = Slasher::open(small_config(dir.path()), logger()).unwrap();
+
+    slasher.accept_block_header(block_header(5, 1, 1));
+    slasher.accept_block_header(block_header(5, 1, 1));
+    slasher.accept_block_header(block_header(5, 2, 2));
+    slasher.accept_block_header(block_header(6, 1, 2));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    assert!(slasher.take_proposer_slashings().is_empty());
+
+    slasher.accept_block_header(block_header(5, 1, 3));
+    slasher.process_queued(Epoch::new(0)).unwrap();
+    assert_eq!(slasher.take_proposer_slashings().len(), 1);
+}
+
+#[test]
+fn incompatible_layout() {
+    let dir = tempdir().unwrap();
+    drop(Slasher::<E>::open(small_config(dir.path()), logger()).unwrap());
+
+    let mut config = small_config(dir.path());
+    config.history_length = 32;
+    match Slasher::<E>::open(config, logger()) {
+        Err(Error::ConfigIncompatible { .. }) => (),
+        _ => panic!("database layout change should be rejected"),
+    }
+}
+
+#[test]
+fn history_length_excludes_empty_sentinel() {
+    let dir = tempdir().unwrap();
+    let mut config = small_config(dir.path());
+
+    config.chunk_size = 2;
+    config.history_length = MAX_HISTORY_LENGTH;
+    assert!(config.validate().is_ok());
+
+    // A distance of `u16::MAX` would be indistinguishable from an empty min-span entry.
+    config.chunk_size = 5;
+    config.history_length = u16::MAX as u64;
+    assert!(config.validate().is_err());
+}
@@ -8,6 +8,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 use web3::{
     transports::{EventLoopHandle, Http},
+    types::Address,
     Transport, Web3,
 };
 
@@ -128,6 +129,32 @@ impl GanacheInstance {
             .map_err(|e| format!("Failed to get block number: {:?}", e))
     }
 
+    /// Returns the chain id reported by `eth_chainId`.
+    pub async fn chain_id(&self) -> Result<u64, String> {
+        self.web3
+            .transport()
+            .execute("eth_chainId", vec![])
+            .compat()
+            .await
+            .map_err(|e| format!("Failed to get chain id: {:?}", e))
+            .and_then(|value| {
+                value
+                    .as_str()
+                    .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+                    .ok_or_else(|| format!("Invalid chain id: {}", value))
+            })
+    }
+
+    /// Returns the accounts which are unlocked in the ganache instance.
+    pub async fn accounts(&self) -> Result<Vec<Address>, String> {
+        self.web3
+            .eth()
+            .accounts()
+            .compat()
+            .await
+            .map_err(|e| format!("Failed to get accounts: {:?}", e))
+    }
+
     /// Mines a single block.
     pub async fn evm_mine(&self) -> Result<(), String> {
         self.web3
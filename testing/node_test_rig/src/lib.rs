@@ -39,14 +39,29 @@ impl<E: EthSpec> LocalBeaconNode<E> {
     /// The node created is using the same types as the node we use in production.
     pub async fn production(
         context: RuntimeContext<E>,
-        mut client_config: ClientConfig,
+        client_config: ClientConfig,
     ) -> Result<Self, String> {
         // Creates a temporary directory that will be deleted once this `TempDir` is dropped.
         let datadir = TempDir::new("lighthouse_node_test_rig")
             .expect("should create temp directory for client datadir");
 
+        Self::production_with_datadir(context, client_config, datadir).await
+    }
+
+    /// Starts a new, production beacon node using an existing `datadir`.
+    ///
+    /// Useful for restarting a node with the database, network key and slasher database of a
+    /// previous node.
+    pub async fn production_with_datadir(
+        context: RuntimeContext<E>,
+        mut client_config: ClientConfig,
+        datadir: TempDir,
+    ) -> Result<Self, String> {
         client_config.data_dir = datadir.path().into();
         client_config.network.network_dir = PathBuf::from(datadir.path()).join("network");
+        if let Some(slasher_config) = client_config.slasher.as_mut() {
+            slasher_config.database_path = PathBuf::from(datadir.path()).join("slasher_db");
+        }
 
         ProductionBeaconNode::new(context, client_config)
             .await
@@ -1,5 +1,8 @@
 use crate::local_network::LocalNetwork;
-use node_test_rig::eth2::types::StateId;
+use node_test_rig::eth2::{
+    types::{BlockId, StateId},
+    BeaconNodeHttpClient,
+};
 use std::time::Duration;
 use types::{Epoch, EthSpec, Slot, Unsigned};
 
@@ -143,3 +146,109 @@ pub async fn verify_full_block_production_up_to<E: EthSpec>(
     }
     Ok(())
 }
+
+/// Verifies that all of the given beacon nodes have a head state with a finalized epoch of at
+/// least `epoch`.
+pub async fn verify_finalized_at_least(
+    remote_nodes: &[BeaconNodeHttpClient],
+    epoch: Epoch,
+) -> Result<(), String> {
+    let mut epochs = Vec::with_capacity(remote_nodes.len());
+    for remote_node in remote_nodes {
+        epochs.push(
+            remote_node
+                .get_beacon_states_finality_checkpoints(StateId::Head)
+                .await
+                .map_err(|e| format!("Get finality checkpoints via http failed: {:?}", e))?
+                .ok_or_else(|| "Head state not found".to_string())?
+                .data
+                .finalized
+                .epoch,
+        );
+    }
+
+    if epochs.iter().any(|node_epoch| *node_epoch < epoch) {
+        Err(format!(
+            "Nodes are not finalized at or after epoch {}. Finalized epochs: {:?}",
+            epoch, epochs
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Verifies that all of the given beacon nodes have the same head block.
+pub async fn verify_head_agreement(remote_nodes: &[BeaconNodeHttpClient]) -> Result<(), String> {
+    let mut heads = Vec::with_capacity(remote_nodes.len());
+    for remote_node in remote_nodes {
+        heads.push(
+            remote_node
+                .get_beacon_headers_block_id(BlockId::Head)
+                .await
+                .map_err(|e| format!("Get head via http failed: {:?}", e))?
+                .ok_or_else(|| "Head block not found".to_string())?
+                .data
+                .root,
+        );
+    }
+
+    if heads.windows(2).any(|pair| pair[0] != pair[1]) {
+        Err(format!(
+            "Nodes do not agree on the head. Heads: {:?}",
+            heads
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Verifies that none of the given beacon nodes know of any proposer or attester slashings, and
+/// that no validator has been slashed in their head states.
+///
+/// Slashings are only detected if at least one node in the network is running a slasher.
+pub async fn verify_no_slashings<E: EthSpec>(
+    remote_nodes: &[BeaconNodeHttpClient],
+) -> Result<(), String> {
+    for (i, remote_node) in remote_nodes.iter().enumerate() {
+        let proposer_slashings = remote_node
+            .get_beacon_pool_proposer_slashings()
+            .await
+            .map_err(|e| format!("Get proposer slashings via http failed: {:?}", e))?
+            .data;
+        let attester_slashings = remote_node
+            .get_beacon_pool_attester_slashings::<E>()
+            .await
+            .map_err(|e| format!("Get attester slashings via http failed: {:?}", e))?
+            .data;
+
+        if !proposer_slashings.is_empty() || !attester_slashings.is_empty() {
+            return Err(format!(
+                "Node {} knows of {} proposer slashings and {} attester slashings",
+                i,
+                proposer_slashings.len(),
+                attester_slashings.len()
+            ));
+        }
+
+        // Slashings which have already been included in a block are no longer in the pool.
+        let slashed = remote_node
+            .get_beacon_states_validators(StateId::Head)
+            .await
+            .map_err(|e| format!("Get validators via http failed: {:?}", e))?
+            .ok_or_else(|| "Head state not found".to_string())?
+            .data
+            .into_iter()
+            .filter(|validator| validator.validator.slashed)
+            .map(|validator| validator.index)
+            .collect::<Vec<_>>();
+
+        if !slashed.is_empty() {
+            return Err(format!(
+                "Node {} has slashed validators in its head state: {:?}",
+                i, slashed
+            ));
+        }
+    }
+
+    Ok(())
+}
@@ -105,4 +105,21 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                         .help("Sync verification strategy to run."),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("scenario-sim")
+                .about(
+                    "Runs a simulation described by a YAML scenario file. The scenario may inject \
+                    network faults (partitions, killed nodes, clock skew, delayed or dropped \
+                    gossip) at given slots and assert that the network finalizes, agrees on \
+                    the head and produces no slashable messages.",
+                )
+                .arg(
+                    Arg::with_name("scenario")
+                        .long("scenario")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to a YAML scenario file, e.g. scenarios/partition.yaml"),
+                ),
+        )
 }
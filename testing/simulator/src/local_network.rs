@@ -8,7 +8,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 use types::{Epoch, EthSpec};
 
-const BOOTNODE_PORT: u16 = 42424;
+pub const BOOTNODE_PORT: u16 = 42424;
 
 /// Helper struct to reduce `Arc` usage.
 pub struct Inner<E: EthSpec> {
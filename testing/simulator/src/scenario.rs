@@ -0,0 +1,700 @@
+//! Runs a simulation which is driven by a YAML scenario file.
+//!
+//! A scenario describes a network of beacon nodes (each with a single validator client) and a
+//! list of steps. Each step is executed half-way through its `slot` and either injects a fault
+//! into the network (e.g., a partition or a killed node) or asserts some property of the network
+//! (e.g., that it has finalized).
+//!
+//! Example:
+//!
+//! ```yaml
+//! nodes: 4
+//! validators_per_node: 16
+//! steps:
+//!   - slot: 16
+//!     action: partition
+//!     groups: [[0, 1], [2, 3]]
+//!   - slot: 40
+//!     action: heal
+//!   - slot: 80
+//!     action: assert_finalized_epoch
+//!     epoch: 6
+//! ```
+//!
+//! See the `scenarios` directory for more examples.
+use crate::checks;
+use crate::local_network::BOOTNODE_PORT;
+use crate::E;
+use clap::ArgMatches;
+use eth2_libp2p::TransportFaults;
+use node_test_rig::{
+    environment::{EnvironmentBuilder, RuntimeContext},
+    eth2::BeaconNodeHttpClient,
+    testing_client_config, testing_validator_config, ClientConfig, ClientGenesis, LocalBeaconNode,
+    LocalValidatorClient, ValidatorConfig, ValidatorFiles,
+};
+use rayon::prelude::*;
+use serde_derive::Deserialize;
+use std::collections::HashSet;
+use std::fs::File;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tempdir::TempDir;
+use tokio::time::{delay_for, delay_until, Instant};
+use types::{Epoch, EthSpec};
+
+/// The time given to a killed node to release its resources (e.g., its database) before it is
+/// restarted.
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// The time between genesis and the start of the simulation.
+const GENESIS_DELAY: Duration = Duration::from_secs(10);
+
+/// The beacon node which runs a slasher.
+const SLASHER_NODE: usize = 0;
+
+fn default_speed_up_factor() -> u64 {
+    3
+}
+
+/// A simulation, as described by a scenario file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// The number of beacon nodes, each of which has a single validator client.
+    pub nodes: usize,
+    /// The number of validators managed by each validator client.
+    pub validators_per_node: usize,
+    /// Divides the slot duration by this factor.
+    #[serde(default = "default_speed_up_factor")]
+    pub speed_up_factor: u64,
+    /// The steps of the scenario, which must be ordered by slot.
+    pub steps: Vec<Step>,
+}
+
+/// An action to be executed at some slot.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Step {
+    pub slot: u64,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    /// Splits the beacon nodes into groups which cannot communicate with each other. Every node
+    /// must be in exactly one group.
+    Partition { groups: Vec<Vec<usize>> },
+    /// Removes any partition.
+    Heal,
+    /// Shuts down a beacon node, retaining its database.
+    KillBeaconNode { node: usize },
+    /// Starts a previously killed beacon node, using its previous database.
+    RestartBeaconNode { node: usize },
+    /// Shuts down a validator client, retaining its keys and slashing protection database.
+    KillValidatorClient { validator_client: usize },
+    /// Starts a previously killed validator client.
+    RestartValidatorClient { validator_client: usize },
+    /// Restarts a beacon node with its slot clock offset from the system clock by `millis`.
+    SkewClock { node: usize, millis: i64 },
+    /// Delays all bytes received by a beacon node from its peers by `millis`.
+    DelayGossip { node: usize, millis: u64 },
+    /// Discards all bytes received by a beacon node from its peers.
+    DropGossip { node: usize },
+    /// Removes any gossip delay or drop from a beacon node.
+    RestoreGossip { node: usize },
+    /// Asserts that the given nodes (or all running nodes) have finalized at least `epoch`.
+    AssertFinalizedEpoch {
+        epoch: u64,
+        #[serde(default)]
+        nodes: Option<Vec<usize>>,
+    },
+    /// Asserts that the given nodes (or all running nodes) have the same head block.
+    AssertHeadAgreement {
+        #[serde(default)]
+        nodes: Option<Vec<usize>>,
+    },
+    /// Asserts that no running node knows of a proposer or attester slashing, or has a slashed
+    /// validator in its head state. The node running the slasher must be running.
+    AssertNoSlashings,
+}
+
+impl Scenario {
+    pub fn from_file(path: &PathBuf) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Unable to open scenario file {:?}: {:?}", path, e))?;
+        let scenario: Self = serde_yaml::from_reader(file)
+            .map_err(|e| format!("Unable to parse scenario file {:?}: {:?}", path, e))?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Checks that all steps are ordered and only refer to nodes that exist.
+    fn validate(&self) -> Result<(), String> {
+        if self.nodes == 0 {
+            return Err("A scenario must have at least one node".into());
+        }
+        if self.speed_up_factor == 0 {
+            return Err("speed_up_factor must be greater than zero".into());
+        }
+
+        let check_node = |node: usize| {
+            if node < self.nodes {
+                Ok(())
+            } else {
+                Err(format!(
+                    "Node {} does not exist, the scenario has {} nodes",
+                    node, self.nodes
+                ))
+            }
+        };
+
+        let mut prev_slot = 0;
+        let mut slasher_running = true;
+        for step in &self.steps {
+            if step.slot < prev_slot {
+                return Err(format!(
+                    "Steps must be ordered by slot, slot {} is after slot {}",
+                    step.slot, prev_slot
+                ));
+            }
+            prev_slot = step.slot;
+
+            match &step.action {
+                Action::Partition { groups } => {
+                    let mut seen = HashSet::new();
+                    for node in groups.iter().flatten() {
+                        check_node(*node)?;
+                        if !seen.insert(*node) {
+                            return Err(format!("Node {} is in more than one group", node));
+                        }
+                    }
+                    if seen.len() != self.nodes {
+                        return Err("A partition must place every node in a group".into());
+                    }
+                }
+                Action::KillBeaconNode { node } => {
+                    check_node(*node)?;
+                    if *node == SLASHER_NODE {
+                        slasher_running = false;
+                    }
+                }
+                Action::RestartBeaconNode { node } => {
+                    check_node(*node)?;
+                    if *node == SLASHER_NODE {
+                        slasher_running = true;
+                    }
+                }
+                Action::SkewClock { node, .. }
+                | Action::DelayGossip { node, .. }
+                | Action::DropGossip { node }
+                | Action::RestoreGossip { node } => check_node(*node)?,
+                Action::KillValidatorClient { validator_client }
+                | Action::RestartValidatorClient { validator_client } => {
+                    check_node(*validator_client)?
+                }
+                Action::AssertFinalizedEpoch { nodes, .. }
+                | Action::AssertHeadAgreement { nodes } => {
+                    for node in nodes.iter().flatten() {
+                        check_node(*node)?;
+                    }
+                }
+                Action::AssertNoSlashings => {
+                    if !slasher_running {
+                        return Err(format!(
+                            "Cannot assert no slashings at slot {} whilst the slasher node ({}) \
+                             is stopped",
+                            step.slot, SLASHER_NODE
+                        ));
+                    }
+                }
+                Action::Heal => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A beacon node which may be stopped and started.
+struct BeaconNodeHandle {
+    config: ClientConfig,
+    faults: TransportFaults,
+    /// The running node, along with the signal which shuts it down when dropped.
+    running: Option<(LocalBeaconNode<E>, exit_future::Signal)>,
+    /// The data directory of a stopped node.
+    datadir: Option<TempDir>,
+}
+
+/// A validator client which may be stopped and started.
+struct ValidatorClientHandle {
+    config: ValidatorConfig,
+    /// The running client, along with the signal which shuts it down when dropped.
+    running: Option<(LocalValidatorClient<E>, exit_future::Signal)>,
+    /// The files of a stopped client.
+    files: Option<ValidatorFiles>,
+}
+
+/// A network of beacon nodes and validator clients which can be manipulated by a scenario.
+///
+/// Unlike `LocalNetwork`, each node is given its own exit signal so that it may be shut down
+/// and restarted without affecting the rest of the network.
+struct ScenarioNetwork {
+    context: RuntimeContext<E>,
+    beacon_nodes: Vec<BeaconNodeHandle>,
+    validator_clients: Vec<ValidatorClientHandle>,
+}
+
+impl ScenarioNetwork {
+    /// Returns a context for a single node, which is shut down when the returned signal is fired
+    /// or dropped.
+    fn node_context(&self, service_name: String) -> (RuntimeContext<E>, exit_future::Signal) {
+        let (signal, exit) = exit_future::signal();
+        let context = self.context.service_context(service_name);
+        let context = RuntimeContext {
+            executor: context.executor.clone_with_exit(exit),
+            ..context
+        };
+        (context, signal)
+    }
+
+    /// Adds and starts a beacon node. The first node acts as the boot node for all others.
+    async fn add_beacon_node(&mut self, mut config: ClientConfig) -> Result<(), String> {
+        let index = self.beacon_nodes.len();
+        let port = BOOTNODE_PORT + index as u16;
+        config.network.discovery_port = port;
+        config.network.libp2p_port = port;
+        config.network.enr_udp_port = Some(port);
+        config.network.enr_tcp_port = Some(port);
+
+        if let Some(boot_node) = self.beacon_nodes.first() {
+            let enr = boot_node
+                .running
+                .as_ref()
+                .and_then(|(node, _)| node.client.enr())
+                .ok_or_else(|| "Boot node must be running".to_string())?;
+            config.network.boot_nodes_enr.push(enr);
+        }
+
+        let faults = TransportFaults::default();
+        config.network.transport_faults = Some(faults.clone());
+
+        let datadir = TempDir::new("lighthouse_scenario_node")
+            .map_err(|e| format!("Unable to create beacon node datadir: {:?}", e))?;
+
+        self.beacon_nodes.push(BeaconNodeHandle {
+            config,
+            faults,
+            running: None,
+            datadir: Some(datadir),
+        });
+        self.start_beacon_node(index).await
+    }
+
+    async fn start_beacon_node(&mut self, index: usize) -> Result<(), String> {
+        let (context, signal) = self.node_context(format!("node_{}", index));
+        let handle = self.beacon_node_mut(index)?;
+        let datadir = handle
+            .datadir
+            .take()
+            .ok_or_else(|| format!("Beacon node {} is already running", index))?;
+
+        let node =
+            LocalBeaconNode::production_with_datadir(context, handle.config.clone(), datadir)
+                .await?;
+
+        // Reuse the same HTTP port after a restart, so validator clients can reconnect.
+        let http_addr = node
+            .client
+            .http_api_listen_addr()
+            .ok_or_else(|| "Beacon node must have a http server".to_string())?;
+        handle.config.http_api.listen_port = http_addr.port();
+
+        handle.running = Some((node, signal));
+        Ok(())
+    }
+
+    fn stop_beacon_node(&mut self, index: usize) -> Result<(), String> {
+        let handle = self.beacon_node_mut(index)?;
+        let (node, signal) = handle
+            .running
+            .take()
+            .ok_or_else(|| format!("Beacon node {} is not running", index))?;
+        drop(signal);
+
+        let LocalBeaconNode { client, datadir } = node;
+        drop(client);
+        handle.datadir = Some(datadir);
+        Ok(())
+    }
+
+    /// Adds and starts a validator client, attached to the beacon node with the same index.
+    async fn add_validator_client(
+        &mut self,
+        mut config: ValidatorConfig,
+        files: ValidatorFiles,
+    ) -> Result<(), String> {
+        let index = self.validator_clients.len();
+        let http_port = self.beacon_node_mut(index)?.config.http_api.listen_port;
+        config.beacon_node = format!("http://{}:{}", Ipv4Addr::LOCALHOST, http_port);
+
+        self.validator_clients.push(ValidatorClientHandle {
+            config,
+            running: None,
+            files: Some(files),
+        });
+        self.start_validator_client(index).await
+    }
+
+    async fn start_validator_client(&mut self, index: usize) -> Result<(), String> {
+        let (context, signal) = self.node_context(format!("validator_{}", index));
+        let handle = self
+            .validator_clients
+            .get_mut(index)
+            .ok_or_else(|| format!("No validator client for index {}", index))?;
+        let files = handle
+            .files
+            .take()
+            .ok_or_else(|| format!("Validator client {} is already running", index))?;
+
+        let validator_client = LocalValidatorClient::production_with_insecure_keypairs(
+            context,
+            handle.config.clone(),
+            files,
+        )
+        .await?;
+
+        handle.running = Some((validator_client, signal));
+        Ok(())
+    }
+
+    fn stop_validator_client(&mut self, index: usize) -> Result<(), String> {
+        let handle = self
+            .validator_clients
+            .get_mut(index)
+            .ok_or_else(|| format!("No validator client for index {}", index))?;
+        let (validator_client, signal) = handle
+            .running
+            .take()
+            .ok_or_else(|| format!("Validator client {} is not running", index))?;
+        drop(signal);
+
+        let LocalValidatorClient { client, files } = validator_client;
+        drop(client);
+        handle.files = Some(files);
+        Ok(())
+    }
+
+    fn beacon_node_mut(&mut self, index: usize) -> Result<&mut BeaconNodeHandle, String> {
+        self.beacon_nodes
+            .get_mut(index)
+            .ok_or_else(|| format!("No beacon node for index {}", index))
+    }
+
+    /// Returns HTTP clients for the given beacon nodes, or all running beacon nodes if `nodes` is
+    /// `None`.
+    fn remote_nodes(
+        &self,
+        nodes: &Option<Vec<usize>>,
+    ) -> Result<Vec<BeaconNodeHttpClient>, String> {
+        let indices = nodes
+            .clone()
+            .unwrap_or_else(|| (0..self.beacon_nodes.len()).collect());
+
+        let mut remote_nodes = vec![];
+        for index in indices {
+            match self
+                .beacon_nodes
+                .get(index)
+                .and_then(|h| h.running.as_ref())
+            {
+                Some((node, _)) => remote_nodes.push(node.remote_node()?),
+                // Stopped nodes are only an error if they were explicitly requested.
+                None if nodes.is_some() => {
+                    return Err(format!("Beacon node {} is not running", index))
+                }
+                None => {}
+            }
+        }
+        Ok(remote_nodes)
+    }
+
+    async fn execute(&mut self, action: &Action) -> Result<(), String> {
+        match action {
+            Action::Partition { groups } => {
+                for group in groups {
+                    let blocked_ports = groups
+                        .iter()
+                        .filter(|other| *other != group)
+                        .flatten()
+                        .map(|node| BOOTNODE_PORT + *node as u16)
+                        .collect::<Vec<_>>();
+                    for node in group {
+                        let faults = &self.beacon_node_mut(*node)?.faults;
+                        faults.unblock_all_ports();
+                        faults.block_ports(blocked_ports.iter().copied());
+                    }
+                }
+                Ok(())
+            }
+            Action::Heal => {
+                for handle in &self.beacon_nodes {
+                    handle.faults.unblock_all_ports();
+                }
+                Ok(())
+            }
+            Action::KillBeaconNode { node } => self.stop_beacon_node(*node),
+            Action::RestartBeaconNode { node } => self.start_beacon_node(*node).await,
+            Action::KillValidatorClient { validator_client } => {
+                self.stop_validator_client(*validator_client)
+            }
+            Action::RestartValidatorClient { validator_client } => {
+                self.start_validator_client(*validator_client).await
+            }
+            Action::SkewClock { node, millis } => {
+                self.beacon_node_mut(*node)?.config.slot_clock_skew_millis = *millis;
+                self.stop_beacon_node(*node)?;
+                delay_for(RESTART_DELAY).await;
+                self.start_beacon_node(*node).await
+            }
+            Action::DelayGossip { node, millis } => {
+                let faults = &self.beacon_node_mut(*node)?.faults;
+                faults.set_delay(Duration::from_millis(*millis));
+                Ok(())
+            }
+            Action::DropGossip { node } => {
+                self.beacon_node_mut(*node)?.faults.set_drop_all(true);
+                Ok(())
+            }
+            Action::RestoreGossip { node } => {
+                let faults = &self.beacon_node_mut(*node)?.faults;
+                faults.set_delay(Duration::from_secs(0));
+                faults.set_drop_all(false);
+                Ok(())
+            }
+            Action::AssertFinalizedEpoch { epoch, nodes } => {
+                checks::verify_finalized_at_least(&self.remote_nodes(nodes)?, Epoch::new(*epoch))
+                    .await
+            }
+            Action::AssertHeadAgreement { nodes } => {
+                checks::verify_head_agreement(&self.remote_nodes(nodes)?).await
+            }
+            Action::AssertNoSlashings => {
+                // Without the slasher, slashable messages would go undetected.
+                if self
+                    .beacon_nodes
+                    .get(SLASHER_NODE)
+                    .map_or(true, |handle| handle.running.is_none())
+                {
+                    return Err(format!(
+                        "The slasher node ({}) is not running, so slashings cannot be detected",
+                        SLASHER_NODE
+                    ));
+                }
+                checks::verify_no_slashings::<E>(&self.remote_nodes(&None)?).await
+            }
+        }
+    }
+}
+
+pub fn run_scenario_sim(matches: &ArgMatches) -> Result<(), String> {
+    let scenario_path = value_t!(matches, "scenario", PathBuf).map_err(|e| e.to_string())?;
+    let scenario = Scenario::from_file(&scenario_path)?;
+
+    println!("Scenario Simulator:");
+    println!(" scenario:{:?}", scenario_path);
+    println!(" nodes:{}", scenario.nodes);
+    println!(" validators_per_node:{}", scenario.validators_per_node);
+    println!(" steps:{}", scenario.steps.len());
+
+    let node_count = scenario.nodes;
+    let validators_per_node = scenario.validators_per_node;
+
+    // Generate the directories and keystores required for the validator clients.
+    let validator_files = (0..node_count)
+        .into_par_iter()
+        .map(|i| {
+            println!(
+                "Generating keystores for validator {} of {}",
+                i + 1,
+                node_count
+            );
+
+            let indices =
+                (i * validators_per_node..(i + 1) * validators_per_node).collect::<Vec<_>>();
+            ValidatorFiles::with_keystores(&indices).unwrap()
+        })
+        .collect::<Vec<_>>();
+
+    let log_level = "debug";
+    let log_format = None;
+
+    let mut env = EnvironmentBuilder::minimal()
+        .async_logger(log_level, log_format)?
+        .multi_threaded_tokio_runtime()?
+        .build()?;
+
+    let spec = &mut env.eth2_config.spec;
+    spec.milliseconds_per_slot /= scenario.speed_up_factor;
+
+    let genesis_time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|_| "should get system time")?
+        + GENESIS_DELAY;
+    let genesis_instant = Instant::now() + GENESIS_DELAY;
+    let slot_duration = Duration::from_millis(spec.milliseconds_per_slot);
+
+    let mut beacon_config = testing_client_config();
+    beacon_config.genesis = ClientGenesis::Interop {
+        validator_count: validators_per_node * node_count,
+        genesis_time: genesis_time.as_secs(),
+    };
+    beacon_config.dummy_eth1_backend = true;
+    beacon_config.sync_eth1_chain = true;
+    beacon_config.network.enr_address = Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+
+    let context = env.core_context();
+
+    let main_future = async {
+        let mut network = ScenarioNetwork {
+            context,
+            beacon_nodes: vec![],
+            validator_clients: vec![],
+        };
+
+        for i in 0..node_count {
+            let mut config = beacon_config.clone();
+            // Run a slasher on the boot node, so that slashable messages are detected.
+            if i == SLASHER_NODE {
+                config.slasher = Some(slasher::Config::new(PathBuf::new()));
+            }
+            network.add_beacon_node(config).await?;
+        }
+
+        for files in validator_files {
+            network
+                .add_validator_client(testing_validator_config(), files)
+                .await?;
+        }
+
+        for step in &scenario.steps {
+            // Execute each step half-way through its slot, so that blocks and attestations for
+            // the slot have been published.
+            delay_until(genesis_instant + slot_duration * step.slot as u32 + slot_duration / 2)
+                .await;
+
+            println!(
+                "Slot {} (epoch {}): {:?}",
+                step.slot,
+                step.slot / E::slots_per_epoch(),
+                step.action
+            );
+            network
+                .execute(&step.action)
+                .await
+                .map_err(|e| format!("Step at slot {} failed: {}", step.slot, e))?;
+        }
+
+        println!(
+            "Scenario complete. Finished with {} beacon nodes and {} validator clients",
+            network.beacon_nodes.len(),
+            network.validator_clients.len()
+        );
+
+        // Be explicit about dropping the network, as this kills all the nodes.
+        drop(network);
+        Ok::<(), String>(())
+    };
+
+    env.runtime().block_on(main_future)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn parse(yaml: &str) -> Result<Scenario, String> {
+        let scenario: Scenario = serde_yaml::from_str(yaml).map_err(|e| e.to_string())?;
+        scenario.validate().map(|()| scenario)
+    }
+
+    #[test]
+    fn shipped_scenarios_are_valid() {
+        let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("scenarios");
+        let mut count = 0;
+        for entry in fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().map_or(false, |ext| ext == "yaml") {
+                Scenario::from_file(&path).unwrap();
+                count += 1;
+            }
+        }
+        assert!(count > 0, "no scenarios found in {:?}", dir);
+    }
+
+    #[test]
+    fn invalid_scenarios() {
+        let unknown_node = r#"
+nodes: 2
+validators_per_node: 1
+steps:
+  - slot: 1
+    action: drop_gossip
+    node: 2
+"#;
+        let unordered_steps = r#"
+nodes: 2
+validators_per_node: 1
+steps:
+  - slot: 2
+    action: heal
+  - slot: 1
+    action: heal
+"#;
+        let incomplete_partition = r#"
+nodes: 3
+validators_per_node: 1
+steps:
+  - slot: 1
+    action: partition
+    groups: [[0], [1]]
+"#;
+
+        assert!(parse(unknown_node).is_err());
+        assert!(parse(unordered_steps).is_err());
+        assert!(parse(incomplete_partition).is_err());
+    }
+
+    #[test]
+    fn assert_no_slashings_requires_slasher() {
+        let stopped = r#"
+nodes: 2
+validators_per_node: 1
+steps:
+  - slot: 1
+    action: kill_beacon_node
+    node: 0
+  - slot: 3
+    action: assert_no_slashings
+"#;
+        let restarted = r#"
+nodes: 2
+validators_per_node: 1
+steps:
+  - slot: 1
+    action: kill_beacon_node
+    node: 0
+  - slot: 2
+    action: restart_beacon_node
+    node: 0
+  - slot: 3
+    action: assert_no_slashings
+"#;
+
+        assert!(parse(stopped).is_err());
+        assert!(parse(restarted).is_ok());
+    }
+}
@@ -1,22 +1,31 @@
 use crate::{
     duties_service::{DutiesService, DutyAndProof},
+    metrics,
     validator_store::ValidatorStore,
 };
 use environment::RuntimeContext;
-use eth2::BeaconNodeHttpClient;
+use eth2::{types::SseHead, BeaconNodeHttpClient, StatusCode};
 use futures::StreamExt;
-use slog::{crit, error, info, trace};
+use parking_lot::Mutex;
+use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
-use tokio::time::{delay_until, interval_at, Duration, Instant};
+use tokio::time::{delay_for, delay_until, interval_at, Duration, Instant};
 use tree_hash::TreeHash;
 use types::{
     AggregateSignature, Attestation, AttestationData, BitList, ChainSpec, CommitteeIndex, EthSpec,
     Slot,
 };
 
+/// The delay before resubscribing to head events after the stream fails.
+const HEAD_EVENTS_RETRY_DELAY: Duration = Duration::from_secs(6);
+
+/// The longest delay between attempts to subscribe to head events, reached by doubling
+/// `HEAD_EVENTS_RETRY_DELAY` after each consecutive failure.
+const HEAD_EVENTS_MAX_RETRY_DELAY: Duration = Duration::from_secs(384);
+
 /// Builds an `AttestationService`.
 pub struct AttestationServiceBuilder<T, E: EthSpec> {
     duties_service: Option<DutiesService<T, E>>,
@@ -24,6 +33,9 @@ pub struct AttestationServiceBuilder<T, E: EthSpec> {
     slot_clock: Option<T>,
     beacon_node: Option<BeaconNodeHttpClient>,
     context: Option<RuntimeContext<E>>,
+    dry_run: bool,
+    head_events_beacon_node: Option<BeaconNodeHttpClient>,
+    aggregation_delay: Option<Duration>,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
@@ -34,6 +46,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             slot_clock: None,
             beacon_node: None,
             context: None,
+            dry_run: false,
+            head_events_beacon_node: None,
+            aggregation_delay: None,
         }
     }
 
@@ -62,7 +77,53 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    /// If `true`, attestations and aggregates are signed but never published.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// If `Some`, attestations are produced as soon as `beacon_node` reports a head block for the
+    /// current slot, with the usual 1/3 of a slot timer as a fallback.
+    ///
+    /// The client should not have a request timeout, since the event stream is long-lived.
+    pub fn head_events_beacon_node(mut self, beacon_node: Option<BeaconNodeHttpClient>) -> Self {
+        self.head_events_beacon_node = beacon_node;
+        self
+    }
+
+    /// The time after the start of the slot at which to produce aggregates. Defaults to 2/3 of the
+    /// way through the slot, and must be no earlier than 1/3 of the way through the slot (when the
+    /// attestation timer fires).
+    pub fn aggregation_delay(mut self, delay: Option<Duration>) -> Self {
+        self.aggregation_delay = delay;
+        self
+    }
+
     pub fn build(self) -> Result<AttestationService<T, E>, String> {
+        let slot_clock = self
+            .slot_clock
+            .ok_or_else(|| "Cannot build AttestationService without slot_clock")?;
+
+        let slot_duration = slot_clock.slot_duration();
+        let aggregation_delay = self.aggregation_delay.unwrap_or(slot_duration * 2 / 3);
+        if aggregation_delay >= slot_duration {
+            return Err(format!(
+                "The aggregation delay ({}ms) must be less than the slot duration ({}ms)",
+                aggregation_delay.as_millis(),
+                slot_duration.as_millis()
+            ));
+        }
+        // Aggregating before attesting would aggregate before the attestations of this node (and
+        // most others) have been published.
+        if aggregation_delay < slot_duration / 3 {
+            return Err(format!(
+                "The aggregation delay ({}ms) must be at least 1/3 of the slot duration ({}ms)",
+                aggregation_delay.as_millis(),
+                (slot_duration / 3).as_millis()
+            ));
+        }
+
         Ok(AttestationService {
             inner: Arc::new(Inner {
                 duties_service: self
@@ -71,15 +132,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 validator_store: self
                     .validator_store
                     .ok_or_else(|| "Cannot build AttestationService without validator_store")?,
-                slot_clock: self
-                    .slot_clock
-                    .ok_or_else(|| "Cannot build AttestationService without slot_clock")?,
+                slot_clock,
                 beacon_node: self
                     .beacon_node
                     .ok_or_else(|| "Cannot build AttestationService without beacon_node")?,
                 context: self
                     .context
                     .ok_or_else(|| "Cannot build AttestationService without runtime_context")?,
+                dry_run: self.dry_run,
+                head_events_beacon_node: self.head_events_beacon_node,
+                aggregation_delay,
+                last_attested_slot: Mutex::new(None),
             }),
         })
     }
@@ -92,9 +155,19 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: T,
     beacon_node: BeaconNodeHttpClient,
     context: RuntimeContext<E>,
+    dry_run: bool,
+    head_events_beacon_node: Option<BeaconNodeHttpClient>,
+    aggregation_delay: Duration,
+    /// The most recent slot for which attestation tasks were spawned, used to ensure that the head
+    /// event and the timer do not both trigger attestations for the same slot.
+    last_attested_slot: Mutex<Option<Slot>>,
 }
 
-/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
+/// Attempts to produce attestations for all known validators as soon as the beacon node imports
+/// the block for the slot, or 1/3rd of the way through the slot if that happens first.
+///
+/// Aggregates are produced `aggregation_delay` after the start of the slot (2/3rds of the way
+/// through, by default).
 ///
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
@@ -133,7 +206,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         info!(
             log,
             "Attestation production service started";
-            "next_update_millis" => duration_to_next_slot.as_millis()
+            "next_update_millis" => duration_to_next_slot.as_millis(),
+            "attest_on_head" => self.head_events_beacon_node.is_some(),
+            "aggregation_delay_millis" => self.aggregation_delay.as_millis(),
         );
 
         let mut interval = {
@@ -146,21 +221,29 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
 
         let executor = self.context.executor.clone();
 
+        if let Some(beacon_node) = self.head_events_beacon_node.clone() {
+            executor.spawn(
+                self.clone().attest_on_head_events(beacon_node),
+                "attestation_head_events",
+            );
+        }
+
         let interval_fut = async move {
             while interval.next().await.is_some() {
                 let log = self.context.log();
 
-                if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
+                let result = self
+                    .slot_clock
+                    .now()
+                    .ok_or_else(|| "Failed to read slot clock".to_string())
+                    .and_then(|slot| self.attest_once(slot, metrics::TIMER));
+
+                if let Err(e) = result {
                     crit!(
                         log,
                         "Failed to spawn attestation tasks";
                         "error" => e
                     )
-                } else {
-                    trace!(
-                        log,
-                        "Spawned attestation tasks";
-                    )
                 }
             }
         };
@@ -169,23 +252,134 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         Ok(())
     }
 
+    /// Subscribes to head events from `beacon_node` and attests as soon as the block for the
+    /// current slot is imported, resubscribing whenever the stream ends.
+    ///
+    /// Consecutive failures to subscribe back off exponentially. A beacon node which does not
+    /// support the events endpoint is only reported once.
+    async fn attest_on_head_events(self, beacon_node: BeaconNodeHttpClient) {
+        let log = self.context.log().clone();
+        let mut retry_delay = HEAD_EVENTS_RETRY_DELAY;
+        let mut reported_unsupported = false;
+
+        loop {
+            match beacon_node.get_events_head().await {
+                Ok(mut events) => {
+                    info!(log, "Subscribed to head events");
+                    retry_delay = HEAD_EVENTS_RETRY_DELAY;
+                    reported_unsupported = false;
+
+                    loop {
+                        match events.next().await {
+                            Ok(Some(head)) => self.on_head_event(head),
+                            Ok(None) => {
+                                warn!(log, "Head event stream closed by beacon node");
+                                break;
+                            }
+                            Err(e) => {
+                                warn!(
+                                    log,
+                                    "Head event stream failed";
+                                    "error" => format!("{:?}", e),
+                                );
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) if e.status() == Some(StatusCode::NOT_FOUND) => {
+                    if !reported_unsupported {
+                        warn!(
+                            log,
+                            "Beacon node does not support head events";
+                            "fallback" => "attesting 1/3 of the way through each slot",
+                        );
+                        reported_unsupported = true;
+                    }
+                    retry_delay = std::cmp::min(retry_delay * 2, HEAD_EVENTS_MAX_RETRY_DELAY);
+                }
+                Err(e) => {
+                    warn!(
+                        log,
+                        "Unable to subscribe to head events";
+                        "error" => format!("{:?}", e),
+                        "retry_secs" => retry_delay.as_secs(),
+                        "fallback" => "attesting 1/3 of the way through each slot",
+                    );
+                    retry_delay = std::cmp::min(retry_delay * 2, HEAD_EVENTS_MAX_RETRY_DELAY);
+                }
+            }
+
+            delay_for(retry_delay).await;
+        }
+    }
+
+    fn on_head_event(&self, head: SseHead) {
+        let log = self.context.log();
+
+        // A head from an earlier slot is either a late block or a re-org, neither of which mean
+        // that the block for this slot has arrived.
+        if self.slot_clock.now() != Some(head.slot) {
+            return;
+        }
+
+        debug!(
+            log,
+            "Head block imported";
+            "slot" => head.slot.as_u64(),
+            "block" => format!("{:?}", head.block),
+        );
+
+        if let Err(e) = self.attest_once(head.slot, metrics::HEAD_EVENT) {
+            crit!(
+                log,
+                "Failed to spawn attestation tasks";
+                "error" => e
+            )
+        }
+    }
+
+    /// Spawns the attestation tasks for `slot`, unless they have already been spawned by the other
+    /// trigger.
+    ///
+    /// Returns `Ok(false)` if the tasks for `slot` (or a later slot) were already spawned.
+    fn attest_once(&self, slot: Slot, trigger: &str) -> Result<bool, String> {
+        {
+            let mut last_attested_slot = self.last_attested_slot.lock();
+            if last_attested_slot.map_or(false, |last| last >= slot) {
+                return Ok(false);
+            }
+            *last_attested_slot = Some(slot);
+        }
+
+        metrics::inc_counter_vec(&metrics::ATTESTATION_TRIGGERS_TOTAL, &[trigger]);
+
+        self.spawn_attestation_tasks(slot)?;
+
+        trace!(
+            self.context.log(),
+            "Spawned attestation tasks";
+            "slot" => slot.as_u64(),
+            "trigger" => trigger,
+        );
+
+        Ok(true)
+    }
+
     /// For each each required attestation, spawn a new task that downloads, signs and uploads the
     /// attestation to the beacon node.
-    fn spawn_attestation_tasks(&self, slot_duration: Duration) -> Result<(), String> {
-        let slot = self
-            .slot_clock
-            .now()
-            .ok_or_else(|| "Failed to read slot clock".to_string())?;
-        let duration_to_next_slot = self
+    fn spawn_attestation_tasks(&self, slot: Slot) -> Result<(), String> {
+        let duration_since_start_of_slot = self
             .slot_clock
-            .duration_to_next_slot()
-            .ok_or_else(|| "Unable to determine duration to next slot".to_string())?;
+            .duration_since_start_of(slot)
+            .ok_or_else(|| "Unable to determine duration since start of slot".to_string())?;
 
-        // If a validator needs to publish an aggregate attestation, they must do so at 2/3
-        // through the slot. This delay triggers at this time
+        // If a validator needs to publish an aggregate attestation, they must do so
+        // `aggregation_delay` after the start of the slot. This delay triggers at this time.
         let aggregate_production_instant = Instant::now()
-            + duration_to_next_slot
-                .checked_sub(slot_duration / 3)
+            + self
+                .aggregation_delay
+                .checked_sub(duration_since_start_of_slot)
                 .unwrap_or_else(|| Duration::from_secs(0));
 
         let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
@@ -232,7 +426,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     ///
     /// The given `validator_duties` should already be filtered to only contain those that match
     /// `slot` and `committee_index`. Critical errors will be logged if this is not the case.
-    async fn publish_attestations_and_aggregates(
+    pub(crate) async fn publish_attestations_and_aggregates(
         self,
         slot: Slot,
         committee_index: CommitteeIndex,
@@ -268,7 +462,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         // If an attestation was produced, make an aggregate.
         if let Some(attestation_data) = attestation_opt {
             // First, wait until the `aggregation_production_instant` (2/3rds
-            // of the way though the slot). As verified in the
+            // of the way though the slot, by default). As verified in the
             // `delay_triggers_when_in_the_past` test, this code will still run
             // even if the instant has already elapsed.
             delay_until(aggregate_production_instant).await;
@@ -379,6 +573,19 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 )
                 .ok_or_else(|| "Failed to sign attestation".to_string())?;
 
+            if self.dry_run {
+                metrics::inc_counter_vec(&metrics::DRY_RUN_WITHHELD_TOTAL, &[metrics::ATTESTATION]);
+                info!(
+                    log,
+                    "Signed attestation but did not publish (dry run)";
+                    "head_block" => format!("{:?}", attestation.data.beacon_block_root),
+                    "committee_index" => attestation.data.index,
+                    "slot" => attestation.data.slot.as_u64(),
+                    "type" => "unaggregated",
+                );
+                continue;
+            }
+
             match self
                 .beacon_node
                 .post_beacon_pool_attestations(&attestation)
@@ -477,6 +684,21 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
 
             let attestation = &signed_aggregate_and_proof.message.aggregate;
 
+            if self.dry_run {
+                metrics::inc_counter_vec(&metrics::DRY_RUN_WITHHELD_TOTAL, &[metrics::AGGREGATE]);
+                info!(
+                    log,
+                    "Signed attestation but did not publish (dry run)";
+                    "aggregator" => signed_aggregate_and_proof.message.aggregator_index,
+                    "signatures" => attestation.aggregation_bits.num_set_bits(),
+                    "head_block" => format!("{:?}", attestation.data.beacon_block_root),
+                    "committee_index" => attestation.data.index,
+                    "slot" => attestation.data.slot.as_u64(),
+                    "type" => "aggregated",
+                );
+                continue;
+            }
+
             match self
                 .beacon_node
                 .post_validator_aggregate_and_proof(&signed_aggregate_and_proof)
@@ -510,8 +732,128 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        duties_service::DutiesServiceBuilder, fork_service::ForkServiceBuilder,
+        initialized_validators::InitializedValidators,
+    };
+    use account_utils::validator_definitions::ValidatorDefinitions;
+    use environment::{Environment, EnvironmentBuilder};
+    use eth2::Url;
     use futures::future::FutureExt;
     use parking_lot::RwLock;
+    use slashing_protection::SlashingDatabase;
+    use slot_clock::TestingSlotClock;
+    use tempfile::{tempdir, TempDir};
+    use types::{Hash256, MinimalEthSpec};
+
+    type E = MinimalEthSpec;
+
+    const SLOT_DURATION: Duration = Duration::from_secs(6);
+
+    fn environment() -> Environment<E> {
+        EnvironmentBuilder::minimal()
+            .null_logger()
+            .unwrap()
+            .multi_threaded_tokio_runtime()
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    /// Returns a builder for an `AttestationService` without any validators, along with the
+    /// directory holding its slashing protection database.
+    fn builder(
+        env: &mut Environment<E>,
+    ) -> (AttestationServiceBuilder<TestingSlotClock, E>, TempDir) {
+        let context = env.core_context();
+        let log = context.log().clone();
+        let dir = tempdir().unwrap();
+
+        let validators = env
+            .runtime()
+            .block_on(InitializedValidators::from_definitions(
+                ValidatorDefinitions::default(),
+                dir.path().into(),
+                false,
+                log.clone(),
+            ))
+            .unwrap();
+        let validator_store = ValidatorStore::new(
+            validators,
+            SlashingDatabase::create(&dir.path().join("slashing_protection.sqlite")).unwrap(),
+            Hash256::zero(),
+            E::default_spec(),
+            ForkServiceBuilder::testing_only(log.clone())
+                .build()
+                .unwrap(),
+            log,
+        );
+
+        let slot_clock = TestingSlotClock::new(Slot::new(0), Duration::from_secs(0), SLOT_DURATION);
+        let beacon_node = BeaconNodeHttpClient::new(Url::parse("http://127.0.0.1").unwrap());
+        let duties_service = DutiesServiceBuilder::new()
+            .validator_store(validator_store.clone())
+            .slot_clock(slot_clock.clone())
+            .beacon_node(beacon_node.clone())
+            .runtime_context(context.clone())
+            .build()
+            .unwrap();
+
+        let builder = AttestationServiceBuilder::new()
+            .duties_service(duties_service)
+            .validator_store(validator_store)
+            .slot_clock(slot_clock)
+            .beacon_node(beacon_node)
+            .runtime_context(context);
+
+        (builder, dir)
+    }
+
+    #[test]
+    fn head_event_and_timer_attest_once_per_slot() {
+        let mut env = environment();
+        let (builder, _dir) = builder(&mut env);
+        let service = builder.build().unwrap();
+        service.slot_clock.set_slot(5);
+
+        // The head event arrives first, so the timer is ignored.
+        assert_eq!(
+            service.attest_once(Slot::new(5), metrics::HEAD_EVENT),
+            Ok(true)
+        );
+        assert_eq!(service.attest_once(Slot::new(5), metrics::TIMER), Ok(false));
+
+        // The block is late, so the timer fires first and the head event is ignored.
+        service.slot_clock.set_slot(6);
+        assert_eq!(service.attest_once(Slot::new(6), metrics::TIMER), Ok(true));
+        assert_eq!(
+            service.attest_once(Slot::new(6), metrics::HEAD_EVENT),
+            Ok(false)
+        );
+
+        // A trigger for an earlier slot never attests again.
+        assert_eq!(service.attest_once(Slot::new(5), metrics::TIMER), Ok(false));
+    }
+
+    #[test]
+    fn aggregation_delay_must_be_within_slot() {
+        let mut env = environment();
+
+        for (delay, valid) in &[
+            (SLOT_DURATION / 3 - Duration::from_millis(1), false),
+            (SLOT_DURATION / 3, true),
+            (SLOT_DURATION * 2 / 3, true),
+            (SLOT_DURATION, false),
+        ] {
+            let (builder, _dir) = builder(&mut env);
+            assert_eq!(
+                builder.aggregation_delay(Some(*delay)).build().is_ok(),
+                *valid,
+                "{:?}",
+                delay
+            );
+        }
+    }
 
     /// This test is to ensure that a `tokio_timer::Delay` with an instant in the past will still
     /// trigger.
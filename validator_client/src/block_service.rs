@@ -1,4 +1,4 @@
-use crate::validator_store::ValidatorStore;
+use crate::{metrics, validator_store::ValidatorStore};
 use environment::RuntimeContext;
 use eth2::{types::Graffiti, BeaconNodeHttpClient};
 use futures::channel::mpsc::Receiver;
@@ -16,6 +16,7 @@ pub struct BlockServiceBuilder<T, E: EthSpec> {
     beacon_node: Option<BeaconNodeHttpClient>,
     context: Option<RuntimeContext<E>>,
     graffiti: Option<Graffiti>,
+    dry_run: bool,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -26,6 +27,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             beacon_node: None,
             context: None,
             graffiti: None,
+            dry_run: false,
         }
     }
 
@@ -54,6 +56,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    /// If `true`, blocks are signed but never published.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -70,6 +78,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                     .context
                     .ok_or_else(|| "Cannot build BlockService without runtime_context")?,
                 graffiti: self.graffiti,
+                dry_run: self.dry_run,
             }),
         })
     }
@@ -82,6 +91,7 @@ pub struct Inner<T, E: EthSpec> {
     beacon_node: BeaconNodeHttpClient,
     context: RuntimeContext<E>,
     graffiti: Option<Graffiti>,
+    dry_run: bool,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -206,7 +216,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
     }
 
     /// Produce a block at the given slot for validator_pubkey
-    async fn publish_block(self, slot: Slot, validator_pubkey: PublicKey) -> Result<(), String> {
+    pub(crate) async fn publish_block(
+        self,
+        slot: Slot,
+        validator_pubkey: PublicKey,
+    ) -> Result<(), String> {
         let log = self.context.log();
 
         let current_slot = self
@@ -231,6 +245,19 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             .sign_block(&validator_pubkey, block, current_slot)
             .ok_or_else(|| "Unable to sign block".to_string())?;
 
+        if self.dry_run {
+            metrics::inc_counter_vec(&metrics::DRY_RUN_WITHHELD_TOTAL, &[metrics::BLOCK]);
+            info!(
+                log,
+                "Signed block but did not publish (dry run)";
+                "block_root" => format!("{:?}", signed_block.canonical_root()),
+                "deposits" => signed_block.message.body.deposits.len(),
+                "attestations" => signed_block.message.body.attestations.len(),
+                "slot" => signed_block.slot().as_u64(),
+            );
+            return Ok(());
+        }
+
         self.beacon_node
             .post_beacon_blocks(&signed_block)
             .await
@@ -25,6 +25,23 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .conflicts_with("beacon-node"),
         )
+        .arg(
+            Arg::with_name("beacon-node-token-file")
+                .long("beacon-node-token-file")
+                .value_name("PATH")
+                .help("A file containing the bearer token to present to the beacon node HTTP \
+                    API. The token must have write access to the beacon node.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("beacon-node-tls-ca-cert")
+                .long("beacon-node-tls-ca-cert")
+                .value_name("PATH")
+                .help("A PEM-encoded certificate to trust when connecting to the beacon node \
+                    over HTTPS, in addition to the system root certificates. Useful if the \
+                    beacon node uses a self-signed certificate.")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("validators-dir")
                 .long("validators-dir")
@@ -91,6 +108,38 @@ pub fn cli_app<'a, 'b>() -> App<'a, 'b> {
                       node is not synced.",
                 ),
         )
+        .arg(
+            Arg::with_name("dry-run")
+                .long("dry-run")
+                .help(
+                    "If present, perform duties and sign blocks and attestations as normal, but \
+                    never publish them. Signing is checked against a scratch copy of the slashing \
+                    protection database, which is recreated at each start. Useful for rehearsing \
+                    a migration to a new host without risking a slashing."
+                )
+        )
+        .arg(
+            Arg::with_name("disable-attest-on-head")
+                .long("disable-attest-on-head")
+                .help(
+                    "If present, always attest 1/3 of the way through the slot. By default, the \
+                    validator client attests as soon as the beacon node reports that it has \
+                    imported the block for the current slot, falling back to 1/3 of the way \
+                    through the slot if the block is late or missing."
+                )
+        )
+        .arg(
+            Arg::with_name("aggregation-delay")
+                .long("aggregation-delay")
+                .value_name("MILLIS")
+                .help(
+                    "The time after the start of each slot at which to produce aggregate \
+                    attestations, in milliseconds. Must be at least 1/3 of the slot duration \
+                    (when attestations are produced) and less than the slot duration. Defaults to \
+                    2/3 of the slot duration."
+                )
+                .takes_value(true)
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::with_name("graffiti")
@@ -10,6 +10,7 @@ use serde_derive::{Deserialize, Serialize};
 use slog::{warn, Logger};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 use types::GRAFFITI_BYTES_LEN;
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
@@ -25,6 +26,11 @@ pub struct Config {
     ///
     /// Should be similar to `http://localhost:8080`
     pub beacon_node: String,
+    /// A file containing the bearer token to present to the beacon node API.
+    pub beacon_node_api_token_file: Option<PathBuf>,
+    /// A PEM-encoded certificate to trust when connecting to the beacon node over HTTPS, in
+    /// addition to the system root certificates.
+    pub beacon_node_tls_ca_cert: Option<PathBuf>,
     /// If true, the validator client will still poll for duties and produce blocks even if the
     /// beacon node is not synced at startup.
     pub allow_unsynced_beacon_node: bool,
@@ -36,6 +42,15 @@ pub struct Config {
     pub init_slashing_protection: bool,
     /// Graffiti to be inserted everytime we create a block.
     pub graffiti: Option<Graffiti>,
+    /// If true, sign blocks and attestations as normal but never publish them. A scratch copy of
+    /// the slashing protection database is used so the real database is never modified.
+    pub dry_run: bool,
+    /// If true, attest as soon as the beacon node reports a head block for the current slot,
+    /// rather than waiting until 1/3 of the way through the slot.
+    pub attest_on_head: bool,
+    /// The time after the start of the slot at which to produce aggregates. If `None`, aggregates
+    /// are produced 2/3 of the way through the slot.
+    pub aggregation_delay: Option<Duration>,
     /// Configuration for the HTTP REST API.
     pub http_api: http_api::Config,
 }
@@ -55,11 +70,16 @@ impl Default for Config {
             validator_dir,
             secrets_dir,
             beacon_node: DEFAULT_BEACON_NODE.to_string(),
+            beacon_node_api_token_file: None,
+            beacon_node_tls_ca_cert: None,
             allow_unsynced_beacon_node: false,
             delete_lockfiles: false,
             disable_auto_discover: false,
             init_slashing_protection: false,
             graffiti: None,
+            dry_run: false,
+            attest_on_head: true,
+            aggregation_delay: None,
             http_api: <_>::default(),
         }
     }
@@ -119,10 +139,17 @@ impl Config {
             config.beacon_node = server;
         }
 
+        config.beacon_node_api_token_file = parse_optional(cli_args, "beacon-node-token-file")?;
+        config.beacon_node_tls_ca_cert = parse_optional(cli_args, "beacon-node-tls-ca-cert")?;
+
         config.allow_unsynced_beacon_node = cli_args.is_present("allow-unsynced");
         config.delete_lockfiles = cli_args.is_present("delete-lockfiles");
         config.disable_auto_discover = cli_args.is_present("disable-auto-discover");
         config.init_slashing_protection = cli_args.is_present("init-slashing-protection");
+        config.dry_run = cli_args.is_present("dry-run");
+        config.attest_on_head = !cli_args.is_present("disable-attest-on-head");
+        config.aggregation_delay =
+            parse_optional::<u64>(cli_args, "aggregation-delay")?.map(Duration::from_millis);
 
         if let Some(input_graffiti) = cli_args.value_of("graffiti") {
             let graffiti_bytes = input_graffiti.as_bytes();
@@ -146,21 +146,22 @@ impl ApiSecret {
         format!("{}{}", PK_PREFIX, self.pubkey_string())
     }
 
-    /// Returns the value of the `Authorization` header which is used for verifying incoming HTTP
-    /// requests.
-    fn auth_header_value(&self) -> String {
-        format!("Basic {}", self.api_token())
-    }
-
     /// Returns a `warp` header which filters out request that have a missing or inaccurate
     /// `Authorization` header.
+    ///
+    /// The API token may be supplied as either `Basic <api-token>` or, as expected by the standard
+    /// keymanager API, `Bearer <api-token>`.
     pub fn authorization_header_filter(&self) -> warp::filters::BoxedFilter<()> {
-        let expected = self.auth_header_value();
+        let expected = self.api_token();
         warp::any()
             .map(move || expected.clone())
             .and(warp::filters::header::header("Authorization"))
             .and_then(move |expected: String, header: String| async move {
-                if header == expected {
+                let token = header
+                    .strip_prefix("Basic ")
+                    .or_else(|| header.strip_prefix("Bearer "));
+
+                if token == Some(expected.as_str()) {
                     Ok(())
                 } else {
                     Err(warp_utils::reject::invalid_auth(header))
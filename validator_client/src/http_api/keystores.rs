@@ -0,0 +1,252 @@
+//! Implementation of the standard keymanager API (`eth/v1/keystores`).
+
+use crate::ValidatorStore;
+use account_utils::{validator_definitions::SigningDefinition, ZeroizeString};
+use eth2::lighthouse_vc::std_types::{
+    DeleteKeystoreStatus, DeleteKeystoresRequest, DeleteKeystoresResponse, ImportKeystoreStatus,
+    ImportKeystoresRequest, ImportKeystoresResponse, ListKeystoresResponse, SingleKeystoreResponse,
+    Status,
+};
+use eth2_keystore::Keystore;
+use slashing_protection::interchange::Interchange;
+use slog::{info, warn, Logger};
+use slot_clock::SlotClock;
+use std::path::Path;
+use types::{EthSpec, PublicKey, PublicKeyBytes};
+use validator_dir::Builder as ValidatorDirBuilder;
+use warp::Rejection;
+use warp_utils::reject::{custom_bad_request, custom_server_error};
+
+/// Lists the keystores of all validators known to `validator_store`, enabled or not.
+pub fn list<T: SlotClock + 'static, E: EthSpec>(
+    validator_store: ValidatorStore<T, E>,
+) -> ListKeystoresResponse {
+    let initialized_validators_rwlock = validator_store.initialized_validators();
+    let initialized_validators = initialized_validators_rwlock.read();
+
+    let data = initialized_validators
+        .validator_definitions()
+        .iter()
+        .map(|def| {
+            let derivation_path = match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    ..
+                } => Keystore::from_json_file(voting_keystore_path)
+                    .ok()
+                    .and_then(|keystore| keystore.path())
+                    .filter(|path| !path.is_empty()),
+            };
+
+            SingleKeystoreResponse {
+                validating_pubkey: PublicKeyBytes::from(&def.voting_public_key),
+                derivation_path,
+                readonly: false,
+            }
+        })
+        .collect();
+
+    ListKeystoresResponse { data }
+}
+
+/// Imports the slashing protection data in `request` and then each of its keystores.
+///
+/// If the slashing protection data cannot be imported then no keystores are imported.
+///
+/// Slashing protection data is rejected when `dry_run` is set, since it would only be imported into
+/// the scratch database and then lost.
+pub fn import<T: SlotClock + 'static, E: EthSpec>(
+    request: ImportKeystoresRequest,
+    validator_dir: &Path,
+    validator_store: ValidatorStore<T, E>,
+    dry_run: bool,
+    log: Logger,
+) -> Result<ImportKeystoresResponse, Rejection> {
+    if request.keystores.len() != request.passwords.len() {
+        return Err(custom_bad_request(format!(
+            "mismatched numbers of keystores ({}) and passwords ({})",
+            request.keystores.len(),
+            request.passwords.len(),
+        )));
+    }
+
+    if dry_run && request.slashing_protection.is_some() {
+        return Err(custom_bad_request(
+            "slashing protection cannot be imported in dry run mode".to_string(),
+        ));
+    }
+
+    let interchange = request
+        .slashing_protection
+        .as_deref()
+        .map(Interchange::from_json_str)
+        .transpose()
+        .map_err(|e| custom_bad_request(format!("invalid slashing protection: {:?}", e)))?;
+
+    if let Some(interchange) = &interchange {
+        if let Err(e) = validator_store.import_slashing_protection(interchange) {
+            warn!(
+                log,
+                "Failed to import slashing protection";
+                "error" => &e,
+            );
+            let data = request
+                .keystores
+                .iter()
+                .map(|_| Status::error(ImportKeystoreStatus::Error, e.clone()))
+                .collect();
+            return Ok(ImportKeystoresResponse { data });
+        }
+    }
+
+    let data = request
+        .keystores
+        .into_iter()
+        .zip(request.passwords.into_iter())
+        .map(|(keystore, password)| {
+            let keystore = keystore.0;
+            let pubkey_str = keystore.pubkey().to_string();
+            let status =
+                import_single_keystore(keystore, password, validator_dir, &validator_store)
+                    .unwrap_or_else(|e| Status::error(ImportKeystoreStatus::Error, e));
+
+            match status.status {
+                ImportKeystoreStatus::Imported => {
+                    info!(log, "Imported keystore via standard HTTP API"; "pubkey" => pubkey_str)
+                }
+                ImportKeystoreStatus::Duplicate => {}
+                ImportKeystoreStatus::Error => warn!(
+                    log,
+                    "Error importing keystore";
+                    "pubkey" => pubkey_str,
+                    "error" => status.message.as_deref().unwrap_or(""),
+                ),
+            }
+
+            status
+        })
+        .collect();
+
+    Ok(ImportKeystoresResponse { data })
+}
+
+fn import_single_keystore<T: SlotClock + 'static, E: EthSpec>(
+    keystore: Keystore,
+    password: ZeroizeString,
+    validator_dir: &Path,
+    validator_store: &ValidatorStore<T, E>,
+) -> Result<Status<ImportKeystoreStatus>, String> {
+    let pubkey = keystore
+        .public_key()
+        .ok_or_else(|| format!("invalid pubkey: {}", keystore.pubkey()))?;
+
+    if validator_store
+        .initialized_validators()
+        .read()
+        .is_enabled(&pubkey)
+        .is_some()
+    {
+        return Ok(Status::ok(ImportKeystoreStatus::Duplicate));
+    }
+
+    // Check to ensure the password is correct.
+    keystore
+        .decrypt_keypair(password.as_ref())
+        .map_err(|e| format!("incorrect password: {:?}", e))?;
+
+    let validator_dir = ValidatorDirBuilder::new(validator_dir.to_path_buf())
+        .voting_keystore(keystore, password.as_ref())
+        .store_withdrawal_keystore(false)
+        .build()
+        .map_err(|e| format!("failed to build validator directory: {:?}", e))?;
+
+    tokio::runtime::Handle::current()
+        .block_on(validator_store.add_validator_keystore(
+            validator_dir.voting_keystore_path(),
+            password,
+            true,
+        ))
+        .map_err(|e| format!("failed to initialize validator: {:?}", e))?;
+
+    Ok(Status::ok(ImportKeystoreStatus::Imported))
+}
+
+/// Deletes the keystores in `request` and returns the slashing protection data for their keys.
+///
+/// The keys are removed before the slashing protection data is exported so that no further
+/// messages can be signed which are missing from the export.
+pub fn delete<T: SlotClock + 'static, E: EthSpec>(
+    request: DeleteKeystoresRequest,
+    validator_store: ValidatorStore<T, E>,
+    log: Logger,
+) -> Result<DeleteKeystoresResponse, Rejection> {
+    let mut pubkeys = Vec::with_capacity(request.pubkeys.len());
+
+    let mut data = request
+        .pubkeys
+        .iter()
+        .map(|pubkey_bytes| {
+            let pubkey = match pubkey_bytes.decompress() {
+                Ok(pubkey) => pubkey,
+                Err(e) => {
+                    pubkeys.push(None);
+                    return Status::error(
+                        DeleteKeystoreStatus::Error,
+                        format!("invalid pubkey: {:?}", e),
+                    );
+                }
+            };
+
+            let status = match tokio::runtime::Handle::current()
+                .block_on(validator_store.delete_validator_keystore(&pubkey))
+            {
+                Ok(true) => {
+                    info!(
+                        log,
+                        "Deleted keystore via standard HTTP API";
+                        "pubkey" => format!("{:?}", pubkey),
+                    );
+                    Status::ok(DeleteKeystoreStatus::Deleted)
+                }
+                // Updated to `NotActive` below if slashing protection data is found.
+                Ok(false) => Status::ok(DeleteKeystoreStatus::NotFound),
+                Err(e) => {
+                    warn!(
+                        log,
+                        "Error deleting keystore";
+                        "pubkey" => format!("{:?}", pubkey),
+                        "error" => &e,
+                    );
+                    Status::error(DeleteKeystoreStatus::Error, e)
+                }
+            };
+
+            pubkeys.push(Some(pubkey));
+            status
+        })
+        .collect::<Vec<_>>();
+
+    let export_pubkeys: Vec<PublicKey> = pubkeys.iter().flatten().cloned().collect();
+    let interchange = validator_store
+        .export_slashing_protection(&export_pubkeys)
+        .map_err(custom_server_error)?;
+
+    for (status, pubkey) in data.iter_mut().zip(&pubkeys) {
+        if status.status == DeleteKeystoreStatus::NotFound
+            && pubkey.as_ref().map_or(false, |pubkey| {
+                interchange.data.iter().any(|data| data.pubkey == *pubkey)
+            })
+        {
+            status.status = DeleteKeystoreStatus::NotActive;
+        }
+    }
+
+    let slashing_protection = serde_json::to_string(&interchange).map_err(|e| {
+        custom_server_error(format!("unable to serialize slashing protection: {:?}", e))
+    })?;
+
+    Ok(DeleteKeystoresResponse {
+        data,
+        slashing_protection,
+    })
+}
@@ -1,12 +1,17 @@
 mod api_secret;
 mod create_validator;
+mod keystores;
 mod tests;
 
 use crate::ValidatorStore;
 use account_utils::mnemonic_from_phrase;
 use create_validator::create_validators;
-use eth2::lighthouse_vc::types::{self as api_types, PublicKey, PublicKeyBytes};
+use eth2::lighthouse_vc::{
+    std_types::{DeleteKeystoresRequest, ImportKeystoresRequest},
+    types::{self as api_types, PublicKey, PublicKeyBytes},
+};
 use lighthouse_version::version_with_platform;
+use logging::{LogFilter, LogFilterHandle};
 use serde::{Deserialize, Serialize};
 use slog::{crit, info, Logger};
 use slot_clock::SlotClock;
@@ -55,6 +60,9 @@ pub struct Context<T: Clone, E: EthSpec> {
     pub validator_dir: Option<PathBuf>,
     pub spec: ChainSpec,
     pub config: Config,
+    /// Set when the validator client is running with `--dry-run`.
+    pub dry_run: bool,
+    pub log_filter: Option<LogFilterHandle>,
     pub log: Logger,
     pub _phantom: PhantomData<E>,
 }
@@ -104,7 +112,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
     // Configure CORS.
     let cors_builder = {
         let builder = warp::cors()
-            .allow_methods(vec!["GET", "POST", "PATCH"])
+            .allow_methods(vec!["GET", "POST", "PATCH", "DELETE"])
             .allow_headers(vec!["Content-Type", "Authorization"]);
 
         warp_utils::cors::set_builder_origins(
@@ -149,9 +157,26 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    let dry_run = ctx.dry_run;
+    let dry_run_filter = warp::any().map(move || dry_run);
+
     let inner_spec = Arc::new(ctx.spec.clone());
     let spec_filter = warp::any().map(move || inner_spec.clone());
 
+    let inner_log_filter_handle = ctx.log_filter.clone();
+    let log_levels_filter = warp::any()
+        .map(move || inner_log_filter_handle.clone())
+        .and_then(|log_levels: Option<LogFilterHandle>| async move {
+            log_levels.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "log levels cannot be changed at runtime.".to_string(),
+                )
+            })
+        });
+
+    let inner_log = ctx.log.clone();
+    let log_filter = warp::any().map(move || inner_log.clone());
+
     // GET lighthouse/version
     let get_node_version = warp::path("lighthouse")
         .and(warp::path("version"))
@@ -321,7 +346,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and(warp::path("keystore"))
         .and(warp::path::end())
         .and(warp::body::json())
-        .and(validator_dir_filter)
+        .and(validator_dir_filter.clone())
         .and(validator_store_filter.clone())
         .and(signer.clone())
         .and_then(
@@ -382,8 +407,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and(warp::path::param::<PublicKey>())
         .and(warp::path::end())
         .and(warp::body::json())
-        .and(validator_store_filter)
-        .and(signer)
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
         .and_then(
             |validator_pubkey: PublicKey,
              body: api_types::ValidatorPatchRequest,
@@ -419,6 +444,108 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // Standard keymanager API.
+    let eth_v1 = warp::path("eth").and(warp::path("v1"));
+
+    // GET eth/v1/keystores
+    let get_std_keystores = eth_v1
+        .and(warp::path("keystores"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(signer.clone())
+        .and_then(|validator_store: ValidatorStore<T, E>, signer| {
+            blocking_signed_json_task(signer, move || Ok(keystores::list(validator_store.clone())))
+        });
+
+    // POST eth/v1/keystores
+    let post_std_keystores = eth_v1
+        .and(warp::path("keystores"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_dir_filter)
+        .and(validator_store_filter.clone())
+        .and(dry_run_filter)
+        .and(log_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |body: ImportKeystoresRequest,
+             validator_dir: PathBuf,
+             validator_store: ValidatorStore<T, E>,
+             dry_run: bool,
+             log: Logger,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    keystores::import(
+                        body.clone(),
+                        &validator_dir,
+                        validator_store.clone(),
+                        dry_run,
+                        log.clone(),
+                    )
+                })
+            },
+        );
+
+    // DELETE eth/v1/keystores
+    let delete_std_keystores = eth_v1
+        .and(warp::path("keystores"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter)
+        .and(log_filter.clone())
+        .and(signer.clone())
+        .and_then(
+            |body: DeleteKeystoresRequest,
+             validator_store: ValidatorStore<T, E>,
+             log: Logger,
+             signer| {
+                blocking_signed_json_task(signer, move || {
+                    keystores::delete(body.clone(), validator_store.clone(), log.clone())
+                })
+            },
+        );
+
+    // GET lighthouse/logging
+    let get_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(log_levels_filter.clone())
+        .and(signer.clone())
+        .and_then(|log_levels: LogFilterHandle, signer| {
+            blocking_signed_json_task(signer, move || {
+                Ok(api_types::GenericResponse::from(api_types::LoggingConfig {
+                    filter: log_levels.get().to_string(),
+                }))
+            })
+        });
+
+    // POST lighthouse/logging
+    let post_lighthouse_logging = warp::path("lighthouse")
+        .and(warp::path("logging"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(log_levels_filter)
+        .and(log_filter)
+        .and(signer)
+        .and_then(
+            |body: api_types::LoggingConfig, log_levels: LogFilterHandle, log: Logger, signer| {
+                blocking_signed_json_task(signer, move || {
+                    let filter = body
+                        .filter
+                        .parse::<LogFilter>()
+                        .map_err(warp_utils::reject::custom_bad_request)?;
+
+                    info!(log, "Updated log levels"; "filter" => filter.to_string());
+                    let response = api_types::LoggingConfig {
+                        filter: filter.to_string(),
+                    };
+                    log_levels.set(filter);
+
+                    Ok(api_types::GenericResponse::from(response))
+                })
+            },
+        );
+
     let routes = warp::any()
         .and(authorization_header_filter)
         .and(
@@ -427,15 +554,20 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                     .or(get_lighthouse_health)
                     .or(get_lighthouse_spec)
                     .or(get_lighthouse_validators)
-                    .or(get_lighthouse_validators_pubkey),
+                    .or(get_lighthouse_validators_pubkey)
+                    .or(get_lighthouse_logging)
+                    .or(get_std_keystores),
             ),
         )
         .or(warp::post().and(
             post_validators
                 .or(post_validators_keystore)
-                .or(post_validators_mnemonic),
+                .or(post_validators_mnemonic)
+                .or(post_lighthouse_logging)
+                .or(post_std_keystores),
         ))
         .or(warp::patch().and(patch_validators))
+        .or(warp::delete().and(delete_std_keystores))
         // Maps errors into HTTP responses.
         .recover(warp_utils::reject::handle_rejection)
         // Add a `Server` header.
@@ -7,17 +7,24 @@ use crate::{
 };
 use account_utils::{
     eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
-    ZeroizeString,
+    validator_definitions::ValidatorDefinition, ZeroizeString,
 };
 use deposit_contract::decode_eth1_tx_data;
 use environment::null_logger;
 use eth2::{
-    lighthouse_vc::{http_client::ValidatorClientHttpClient, types::*},
+    lighthouse_vc::{http_client::ValidatorClientHttpClient, std_types::*, types::*},
     Url,
 };
 use eth2_keystore::KeystoreBuilder;
+use logging::LogFilterHandle;
 use parking_lot::RwLock;
-use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use slashing_protection::{
+    interchange::{
+        CompleteInterchangeData, Interchange, InterchangeFormat, InterchangeMetadata,
+        SignedBlock as InterchangeBlock,
+    },
+    SlashingDatabase, SLASHING_PROTECTION_FILENAME, SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+};
 use slot_clock::TestingSlotClock;
 use std::marker::PhantomData;
 use std::net::Ipv4Addr;
@@ -34,11 +41,15 @@ struct ApiTester {
     initialized_validators: Arc<RwLock<InitializedValidators>>,
     url: Url,
     _server_shutdown: oneshot::Sender<()>,
-    _validator_dir: TempDir,
+    validator_dir: TempDir,
 }
 
 impl ApiTester {
     pub async fn new() -> Self {
+        Self::new_with_dry_run(false).await
+    }
+
+    pub async fn new_with_dry_run(dry_run: bool) -> Self {
         let log = null_logger().unwrap();
 
         let validator_dir = tempdir().unwrap();
@@ -91,6 +102,8 @@ impl ApiTester {
                 listen_port: 0,
                 allow_origin: None,
             },
+            dry_run,
+            log_filter: Some(LogFilterHandle::default()),
             log,
             _phantom: PhantomData,
         });
@@ -115,7 +128,7 @@ impl ApiTester {
 
         Self {
             initialized_validators,
-            _validator_dir: validator_dir,
+            validator_dir,
             client,
             url,
             _server_shutdown: shutdown_tx,
@@ -171,6 +184,36 @@ impl ApiTester {
 
         self
     }
+    pub async fn test_lighthouse_logging(self) -> Self {
+        let filter = |filter: &str| LoggingConfig {
+            filter: filter.to_string(),
+        };
+
+        assert_eq!(
+            self.client.get_lighthouse_logging().await.unwrap().data,
+            filter("info")
+        );
+
+        let result = self
+            .client
+            .post_lighthouse_logging(&filter("debug,validator_client=trace"))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(result, filter("debug,validator_client=trace"));
+        assert_eq!(
+            self.client.get_lighthouse_logging().await.unwrap().data,
+            result
+        );
+
+        self.client
+            .post_lighthouse_logging(&filter("validator_client=loud"))
+            .await
+            .unwrap_err();
+
+        self
+    }
+
     pub fn vals_total(&self) -> usize {
         self.initialized_validators.read().num_total()
     }
@@ -407,6 +450,234 @@ impl ApiTester {
 
         self
     }
+
+    pub async fn test_std_keystores(self) -> Self {
+        let keystore = |keypair: &Keypair, password: &ZeroizeString| {
+            KeystoreBuilder::new(keypair, password.as_ref(), "m/12381/3600/0/0/0".to_string())
+                .unwrap()
+                .build()
+                .map(KeystoreJsonStr)
+                .unwrap()
+        };
+        let password = ZeroizeString::from("password".to_string());
+        let keypairs = (0..2).map(|_| Keypair::random()).collect::<Vec<_>>();
+        let keystores = keypairs
+            .iter()
+            .map(|keypair| keystore(keypair, &password))
+            .collect::<Vec<_>>();
+        let pubkeys = keypairs
+            .iter()
+            .map(|keypair| PublicKeyBytes::from(&keypair.pk))
+            .collect::<Vec<_>>();
+
+        let interchange = Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format: InterchangeFormat::Complete,
+                interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root: Hash256::repeat_byte(42),
+            },
+            data: vec![CompleteInterchangeData {
+                pubkey: keypairs[0].pk.clone(),
+                signed_blocks: vec![InterchangeBlock {
+                    slot: Slot::new(1),
+                    signing_root: Some(Hash256::repeat_byte(1)),
+                }],
+                signed_attestations: vec![],
+            }],
+        };
+
+        // Import both keystores along with some slashing protection data.
+        let response = self
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: keystores.clone(),
+                passwords: vec![password.clone(), password.clone()],
+                slashing_protection: Some(serde_json::to_string(&interchange).unwrap()),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.data,
+            vec![Status::ok(ImportKeystoreStatus::Imported); 2]
+        );
+        assert_eq!(self.vals_enabled(), 2);
+
+        // Importing again is a no-op and an incorrect password is an error.
+        let response = self
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: vec![
+                    keystores[0].clone(),
+                    keystore(&Keypair::random(), &password),
+                ],
+                passwords: vec![password.clone(), ZeroizeString::from("wrong".to_string())],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.data[0],
+            Status::ok(ImportKeystoreStatus::Duplicate)
+        );
+        assert_eq!(response.data[1].status, ImportKeystoreStatus::Error);
+        assert_eq!(self.vals_total(), 2);
+
+        let listed = self.client.get_keystores().await.unwrap().data;
+        assert_eq!(listed.len(), 2);
+        for pubkey in &pubkeys {
+            let keystore = listed
+                .iter()
+                .find(|keystore| keystore.validating_pubkey == *pubkey)
+                .unwrap();
+            assert_eq!(
+                keystore.derivation_path.as_deref(),
+                Some("m/12381/3600/0/0/0")
+            );
+            assert!(!keystore.readonly);
+        }
+
+        // Delete the first keystore and a key which was never imported.
+        let unknown_pubkey = PublicKeyBytes::from(&Keypair::random().pk);
+        let response = self
+            .client
+            .delete_keystores(&DeleteKeystoresRequest {
+                pubkeys: vec![pubkeys[0].clone(), unknown_pubkey],
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.data,
+            vec![
+                Status::ok(DeleteKeystoreStatus::Deleted),
+                Status::ok(DeleteKeystoreStatus::NotFound)
+            ]
+        );
+        assert!(Interchange::from_json_str(&response.slashing_protection)
+            .unwrap()
+            .equiv(&interchange));
+        assert_eq!(self.vals_total(), 1);
+        assert_eq!(self.client.get_keystores().await.unwrap().data.len(), 1);
+
+        // The slashing protection data outlives the key.
+        let response = self
+            .client
+            .delete_keystores(&DeleteKeystoresRequest {
+                pubkeys: vec![pubkeys[0].clone()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.data,
+            vec![Status::ok(DeleteKeystoreStatus::NotActive)]
+        );
+
+        self
+    }
+
+    pub async fn test_std_keystores_dry_run(self) -> Self {
+        let password = ZeroizeString::from("password".to_string());
+        let keypair = Keypair::random();
+        let keystore = KeystoreBuilder::new(&keypair, password.as_ref(), String::new())
+            .unwrap()
+            .build()
+            .map(KeystoreJsonStr)
+            .unwrap();
+        let interchange = Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format: InterchangeFormat::Complete,
+                interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root: Hash256::repeat_byte(42),
+            },
+            data: vec![],
+        };
+
+        // Slashing protection data would only reach the scratch database, so it is rejected.
+        let err = self
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: vec![keystore.clone()],
+                passwords: vec![password.clone()],
+                slashing_protection: Some(serde_json::to_string(&interchange).unwrap()),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().map(Into::into), Some(400));
+        assert_eq!(self.vals_total(), 0);
+
+        // Keystores alone can still be imported.
+        let response = self
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: vec![keystore],
+                passwords: vec![password],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.data,
+            vec![Status::ok(ImportKeystoreStatus::Imported)]
+        );
+        assert_eq!(self.vals_total(), 1);
+
+        self
+    }
+
+    pub async fn test_delete_keystore_in_shared_dir(self) -> Self {
+        // Mimic the output of the `eth2.0-deposit-cli`, with two keystores in one directory.
+        let keys_dir = self.validator_dir.path().join("validator_keys");
+        std::fs::create_dir(&keys_dir).unwrap();
+
+        let password = ZeroizeString::from("password".to_string());
+        let mut pubkeys = vec![];
+        let mut paths = vec![];
+        for i in 0..2 {
+            let keypair = Keypair::random();
+            let path = format!("m/12381/3600/{}/0/0", i);
+            let keystore = KeystoreBuilder::new(&keypair, password.as_ref(), path)
+                .unwrap()
+                .build()
+                .unwrap();
+            let keystore_path =
+                keys_dir.join(format!("keystore-m_12381_3600_{}_0_0-1608000000.json", i));
+            keystore
+                .to_json_writer(std::fs::File::create(&keystore_path).unwrap())
+                .unwrap();
+
+            let def = ValidatorDefinition::new_keystore_with_password(
+                &keystore_path,
+                Some(password.clone()),
+            )
+            .unwrap();
+            self.initialized_validators
+                .write()
+                .add_definition(def)
+                .await
+                .unwrap();
+
+            pubkeys.push(PublicKeyBytes::from(&keypair.pk));
+            paths.push(keystore_path);
+        }
+
+        let response = self
+            .client
+            .delete_keystores(&DeleteKeystoresRequest {
+                pubkeys: vec![pubkeys[0].clone()],
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            response.data,
+            vec![Status::ok(DeleteKeystoreStatus::Deleted)]
+        );
+
+        // Only the deleted keystore is removed from the shared directory.
+        assert!(!paths[0].exists());
+        assert!(paths[1].exists());
+        assert_eq!(self.client.get_keystores().await.unwrap().data.len(), 1);
+
+        self
+    }
 }
 
 struct HdValidatorScenario {
@@ -439,6 +710,8 @@ async fn simple_getters() {
         .test_get_lighthouse_health()
         .await
         .test_get_lighthouse_spec()
+        .await
+        .test_lighthouse_logging()
         .await;
 }
 
@@ -528,3 +801,24 @@ async fn keystore_validator_creation() {
         .assert_enabled_validators_count(1)
         .assert_validators_count(2);
 }
+
+#[tokio::test(core_threads = 2)]
+async fn std_keystores() {
+    ApiTester::new().await.test_std_keystores().await;
+}
+
+#[tokio::test(core_threads = 2)]
+async fn std_keystores_dry_run() {
+    ApiTester::new_with_dry_run(true)
+        .await
+        .test_std_keystores_dry_run()
+        .await;
+}
+
+#[tokio::test(core_threads = 2)]
+async fn delete_keystore_in_shared_dir() {
+    ApiTester::new()
+        .await
+        .test_delete_keystore_in_shared_dir()
+        .await;
+}
@@ -7,18 +7,19 @@
 //! validators are managed by this validator client.
 
 use account_utils::{
+    password_source::{self, PasswordSource},
     read_password, read_password_from_user,
     validator_definitions::{
         self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, CONFIG_FILENAME,
     },
-    ZeroizeString,
+    PlainText, ZeroizeString,
 };
 use eth2_keystore::Keystore;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use types::{Keypair, PublicKey};
 
 use crate::key_cache;
@@ -52,6 +53,8 @@ pub enum Error {
     UnableToDecryptKeystore(eth2_keystore::Error),
     /// There was a filesystem error when reading the keystore password from disk.
     UnableToReadVotingKeystorePassword(io::Error),
+    /// The keystore password could not be read from its `PasswordSource`.
+    UnableToReadVotingKeystorePasswordFromSource(password_source::Error),
     /// There was an error updating the on-disk validator definitions file.
     UnableToSaveDefinitions(validator_definitions::Error),
     /// It is not legal to try and initialize a disabled validator definition.
@@ -64,6 +67,8 @@ pub enum Error {
     UnableToDeleteLockfile(io::Error),
     /// Cannot initialize the same validator twice.
     DuplicatePublicKey,
+    /// There was a filesystem error when deleting a voting keystore.
+    UnableToDeleteVotingKeystore(io::Error),
 }
 
 /// A method used by a validator to sign messages.
@@ -101,6 +106,39 @@ fn get_lockfile_path(file_path: &PathBuf) -> Option<PathBuf> {
         })
 }
 
+/// Returns `true` if `voting_keystore_path` is in a `ValidatorDir` of its own within
+/// `validators_dir`: a directory named after `voting_public_key` which contains nothing but the
+/// keystore and its lockfile.
+fn is_own_validator_dir(
+    validators_dir: &Path,
+    voting_public_key: &PublicKey,
+    voting_keystore_path: &PathBuf,
+) -> bool {
+    let dir = match voting_keystore_path.parent() {
+        Some(dir) => dir,
+        None => return false,
+    };
+
+    let expected_name = voting_public_key.to_hex_string();
+    if dir.parent() != Some(validators_dir)
+        || dir.file_name().and_then(|name| name.to_str()) != Some(expected_name.as_str())
+    {
+        return false;
+    }
+
+    let lockfile_path = get_lockfile_path(voting_keystore_path);
+    fs::read_dir(dir)
+        .map(|mut entries| {
+            entries.all(|entry| {
+                entry.map_or(false, |entry| {
+                    let path = entry.path();
+                    path == *voting_keystore_path || Some(&path) == lockfile_path.as_ref()
+                })
+            })
+        })
+        .unwrap_or(false)
+}
+
 fn create_lock_file(
     file_path: &PathBuf,
     delete_lockfiles: bool,
@@ -165,6 +203,7 @@ impl InitializedValidator {
                 voting_keystore_path,
                 voting_keystore_password_path,
                 voting_keystore_password,
+                voting_keystore_password_source,
             } => {
                 use std::collections::hash_map::Entry::*;
                 let voting_keystore = match key_stores.entry(voting_keystore_path.clone()) {
@@ -181,35 +220,24 @@ impl InitializedValidator {
                     // to keep if off the core executor. This also has the fortunate effect of
                     // interrupting the potentially long-running task during shut down.
                     let (password, keypair) = tokio::task::spawn_blocking(move || {
-                        Ok(
-                            match (voting_keystore_password_path, voting_keystore_password) {
-                                // If the password is supplied, use it and ignore the path
-                                // (if supplied).
-                                (_, Some(password)) => (
-                                    password.as_ref().to_vec().into(),
-                                    keystore
-                                        .decrypt_keypair(password.as_ref())
-                                        .map_err(Error::UnableToDecryptKeystore)?,
-                                ),
-                                // If only the path is supplied, use the path.
-                                (Some(path), None) => {
-                                    let password = read_password(path)
-                                        .map_err(Error::UnableToReadVotingKeystorePassword)?;
-                                    let keypair = keystore
-                                        .decrypt_keypair(password.as_bytes())
-                                        .map_err(Error::UnableToDecryptKeystore)?;
-                                    (password, keypair)
-                                }
-                                // If there is no password available, maybe prompt for a password.
-                                (None, None) => {
-                                    let (password, keypair) = unlock_keystore_via_stdin_password(
-                                        &keystore,
-                                        &keystore_path,
-                                    )?;
-                                    (password.as_ref().to_vec().into(), keypair)
-                                }
-                            },
-                        )
+                        match read_voting_keystore_password(
+                            voting_keystore_password.as_ref(),
+                            voting_keystore_password_source.as_ref(),
+                            voting_keystore_password_path.as_ref(),
+                        )? {
+                            Some(password) => {
+                                let keypair = keystore
+                                    .decrypt_keypair(password.as_bytes())
+                                    .map_err(Error::UnableToDecryptKeystore)?;
+                                Ok((password, keypair))
+                            }
+                            // If there is no password available, maybe prompt for a password.
+                            None => {
+                                let (password, keypair) =
+                                    unlock_keystore_via_stdin_password(&keystore, &keystore_path)?;
+                                Ok((password.as_ref().to_vec().into(), keypair))
+                            }
+                        }
                     })
                     .await
                     .map_err(Error::TokioJoin)??;
@@ -271,6 +299,59 @@ impl Drop for InitializedValidator {
     }
 }
 
+/// Reads the password for a local keystore from the first of `voting_keystore_password`,
+/// `voting_keystore_password_source` and `voting_keystore_password_path` which is supplied.
+///
+/// Returns `None` if none of them are supplied.
+fn read_voting_keystore_password(
+    voting_keystore_password: Option<&ZeroizeString>,
+    voting_keystore_password_source: Option<&PasswordSource>,
+    voting_keystore_password_path: Option<&PathBuf>,
+) -> Result<Option<PlainText>, Error> {
+    if let Some(password) = voting_keystore_password {
+        Ok(Some(password.as_ref().to_vec().into()))
+    } else if let Some(source) = voting_keystore_password_source {
+        source
+            .read()
+            .map(Some)
+            .map_err(Error::UnableToReadVotingKeystorePasswordFromSource)
+    } else if let Some(path) = voting_keystore_password_path {
+        read_password(path)
+            .map(Some)
+            .map_err(Error::UnableToReadVotingKeystorePassword)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Reads the password for the keystore in `signing_definition`, prompting for it via `stdin` if it
+/// is not supplied.
+fn read_or_prompt_voting_keystore_password(
+    signing_definition: &SigningDefinition,
+) -> Result<PlainText, Error> {
+    match signing_definition {
+        SigningDefinition::LocalKeystore {
+            voting_keystore_password_path,
+            voting_keystore_password,
+            voting_keystore_password_source,
+            voting_keystore_path,
+        } => {
+            if let Some(password) = read_voting_keystore_password(
+                voting_keystore_password.as_ref(),
+                voting_keystore_password_source.as_ref(),
+                voting_keystore_password_path.as_ref(),
+            )? {
+                Ok(password)
+            } else {
+                let keystore = open_keystore(voting_keystore_path)?;
+                let (password, _) =
+                    unlock_keystore_via_stdin_password(&keystore, voting_keystore_path)?;
+                Ok(password.as_ref().to_vec().into())
+            }
+        }
+    }
+}
+
 /// Try to unlock `keystore` at `keystore_path` by prompting the user via `stdin`.
 fn unlock_keystore_via_stdin_password(
     keystore: &Keystore,
@@ -278,11 +359,12 @@ fn unlock_keystore_via_stdin_password(
 ) -> Result<(ZeroizeString, Keypair), Error> {
     eprintln!("");
     eprintln!(
-        "The {} file does not contain either of the following fields for {:?}:",
+        "The {} file does not contain any of the following fields for {:?}:",
         CONFIG_FILENAME, keystore_path
     );
     eprintln!("");
     eprintln!(" - voting_keystore_password");
+    eprintln!(" - voting_keystore_password_source");
     eprintln!(" - voting_keystore_password_path");
     eprintln!("");
     eprintln!(
@@ -391,6 +473,68 @@ impl InitializedValidators {
         Ok(())
     }
 
+    /// Remove the validator with `voting_public_key` from `self`, deleting its definition and its
+    /// voting keystore from disk.
+    ///
+    /// Returns `Ok(false)` if there is no such validator.
+    ///
+    /// ## Notes
+    ///
+    /// If the keystore is in a directory of its own within `self.validators_dir` (i.e., it was
+    /// created by a `validator_dir::Builder`) then the whole directory is deleted. Otherwise only
+    /// the keystore file is deleted, so that other keystores sharing its directory (e.g., those
+    /// from the `eth2.0-deposit-cli`) are left in place.
+    pub async fn delete_definition_and_keystore(
+        &mut self,
+        voting_public_key: &PublicKey,
+    ) -> Result<bool, Error> {
+        if self.is_enabled(voting_public_key).is_none() {
+            return Ok(false);
+        }
+
+        // Disabling the validator first stops it from signing, removes its lockfile and removes it
+        // from the key cache.
+        self.set_validator_status(voting_public_key, false).await?;
+
+        let def = match self.definitions.remove(voting_public_key) {
+            Some(def) => def,
+            None => return Ok(false),
+        };
+
+        self.definitions
+            .save(&self.validators_dir)
+            .map_err(Error::UnableToSaveDefinitions)?;
+
+        match &def.signing_definition {
+            SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                ..
+            } => {
+                match voting_keystore_path.parent() {
+                    Some(dir)
+                        if is_own_validator_dir(
+                            &self.validators_dir,
+                            voting_public_key,
+                            voting_keystore_path,
+                        ) =>
+                    {
+                        fs::remove_dir_all(dir)
+                    }
+                    _ => fs::remove_file(voting_keystore_path),
+                }
+                .map_err(Error::UnableToDeleteVotingKeystore)?;
+            }
+        }
+
+        info!(
+            self.log,
+            "Deleted validator";
+            "voting_pubkey" => format!("{:?}", voting_public_key)
+        );
+
+        Ok(true)
+    }
+
     /// Returns a slice of all defined validators (regardless of their enabled state).
     pub fn validator_definitions(&self) -> &[ValidatorDefinition] {
         self.definitions.as_slice()
@@ -479,34 +623,25 @@ impl InitializedValidators {
         }
 
         //collect passwords
-        let mut passwords = Vec::new();
+        let mut signing_definitions = Vec::new();
         let mut public_keys = Vec::new();
         for uuid in cache.uuids() {
             let def = definitions_map.get(uuid).expect("Existence checked before");
-            let pw = match &def.signing_definition {
-                SigningDefinition::LocalKeystore {
-                    voting_keystore_password_path,
-                    voting_keystore_password,
-                    voting_keystore_path,
-                } => {
-                    if let Some(p) = voting_keystore_password {
-                        p.as_ref().to_vec().into()
-                    } else if let Some(path) = voting_keystore_password_path {
-                        read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?
-                    } else {
-                        let keystore = open_keystore(voting_keystore_path)?;
-                        unlock_keystore_via_stdin_password(&keystore, &voting_keystore_path)?
-                            .0
-                            .as_ref()
-                            .to_vec()
-                            .into()
-                    }
-                }
-            };
-            passwords.push(pw);
+            signing_definitions.push(def.signing_definition.clone());
             public_keys.push(def.voting_public_key.clone());
         }
 
+        // Reading a password may run a command or prompt on stdin, so keep it off the core
+        // executor.
+        let passwords = tokio::task::spawn_blocking(move || {
+            signing_definitions
+                .iter()
+                .map(read_or_prompt_voting_keystore_password)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .await
+        .map_err(Error::TokioJoin)??;
+
         //decrypt
         tokio::task::spawn_blocking(move || match cache.decrypt(passwords, public_keys) {
             Ok(_) | Err(key_cache::Error::AlreadyDecrypted) => cache,
@@ -1,3 +1,6 @@
+#[macro_use]
+extern crate lazy_static;
+
 mod attestation_service;
 mod block_service;
 mod cli;
@@ -7,6 +10,7 @@ mod fork_service;
 mod initialized_validators;
 mod is_synced;
 mod key_cache;
+mod metrics;
 mod notifier;
 mod validator_duty;
 mod validator_store;
@@ -22,7 +26,7 @@ use block_service::{BlockService, BlockServiceBuilder};
 use clap::ArgMatches;
 use duties_service::{DutiesService, DutiesServiceBuilder};
 use environment::RuntimeContext;
-use eth2::{reqwest::ClientBuilder, BeaconNodeHttpClient, StatusCode, Url};
+use eth2::{BeaconNodeHttpClient, StatusCode, Url};
 use fork_service::{ForkService, ForkServiceBuilder};
 use futures::channel::mpsc;
 use http_api::ApiSecret;
@@ -32,8 +36,10 @@ use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use slog::{error, info, warn, Logger};
 use slot_clock::SlotClock;
 use slot_clock::SystemTimeSlotClock;
+use std::fs;
 use std::marker::PhantomData;
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{delay_for, Duration};
@@ -46,6 +52,10 @@ const RETRY_DELAY: Duration = Duration::from_secs(2);
 /// The global timeout for HTTP requests to the beacon node.
 const HTTP_TIMEOUT: Duration = Duration::from_secs(12);
 
+/// The filename within the `validators` directory of the scratch slashing protection database
+/// which is used instead of the real one with `--dry-run`.
+const DRY_RUN_SLASHING_PROTECTION_FILENAME: &str = "dry_run_slashing_protection.sqlite";
+
 pub struct ProductionValidatorClient<T: EthSpec> {
     context: RuntimeContext<T>,
     duties_service: DutiesService<SystemTimeSlotClock, T>,
@@ -129,7 +139,18 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
         // Create the slashing database if there are no validators, even if
         // `init_slashing_protection` is not supplied. There is no risk in creating a slashing
         // database without any validators in it.
-        let slashing_db_path = config.validator_dir.join(SLASHING_PROTECTION_FILENAME);
+        let slashing_db_path = if config.dry_run {
+            let path = init_dry_run_slashing_db(&config.validator_dir)?;
+            warn!(
+                log,
+                "Dry run mode enabled";
+                "msg" => "blocks and attestations will be signed but not published",
+                "slashing_protection" => format!("{:?}", path),
+            );
+            path
+        } else {
+            config.validator_dir.join(SLASHING_PROTECTION_FILENAME)
+        };
         let slashing_protection = if config.init_slashing_protection || voting_pubkeys.is_empty() {
             SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
                 format!(
@@ -170,12 +191,43 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .beacon_node
             .parse()
             .map_err(|e| format!("Unable to parse beacon node URL: {:?}", e))?;
-        let beacon_node_http_client = ClientBuilder::new()
-            .timeout(HTTP_TIMEOUT)
-            .build()
-            .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
+        let api_token = config
+            .beacon_node_api_token_file
+            .as_ref()
+            .map(|path| {
+                fs::read_to_string(path)
+                    .map(|token| token.trim().to_string())
+                    .map_err(|e| format!("Unable to read {:?}: {}", path, e))
+            })
+            .transpose()?;
+        let tls_ca_cert = config
+            .beacon_node_tls_ca_cert
+            .as_ref()
+            .map(|path| fs::read(path).map_err(|e| format!("Unable to read {:?}: {}", path, e)))
+            .transpose()?;
+        let beacon_node_http_client =
+            BeaconNodeHttpClient::client_builder(api_token.as_deref(), tls_ca_cert.as_deref())
+                .map_err(|e| format!("Unable to configure HTTP client: {:?}", e))?
+                .timeout(HTTP_TIMEOUT)
+                .build()
+                .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
         let beacon_node =
-            BeaconNodeHttpClient::from_components(beacon_node_url, beacon_node_http_client);
+            BeaconNodeHttpClient::from_components(beacon_node_url.clone(), beacon_node_http_client);
+
+        // The event stream is long-lived, so it uses a separate client without `HTTP_TIMEOUT`.
+        let head_events_beacon_node = if config.attest_on_head {
+            let client =
+                BeaconNodeHttpClient::client_builder(api_token.as_deref(), tls_ca_cert.as_deref())
+                    .map_err(|e| format!("Unable to configure HTTP client: {:?}", e))?
+                    .build()
+                    .map_err(|e| format!("Unable to build HTTP client: {:?}", e))?;
+            Some(BeaconNodeHttpClient::from_components(
+                beacon_node_url,
+                client,
+            ))
+        } else {
+            None
+        };
 
         // Perform some potentially long-running initialization tasks.
         let (yaml_config, genesis_time, genesis_validators_root) = tokio::select! {
@@ -237,6 +289,7 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .beacon_node(beacon_node.clone())
             .runtime_context(context.service_context("block".into()))
             .graffiti(config.graffiti)
+            .dry_run(config.dry_run)
             .build()?;
 
         let attestation_service = AttestationServiceBuilder::new()
@@ -245,6 +298,9 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
             .validator_store(validator_store.clone())
             .beacon_node(beacon_node)
             .runtime_context(context.service_context("attestation".into()))
+            .dry_run(config.dry_run)
+            .head_events_beacon_node(head_events_beacon_node)
+            .aggregation_delay(config.aggregation_delay)
             .build()?;
 
         Ok(Self {
@@ -301,6 +357,8 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
                 validator_dir: Some(self.config.validator_dir.clone()),
                 spec: self.context.eth2_config.spec.clone(),
                 config: self.config.http_api.clone(),
+                dry_run: self.config.dry_run,
+                log_filter: self.context.log_filter.clone(),
                 log: log.clone(),
                 _phantom: PhantomData,
             });
@@ -325,6 +383,29 @@ impl<T: EthSpec> ProductionValidatorClient<T> {
     }
 }
 
+/// Replaces the scratch slashing protection database used by `--dry-run` with a copy of the real
+/// database in `validator_dir`, returning its path.
+///
+/// If there is no real database then any existing scratch database is deleted.
+fn init_dry_run_slashing_db(validator_dir: &Path) -> Result<PathBuf, String> {
+    let real_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+    let dry_run_path = validator_dir.join(DRY_RUN_SLASHING_PROTECTION_FILENAME);
+
+    if real_path.exists() {
+        fs::copy(&real_path, &dry_run_path).map_err(|e| {
+            format!(
+                "Unable to copy slashing protection database to {:?}: {:?}",
+                dry_run_path, e
+            )
+        })?;
+    } else if dry_run_path.exists() {
+        fs::remove_file(&dry_run_path)
+            .map_err(|e| format!("Unable to delete {:?}: {:?}", dry_run_path, e))?;
+    }
+
+    Ok(dry_run_path)
+}
+
 async fn init_from_beacon_node<E: EthSpec>(
     beacon_node: &BeaconNodeHttpClient,
     context: &RuntimeContext<E>,
@@ -427,3 +508,221 @@ async fn wait_for_node(beacon_node: &BeaconNodeHttpClient, log: &Logger) -> Resu
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use account_utils::{validator_definitions::ValidatorDefinition, ZeroizeString};
+    use duties_service::DutyAndProof;
+    use environment::EnvironmentBuilder;
+    use eth2::types::GenericResponse;
+    use parking_lot::Mutex;
+    use slot_clock::TestingSlotClock;
+    use types::{
+        test_utils::generate_deterministic_keypair, AggregateSignature, Attestation,
+        AttestationData, BeaconBlock, BitList, ChainSpec, Checkpoint, MinimalEthSpec, Slot,
+    };
+    use validator_dir::insecure_keys::{generate_deterministic_keystore, INSECURE_PASSWORD};
+    use validator_duty::ValidatorDuty;
+    use warp::{path::FullPath, Filter};
+
+    type E = MinimalEthSpec;
+
+    const SLOT: u64 = 9;
+    const COMMITTEE_LENGTH: usize = 4;
+
+    /// Serves just enough of the beacon node API to produce a block, an attestation and an
+    /// aggregate, recording the path of every `POST` request.
+    fn mock_beacon_node(
+        spec: ChainSpec,
+        posts: Arc<Mutex<Vec<String>>>,
+    ) -> (SocketAddr, impl std::future::Future<Output = ()>) {
+        let slot = Slot::new(SLOT);
+        let attestation_data = AttestationData {
+            slot,
+            index: 0,
+            beacon_block_root: Hash256::repeat_byte(1),
+            source: Checkpoint {
+                epoch: slot.epoch(E::slots_per_epoch()) - 1,
+                root: Hash256::repeat_byte(2),
+            },
+            target: Checkpoint {
+                epoch: slot.epoch(E::slots_per_epoch()),
+                root: Hash256::repeat_byte(3),
+            },
+        };
+        let mut aggregate = Attestation::<E> {
+            aggregation_bits: BitList::with_capacity(COMMITTEE_LENGTH).unwrap(),
+            data: attestation_data.clone(),
+            signature: AggregateSignature::infinity(),
+        };
+        aggregate.aggregation_bits.set(0, true).unwrap();
+
+        let blocks = warp::path!("eth" / "v1" / "validator" / "blocks" / u64).map(move |slot| {
+            let mut block = BeaconBlock::<E>::empty(&spec);
+            block.slot = Slot::new(slot);
+            warp::reply::json(&GenericResponse::from(block))
+        });
+        let attestation_data = warp::path!("eth" / "v1" / "validator" / "attestation_data")
+            .map(move || warp::reply::json(&GenericResponse::from(attestation_data.clone())));
+        let aggregate = warp::path!("eth" / "v1" / "validator" / "aggregate_attestation")
+            .map(move || warp::reply::json(&GenericResponse::from(aggregate.clone())));
+        let post = warp::post()
+            .and(warp::path::full())
+            .map(move |path: FullPath| {
+                posts.lock().push(path.as_str().to_string());
+                warp::reply()
+            });
+
+        let routes = warp::get()
+            .and(blocks.or(attestation_data).or(aggregate))
+            .or(post);
+
+        warp::serve(routes).bind_ephemeral(([127, 0, 0, 1], 0))
+    }
+
+    #[test]
+    fn dry_run_signs_without_publishing() {
+        let mut env = EnvironmentBuilder::minimal()
+            .null_logger()
+            .unwrap()
+            .multi_threaded_tokio_runtime()
+            .unwrap()
+            .build()
+            .unwrap();
+        let context = env.core_context();
+        let log = context.log().clone();
+        let spec = context.eth2_config.spec.clone();
+
+        let validator_dir = tempfile::tempdir().unwrap();
+        let keypair = generate_deterministic_keypair(0);
+        let genesis_validators_root = Hash256::repeat_byte(42);
+
+        // Populate the real slashing protection database with a previous proposal.
+        let real_path = validator_dir.path().join(SLASHING_PROTECTION_FILENAME);
+        {
+            let real_db = SlashingDatabase::create(&real_path).unwrap();
+            real_db.register_validator(&keypair.pk).unwrap();
+            real_db
+                .check_and_insert_block_signing_root(
+                    &keypair.pk,
+                    Slot::new(1),
+                    Hash256::repeat_byte(1),
+                )
+                .unwrap();
+        }
+        let real_db_bytes = fs::read(&real_path).unwrap();
+
+        let (keystore, _) = generate_deterministic_keystore(0).unwrap();
+        let keystore_path = validator_dir.path().join("voting-keystore.json");
+        keystore
+            .to_json_writer(fs::File::create(&keystore_path).unwrap())
+            .unwrap();
+        let password = ZeroizeString::from(String::from_utf8(INSECURE_PASSWORD.to_vec()).unwrap());
+        let definition =
+            ValidatorDefinition::new_keystore_with_password(&keystore_path, Some(password))
+                .unwrap();
+
+        let posts = Arc::new(Mutex::new(vec![]));
+
+        env.runtime().block_on(async {
+            let (addr, server) = mock_beacon_node(spec.clone(), posts.clone());
+            tokio::spawn(server);
+            let beacon_node =
+                BeaconNodeHttpClient::new(Url::parse(&format!("http://{}", addr)).unwrap());
+
+            let mut validators = InitializedValidators::from_definitions(
+                ValidatorDefinitions::default(),
+                validator_dir.path().into(),
+                false,
+                log.clone(),
+            )
+            .await
+            .unwrap();
+            validators.add_definition(definition).await.unwrap();
+
+            let slashing_db_path = init_dry_run_slashing_db(validator_dir.path()).unwrap();
+            let validator_store: ValidatorStore<TestingSlotClock, E> = ValidatorStore::new(
+                validators,
+                SlashingDatabase::open(&slashing_db_path).unwrap(),
+                genesis_validators_root,
+                spec.clone(),
+                ForkServiceBuilder::testing_only(log.clone())
+                    .build()
+                    .unwrap(),
+                log.clone(),
+            );
+
+            let slot_clock =
+                TestingSlotClock::new(Slot::new(0), Duration::from_secs(0), Duration::from_secs(6));
+            slot_clock.set_slot(SLOT);
+
+            let block_service = BlockServiceBuilder::new()
+                .validator_store(validator_store.clone())
+                .slot_clock(slot_clock.clone())
+                .beacon_node(beacon_node.clone())
+                .runtime_context(context.clone())
+                .dry_run(true)
+                .build()
+                .unwrap();
+            block_service
+                .publish_block(Slot::new(SLOT), keypair.pk.clone())
+                .await
+                .unwrap();
+
+            let duties_service = DutiesServiceBuilder::new()
+                .validator_store(validator_store.clone())
+                .slot_clock(slot_clock.clone())
+                .beacon_node(beacon_node.clone())
+                .runtime_context(context.clone())
+                .build()
+                .unwrap();
+            let attestation_service = AttestationServiceBuilder::new()
+                .duties_service(duties_service)
+                .validator_store(validator_store.clone())
+                .slot_clock(slot_clock)
+                .beacon_node(beacon_node)
+                .runtime_context(context.clone())
+                .dry_run(true)
+                .build()
+                .unwrap();
+
+            let duty = DutyAndProof {
+                duty: ValidatorDuty {
+                    validator_pubkey: keypair.pk.clone(),
+                    validator_index: Some(0),
+                    attestation_slot: Some(Slot::new(SLOT)),
+                    attestation_committee_index: Some(0),
+                    attestation_committee_position: Some(0),
+                    committee_count_at_slot: Some(1),
+                    committee_length: Some(COMMITTEE_LENGTH as u64),
+                    block_proposal_slots: Some(vec![Slot::new(SLOT)]),
+                },
+                // Always aggregate, regardless of whether the proof elects an aggregator.
+                selection_proof: validator_store
+                    .produce_selection_proof(&keypair.pk, Slot::new(SLOT)),
+            };
+            attestation_service
+                .publish_attestations_and_aggregates(
+                    Slot::new(SLOT),
+                    0,
+                    vec![duty],
+                    tokio::time::Instant::now(),
+                )
+                .await
+                .unwrap();
+
+            // Everything was signed, and recorded in the scratch database.
+            let interchange = validator_store
+                .export_slashing_protection(&[keypair.pk.clone()])
+                .unwrap();
+            assert_eq!(interchange.data.len(), 1);
+            assert_eq!(interchange.data[0].signed_blocks.len(), 2);
+            assert_eq!(interchange.data[0].signed_attestations.len(), 1);
+        });
+
+        let posts = posts.lock();
+        assert!(posts.is_empty(), "dry run published: {:?}", *posts);
+        assert_eq!(fs::read(&real_path).unwrap(), real_db_bytes);
+    }
+}
@@ -0,0 +1,26 @@
+pub use lighthouse_metrics::*;
+
+pub const BLOCK: &str = "block";
+pub const ATTESTATION: &str = "attestation";
+pub const AGGREGATE: &str = "aggregate";
+
+pub const HEAD_EVENT: &str = "head_event";
+pub const TIMER: &str = "timer";
+
+lazy_static! {
+    pub static ref DRY_RUN_WITHHELD_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_dry_run_withheld_total",
+        "Count of signed messages which were not published because of --dry-run",
+        &["type"]
+    );
+    pub static ref ATTESTATION_TRIGGERS_TOTAL: Result<IntCounterVec> = try_create_int_counter_vec(
+        "vc_attestation_triggers_total",
+        "Count of slots in which attestation was triggered by a head event or by the timer",
+        &["trigger"]
+    );
+}
+
+/// Returns the number of messages of `message_type` withheld because of `--dry-run`.
+pub fn dry_run_withheld(message_type: &str) -> u64 {
+    get_int_counter(&DRY_RUN_WITHHELD_TOTAL, &[message_type]).map_or(0, |counter| counter.get())
+}
@@ -1,4 +1,4 @@
-use crate::{is_synced::is_synced, ProductionValidatorClient};
+use crate::{is_synced::is_synced, metrics, ProductionValidatorClient};
 use futures::StreamExt;
 use slog::{error, info};
 use slot_clock::SlotClock;
@@ -11,6 +11,7 @@ pub fn spawn_notifier<T: EthSpec>(client: &ProductionValidatorClient<T>) -> Resu
     let executor = context.executor.clone();
     let duties_service = client.duties_service.clone();
     let allow_unsynced_beacon_node = client.config.allow_unsynced_beacon_node;
+    let dry_run = client.config.dry_run;
 
     let slot_duration = Duration::from_millis(context.eth2_config.spec.milliseconds_per_slot);
     let duration_to_next_slot = duties_service
@@ -80,6 +81,16 @@ pub fn spawn_notifier<T: EthSpec>(client: &ProductionValidatorClient<T>) -> Resu
                         "slot" => format!("{}", slot),
                     );
                 }
+
+                if dry_run && slot == epoch.start_slot(T::slots_per_epoch()) {
+                    info!(
+                        log,
+                        "Dry run totals";
+                        "withheld_blocks" => metrics::dry_run_withheld(metrics::BLOCK),
+                        "withheld_attestations" => metrics::dry_run_withheld(metrics::ATTESTATION),
+                        "withheld_aggregates" => metrics::dry_run_withheld(metrics::AGGREGATE),
+                    );
+                }
             } else {
                 error!(log, "Unable to read slot clock");
             }
@@ -1,7 +1,7 @@
 use crate::{fork_service::ForkService, initialized_validators::InitializedValidators};
 use account_utils::{validator_definitions::ValidatorDefinition, ZeroizeString};
 use parking_lot::RwLock;
-use slashing_protection::{NotSafe, Safe, SlashingDatabase};
+use slashing_protection::{interchange::Interchange, NotSafe, Safe, SlashingDatabase};
 use slog::{crit, error, warn, Logger};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
@@ -109,6 +109,47 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(validator_def)
     }
 
+    /// Remove a validator from `self`, deleting its definition and voting keystore from the
+    /// filesystem.
+    ///
+    /// The validator stops performing duties, but its slashing protection data is retained.
+    ///
+    /// Returns `Ok(false)` if the validator is not known to `self`.
+    pub async fn delete_validator_keystore(
+        &self,
+        voting_public_key: &PublicKey,
+    ) -> Result<bool, String> {
+        self.validators
+            .write()
+            .delete_definition_and_keystore(voting_public_key)
+            .await
+            .map_err(|e| format!("Unable to delete validator: {:?}", e))
+    }
+
+    /// Import the slashing protection data in an EIP-3076 `interchange`.
+    pub fn import_slashing_protection(&self, interchange: &Interchange) -> Result<(), String> {
+        self.slashing_protection
+            .import_interchange_info(interchange, self.genesis_validators_root)
+            .map_err(|e| format!("Unable to import slashing protection data: {:?}", e))
+    }
+
+    /// Export the slashing protection data for `voting_public_keys` as an EIP-3076 interchange.
+    pub fn export_slashing_protection(
+        &self,
+        voting_public_keys: &[PublicKey],
+    ) -> Result<Interchange, String> {
+        let mut interchange = self
+            .slashing_protection
+            .export_interchange_info(self.genesis_validators_root)
+            .map_err(|e| format!("Unable to export slashing protection data: {:?}", e))?;
+
+        interchange
+            .data
+            .retain(|data| voting_public_keys.contains(&data.pubkey));
+
+        Ok(interchange)
+    }
+
     pub fn voting_pubkeys(&self) -> Vec<PublicKey> {
         self.validators
             .read()